@@ -0,0 +1,160 @@
+//! Compares ways of storing the generated actions of a position
+//!
+//! * boxed iterator: every piece adds an iterator to a boxed chain, which is collected at the
+//!   end. This was the original design of the move generator and is rebuilt here from the
+//!   public building blocks.
+//! * vec: the current generator pushing into a growing vector, see `movegen::all_moves`
+//! * move list: the current generator writing into a reused stack allocated `MoveList`, the
+//!   default of `movegen::legal_moves` and perft
+//!
+//! All designs filter the pseudo legal actions with `movegen::is_legal` and produce the same
+//! actions for the benchmarked positions, which have no castling or en passant actions.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_chess::core::bitboard::{constants, Bitboard};
+use rust_chess::core::Square;
+use rust_chess::game_representation::PieceType;
+use rust_chess::game_representation::{Color, Game};
+use rust_chess::move_generation::core::{
+    BlackMoveGenColor, CaptureActionIterator, MoveGenColor, PawnCaptureIterator, PawnPushIterator,
+    PromotionIterator, QuietActionIterator, WhiteMoveGenColor,
+};
+use rust_chess::move_generation::{movegen, Action, MoveList};
+
+const POSITIONS: [(&str, &str); 3] = [
+    (
+        "kiwipete",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w - - 0 1",
+    ),
+    ("promotions", "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1"),
+    (
+        "middlegame",
+        "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP1B1PPP/R2QKB1R w - - 0 1",
+    ),
+];
+
+/// Generates the pseudo legal actions by chaining boxed iterators
+fn boxed_moves<T: MoveGenColor>(game: &Game) -> Vec<Action> {
+    let board = &game.board;
+    let all_pieces = game.occupancy().0;
+    let color = if T::is_white() {
+        Color::White
+    } else {
+        Color::Black
+    };
+    let own = game.occupancy_of(color).0;
+    let other = all_pieces & !own;
+    let (last_rank, pawn_delta) = if T::is_white() {
+        (constants::RANKS[7], 8)
+    } else {
+        (constants::RANKS[0], -8)
+    };
+
+    let pushed = movegen::single_pawn_pushes::<T>(board.pawns & own, !all_pieces);
+    let double = movegen::double_pawn_pushes::<T>(pushed, !all_pieces);
+    let mut iter: Box<dyn Iterator<Item = Action>> =
+        Box::new(PawnPushIterator::new::<T>(pushed & !last_rank, double));
+    for to in Bitboard(pushed & last_rank).map(Square::index) {
+        let from = (to as i8 + pawn_delta) as u8;
+        iter = Box::new(iter.chain(PromotionIterator::new(from, to, None)));
+    }
+    let west = movegen::pawn_attacks_west::<T>(board.pawns & own) & other;
+    let east = movegen::pawn_attacks_east::<T>(board.pawns & own) & other;
+    for (captures, delta) in [(west, pawn_delta + 1), (east, pawn_delta - 1)] {
+        for to in Bitboard(captures & last_rank).map(Square::index) {
+            let from = (to as i8 + delta) as u8;
+            let captured = board.get_piecetype_on(to);
+            iter = Box::new(iter.chain(PromotionIterator::new(from, to, captured)));
+        }
+        iter = Box::new(iter.chain(PawnCaptureIterator::new(
+            captures & !last_rank,
+            delta,
+            board,
+        )));
+    }
+
+    let pieces = [
+        (board.bishops & !board.rooks, PieceType::Bishop),
+        (board.rooks & !board.bishops, PieceType::Rook),
+        (board.bishops & board.rooks, PieceType::Queen),
+        (board.knights, PieceType::Knight),
+        (board.kings, PieceType::King),
+    ];
+    for (fields, piece) in pieces {
+        for index in Bitboard(fields & own).map(Square::index) {
+            let from = 1 << index;
+            let attacks = match piece {
+                PieceType::Bishop => movegen::bishop_attacks(from, all_pieces),
+                PieceType::Rook => movegen::rook_attacks(from, all_pieces),
+                PieceType::Queen => {
+                    movegen::bishop_attacks(from, all_pieces)
+                        | movegen::rook_attacks(from, all_pieces)
+                }
+                PieceType::Knight => constants::KNIGHT_MASKS[index as usize],
+                _ => constants::KING_MASKS[index as usize],
+            };
+            iter = Box::new(iter.chain(QuietActionIterator::new(
+                attacks & !all_pieces,
+                piece,
+                index,
+            )));
+            iter = Box::new(iter.chain(CaptureActionIterator::new(
+                attacks & other,
+                piece,
+                index,
+                board,
+            )));
+        }
+    }
+    iter.collect()
+}
+
+fn legal_boxed(game: &Game) -> Vec<Action> {
+    let mut moves = match game.color_to_move {
+        Color::White => boxed_moves::<WhiteMoveGenColor>(game),
+        Color::Black => boxed_moves::<BlackMoveGenColor>(game),
+    };
+    moves.retain(|action| movegen::is_legal(action, game));
+    moves
+}
+
+fn legal_vec(game: &Game) -> Vec<Action> {
+    let in_check = game.is_in_check();
+    let mut moves = match game.color_to_move {
+        Color::White => movegen::all_moves::<WhiteMoveGenColor>(0, in_check, game),
+        Color::Black => movegen::all_moves::<BlackMoveGenColor>(0, in_check, game),
+    };
+    moves.retain(|action| movegen::is_legal(action, game));
+    moves
+}
+
+fn sorted(mut actions: Vec<Action>) -> Vec<String> {
+    let mut uci: Vec<String> = actions.drain(..).map(|action| action.to_uci()).collect();
+    uci.sort();
+    uci
+}
+
+fn move_storage(c: &mut Criterion) {
+    for (name, fen) in POSITIONS.iter() {
+        let game = Game::from_fen(fen).unwrap();
+        let expected = sorted(movegen::legal_moves(&game));
+        assert_eq!(sorted(legal_boxed(&game)), expected, "{}", name);
+        assert_eq!(sorted(legal_vec(&game)), expected, "{}", name);
+
+        let mut group = c.benchmark_group(format!("move storage/{}", name));
+        group.bench_function("boxed iterator", |b| {
+            b.iter(|| legal_boxed(black_box(&game)))
+        });
+        group.bench_function("vec", |b| b.iter(|| legal_vec(black_box(&game))));
+        group.bench_function("move list", |b| {
+            let mut moves = MoveList::new();
+            b.iter(|| {
+                movegen::generate_moves_into(black_box(&game), &mut moves);
+                moves.len()
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, move_storage);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_chess::game_representation::Game;
+use rust_chess::move_generation::movegen;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+/// Compares the magic bitboard lookups with flooding along the rays
+fn slider_attacks(c: &mut Criterion) {
+    let game = Game::from_fen(KIWIPETE).unwrap();
+    let occupied = game.board.occupancy().0;
+    let mut group = c.benchmark_group("slider attacks");
+    group.bench_function("magic", |b| {
+        b.iter(|| {
+            (0..64).fold(0, |acc, index| {
+                acc ^ movegen::bishop_attacks(1 << index, black_box(occupied))
+                    ^ movegen::rook_attacks(1 << index, black_box(occupied))
+            })
+        })
+    });
+    group.bench_function("flood fill", |b| {
+        b.iter(|| {
+            (0..64).fold(0, |acc, index| {
+                acc ^ movegen::bishop_rays(1 << index, black_box(occupied))
+                    ^ movegen::rook_rays(1 << index, black_box(occupied))
+            })
+        })
+    });
+    group.finish();
+}
+
+fn perft(c: &mut Criterion) {
+    let mut group = c.benchmark_group("perft");
+    group.sample_size(10);
+    group.bench_function("startpos 4", |b| {
+        let mut game = Game::startpos();
+        b.iter(|| game.perft(black_box(4)))
+    });
+    group.bench_function("kiwipete 3", |b| {
+        let mut game = Game::from_fen(KIWIPETE).unwrap();
+        b.iter(|| game.perft(black_box(3)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, slider_attacks, perft);
+criterion_main!(benches);
@@ -0,0 +1,160 @@
+use crate::game_representation::Color;
+use crate::move_generation::Action;
+use crate::record::RecordedGame;
+use std::collections::HashSet;
+
+/// Engine information about a single position
+#[derive(Clone, Copy, Debug)]
+pub struct PositionAnnotation {
+    /// Evaluation in centipawns from the point of view of white
+    pub eval: i32,
+    /// The best move in this position, if known
+    pub best_move: Option<Action>,
+}
+
+/// A recorded game with an annotation for every position
+///
+/// `annotations[i]` belongs to the position after `i` halfmoves, so there is one more
+/// annotation than recorded actions.
+pub struct AnnotatedGame {
+    pub record: RecordedGame,
+    pub annotations: Vec<PositionAnnotation>,
+}
+
+/// A position right after a blunder, usable as a tactics exercise
+#[derive(Clone, Debug)]
+pub struct BlunderSpot {
+    /// The position after the blunder, the side to move can punish it
+    pub fen: String,
+    /// The move that lost the material or position
+    pub blunder: Action,
+    /// The best reply to the blunder, the solution of the exercise
+    pub refutation: Action,
+    /// Centipawns lost by the blunder from the point of view of the blundering side
+    pub loss: i32,
+}
+
+/// Returns all positions where the played move lost at least `threshold` centipawns
+///
+/// Every returned spot contains the position after the blunder together with the refutation
+/// taken from the annotation of that position. Blunders without a known refutation and games
+/// without exactly one annotation per position are skipped. The same position is only returned
+/// once, even if it occurs in multiple games; positions are compared by their zobrist hash,
+/// which ignores the clocks.
+pub fn extract_blunders(games: &[AnnotatedGame], threshold: i32) -> Vec<BlunderSpot> {
+    let mut seen = HashSet::new();
+    let mut spots = Vec::new();
+    for game in games {
+        if game.annotations.len() != game.record.len() + 1 {
+            continue;
+        }
+        let mut position = game.record.position_at(0);
+        for (ply, action) in game.record.actions().iter().enumerate() {
+            let mover = position.color_to_move;
+            position.execute_action(action);
+            let before = game.annotations[ply].eval;
+            let after = game.annotations[ply + 1].eval;
+            let loss = match mover {
+                Color::White => before - after,
+                Color::Black => after - before,
+            };
+            if loss < threshold {
+                continue;
+            }
+            let refutation = match game.annotations[ply + 1].best_move {
+                Some(refutation) => refutation,
+                None => continue,
+            };
+            if !seen.insert(position.get_hash()) {
+                continue;
+            }
+            spots.push(BlunderSpot {
+                fen: position.to_fen(),
+                blunder: *action,
+                refutation,
+                loss,
+            });
+        }
+    }
+    spots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_representation::Game;
+
+    fn annotated(moves: &[&str], evals: &[i32], best: &[Option<&str>]) -> AnnotatedGame {
+        let mut record = RecordedGame::new();
+        let mut game = Game::startpos();
+        let mut annotations = Vec::new();
+        for (i, eval) in evals.iter().enumerate() {
+            let best_move = best[i].map(|san| Action::from_san(san, &game).unwrap());
+            annotations.push(PositionAnnotation {
+                eval: *eval,
+                best_move,
+            });
+            if let Some(san) = moves.get(i) {
+                let action = Action::from_san(san, &game).unwrap();
+                game.execute_action(&action);
+                record.push(action);
+            }
+        }
+        AnnotatedGame {
+            record,
+            annotations,
+        }
+    }
+
+    #[test]
+    fn finds_blunders_of_both_sides() {
+        let game = annotated(
+            &["e4", "e5", "Qh5", "Ke7", "Qxe5"],
+            &[20, 30, 25, -150, 500, 480],
+            &[None, None, None, None, Some("Qxe5"), None],
+        );
+        let spots = extract_blunders(&[game], 200);
+        assert_eq!(spots.len(), 1);
+        assert_eq!(spots[0].loss, 650);
+        assert_eq!(
+            spots[0].fen,
+            "rnbq1bnr/ppppkppp/8/4p2Q/4P3/8/PPPP1PPP/RNB1KBNR w KQ - 2 3"
+        );
+        assert_eq!(spots[0].refutation.pretty(), "Qh5xe5");
+
+        // white's Qh5 only lost 175 centipawns
+        let game = annotated(
+            &["e4", "e5", "Qh5", "Ke7", "Qxe5"],
+            &[20, 30, 25, -150, 500, 480],
+            &[None, None, None, Some("Nc6"), Some("Qxe5"), None],
+        );
+        assert_eq!(extract_blunders(&[game], 150).len(), 2);
+    }
+
+    #[test]
+    fn deduplicates_positions() {
+        let games: Vec<_> = (0..3)
+            .map(|_| {
+                annotated(
+                    &["f3", "e5", "g4"],
+                    &[0, -50, -50, -10000],
+                    &[None, None, None, Some("Qh4")],
+                )
+            })
+            .collect();
+        let spots = extract_blunders(&games, 300);
+        assert_eq!(spots.len(), 1);
+        assert_eq!(spots[0].loss, 9950);
+    }
+
+    #[test]
+    fn skips_incomplete_annotations() {
+        let mut game = annotated(
+            &["f3", "e5", "g4"],
+            &[0, -50, -50, -10000],
+            &[None, None, None, Some("Qh4")],
+        );
+        game.annotations.pop();
+        assert!(extract_blunders(&[game], 300).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::game_representation::Game;
+use crate::move_generation::Action;
+use crate::search::{SearchLimits, Searcher};
+use std::collections::HashMap;
+
+/// The completed analysis of a position
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedAnalysis {
+    /// The depth the position was searched to
+    pub depth: u32,
+    /// The score in centipawns from the point of view of the color to move
+    pub score: i32,
+    /// The expected line of play, empty if the color to move has no legal action
+    pub pv: Vec<Action>,
+}
+
+/// A cache of analysis results keyed by the zobrist hash of the position
+///
+/// Stepping back and forth through a game analyses the same positions again and again. Results
+/// kept here are reused as long as they were searched at least as deep as requested. Once the
+/// cache is full, the result that was used least recently is dropped. Positions with the same
+/// hash but a different history share one result, so repetitions are not taken into account.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::analysis::AnalysisCache;
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::record::GameTree;
+/// # use rust_chess::search::{SearchLimits, Searcher};
+/// let mut cache = AnalysisCache::new(64);
+/// let mut searcher = Searcher::new(SearchLimits::default());
+/// let mut tree = GameTree::new();
+/// tree.play(Action::from_san("e4", tree.position()).unwrap()).unwrap();
+/// let first = cache.analyze(tree.position(), 3, &mut searcher);
+/// tree.back();
+/// cache.analyze(tree.position(), 3, &mut searcher);
+/// tree.forward();
+/// // the position after e4 is not searched again
+/// assert_eq!(cache.analyze(tree.position(), 2, &mut searcher), first);
+/// assert_eq!(cache.len(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AnalysisCache {
+    capacity: usize,
+    // every entry remembers the tick of its last use
+    entries: HashMap<u64, (CachedAnalysis, u64)>,
+    tick: u64,
+}
+
+impl AnalysisCache {
+    /// Returns an empty cache holding up to `capacity` results, but at least one
+    pub fn new(capacity: usize) -> AnalysisCache {
+        AnalysisCache {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the number of results the cache can hold
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of cached results
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no result is cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all results
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns the result of the position with the given hash and marks it as recently used
+    pub fn get(&mut self, hash: u64) -> Option<&CachedAnalysis> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(&hash).map(|(analysis, used)| {
+            *used = tick;
+            &*analysis
+        })
+    }
+
+    /// Stores the result of the position with the given hash
+    ///
+    /// A result of the same position is replaced. If the cache is full, the result used least
+    /// recently is dropped.
+    pub fn insert(&mut self, hash: u64, analysis: CachedAnalysis) {
+        self.tick += 1;
+        if !self.entries.contains_key(&hash) && self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(&key, _)| key);
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(hash, (analysis, self.tick));
+    }
+
+    /// Returns the analysis of the position to at least the given depth
+    ///
+    /// A cached result of the position is returned if it is deep enough, otherwise the position
+    /// is searched to exactly `depth` plies and the result replaces the cached one. The limits
+    /// of the searcher are changed for the search.
+    pub fn analyze(&mut self, game: &Game, depth: u32, searcher: &mut Searcher) -> CachedAnalysis {
+        let hash = game.get_hash();
+        match self.get(hash) {
+            Some(cached) if cached.depth >= depth => return cached.clone(),
+            _ => {}
+        }
+        searcher.set_limits(SearchLimits::depth(depth));
+        let result = searcher.search(game);
+        let analysis = CachedAnalysis {
+            depth: result.depth,
+            score: result.score,
+            pv: result.pv,
+        };
+        self.insert(hash, analysis.clone());
+        analysis
+    }
+}
+
+impl Default for AnalysisCache {
+    /// Returns a cache of 1024 results
+    fn default() -> Self {
+        AnalysisCache::new(1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let analysis = |depth| CachedAnalysis {
+            depth,
+            score: 0,
+            pv: Vec::new(),
+        };
+        let mut cache = AnalysisCache::new(2);
+        cache.insert(1, analysis(1));
+        cache.insert(2, analysis(2));
+        assert!(cache.get(1).is_some());
+        cache.insert(3, analysis(3));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(2).is_none());
+        assert_eq!(cache.get(1), Some(&analysis(1)));
+
+        // replacing a result does not evict another one
+        cache.insert(3, analysis(4));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(3), Some(&analysis(4)));
+
+        // a deeper request searches again and replaces the shallow result
+        let game = Game::startpos();
+        let mut searcher = Searcher::new(SearchLimits::default());
+        let shallow = cache.analyze(&game, 1, &mut searcher);
+        assert_eq!(shallow.depth, 1);
+        assert_eq!(cache.analyze(&game, 2, &mut searcher).depth, 2);
+        assert_eq!(cache.get(game.get_hash()).unwrap().depth, 2);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::core::Square;
+use crate::evaluation::see;
+use crate::game_representation::{Game, PieceType};
+use crate::move_generation::{movegen, Action};
+use crate::search::{SearchLimits, Searcher};
+use std::fmt;
+
+/// Why an action was picked as a candidate move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateReason {
+    /// The best move of the search, with its score in centipawns for the side to move
+    EngineChoice(i32),
+    Checkmate,
+    Check,
+    /// Captures the piece, winning the given material by static exchange evaluation
+    Capture(PieceType, i32),
+    /// Attacks an opposing piece on the field that is undefended or worth more than the attacker
+    Threat(PieceType, Square),
+}
+
+impl fmt::Display for CandidateReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CandidateReason::EngineChoice(score) => {
+                write!(f, "engine choice ({:+.2})", f64::from(*score) / 100.0)
+            }
+            CandidateReason::Checkmate => f.write_str("checkmate"),
+            CandidateReason::Check => f.write_str("gives check"),
+            CandidateReason::Capture(piece, gain) if *gain > 0 => {
+                write!(f, "captures the {} winning {}", piece_name(*piece), gain)
+            }
+            CandidateReason::Capture(piece, _) => write!(f, "captures the {}", piece_name(*piece)),
+            CandidateReason::Threat(piece, square) => {
+                write!(f, "threatens the {} on {}", piece_name(*piece), square)
+            }
+        }
+    }
+}
+
+/// An action worth considering together with the reasons it was picked
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CandidateMove {
+    pub action: Action,
+    /// The action in Standard Algebraic Notation
+    pub san: String,
+    /// The reasons, ordered from the most to the least important
+    pub reasons: Vec<CandidateReason>,
+    /// A single line combining the notation and all reasons, e.g. `Nxe5: captures the pawn`
+    pub justification: String,
+}
+
+/// Options for [`candidate_moves`]
+///
+/// [`candidate_moves`]: fn.candidate_moves.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CandidateOptions {
+    /// The maximum number of candidates returned
+    pub max_candidates: usize,
+    /// The depth of the search picking the engine choice, 0 disables the search
+    pub search_depth: u32,
+}
+
+impl Default for CandidateOptions {
+    fn default() -> Self {
+        CandidateOptions {
+            max_candidates: 5,
+            search_depth: 4,
+        }
+    }
+}
+
+/// Returns the moves a human would look at first, each with a short justification
+///
+/// Candidates are checks, captures, threats and the best move of a shallow search. Like a
+/// human player, the selection skips moves that give away material by static exchange
+/// evaluation, unless they mate or are the choice of the engine. The candidates are ordered
+/// by the engine choice, checkmates, captures by the material they win, checks and threats.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::analysis::{candidate_moves, CandidateOptions, CandidateReason};
+/// # use rust_chess::game_representation::Game;
+/// let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1").unwrap();
+/// let options = CandidateOptions { search_depth: 0, ..CandidateOptions::default() };
+/// let candidates = candidate_moves(&game, &options);
+/// assert_eq!(candidates[0].justification, "Rd8#: checkmate");
+/// assert_eq!(candidates[0].reasons, vec![CandidateReason::Checkmate]);
+/// ```
+pub fn candidate_moves(game: &Game, options: &CandidateOptions) -> Vec<CandidateMove> {
+    let engine_choice = if options.search_depth > 0 {
+        let result = Searcher::new(SearchLimits::depth(options.search_depth)).search(game);
+        result.best_move.map(|action| (action, result.score))
+    } else {
+        None
+    };
+
+    let mut candidates = Vec::new();
+    let mut state = game.clone();
+    for action in movegen::legal_moves(game) {
+        let mut reasons = Vec::new();
+        let engine_move = engine_choice.filter(|(choice, _)| *choice == action);
+        if let Some((_, score)) = engine_move {
+            reasons.push(CandidateReason::EngineChoice(score));
+        }
+        let exchange = see(game, &action);
+
+        let token = state.execute_action(&action);
+        let checkmate = state.is_checkmate();
+        if checkmate {
+            reasons.push(CandidateReason::Checkmate);
+        } else if state.is_in_check() {
+            reasons.push(CandidateReason::Check);
+        }
+        if let Some(captured) = action.get_capture_piece() {
+            reasons.push(CandidateReason::Capture(captured, exchange));
+        }
+        if let Some((piece, square)) = biggest_threat(&state, &action) {
+            reasons.push(CandidateReason::Threat(piece, square));
+        }
+        state.undo_action(&action, token);
+
+        let loses_material = exchange < 0;
+        if reasons.is_empty() || (loses_material && engine_move.is_none() && !checkmate) {
+            continue;
+        }
+        let san = action.to_san(game);
+        let descriptions: Vec<String> = reasons.iter().map(|r| r.to_string()).collect();
+        candidates.push(CandidateMove {
+            action,
+            justification: format!("{}: {}", san, descriptions.join(", ")),
+            san,
+            reasons,
+        });
+    }
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(priority(&candidate.reasons)));
+    candidates.truncate(options.max_candidates);
+    candidates
+}
+
+/// Returns the most valuable opposing piece the moved piece attacks after the action
+///
+/// Only pieces that are undefended or worth more than the attacker count as threatened, kings
+/// are left to the check detection.
+fn biggest_threat(after: &Game, action: &Action) -> Option<(PieceType, Square)> {
+    let mover = after.color_to_move.get_opponent_color();
+    let to = Square::from_index(action.get_to_index()).expect("index is on the board");
+    let attacker_value = after
+        .board
+        .piece_at(to)
+        .map_or(0, |(piece, _)| piece.get_value());
+    after
+        .board
+        .occupancy_of(after.color_to_move)
+        .filter_map(|square| {
+            let piece = after.board.piece_at(square)?.0;
+            let attacked = after.board.attackers_to(square, mover).contains(to);
+            let defended = after.board.is_attacked_by(square, after.color_to_move);
+            let threatened = piece != PieceType::King
+                && attacked
+                && (!defended || piece.get_value() > attacker_value);
+            if threatened {
+                Some((piece, square))
+            } else {
+                None
+            }
+        })
+        .max_by_key(|(piece, _)| piece.get_value())
+}
+
+/// Returns the sort key of a candidate, higher keys are listed first
+fn priority(reasons: &[CandidateReason]) -> i32 {
+    reasons
+        .iter()
+        .map(|reason| match reason {
+            CandidateReason::EngineChoice(_) => 100_000,
+            CandidateReason::Checkmate => 50_000,
+            CandidateReason::Capture(_, gain) => 10_000 + gain,
+            CandidateReason::Check => 5_000,
+            CandidateReason::Threat(piece, _) => 1_000 + piece.get_value() / 10,
+        })
+        .sum()
+}
+
+fn piece_name(piece: PieceType) -> &'static str {
+    match piece {
+        PieceType::Pawn => "pawn",
+        PieceType::Knight => "knight",
+        PieceType::Bishop => "bishop",
+        PieceType::Rook => "rook",
+        PieceType::Queen => "queen",
+        PieceType::King => "king",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hanging_moves_are_skipped() {
+        // Nxe5 loses the knight for a pawn, Bb5 attacks the rook on a6 without support
+        let game = Game::from_fen("4k3/8/r7/4p3/8/3N4/8/3BK3 w - - 0 1").unwrap();
+        let options = CandidateOptions {
+            search_depth: 0,
+            ..CandidateOptions::default()
+        };
+        let candidates = candidate_moves(&game, &options);
+        let sans: Vec<&str> = candidates.iter().map(|c| c.san.as_str()).collect();
+        assert_eq!(sans[0], "Nxe5");
+        assert!(sans.contains(&"Nb4"));
+        assert_eq!(
+            candidates[0].justification,
+            "Nxe5: captures the pawn winning 100"
+        );
+        assert!(candidates.len() <= options.max_candidates);
+    }
+
+    #[test]
+    fn engine_choice_comes_first() {
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1").unwrap();
+        let candidates = candidate_moves(&game, &CandidateOptions::default());
+        assert_eq!(candidates[0].san, "Rd8#");
+        assert!(matches!(
+            candidates[0].reasons[..],
+            [CandidateReason::EngineChoice(_), CandidateReason::Checkmate]
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::core::Square;
+use crate::game_representation::{Color, Game};
+
+/// The number of pieces of each side attacking a single field
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SquareControl {
+    pub white: u8,
+    pub black: u8,
+}
+
+impl SquareControl {
+    /// Returns the side with more attackers on the field, `None` if both have the same number
+    pub fn controller(&self) -> Option<Color> {
+        match self.white.cmp(&self.black) {
+            std::cmp::Ordering::Greater => Some(Color::White),
+            std::cmp::Ordering::Less => Some(Color::Black),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Returns the number of white attackers minus the number of black attackers
+    pub fn balance(&self) -> i8 {
+        self.white as i8 - self.black as i8
+    }
+}
+
+/// Returns which side controls every field of the board and by how many attackers
+///
+/// The result is indexed by [`Square::index`], starting with a8. Attackers are counted as in
+/// [`Board::attackers_to`]: pieces behind other pieces on the same line are not counted and
+/// pinned pieces still attack. Occupied fields are controlled like empty ones, the piece on a
+/// field does not attack it.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::analysis::control_map;
+/// # use rust_chess::core::Square;
+/// # use rust_chess::game_representation::{Color, Game};
+/// let map = control_map(&Game::startpos());
+/// let f3: Square = "f3".parse().unwrap();
+/// assert_eq!((map[f3.index() as usize].white, map[f3.index() as usize].black), (3, 0));
+/// let e4: Square = "e4".parse().unwrap();
+/// assert_eq!(map[e4.index() as usize].controller(), None);
+/// let white_fields = map.iter().filter(|c| c.controller() == Some(Color::White)).count();
+/// assert_eq!(white_fields, 22);
+/// ```
+///
+/// [`Square::index`]: ../core/struct.Square.html#method.index
+/// [`Board::attackers_to`]: ../game_representation/struct.Board.html#method.attackers_to
+pub fn control_map(game: &Game) -> [SquareControl; 64] {
+    let mut map = [SquareControl::default(); 64];
+    for square in Square::all() {
+        map[square.index() as usize] = SquareControl {
+            white: game.board.attackers_to(square, Color::White).count() as u8,
+            black: game.board.attackers_to(square, Color::Black).count() as u8,
+        };
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contested_fields() {
+        let game = Game::from_fen("4k3/8/8/3p4/4P3/2N5/8/3QK3 w - - 0 1").unwrap();
+        let map = control_map(&game);
+        let control = |field: &str| map[field.parse::<Square>().unwrap().index() as usize];
+        // the queen attacks along the file up to the black pawn
+        assert_eq!(control("d5"), SquareControl { white: 3, black: 0 });
+        assert_eq!(control("d4"), SquareControl { white: 1, black: 0 });
+        assert_eq!(control("e4"), SquareControl { white: 1, black: 1 });
+        assert_eq!(control("e4").controller(), None);
+        assert_eq!(control("d7").controller(), Some(Color::Black));
+        assert_eq!(control("e2"), SquareControl { white: 3, black: 0 });
+        assert_eq!(control("e2").balance(), 3);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::game_representation::Game;
+use crate::move_generation::{movegen, Action};
+use crate::search::{SearchLimits, Searcher};
+
+/// Options for [`forced_line`]
+///
+/// [`forced_line`]: fn.forced_line.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForcedLineOptions {
+    /// The maximum length of the line in plies
+    pub max_plies: usize,
+    /// The search depth of the positions after every action
+    pub depth: u32,
+    /// An action loses if it scores this many centipawns worse than the best action
+    pub margin: i32,
+}
+
+impl Default for ForcedLineOptions {
+    fn default() -> Self {
+        ForcedLineOptions {
+            max_plies: 8,
+            depth: 3,
+            margin: 200,
+        }
+    }
+}
+
+/// Returns the line in which the side to move has a single non-losing action at every turn
+///
+/// All actions of the side to move are scored by a search. If exactly one of them is within
+/// the margin of the best one, it is played, followed by the best reply of the opponent, and
+/// the line continues. The line ends with the last forced action, so it is empty if the side
+/// to move has several good actions, and it always has an odd length otherwise.
+///
+/// Puzzle generators use this to make sure an exercise has a unique solution of the wanted
+/// length.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::analysis::{forced_line, ForcedLineOptions};
+/// # use rust_chess::game_representation::Game;
+/// // only Rd8 mates, every other move lets black escape
+/// let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1").unwrap();
+/// let line = forced_line(&game, &ForcedLineOptions::default());
+/// let sans: Vec<String> = line.iter().map(|a| a.to_san(&game)).collect();
+/// assert_eq!(sans, vec!["Rd8#"]);
+/// assert!(forced_line(&Game::startpos(), &ForcedLineOptions::default()).is_empty());
+/// ```
+pub fn forced_line(game: &Game, options: &ForcedLineOptions) -> Vec<Action> {
+    let mut searcher = Searcher::new(SearchLimits::depth(options.depth.max(1)));
+    let mut state = game.clone();
+    let mut line = Vec::new();
+    let mut forced_length = 0;
+    while line.len() < options.max_plies {
+        let forced = match only_move(&mut searcher, &mut state, options) {
+            Some(action) => action,
+            None => break,
+        };
+        state.execute_action(&forced);
+        line.push(forced);
+        forced_length = line.len();
+        if line.len() == options.max_plies {
+            break;
+        }
+        match searcher.search(&state).best_move {
+            Some(reply) => {
+                state.execute_action(&reply);
+                line.push(reply);
+            }
+            None => break,
+        }
+    }
+    line.truncate(forced_length);
+    line
+}
+
+/// Returns the single action not scoring worse than the best one by more than the margin
+fn only_move(
+    searcher: &mut Searcher,
+    state: &mut Game,
+    options: &ForcedLineOptions,
+) -> Option<Action> {
+    let mut scores = Vec::new();
+    for action in movegen::legal_moves(state) {
+        let token = state.execute_action(&action);
+        let score = -searcher.search(state).score;
+        state.undo_action(&action, token);
+        scores.push((action, score));
+    }
+    let best = scores.iter().map(|(_, score)| *score).max()?;
+    let mut good = scores
+        .into_iter()
+        .filter(|(_, score)| *score >= best - options.margin);
+    match (good.next(), good.next()) {
+        (Some((action, _)), None) => Some(action),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_moves_of_a_longer_line() {
+        let game =
+            Game::from_fen("r1b2k1r/ppp1bppp/8/1B1Q4/5q2/2P5/PPP2PPP/R3R1K1 w - - 1 0").unwrap();
+        let options = ForcedLineOptions {
+            depth: 2,
+            ..ForcedLineOptions::default()
+        };
+        let line = forced_line(&game, &options);
+        let mut state = game.clone();
+        let mut sans = Vec::new();
+        for action in &line {
+            sans.push(action.to_san(&state));
+            state.execute_action(action);
+        }
+        assert_eq!(sans, vec!["Qd8+", "Bxd8", "Re8#"]);
+
+        let short = ForcedLineOptions {
+            max_plies: 2,
+            ..options
+        };
+        assert_eq!(forced_line(&game, &short), line[..1].to_vec());
+    }
+}
@@ -0,0 +1,20 @@
+//! Tools for analysing positions and games annotated by an engine or a human
+
+mod blunders;
+mod cache;
+mod candidates;
+mod control;
+mod forced;
+mod move_match;
+mod puzzle;
+
+pub use blunders::{extract_blunders, AnnotatedGame, BlunderSpot, PositionAnnotation};
+pub use cache::{AnalysisCache, CachedAnalysis};
+pub use candidates::{candidate_moves, CandidateMove, CandidateOptions, CandidateReason};
+pub use control::{control_map, SquareControl};
+pub use forced::{forced_line, ForcedLineOptions};
+pub use move_match::{
+    move_match_report, DepthAnalysis, DepthMatch, MoveMatchReport, PhaseLoss, LOSS_BUCKETS,
+    MAX_LOSS,
+};
+pub use puzzle::{verify_puzzle, PuzzleOptions, PuzzleRejection};
@@ -0,0 +1,278 @@
+use super::PositionAnnotation;
+use crate::game_representation::Color;
+use crate::record::{GamePhase, RecordedGame};
+
+/// Losses above this many centipawns are capped, so lost mates do not dominate the averages
+pub const MAX_LOSS: i32 = 1000;
+/// Upper bounds (exclusive) of the centipawn loss buckets of [`PhaseLoss::histogram`]
+///
+/// The last bucket holds all losses of at least the last bound.
+///
+/// [`PhaseLoss::histogram`]: struct.PhaseLoss.html#method.histogram
+pub const LOSS_BUCKETS: [i32; 5] = [10, 25, 50, 100, 300];
+
+/// Engine analysis of every position of a game at a single search depth
+///
+/// Just like for an [`AnnotatedGame`], `annotations[i]` belongs to the position after `i`
+/// halfmoves.
+///
+/// [`AnnotatedGame`]: struct.AnnotatedGame.html
+#[derive(Clone, Debug)]
+pub struct DepthAnalysis {
+    pub depth: u32,
+    pub annotations: Vec<PositionAnnotation>,
+}
+
+/// How often the played moves matched the best move of the engine at one depth
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepthMatch {
+    pub depth: u32,
+    /// Moves equal to the best move of the engine
+    pub matched: usize,
+    /// Moves played in positions with a known best move
+    pub considered: usize,
+}
+
+impl DepthMatch {
+    /// Returns the share of matched moves in percent, 0 if no move was considered
+    pub fn percentage(&self) -> f64 {
+        if self.considered == 0 {
+            0.0
+        } else {
+            self.matched as f64 * 100.0 / self.considered as f64
+        }
+    }
+}
+
+/// The centipawn losses of all moves played during one phase of the game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseLoss {
+    pub phase: GamePhase,
+    /// The loss of every move in the order played, between 0 and [`MAX_LOSS`]
+    ///
+    /// [`MAX_LOSS`]: constant.MAX_LOSS.html
+    pub losses: Vec<i32>,
+}
+
+impl PhaseLoss {
+    /// Returns the average centipawn loss, 0 if no move was played in this phase
+    pub fn average(&self) -> f64 {
+        if self.losses.is_empty() {
+            0.0
+        } else {
+            self.losses.iter().sum::<i32>() as f64 / self.losses.len() as f64
+        }
+    }
+
+    /// Returns the number of moves per loss bucket, see [`LOSS_BUCKETS`]
+    ///
+    /// [`LOSS_BUCKETS`]: constant.LOSS_BUCKETS.html
+    pub fn histogram(&self) -> [usize; LOSS_BUCKETS.len() + 1] {
+        let mut histogram = [0; LOSS_BUCKETS.len() + 1];
+        for loss in &self.losses {
+            let bucket = LOSS_BUCKETS
+                .iter()
+                .position(|bound| loss < bound)
+                .unwrap_or(LOSS_BUCKETS.len());
+            histogram[bucket] += 1;
+        }
+        histogram
+    }
+}
+
+/// Move-match statistics of one player in one game
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveMatchReport {
+    pub color: Color,
+    /// Match statistics for every analysed depth, in increasing order of depth
+    pub depth_matches: Vec<DepthMatch>,
+    /// Loss distributions for every phase the player moved in, in the order of the game
+    pub phase_losses: Vec<PhaseLoss>,
+}
+
+/// Computes move-match percentages and centipawn loss distributions for one player
+///
+/// Every analysis produces the match statistics for its depth, analyses without exactly one
+/// annotation per position are skipped. The centipawn losses are computed from the evaluations
+/// of the deepest valid analysis and split up by [`RecordedGame::phases`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::analysis::{move_match_report, DepthAnalysis, PositionAnnotation};
+/// # use rust_chess::game_representation::{Color, Game};
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::record::RecordedGame;
+/// let mut game = Game::startpos();
+/// let mut record = RecordedGame::new();
+/// let e4 = Action::from_san("e4", &game).unwrap();
+/// game.execute_action(&e4);
+/// record.push(e4);
+/// let analysis = DepthAnalysis {
+///     depth: 20,
+///     annotations: vec![
+///         PositionAnnotation { eval: 30, best_move: Some(e4) },
+///         PositionAnnotation { eval: 30, best_move: None },
+///     ],
+/// };
+/// let report = move_match_report(&record, &[analysis], Color::White);
+/// assert_eq!(report.depth_matches[0].percentage(), 100.0);
+/// assert_eq!(report.phase_losses[0].average(), 0.0);
+/// ```
+///
+/// [`RecordedGame::phases`]: ../record/struct.RecordedGame.html#method.phases
+pub fn move_match_report(
+    game: &RecordedGame,
+    analyses: &[DepthAnalysis],
+    color: Color,
+) -> MoveMatchReport {
+    let first_mover = game.position_at(0).color_to_move;
+    let moved_by_color = |ply: usize| (ply % 2 == 0) == (first_mover == color);
+
+    let mut valid: Vec<&DepthAnalysis> = analyses
+        .iter()
+        .filter(|analysis| analysis.annotations.len() == game.len() + 1)
+        .collect();
+    valid.sort_by_key(|analysis| analysis.depth);
+
+    let depth_matches = valid
+        .iter()
+        .map(|analysis| {
+            let mut matched = 0;
+            let mut considered = 0;
+            for (ply, action) in game.actions().iter().enumerate() {
+                if !moved_by_color(ply) {
+                    continue;
+                }
+                if let Some(best) = analysis.annotations[ply].best_move {
+                    considered += 1;
+                    if best == *action {
+                        matched += 1;
+                    }
+                }
+            }
+            DepthMatch {
+                depth: analysis.depth,
+                matched,
+                considered,
+            }
+        })
+        .collect();
+
+    let mut phase_losses = Vec::new();
+    if let Some(deepest) = valid.last() {
+        for (phase, range) in game.phases() {
+            let losses: Vec<i32> = range
+                .filter(|ply| moved_by_color(*ply))
+                .map(|ply| {
+                    let before = deepest.annotations[ply].eval;
+                    let after = deepest.annotations[ply + 1].eval;
+                    let loss = match color {
+                        Color::White => before - after,
+                        Color::Black => after - before,
+                    };
+                    loss.clamp(0, MAX_LOSS)
+                })
+                .collect();
+            if !losses.is_empty() {
+                phase_losses.push(PhaseLoss { phase, losses });
+            }
+        }
+    }
+
+    MoveMatchReport {
+        color,
+        depth_matches,
+        phase_losses,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_representation::Game;
+    use crate::move_generation::Action;
+
+    fn record(moves: &[&str]) -> (RecordedGame, Vec<Action>) {
+        let mut record = RecordedGame::new();
+        let mut game = Game::startpos();
+        let mut actions = Vec::new();
+        for san in moves {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+            record.push(action);
+            actions.push(action);
+        }
+        (record, actions)
+    }
+
+    fn analysis(depth: u32, evals: &[i32], best: &[Option<Action>]) -> DepthAnalysis {
+        DepthAnalysis {
+            depth,
+            annotations: evals
+                .iter()
+                .zip(best.iter())
+                .map(|(eval, best_move)| PositionAnnotation {
+                    eval: *eval,
+                    best_move: *best_move,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn matches_per_depth() {
+        let (game, moves) = record(&["e4", "e5", "Nf3", "Nc6"]);
+        let shallow = analysis(
+            8,
+            &[20, 30, 30, 40, 40],
+            &[Some(moves[0]), Some(moves[1]), Some(moves[3]), None, None],
+        );
+        let deep = analysis(
+            24,
+            &[20, 30, 30, 40, 40],
+            &[Some(moves[0]), None, Some(moves[2]), Some(moves[3]), None],
+        );
+        let broken = analysis(30, &[0], &[None]);
+        let report = move_match_report(&game, &[deep, broken, shallow], Color::White);
+        assert_eq!(
+            report.depth_matches,
+            vec![
+                DepthMatch {
+                    depth: 8,
+                    matched: 1,
+                    considered: 2
+                },
+                DepthMatch {
+                    depth: 24,
+                    matched: 2,
+                    considered: 2
+                },
+            ]
+        );
+        assert_eq!(report.depth_matches[0].percentage(), 50.0);
+    }
+
+    #[test]
+    fn losses_from_the_point_of_view_of_the_mover() {
+        let (game, _) = record(&["f3", "e5", "g4", "Qh4"]);
+        let none = [None; 5];
+        let report = move_match_report(
+            &game,
+            &[analysis(20, &[20, -40, -30, -20000, -20000], &none)],
+            Color::White,
+        );
+        assert_eq!(report.phase_losses.len(), 1);
+        assert_eq!(report.phase_losses[0].phase, GamePhase::Opening);
+        assert_eq!(report.phase_losses[0].losses, vec![60, MAX_LOSS]);
+        assert_eq!(report.phase_losses[0].histogram(), [0, 0, 0, 1, 0, 1]);
+
+        // e5 lost 10 centipawns for black, the mate did not lose anything
+        let report = move_match_report(
+            &game,
+            &[analysis(20, &[20, -40, -30, -20000, -20000], &none)],
+            Color::Black,
+        );
+        assert_eq!(report.phase_losses[0].losses, vec![10, 0]);
+        assert_eq!(report.phase_losses[0].average(), 5.0);
+    }
+}
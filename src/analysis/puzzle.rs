@@ -0,0 +1,150 @@
+use crate::game_representation::Game;
+use crate::move_generation::Action;
+use crate::search::{SearchLimits, Searcher};
+use std::fmt;
+
+/// Options for [`verify_puzzle`]
+///
+/// [`verify_puzzle`]: fn.verify_puzzle.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PuzzleOptions {
+    /// The search depth of the verification
+    pub depth: u32,
+    /// The score in centipawns the solution has to reach for the side to move
+    pub min_advantage: i32,
+    /// A second action is a dual if it scores at most this many centipawns worse than the
+    /// solution
+    pub margin: i32,
+}
+
+impl Default for PuzzleOptions {
+    fn default() -> Self {
+        PuzzleOptions {
+            depth: 4,
+            min_advantage: 200,
+            margin: 150,
+        }
+    }
+}
+
+/// Why a position was rejected as a puzzle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PuzzleRejection {
+    /// The side to move has no legal action
+    NoLegalMoves,
+    /// The best action does not reach the required advantage, with its score
+    NotWinning(i32),
+    /// A second action scores within the margin of the best one
+    Dual {
+        best: Action,
+        alternative: Action,
+        /// How many centipawns the alternative scores worse than the best action
+        difference: i32,
+    },
+}
+
+impl fmt::Display for PuzzleRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleRejection::NoLegalMoves => f.write_str("no legal moves"),
+            PuzzleRejection::NotWinning(score) => {
+                write!(f, "best move only scores {:+.2}", f64::from(*score) / 100.0)
+            }
+            PuzzleRejection::Dual {
+                best,
+                alternative,
+                difference,
+            } => write!(
+                f,
+                "{} is a dual of {}, {} centipawns worse",
+                alternative.to_uci(),
+                best.to_uci(),
+                difference
+            ),
+        }
+    }
+}
+
+/// Returns the solution of the puzzle if it has a unique winning first move
+///
+/// The two best actions are found by a MultiPV search. The best action has to reach the
+/// required advantage and the second one has to score worse by more than the margin, so a
+/// solver can not succeed with another move. Two different mates are duals as well, as mate
+/// scores only differ by the length of the mate.
+///
+/// # Errors
+/// * [`PuzzleRejection`] describing why the position is not a puzzle
+///
+/// # Examples
+/// ```
+/// # use rust_chess::analysis::{verify_puzzle, PuzzleOptions, PuzzleRejection};
+/// # use rust_chess::game_representation::Game;
+/// let options = PuzzleOptions { depth: 2, ..PuzzleOptions::default() };
+/// let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1").unwrap();
+/// assert_eq!(verify_puzzle(&game, &options).unwrap().to_san(&game), "Rd8#");
+///
+/// // both rooks mate on the back rank
+/// let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/R2R2K1 w - - 0 1").unwrap();
+/// assert!(matches!(verify_puzzle(&game, &options), Err(PuzzleRejection::Dual { .. })));
+/// ```
+///
+/// [`PuzzleRejection`]: enum.PuzzleRejection.html
+pub fn verify_puzzle(game: &Game, options: &PuzzleOptions) -> Result<Action, PuzzleRejection> {
+    let mut searcher = Searcher::new(SearchLimits::depth(options.depth.max(1)));
+    let lines = searcher.search_multi_pv(game, 2);
+    let (best, score) = match lines.first() {
+        Some(line) => (
+            line.best_move.expect("lines have a first action"),
+            line.score,
+        ),
+        None => return Err(PuzzleRejection::NoLegalMoves),
+    };
+    if score < options.min_advantage {
+        return Err(PuzzleRejection::NotWinning(score));
+    }
+    match lines.get(1) {
+        Some(second) if score - second.score <= options.margin => Err(PuzzleRejection::Dual {
+            best,
+            alternative: second.best_move.expect("lines have a first action"),
+            difference: score - second.score,
+        }),
+        _ => Ok(best),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_solutions() {
+        let options = PuzzleOptions {
+            depth: 3,
+            ..PuzzleOptions::default()
+        };
+        // only the queen sacrifice mates in two
+        let game =
+            Game::from_fen("r1b2k1r/ppp1bppp/8/1B1Q4/5q2/2P5/PPP2PPP/R3R1K1 w - - 1 0").unwrap();
+        assert_eq!(
+            verify_puzzle(&game, &options).unwrap().to_san(&game),
+            "Qd8+"
+        );
+
+        assert!(matches!(
+            verify_puzzle(&Game::startpos(), &options),
+            Err(PuzzleRejection::NotWinning(_))
+        ));
+        let mated = Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+            .unwrap();
+        assert_eq!(
+            verify_puzzle(&mated, &options),
+            Err(PuzzleRejection::NoLegalMoves)
+        );
+
+        // the hanging queen can be taken by the pawn or the knight
+        let game = Game::from_fen("4k3/8/8/3q4/4P3/2N5/8/4K3 w - - 0 1").unwrap();
+        let rejection = verify_puzzle(&game, &options).unwrap_err();
+        assert!(matches!(rejection, PuzzleRejection::Dual { .. }));
+        assert!(rejection.to_string().contains("is a dual of"));
+    }
+}
@@ -0,0 +1,8 @@
+use rust_chess::uci::{self, SearchEngine};
+use std::io;
+
+fn main() -> io::Result<()> {
+    let stdin = io::stdin();
+    uci::run(SearchEngine::new(), stdin.lock(), io::stdout())?;
+    Ok(())
+}
@@ -3,8 +3,10 @@
 //! This module contains helper functions and constants that are imporatant
 //! for working with bitboards without going insane.
 
-use super::ParserError;
+use super::{File, ParserError, Rank, Square};
 use crate::game_representation::PieceType;
+use alloc::{string::String, string::ToString, vec::Vec};
+use core::{fmt, ops};
 
 pub mod constants {
     //! This module contains all constants for working with bitboards
@@ -99,6 +101,244 @@ pub mod constants {
         4679521487814656,
         9077567998918656,
     ];
+
+    /// Bitboard of all fields a king attacks from a given field, index is the shift index
+    pub const KING_MASKS: [u64; 64] = king_masks();
+
+    /// Bitboard of the fields a pawn attacks from a given field
+    ///
+    /// The first index is the color of the pawn with white = 0 and black = 1, the second index
+    /// is the shift index of the field.
+    pub const PAWN_ATTACKS: [[u64; 64]; 2] = [pawn_attacks(true), pawn_attacks(false)];
+
+    /// Bitboard of the field a pawn is pushed to from a given field, ignoring double pushes
+    ///
+    /// Indexed like [`PAWN_ATTACKS`]. Pawns on the last rank can not be pushed, their entry is
+    /// empty.
+    ///
+    /// [`PAWN_ATTACKS`]: constant.PAWN_ATTACKS.html
+    pub const PAWN_PUSHES: [[u64; 64]; 2] = [pawn_pushes(true), pawn_pushes(false)];
+
+    const fn king_masks() -> [u64; 64] {
+        let mut masks = [0; 64];
+        let mut index = 0;
+        while index < 64 {
+            let field = 1u64 << index;
+            let left_right =
+                field | super::bitboard_west_one(field) | super::bitboard_east_one(field);
+            masks[index] = (left_right
+                | super::bitboard_north(left_right, 1)
+                | super::bitboard_south(left_right, 1))
+                & !field;
+            index += 1;
+        }
+        masks
+    }
+
+    const fn pawn_pushes(white: bool) -> [u64; 64] {
+        let mut masks = [0; 64];
+        let mut index = 0;
+        while index < 64 {
+            let field = 1u64 << index;
+            masks[index] = if white {
+                super::bitboard_north(field, 1)
+            } else {
+                super::bitboard_south(field, 1)
+            };
+            index += 1;
+        }
+        masks
+    }
+
+    const fn pawn_attacks(white: bool) -> [u64; 64] {
+        let pushes = pawn_pushes(white);
+        let mut masks = [0; 64];
+        let mut index = 0;
+        while index < 64 {
+            masks[index] =
+                super::bitboard_west_one(pushes[index]) | super::bitboard_east_one(pushes[index]);
+            index += 1;
+        }
+        masks
+    }
+}
+
+/// A set of fields, stored as one bit per field
+///
+/// The bit of a field is its shift index, see [`Square`]. Iterating a bitboard yields the set
+/// fields in index order, from a8 to h1.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::core::bitboard::Bitboard;
+/// # use rust_chess::core::{File, Rank, Square};
+/// let e4: Square = "e4".parse().unwrap();
+/// let board = Bitboard::from_file(File::E) & !Bitboard::from_rank(Rank::Fourth);
+/// assert_eq!(board.count(), 7);
+/// assert!(!board.contains(e4));
+/// assert!((board | e4.into()).contains(e4));
+/// let squares: Vec<String> = Bitboard::from_rank(Rank::Eighth)
+///     .take(2)
+///     .map(|square| square.to_string())
+///     .collect();
+/// assert_eq!(squares, vec!["a8", "b8"]);
+/// ```
+///
+/// [`Square`]: ../struct.Square.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    /// The bitboard without any field set
+    pub const EMPTY: Bitboard = Bitboard(0);
+    /// The bitboard with every field set
+    pub const FULL: Bitboard = Bitboard(!0);
+
+    /// Returns a bitboard with only the given field set
+    #[inline(always)]
+    pub const fn from_square(square: Square) -> Bitboard {
+        Bitboard(square.bitboard())
+    }
+
+    /// Returns a bitboard with all fields of the file set
+    #[inline(always)]
+    pub const fn from_file(file: File) -> Bitboard {
+        Bitboard(constants::FILES[file as usize])
+    }
+
+    /// Returns a bitboard with all fields of the rank set
+    #[inline(always)]
+    pub const fn from_rank(rank: Rank) -> Bitboard {
+        Bitboard(constants::RANKS[rank as usize])
+    }
+
+    /// Returns true if the field is set
+    #[inline(always)]
+    pub const fn contains(self, square: Square) -> bool {
+        self.0 & square.bitboard() != 0
+    }
+
+    /// Returns the number of set fields
+    #[inline(always)]
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Returns true if no field is set
+    #[inline(always)]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the set field with the lowest index, `None` if no field is set
+    pub fn first(self) -> Option<Square> {
+        Square::from_index(self.0.trailing_zeros() as u8).ok()
+    }
+}
+
+impl Iterator for Bitboard {
+    type Item = Square;
+
+    /// Removes and returns the set field with the lowest index
+    fn next(&mut self) -> Option<Square> {
+        let square = self.first()?;
+        self.0 &= self.0 - 1;
+        Some(square)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.0.count_ones() as usize;
+        (count, Some(count))
+    }
+}
+
+impl ExactSizeIterator for Bitboard {}
+
+/// Prints the bitboard as an 8x8 grid with the eighth rank on top, set fields as `x`
+impl fmt::Display for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..8 {
+            let fields: Vec<&str> = (0..8)
+                .map(|file| match self.0 >> (row * 8 + file) & 1 {
+                    1 => "x",
+                    _ => ".",
+                })
+                .collect();
+            writeln!(f, "{}", fields.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<u64> for Bitboard {
+    fn from(bits: u64) -> Bitboard {
+        Bitboard(bits)
+    }
+}
+
+impl From<Bitboard> for u64 {
+    fn from(board: Bitboard) -> u64 {
+        board.0
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Bitboard {
+        Bitboard::from_square(square)
+    }
+}
+
+macro_rules! bitboard_operator {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl ops::$trait for Bitboard {
+            type Output = Bitboard;
+
+            #[inline(always)]
+            fn $method(self, other: Bitboard) -> Bitboard {
+                Bitboard(self.0 $op other.0)
+            }
+        }
+
+        impl ops::$assign_trait for Bitboard {
+            #[inline(always)]
+            fn $assign_method(&mut self, other: Bitboard) {
+                self.0 = self.0 $op other.0;
+            }
+        }
+    };
+}
+
+bitboard_operator!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bitboard_operator!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bitboard_operator!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl ops::Not for Bitboard {
+    type Output = Bitboard;
+
+    #[inline(always)]
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+/// Shifts towards higher indices, a shift by 8 moves every field one rank south
+impl ops::Shl<u8> for Bitboard {
+    type Output = Bitboard;
+
+    #[inline(always)]
+    fn shl(self, amount: u8) -> Bitboard {
+        Bitboard(self.0 << amount)
+    }
+}
+
+/// Shifts towards lower indices, a shift by 8 moves every field one rank north
+impl ops::Shr<u8> for Bitboard {
+    type Output = Bitboard;
+
+    #[inline(always)]
+    fn shr(self, amount: u8) -> Bitboard {
+        Bitboard(self.0 >> amount)
+    }
 }
 
 /// Returns a bitboard from a simple fen-like representation
@@ -117,22 +357,22 @@ pub mod constants {
 ///
 /// # Examples
 /// ```
-/// use core::core::bitboard;
+/// use rust_chess::core::bitboard;
 ///
 /// // creates bitboard with 2 set bits in the second to top rank
 /// assert_eq!(bitboard::from_repr("8/0303/8/8/8/8/8/8").unwrap(), 4352);
 /// ```
-pub fn from_repr(repr: &str) -> Result<u64, &str> {
+pub fn from_repr(repr: &str) -> Result<u64, ParserError> {
     let ranks: Vec<&str> = repr.split('/').collect();
     if ranks.len() != 8 {
-        return Err("Incorrect number of ranks");
+        return Err(ParserError::InvalidParameter("Incorrect number of ranks"));
     }
     let mut ret = 0u64;
     for (rank_idx, rank) in ranks.iter().enumerate() {
         let mut file = 0;
         for c in rank.chars() {
             if file > 7 {
-                return Err("Rank is overfull in repr");
+                return Err(ParserError::InvalidParameter("Rank is overfull in repr"));
             }
             match c {
                 '0' => {
@@ -165,7 +405,9 @@ pub fn from_repr(repr: &str) -> Result<u64, &str> {
                     file += 8;
                 }
                 _ => {
-                    return Err("incorrect character in representation string");
+                    return Err(ParserError::InvalidParameter(
+                        "Incorrect character in representation string",
+                    ));
                 }
             }
         }
@@ -381,8 +623,8 @@ pub fn rank_to_str(rank: u8) -> Result<&'static str, ParserError> {
 /// * if the input is not one of KNBQR
 /// # Examples
 /// ```
-/// # use core::core::bitboard;
-/// # use core::game_representation::PieceType;
+/// # use rust_chess::core::bitboard;
+/// # use rust_chess::game_representation::PieceType;
 /// assert_eq!(bitboard::char_to_piecetype('Q').unwrap(), PieceType::Queen);
 /// ```
 pub fn char_to_piecetype(c: char) -> Result<PieceType, ParserError> {
@@ -399,8 +641,8 @@ pub fn char_to_piecetype(c: char) -> Result<PieceType, ParserError> {
 /// Returns the san char for a given Piecetype
 /// # Examples
 /// ```
-/// # use core::core::bitboard;
-/// # use core::game_representation::PieceType;
+/// # use rust_chess::core::bitboard;
+/// # use rust_chess::game_representation::PieceType;
 /// assert_eq!(bitboard::piecetype_to_char(PieceType::Queen), 'Q');
 /// ```
 pub fn piecetype_to_char(piece: PieceType) -> char {
@@ -415,9 +657,30 @@ pub fn piecetype_to_char(piece: PieceType) -> char {
 }
 
 #[cfg(test)]
+#[allow(clippy::redundant_pattern_matching)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn king_and_pawn_tables() {
+        let field = |name: &str| 1u64 << field_repr_to_index(name).unwrap();
+        let index = |name: &str| field_repr_to_index(name).unwrap() as usize;
+        assert_eq!(
+            constants::KING_MASKS[index("a1")],
+            field("a2") | field("b2") | field("b1")
+        );
+        assert_eq!(constants::KING_MASKS[index("e4")].count_ones(), 8);
+        assert_eq!(
+            constants::PAWN_ATTACKS[0][index("e2")],
+            field("d3") | field("f3")
+        );
+        assert_eq!(constants::PAWN_ATTACKS[1][index("a7")], field("b6"));
+        assert_eq!(constants::PAWN_PUSHES[0][index("h2")], field("h3"));
+        assert_eq!(constants::PAWN_PUSHES[1][index("c5")], field("c4"));
+        assert_eq!(constants::PAWN_PUSHES[0][index("c8")], 0);
+        assert_eq!(constants::PAWN_ATTACKS[1][index("h1")], 0);
+    }
+
     #[test]
     fn bitboard_shifts() {
         let initial = 1 << field_repr_to_index("e2").unwrap();
@@ -626,4 +889,37 @@ mod tests {
         assert!(char_to_piecetype('g').is_err());
         assert!(char_to_piecetype('z').is_err());
     }
+
+    #[test]
+    fn bitboard_operations() {
+        let e4: Square = "e4".parse().unwrap();
+        let board = Bitboard::from(e4);
+        assert_eq!(board.0, 1 << 36);
+        assert_eq!((board >> 8).first().unwrap().to_string(), "e5");
+        assert_eq!((board << 8).first().unwrap().to_string(), "e3");
+        let mut pieces = Bitboard::EMPTY;
+        pieces |= board;
+        pieces ^= Bitboard::from_square("a1".parse().unwrap());
+        assert_eq!(pieces.count(), 2);
+        assert_eq!(pieces.len(), 2);
+        pieces &= !board;
+        assert_eq!(pieces.collect::<Vec<_>>(), vec!["a1".parse().unwrap()]);
+        assert!(Bitboard::EMPTY.is_empty());
+        assert_eq!(Bitboard::EMPTY.first(), None);
+        assert_eq!(Bitboard::FULL.count(), 64);
+        assert_eq!(
+            Bitboard::from_file(File::A) & Bitboard::from_rank(Rank::First),
+            Bitboard::from_square("a1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn bitboard_display() {
+        let board = Bitboard::from(from_repr("07/8/8/8/8/8/8/70").unwrap());
+        assert_eq!(
+            board.to_string(),
+            "x . . . . . . .\n. . . . . . . .\n. . . . . . . .\n. . . . . . . .\n\
+             . . . . . . . .\n. . . . . . . .\n. . . . . . . .\n. . . . . . . x\n"
+        );
+    }
 }
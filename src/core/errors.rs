@@ -1,9 +1,149 @@
+use alloc::{string::String, string::ToString};
+use core::fmt;
+
+/// The six space separated fields of a Forsyth-Edwards Notation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FenField {
+    Board,
+    Color,
+    Castling,
+    EnPassant,
+    HalfMoveClock,
+    FullMoveNumber,
+}
+
+impl fmt::Display for FenField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FenField::Board => "board",
+            FenField::Color => "color",
+            FenField::Castling => "castling",
+            FenField::EnPassant => "en passant",
+            FenField::HalfMoveClock => "half move clock",
+            FenField::FullMoveNumber => "full move number",
+        })
+    }
+}
+
 /// Common error for any parsing problems
 ///
 /// * WrongParameterNumber if anything has the wrong length
 /// * InvalidParameter if a parameter is not in the correct bounds
-#[derive(Debug)]
+/// * BadFenField if a field of a FEN can not be parsed
+/// * BadSanToken if a move in Standard Algebraic Notation is malformed
+/// * IllegalMove if a well formed move can not be played in its position
+/// * AmbiguousMove if a move matches more than one action of its position
+///
+/// Offsets are byte offsets into the parsed string and point at the first offending
+/// character.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::core::{FenField, ParserError};
+/// # use rust_chess::game_representation::Game;
+/// let error = Game::from_fen("8/8/8/8/8/8/8/K6k w KQx - 0 1").unwrap_err();
+/// assert_eq!(
+///     error,
+///     ParserError::BadFenField {
+///         field: FenField::Castling,
+///         token: String::from("KQx"),
+///         offset: 22,
+///         reason: "Castling information is wrong",
+///     }
+/// );
+/// assert_eq!(
+///     error.to_string(),
+///     "Invalid castling field 'KQx' at byte 22: Castling information is wrong"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ParserError {
     WrongParameterNumber,
     InvalidParameter(&'static str),
+    BadFenField {
+        field: FenField,
+        token: String,
+        offset: usize,
+        reason: &'static str,
+    },
+    BadSanToken {
+        token: String,
+        offset: usize,
+        reason: &'static str,
+    },
+    IllegalMove {
+        token: String,
+        reason: &'static str,
+    },
+    AmbiguousMove {
+        token: String,
+    },
+}
+
+impl ParserError {
+    /// Attaches a FEN field to an error of a helper that does not know its input
+    pub(crate) fn in_fen_field(self, field: FenField, token: &str, offset: usize) -> ParserError {
+        match self.reason() {
+            Some(reason) => ParserError::BadFenField {
+                field,
+                token: token.to_string(),
+                offset,
+                reason,
+            },
+            None => self,
+        }
+    }
+
+    /// Attaches a SAN token to an error of a helper that does not know its input
+    pub(crate) fn in_san_token(self, token: &str, offset: usize) -> ParserError {
+        match self.reason() {
+            Some(reason) => ParserError::BadSanToken {
+                token: token.to_string(),
+                offset,
+                reason,
+            },
+            None => self,
+        }
+    }
+
+    /// Returns the description of the two unspecific variants
+    fn reason(&self) -> Option<&'static str> {
+        match self {
+            ParserError::WrongParameterNumber => Some("Wrong number of characters"),
+            ParserError::InvalidParameter(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParserError::WrongParameterNumber => f.write_str("Wrong number of parameters"),
+            ParserError::InvalidParameter(reason) => f.write_str(reason),
+            ParserError::BadFenField {
+                field,
+                token,
+                offset,
+                reason,
+            } => write!(
+                f,
+                "Invalid {} field '{}' at byte {}: {}",
+                field, token, offset, reason
+            ),
+            ParserError::BadSanToken {
+                token,
+                offset,
+                reason,
+            } => write!(f, "Invalid move '{}' at byte {}: {}", token, offset, reason),
+            ParserError::IllegalMove { token, reason } => {
+                write!(f, "Illegal move '{}': {}", token, reason)
+            }
+            ParserError::AmbiguousMove { token } => {
+                write!(f, "Ambiguous move '{}' matches several moves", token)
+            }
+        }
+    }
 }
+
+impl core::error::Error for ParserError {}
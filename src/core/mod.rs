@@ -2,5 +2,8 @@
 
 pub mod bitboard;
 mod errors;
+mod square;
+pub mod zobrist;
 
-pub use errors::ParserError;
+pub use errors::{FenField, ParserError};
+pub use square::{File, Rank, Square};
@@ -0,0 +1,346 @@
+use super::ParserError;
+#[cfg(feature = "serde")]
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A file of the chessboard, from the a-file to the h-file
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum File {
+    A = 0,
+    B = 1,
+    C = 2,
+    D = 3,
+    E = 4,
+    F = 5,
+    G = 6,
+    H = 7,
+}
+
+/// A rank of the chessboard, from the first rank of white to the eighth rank
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rank {
+    First = 0,
+    Second = 1,
+    Third = 2,
+    Fourth = 3,
+    Fifth = 4,
+    Sixth = 5,
+    Seventh = 6,
+    Eighth = 7,
+}
+
+impl File {
+    /// All files from a to h
+    pub const ALL: [File; 8] = [
+        File::A,
+        File::B,
+        File::C,
+        File::D,
+        File::E,
+        File::F,
+        File::G,
+        File::H,
+    ];
+
+    /// Returns the file with the given number, 0 is the a-file
+    pub fn from_index(index: u8) -> Option<File> {
+        File::ALL.get(index as usize).copied()
+    }
+
+    /// Returns the number of the file, 0 for the a-file
+    #[inline(always)]
+    pub const fn index(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the file of a character from 'a' to 'h'
+    ///
+    /// # Errors
+    /// * if the character is not in the range 'a'-'h'
+    pub fn from_char(c: char) -> Result<File, ParserError> {
+        match c {
+            'a'..='h' => Ok(File::ALL[c as usize - 'a' as usize]),
+            _ => Err(ParserError::InvalidParameter("File is out of bounds")),
+        }
+    }
+
+    /// Returns the character of the file, from 'a' to 'h'
+    pub fn to_char(self) -> char {
+        (b'a' + self as u8) as char
+    }
+}
+
+impl Rank {
+    /// All ranks from the first to the eighth
+    pub const ALL: [Rank; 8] = [
+        Rank::First,
+        Rank::Second,
+        Rank::Third,
+        Rank::Fourth,
+        Rank::Fifth,
+        Rank::Sixth,
+        Rank::Seventh,
+        Rank::Eighth,
+    ];
+
+    /// Returns the rank with the given number, 0 is the first rank
+    pub fn from_index(index: u8) -> Option<Rank> {
+        Rank::ALL.get(index as usize).copied()
+    }
+
+    /// Returns the number of the rank, 0 for the first rank
+    #[inline(always)]
+    pub const fn index(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the rank of a character from '1' to '8'
+    ///
+    /// # Errors
+    /// * if the character is not in the range '1'-'8'
+    pub fn from_char(c: char) -> Result<Rank, ParserError> {
+        match c {
+            '1'..='8' => Ok(Rank::ALL[c as usize - '1' as usize]),
+            _ => Err(ParserError::InvalidParameter("Rank is out of bounds")),
+        }
+    }
+
+    /// Returns the character of the rank, from '1' to '8'
+    pub fn to_char(self) -> char {
+        (b'1' + self as u8) as char
+    }
+}
+
+/// A single field of the chessboard
+///
+/// Internally this is the shift index used by the bitboards, so conversions to and from it
+/// are free. The index of every field is shown in the following map, the coordinates used by
+/// [`Action::new`] are `(index % 8, index / 8)`.
+/// ```text
+///      a  b  c  d  e  f  g  h
+///   +-------------------------+
+/// 8 |  0  1  2  3  4  5  6  7 | 8
+/// 7 |  8  9 10 11 12 13 14 15 | 7
+/// 6 | 16 17 18 19 20 21 22 23 | 6
+/// 5 | 24 25 26 27 28 29 30 31 | 5
+/// 4 | 32 33 34 35 36 37 38 39 | 4
+/// 3 | 40 41 42 43 44 45 46 47 | 3
+/// 2 | 48 49 50 51 52 53 54 55 | 2
+/// 1 | 56 57 58 59 60 61 62 63 | 1
+///   +-------------------------+
+///      a  b  c  d  e  f  g  h
+/// ```
+///
+/// # Examples
+/// ```
+/// # use rust_chess::core::{File, Rank, Square};
+/// let e4: Square = "e4".parse().unwrap();
+/// assert_eq!(e4, Square::new(File::E, Rank::Fourth));
+/// assert_eq!(e4.index(), 36);
+/// assert_eq!(e4.offset(1, 2).unwrap().to_string(), "f6");
+/// assert_eq!(e4.offset(4, 0), None);
+/// ```
+///
+/// [`Action::new`]: ../move_generation/struct.Action.html#method.new
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Square(u8);
+
+impl Square {
+    /// Returns the square on the given file and rank
+    #[inline(always)]
+    pub const fn new(file: File, rank: Rank) -> Square {
+        Square((7 - rank as u8) * 8 + file as u8)
+    }
+
+    /// Returns the square of the given shift index
+    ///
+    /// # Errors
+    /// * when the index is bigger than 63
+    pub fn from_index(index: u8) -> Result<Square, ParserError> {
+        if index > 63 {
+            return Err(ParserError::InvalidParameter("index too high"));
+        }
+        Ok(Square(index))
+    }
+
+    /// Returns the square of the given `(x, y)` coordinates, counted from a8
+    ///
+    /// # Errors
+    /// * when a coordinate is bigger than 7
+    pub fn from_coords(coords: (u8, u8)) -> Result<Square, ParserError> {
+        if coords.0 > 7 || coords.1 > 7 {
+            return Err(ParserError::InvalidParameter("coordinates too high"));
+        }
+        Ok(Square(coords.1 * 8 + coords.0))
+    }
+
+    /// Returns an iterator over all 64 squares, from a8 to h1 in index order
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..64).map(Square)
+    }
+
+    /// Returns the shift index of the square
+    #[inline(always)]
+    pub const fn index(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the `(x, y)` coordinates of the square, counted from a8
+    #[inline(always)]
+    pub const fn coords(self) -> (u8, u8) {
+        (self.0 % 8, self.0 / 8)
+    }
+
+    /// Returns the file of the square
+    pub fn file(self) -> File {
+        File::ALL[(self.0 % 8) as usize]
+    }
+
+    /// Returns the rank of the square
+    pub fn rank(self) -> Rank {
+        Rank::ALL[(7 - self.0 / 8) as usize]
+    }
+
+    /// Returns a bitboard with only this square set
+    #[inline(always)]
+    pub const fn bitboard(self) -> u64 {
+        1 << self.0
+    }
+
+    /// Returns the square moved by the given number of files towards h and ranks towards 8
+    ///
+    /// Returns `None` if the result would leave the board.
+    pub fn offset(self, files: i8, ranks: i8) -> Option<Square> {
+        let file = self.file().index() as i8 + files;
+        let rank = self.rank().index() as i8 + ranks;
+        if !(0..8).contains(&file) || !(0..8).contains(&rank) {
+            return None;
+        }
+        Some(Square::new(
+            File::ALL[file as usize],
+            Rank::ALL[rank as usize],
+        ))
+    }
+
+    /// Returns the square mirrored along the middle between the fourth and fifth rank
+    pub fn flip_rank(self) -> Square {
+        Square(self.0 ^ 56)
+    }
+
+    /// Returns the number of king moves between the squares
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::core::Square;
+    /// let e1: Square = "e1".parse().unwrap();
+    /// assert_eq!(e1.distance("g4".parse().unwrap()), 3);
+    /// assert_eq!(e1.distance(e1), 0);
+    /// ```
+    pub fn distance(self, other: Square) -> u8 {
+        let (x, y) = self.coords();
+        let (other_x, other_y) = other.coords();
+        x.abs_diff(other_x).max(y.abs_diff(other_y))
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file().to_char(), self.rank().to_char())
+    }
+}
+
+impl FromStr for Square {
+    type Err = ParserError;
+
+    /// Parses a field in algebraic notation like `e4`
+    fn from_str(s: &str) -> Result<Square, ParserError> {
+        let chars: Vec<char> = s.chars().collect();
+        if chars.len() != 2 {
+            return Err(ParserError::WrongParameterNumber);
+        }
+        Ok(Square::new(
+            File::from_char(chars[0])?,
+            Rank::from_char(chars[1])?,
+        ))
+    }
+}
+
+impl From<Square> for u8 {
+    fn from(square: Square) -> u8 {
+        square.0
+    }
+}
+
+impl TryFrom<u8> for Square {
+    type Error = ParserError;
+
+    fn try_from(index: u8) -> Result<Square, ParserError> {
+        Square::from_index(index)
+    }
+}
+
+/// Squares are serialized by their name like `e4`
+#[cfg(feature = "serde")]
+impl Serialize for Square {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Square {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Square, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bitboard;
+
+    #[test]
+    fn matches_bitboard_helpers() {
+        for square in Square::all() {
+            let repr = bitboard::index_to_field_repr(square.index()).unwrap();
+            assert_eq!(square.to_string(), repr);
+            assert_eq!(repr.parse::<Square>().unwrap(), square);
+            assert_eq!(
+                bitboard::index_to_coords(square.index()).unwrap(),
+                square.coords()
+            );
+            assert_eq!(Square::from_coords(square.coords()).unwrap(), square);
+            assert_eq!(Square::new(square.file(), square.rank()), square);
+        }
+        assert_eq!(Square::all().count(), 64);
+    }
+
+    #[test]
+    fn conversions_and_errors() {
+        let a1 = Square::new(File::A, Rank::First);
+        assert_eq!(u8::from(a1), 56);
+        assert_eq!(Square::try_from(56).unwrap(), a1);
+        assert_eq!(
+            a1.bitboard(),
+            bitboard::constants::FILES[0] & bitboard::constants::RANKS[0]
+        );
+        assert_eq!(a1.flip_rank().to_string(), "a8");
+        assert_eq!(a1.offset(-1, 0), None);
+        assert_eq!(a1.offset(7, 7).unwrap().to_string(), "h8");
+        assert!(Square::try_from(64).is_err());
+        assert!(Square::from_coords((8, 0)).is_err());
+        assert!("e9".parse::<Square>().is_err());
+        assert!("i1".parse::<Square>().is_err());
+        assert!("e".parse::<Square>().is_err());
+        assert_eq!(File::from_index(8), None);
+        assert_eq!(Rank::from_char('8').unwrap(), Rank::Eighth);
+    }
+}
@@ -0,0 +1,98 @@
+//! Zobrist keys for hashing chess positions
+//!
+//! All keys are generated at compile time from a fixed seed, so hashes are stable between
+//! runs and builds. A position hash is the xor of the keys of all pieces on the board, the
+//! castling rights, the en passant file (if any) and the side key if black is to move.
+
+use crate::game_representation::{Color, PieceType};
+
+const SEED: u64 = 0x5eed_c4e5_5b0a_4d00;
+
+/// One step of the splitmix64 generator, returns the next state and the generated number
+pub(crate) const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (state, z ^ (z >> 31))
+}
+
+/// 2 colors * 6 piece types * 64 fields, 16 castling states, 8 en passant files, 1 side key
+const KEY_COUNT: usize = 2 * 6 * 64 + 16 + 8 + 1;
+const CASTLING_OFFSET: usize = 2 * 6 * 64;
+const EN_PASSANT_OFFSET: usize = CASTLING_OFFSET + 16;
+const SIDE_OFFSET: usize = EN_PASSANT_OFFSET + 8;
+
+const fn generate_keys() -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut state = SEED;
+    let mut i = 0;
+    while i < KEY_COUNT {
+        let (next_state, key) = splitmix64(state);
+        state = next_state;
+        keys[i] = key;
+        i += 1;
+    }
+    // no castling rights at all hash to zero, so positions without castling rights
+    // only depend on pieces, side and en passant
+    keys[CASTLING_OFFSET] = 0;
+    keys
+}
+
+const KEYS: [u64; KEY_COUNT] = generate_keys();
+
+/// Returns the key for a piece of the given color on the field with the shift index
+#[inline(always)]
+pub fn piece_key(color: Color, piece: PieceType, index: u8) -> u64 {
+    KEYS[(color as usize * 6 + (piece as usize - 1)) * 64 + index as usize]
+}
+
+/// Returns the key for the raw castling byte, see [`Castling::get_raw`]
+///
+/// [`Castling::get_raw`]: ../../game_representation/struct.Castling.html#method.get_raw
+#[inline(always)]
+pub fn castling_key(castling: u8) -> u64 {
+    KEYS[CASTLING_OFFSET + (castling & 0b1111) as usize]
+}
+
+/// Returns the key for the en passant field with the given shift index, 0 if there is none
+///
+/// Only the file of the en passant field is hashed, the rank follows from the side to move.
+#[inline(always)]
+pub fn en_passant_key(index: u8) -> u64 {
+    if index > 63 {
+        return 0;
+    }
+    KEYS[EN_PASSANT_OFFSET + (index % 8) as usize]
+}
+
+/// Returns the key that is xored into the hash if black is to move
+#[inline(always)]
+pub fn side_key() -> u64 {
+    KEYS[SIDE_OFFSET]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn keys_are_unique() {
+        let keys: HashSet<u64> = KEYS.iter().cloned().collect();
+        // the castling key for no rights is 0 and thus the only duplicate candidate
+        assert_eq!(keys.len(), KEY_COUNT);
+        assert!(KEYS.iter().filter(|k| **k == 0).count() == 1);
+    }
+
+    #[test]
+    fn key_lookup() {
+        assert_ne!(
+            piece_key(Color::White, PieceType::Bishop, 63),
+            piece_key(Color::Black, PieceType::King, 0)
+        );
+        assert_eq!(en_passant_key(255), 0);
+        assert_eq!(en_passant_key(16), en_passant_key(40));
+        assert_eq!(castling_key(0), 0);
+    }
+}
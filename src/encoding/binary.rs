@@ -0,0 +1,252 @@
+use crate::core::ParserError;
+use crate::game_representation::Game;
+use crate::move_generation::{movegen, Action};
+use crate::record::RecordedGame;
+use std::convert::TryFrom;
+
+// A game is stored as the length of its starting FEN, the FEN itself, the number of actions
+// and the index of every action among the sorted legal actions of its position. All numbers
+// are variable length integers holding seven bits per byte, the lowest bits first, with the
+// highest bit set on every byte but the last. The standard starting position is stored as an
+// empty FEN, so most games need one byte per action and up to three bytes for the header.
+
+/// Returns the game encoded into the compact binary format
+///
+/// Every action is stored as its index among the legal actions of its position, which takes a
+/// single byte for all positions with fewer than 128 legal actions. The metadata of the
+/// actions is not stored.
+///
+/// # Errors
+/// * A recorded action is not legal in its position
+///
+/// # Examples
+/// ```
+/// # use rust_chess::encoding::{decode_game, encode_game};
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::record::RecordedGame;
+/// let mut record = RecordedGame::new();
+/// let mut game = Game::startpos();
+/// for san in ["e4", "e5", "Nf3", "Nc6", "Bb5"] {
+///     let action = Action::from_san(san, &game).unwrap();
+///     game.execute_action(&action);
+///     record.push(action);
+/// }
+/// let bytes = encode_game(&record).unwrap();
+/// assert_eq!(bytes.len(), 7);
+/// let decoded = decode_game(&bytes).unwrap();
+/// assert_eq!(decoded.actions(), record.actions());
+/// assert_eq!(decoded.start_fen(), record.start_fen());
+/// ```
+pub fn encode_game(record: &RecordedGame) -> Result<Vec<u8>, ParserError> {
+    let mut bytes = Vec::new();
+    write_game(record, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Returns the games encoded one after another, see [`encode_game`]
+///
+/// # Errors
+/// * A recorded action of any game is not legal in its position
+///
+/// [`encode_game`]: fn.encode_game.html
+pub fn encode_games<'a, I>(records: I) -> Result<Vec<u8>, ParserError>
+where
+    I: IntoIterator<Item = &'a RecordedGame>,
+{
+    let mut bytes = Vec::new();
+    for record in records {
+        write_game(record, &mut bytes)?;
+    }
+    Ok(bytes)
+}
+
+/// Returns the game stored by [`encode_game`]
+///
+/// # Errors
+/// * The data ends in the middle of the game or contains bytes after it
+/// * The starting FEN is not valid
+/// * An action index is not smaller than the number of legal actions of its position
+///
+/// [`encode_game`]: fn.encode_game.html
+pub fn decode_game(bytes: &[u8]) -> Result<RecordedGame, ParserError> {
+    let mut reader = Reader { bytes, position: 0 };
+    let record = reader.read_game()?;
+    if reader.position < bytes.len() {
+        return Err(ParserError::InvalidParameter(
+            "Encoded game is followed by further data",
+        ));
+    }
+    Ok(record)
+}
+
+/// Returns all games stored by [`encode_games`], in their order
+///
+/// # Errors
+/// * Any game can not be decoded, see [`decode_game`]
+///
+/// [`encode_games`]: fn.encode_games.html
+/// [`decode_game`]: fn.decode_game.html
+pub fn decode_games(bytes: &[u8]) -> Result<Vec<RecordedGame>, ParserError> {
+    let mut reader = Reader { bytes, position: 0 };
+    let mut records = Vec::new();
+    while reader.position < bytes.len() {
+        records.push(reader.read_game()?);
+    }
+    Ok(records)
+}
+
+fn write_game(record: &RecordedGame, bytes: &mut Vec<u8>) -> Result<(), ParserError> {
+    let mut game = Game::from_fen(record.start_fen()).expect("start fen was validated");
+    if game.is_standard_startpos() {
+        write_number(0, bytes);
+    } else {
+        write_number(record.start_fen().len() as u64, bytes);
+        bytes.extend_from_slice(record.start_fen().as_bytes());
+    }
+    write_number(record.len() as u64, bytes);
+    for action in record.actions() {
+        let index = sorted_moves(&game)
+            .iter()
+            .position(|legal| legal == action)
+            .ok_or_else(|| ParserError::IllegalMove {
+                token: action.to_uci(),
+                reason: "Move is not legal in the position",
+            })?;
+        write_number(index as u64, bytes);
+        game.execute_action(action);
+    }
+    Ok(())
+}
+
+/// Returns the legal actions of the position in an order independent of the move generation
+fn sorted_moves(game: &Game) -> Vec<Action> {
+    let mut moves = movegen::legal_moves(game);
+    moves.sort_unstable_by_key(|action| {
+        (
+            action.get_from_index(),
+            action.get_to_index(),
+            action.get_promotion_piece().map(|piece| piece as u8),
+        )
+    });
+    moves
+}
+
+fn write_number(mut number: u64, bytes: &mut Vec<u8>) {
+    while number >= 0x80 {
+        bytes.push(number as u8 | 0x80);
+        number >>= 7;
+    }
+    bytes.push(number as u8);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_game(&mut self) -> Result<RecordedGame, ParserError> {
+        let mut record = match self.read_number()? as usize {
+            0 => RecordedGame::new(),
+            length => {
+                let fen = std::str::from_utf8(self.read_bytes(length)?)
+                    .map_err(|_| ParserError::InvalidParameter("Encoded FEN is not valid UTF-8"))?;
+                RecordedGame::from_fen(fen)?
+            }
+        };
+        let mut game = record.position_at(0);
+        for _ in 0..self.read_number()? {
+            let index = self.read_number()?;
+            let action = usize::try_from(index)
+                .ok()
+                .and_then(|index| sorted_moves(&game).get(index).copied())
+                .ok_or(ParserError::InvalidParameter(
+                    "Move index exceeds the number of legal moves",
+                ))?;
+            game.execute_action(&action);
+            record.push(action);
+        }
+        Ok(record)
+    }
+
+    fn read_number(&mut self) -> Result<u64, ParserError> {
+        let mut number = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_bytes(1)?[0];
+            number |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(number);
+            }
+        }
+        Err(ParserError::InvalidParameter("Encoded number is too long"))
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], ParserError> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(ParserError::InvalidParameter("Encoded game ends early"))?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut records = Vec::new();
+        let mut record = RecordedGame::from_fen("4k3/1P6/8/8/8/8/8/R3K2R w KQ - 3 40").unwrap();
+        let mut game = record.position_at(0);
+        for san in ["O-O-O", "Kf7", "b8=N", "Ke6", "Rd6+"] {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+            record.push(action);
+        }
+        records.push(record);
+        records.push(RecordedGame::new());
+
+        // a long game from the starting position takes about one byte per action
+        let mut record = RecordedGame::new();
+        let mut game = Game::startpos();
+        for ply in 0..100 {
+            let moves = sorted_moves(&game);
+            if moves.is_empty() {
+                break;
+            }
+            let action = moves[ply * 7 % moves.len()];
+            game.execute_action(&action);
+            record.push(action);
+        }
+        assert!(encode_game(&record).unwrap().len() <= record.len() + 3);
+        records.push(record);
+
+        let bytes = encode_games(&records).unwrap();
+        let decoded = decode_games(&bytes).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for (decoded, record) in decoded.iter().zip(&records) {
+            assert_eq!(decoded.start_fen(), record.start_fen());
+            assert_eq!(decoded.actions(), record.actions());
+        }
+
+        let single = encode_game(&records[0]).unwrap();
+        assert!(decode_game(&single[..single.len() - 1]).is_err());
+        assert!(decode_game(&bytes).is_err());
+        // the starting position has 20 legal actions
+        assert!(decode_game(&[0, 1, 20]).is_err());
+
+        let mut bytes = Vec::new();
+        write_number(300, &mut bytes);
+        assert_eq!(bytes, vec![0xac, 0x02]);
+        let mut reader = Reader {
+            bytes: &bytes,
+            position: 0,
+        };
+        assert_eq!(reader.read_number(), Ok(300));
+    }
+}
@@ -0,0 +1,5 @@
+//! Compact binary encodings of games for storing large collections
+
+mod binary;
+
+pub use binary::{decode_game, decode_games, encode_game, encode_games};
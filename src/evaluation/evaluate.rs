@@ -0,0 +1,298 @@
+use super::scaling::{self, SCALE_NORMAL};
+use super::{pawns, rooks};
+use crate::core::bitboard::Bitboard;
+use crate::game_representation::{Board, Color, Game, PieceType};
+
+/// The phase weight of a knight or bishop, rooks count twice and queens four times
+const MINOR_PHASE: i32 = 1;
+/// The phase of the starting position, at which only the middlegame tables are used
+const MAX_PHASE: i32 = 24;
+
+// The piece-square tables give the bonus of a white piece on every field in centipawns, laid
+// out like a diagram with a8 first. Black pieces use the field mirrored along the middle rank.
+
+#[rustfmt::skip]
+const PAWN_MG: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  25,  25,  10,   5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const PAWN_EG: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     80,  80,  80,  80,  80,  80,  80,  80,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     30,  30,  30,  30,  30,  30,  30,  30,
+     15,  15,  15,  15,  15,  15,  15,  15,
+      5,   5,   5,   5,   5,   5,   5,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN: [i32; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MG: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_EG: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+/// The parts of a static evaluation, all from the point of view of white
+///
+/// See [`Game::evaluate_breakdown`].
+///
+/// [`Game::evaluate_breakdown`]: ../game_representation/struct.Game.html#method.evaluate_breakdown
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvalBreakdown {
+    /// The score with the middlegame tables
+    pub middlegame: i32,
+    /// The score with the endgame tables
+    pub endgame: i32,
+    /// The weight of the middlegame score, from 0 in pawn endings to 24 with all pieces
+    pub phase: i32,
+    /// The factor the blended score is scaled by in 1/[`SCALE_NORMAL`], lower in drawish endings
+    ///
+    /// [`SCALE_NORMAL`]: constant.SCALE_NORMAL.html
+    pub scale: i32,
+}
+
+impl EvalBreakdown {
+    /// Returns the middlegame and endgame score blended by the phase
+    pub fn unscaled(&self) -> i32 {
+        (self.middlegame * self.phase + self.endgame * (MAX_PHASE - self.phase)) / MAX_PHASE
+    }
+
+    /// Returns the blended score scaled by the scale factor
+    pub fn score(&self) -> i32 {
+        self.unscaled() * self.scale / SCALE_NORMAL
+    }
+}
+
+impl Game {
+    /// Returns a static evaluation of the position in centipawns
+    ///
+    /// The score is the material of both sides plus a bonus for every piece from a
+    /// piece-square table, for passed pawns and for rooks on open files, on the seventh rank or
+    /// connected to each other. Pawns and kings have separate tables for the middlegame and the
+    /// endgame, which are blended by the remaining knights, bishops, rooks and queens. The
+    /// blended score is scaled down in drawish endings like opposite colored bishops. The
+    /// score is from the point of view of the color to move, positive if it is better off.
+    ///
+    /// Neither checks nor threats are looked at, this is meant to be called at the leaves of
+    /// a search.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let game = Game::startpos();
+    /// assert_eq!(game.evaluate(), 0);
+    ///
+    /// // black is a queen down
+    /// let game = Game::from_fen("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1")
+    ///     .unwrap();
+    /// assert!(game.evaluate() < -800);
+    /// ```
+    pub fn evaluate(&self) -> i32 {
+        let score = self.evaluate_breakdown().score();
+        match self.color_to_move {
+            Color::White => score,
+            Color::Black => -score,
+        }
+    }
+
+    /// Returns the parts [`evaluate`] combines, from the point of view of white
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::evaluation::SCALE_NORMAL;
+    /// # use rust_chess::game_representation::Game;
+    /// // opposite colored bishops are hard to win even with a pawn more
+    /// let game = Game::from_fen("2b1k3/8/8/8/8/8/PPP5/2B1K3 b - - 0 1").unwrap();
+    /// let breakdown = game.evaluate_breakdown();
+    /// assert!(breakdown.scale < SCALE_NORMAL);
+    /// assert_eq!(game.evaluate(), -breakdown.score());
+    /// ```
+    ///
+    /// [`evaluate`]: #method.evaluate
+    pub fn evaluate_breakdown(&self) -> EvalBreakdown {
+        let board = &self.board;
+        let (mut middlegame, mut endgame) = (0, 0);
+        for color in [Color::White, Color::Black] {
+            let sign = match color {
+                Color::White => 1,
+                Color::Black => -1,
+            };
+            let (mg, eg) = side_score(board, color);
+            middlegame += sign * mg;
+            endgame += sign * eg;
+        }
+        let mut breakdown = EvalBreakdown {
+            middlegame,
+            endgame,
+            phase: phase(board),
+            scale: SCALE_NORMAL,
+        };
+        let strong = if breakdown.unscaled() >= 0 {
+            Color::White
+        } else {
+            Color::Black
+        };
+        breakdown.scale = scaling::scale_factor(board, strong);
+        breakdown
+    }
+}
+
+/// Returns the middlegame and endgame score of the pieces of one color
+fn side_score(board: &Board, color: Color) -> (i32, i32) {
+    let own = board.occupancy_of(color).0;
+    let queens = board.bishops & board.rooks;
+    let pieces = [
+        (board.pawns, PieceType::Pawn, &PAWN_MG, &PAWN_EG),
+        (board.knights, PieceType::Knight, &KNIGHT, &KNIGHT),
+        (board.bishops & !queens, PieceType::Bishop, &BISHOP, &BISHOP),
+        (board.rooks & !queens, PieceType::Rook, &ROOK, &ROOK),
+        (queens, PieceType::Queen, &QUEEN, &QUEEN),
+        (board.kings, PieceType::King, &KING_MG, &KING_EG),
+    ];
+    let (mut middlegame, mut endgame) = (0, 0);
+    for (fields, piece, mg_table, eg_table) in pieces.iter() {
+        // the king is always on the board, its value would only cancel out
+        let value = match piece {
+            PieceType::King => 0,
+            piece => piece.get_value(),
+        };
+        for square in Bitboard(fields & own) {
+            let index = match color {
+                Color::White => square.index(),
+                Color::Black => square.flip_rank().index(),
+            } as usize;
+            middlegame += value + mg_table[index];
+            endgame += value + eg_table[index];
+        }
+    }
+    for (mg, eg) in [
+        pawns::passed_pawn_score(board, color),
+        rooks::rook_activity(board, color),
+    ] {
+        middlegame += mg;
+        endgame += eg;
+    }
+    (middlegame, endgame)
+}
+
+/// Returns how far the position is from the endgame, from 0 to [`MAX_PHASE`]
+///
+/// [`MAX_PHASE`]: constant.MAX_PHASE.html
+fn phase(board: &Board) -> i32 {
+    let count = |piece| {
+        (board.piece_count(piece, Color::White) + board.piece_count(piece, Color::Black)) as i32
+    };
+    let minors = count(PieceType::Knight) + count(PieceType::Bishop);
+    let phase = MINOR_PHASE * (minors + 2 * count(PieceType::Rook) + 4 * count(PieceType::Queen));
+    phase.min(MAX_PHASE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tapers_between_phases() {
+        assert_eq!(phase(&Board::startpos()), MAX_PHASE);
+        let kings_and_pawns = Game::from_fen("4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1").unwrap();
+        assert_eq!(phase(&kings_and_pawns.board), 0);
+
+        // a centralised king is good in the endgame and bad with queens on the board
+        let endgame = Game::from_fen("8/8/8/4k3/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(endgame.evaluate() < 0);
+        let middlegame = Game::from_fen("qqr5/8/8/4k3/8/8/8/QQR1K3 w - - 0 1").unwrap();
+        assert!(middlegame.evaluate() > 0);
+
+        // passed pawns close to promotion count more in the endgame
+        let advanced = Game::from_fen("4k3/8/1P6/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let behind = Game::from_fen("4k3/8/8/8/8/8/1P6/4K3 w - - 0 1").unwrap();
+        assert!(advanced.evaluate() > behind.evaluate());
+    }
+
+    #[test]
+    fn symmetric_for_both_colors() {
+        let white =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let black =
+            Game::from_fen("r3k2r/pppbbppp/2n2q1P/1P2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R b KQkq - 0 1")
+                .unwrap();
+        assert_eq!(white.evaluate(), black.evaluate());
+        assert_eq!(Game::startpos().evaluate(), 0);
+    }
+}
@@ -0,0 +1,15 @@
+//! Building blocks for evaluating positions and actions
+
+mod evaluate;
+mod pawns;
+mod rooks;
+mod scaling;
+mod see;
+mod symmetry;
+
+pub use evaluate::EvalBreakdown;
+pub use pawns::{passed_pawns, PassedPawn};
+pub use rooks::{has_connected_rooks, is_open_file, is_semi_open_file, rooks_on_seventh};
+pub use scaling::SCALE_NORMAL;
+pub use see::see;
+pub use symmetry::{check_eval_symmetry, AsymmetricEval};
@@ -0,0 +1,188 @@
+use crate::core::bitboard::{constants, Bitboard};
+use crate::core::{Rank, Square};
+use crate::game_representation::{Board, Color, PieceType};
+
+// The bonuses of passed pawns are indexed by the rank of the pawn as seen from its own side,
+// starting with the first rank.
+
+/// The middlegame bonus of a passed pawn
+const RANK_BONUS_MG: [i32; 8] = [0, 0, 5, 10, 15, 25, 40, 0];
+/// The endgame bonus of a passed pawn
+const RANK_BONUS_EG: [i32; 8] = [0, 5, 10, 20, 35, 55, 80, 0];
+/// The endgame bonus per field between the opponent king and the field in front of the pawn
+const OPPONENT_KING_DISTANCE: i32 = 3;
+/// The endgame penalty per field between the own king and the field in front of the pawn
+const OWN_KING_DISTANCE: i32 = 1;
+
+/// A pawn without pawns of the opponent in front of it on its own or an adjacent file
+///
+/// Of doubled pawns only the one in front is passed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PassedPawn {
+    pub square: Square,
+    pub color: Color,
+    /// The rank as seen from the side of the pawn, the second rank for an unmoved pawn
+    pub rank: Rank,
+    /// The piece on the field in front of the pawn
+    pub blocker: Option<(PieceType, Color)>,
+    /// The number of king moves from the own king to the field in front of the pawn
+    pub own_king_distance: Option<u8>,
+    /// The number of king moves from the opponent king to the field in front of the pawn
+    pub opponent_king_distance: Option<u8>,
+}
+
+impl PassedPawn {
+    /// Returns true if a knight or bishop of the opponent stands in front of the pawn
+    ///
+    /// Minor pieces are the best blockaders, as the pawn does not restrict them.
+    pub fn is_blockaded(&self) -> bool {
+        match self.blocker {
+            Some((PieceType::Knight, color)) | Some((PieceType::Bishop, color)) => {
+                color != self.color
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the passed pawns of both colors, the white ones first
+///
+/// # Examples
+/// ```
+/// # use rust_chess::core::Rank;
+/// # use rust_chess::evaluation::passed_pawns;
+/// # use rust_chess::game_representation::{Board, Color, PieceType};
+/// let board = Board::from_fen("4k3/8/1n6/1P6/8/6p1/5P2/4K3").unwrap();
+/// let passed = passed_pawns(&board);
+/// assert_eq!(passed.len(), 1);
+/// assert_eq!(passed[0].square.to_string(), "b5");
+/// assert_eq!(passed[0].rank, Rank::Fifth);
+/// assert_eq!(passed[0].blocker, Some((PieceType::Knight, Color::Black)));
+/// assert!(passed[0].is_blockaded());
+/// assert_eq!(passed[0].opponent_king_distance, Some(3));
+/// ```
+pub fn passed_pawns(board: &Board) -> Vec<PassedPawn> {
+    let mut passed = passed_pawns_of(board, Color::White);
+    passed.extend(passed_pawns_of(board, Color::Black));
+    passed
+}
+
+fn passed_pawns_of(board: &Board, color: Color) -> Vec<PassedPawn> {
+    let own_pawns = board.pieces_of(PieceType::Pawn, color).0;
+    let opponent_pawns = board
+        .pieces_of(PieceType::Pawn, color.get_opponent_color())
+        .0;
+    let king_of = |color| board.pieces_of(PieceType::King, color).first();
+    let (own_king, opponent_king) = (king_of(color), king_of(color.get_opponent_color()));
+    Bitboard(own_pawns)
+        .filter(|&pawn| {
+            let (file, _) = pawn.coords();
+            let span = front_span(pawn, color);
+            span & opponent_pawns == 0 && span & constants::FILES[file as usize] & own_pawns == 0
+        })
+        .map(|pawn| {
+            let push = constants::PAWN_PUSHES[color as usize][pawn.index() as usize];
+            let stop = Bitboard(push).first().unwrap_or(pawn);
+            PassedPawn {
+                square: pawn,
+                color,
+                rank: match color {
+                    Color::White => pawn.rank(),
+                    Color::Black => pawn.flip_rank().rank(),
+                },
+                blocker: if push == 0 {
+                    None
+                } else {
+                    board.piece_at(stop)
+                },
+                own_king_distance: own_king.map(|king| king.distance(stop)),
+                opponent_king_distance: opponent_king.map(|king| king.distance(stop)),
+            }
+        })
+        .collect()
+}
+
+/// Returns the fields in front of the pawn on its own and the adjacent files
+fn front_span(pawn: Square, color: Color) -> u64 {
+    let (file, row) = pawn.coords();
+    // rows are counted from the eighth rank
+    let ahead = match color {
+        Color::White => (1u64 << (row * 8)) - 1,
+        Color::Black => u64::MAX.checked_shl((row as u32 + 1) * 8).unwrap_or(0),
+    };
+    let mut files = constants::FILES[file as usize];
+    if file > 0 {
+        files |= constants::FILES[file as usize - 1];
+    }
+    if file < 7 {
+        files |= constants::FILES[file as usize + 1];
+    }
+    ahead & files
+}
+
+/// Returns the middlegame and endgame bonus of the passed pawns of one color
+///
+/// The bonus grows with the rank of the pawn. In the endgame, the king of the opponent far
+/// away from the field in front of an advanced pawn adds to it, the own king far away takes
+/// from it. A pawn blockaded by a minor piece only gets half the bonus.
+pub(crate) fn passed_pawn_score(board: &Board, color: Color) -> (i32, i32) {
+    let (mut middlegame, mut endgame) = (0, 0);
+    for pawn in passed_pawns_of(board, color) {
+        let rank = pawn.rank.index() as usize;
+        let (mut mg, mut eg) = (RANK_BONUS_MG[rank], RANK_BONUS_EG[rank]);
+        // king distances only matter once the pawn crossed the third rank
+        let weight = rank.saturating_sub(2) as i32;
+        if let Some(distance) = pawn.opponent_king_distance {
+            eg += weight * OPPONENT_KING_DISTANCE * distance as i32;
+        }
+        if let Some(distance) = pawn.own_king_distance {
+            eg -= weight * OWN_KING_DISTANCE * distance as i32;
+        }
+        if pawn.is_blockaded() {
+            mg /= 2;
+            eg /= 2;
+        }
+        middlegame += mg;
+        endgame += eg;
+    }
+    (middlegame, endgame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_representation::Game;
+
+    #[test]
+    fn passed_pawns_score() {
+        let start = Board::startpos();
+        assert!(passed_pawns(&start).is_empty());
+
+        // of the doubled pawns on the a-file only the one in front is passed
+        let board = Board::from_fen("4k3/8/P7/P7/8/7p/2P5/4K3").unwrap();
+        let squares: Vec<String> = passed_pawns(&board)
+            .iter()
+            .map(|pawn| pawn.square.to_string())
+            .collect();
+        assert_eq!(squares, vec!["a6", "c2", "h3"]);
+        assert_eq!(passed_pawns(&board)[2].rank, Rank::Sixth);
+
+        // the bonus grows towards promotion and is halved by a blockading knight
+        let (far, near) = (
+            Board::from_fen("4k3/8/8/8/8/1P6/8/4K3").unwrap(),
+            Board::from_fen("4k3/8/1P6/8/8/8/8/4K3").unwrap(),
+        );
+        assert!(passed_pawn_score(&near, Color::White) > passed_pawn_score(&far, Color::White));
+        let blockaded = Board::from_fen("4k3/1n6/1P6/8/8/8/8/4K3").unwrap();
+        let (mg, eg) = passed_pawn_score(&near, Color::White);
+        assert_eq!(
+            passed_pawn_score(&blockaded, Color::White),
+            (mg / 2, eg / 2)
+        );
+
+        // an escorted pawn is worth more than one the king can not reach
+        let escorted = Game::from_fen("8/k7/2K5/2P5/8/8/8/8 w - - 0 1").unwrap();
+        let alone = Game::from_fen("8/2k5/8/2P5/8/8/8/K7 w - - 0 1").unwrap();
+        assert!(escorted.evaluate() > alone.evaluate());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::core::bitboard::{constants, Bitboard};
+use crate::core::File;
+use crate::game_representation::{Board, Color, PieceType};
+use crate::move_generation::movegen;
+
+// The bonuses are given as middlegame and endgame centipawns per rook, or per side for
+// connected rooks.
+
+/// The bonus of a rook on a file without pawns
+const OPEN_FILE: (i32, i32) = (25, 10);
+/// The bonus of a rook on a file with only pawns of the opponent
+const SEMI_OPEN_FILE: (i32, i32) = (12, 6);
+/// The bonus of a rook on the second rank of the opponent
+const SEVENTH_RANK: (i32, i32) = (15, 25);
+/// The bonus of two rooks protecting each other
+const CONNECTED_ROOKS: (i32, i32) = (10, 5);
+
+/// Returns true if there is no pawn on the file
+///
+/// # Examples
+/// ```
+/// # use rust_chess::core::File;
+/// # use rust_chess::evaluation::is_open_file;
+/// # use rust_chess::game_representation::Board;
+/// let board = Board::from_fen("4k3/pp6/8/8/8/8/1P6/4K3").unwrap();
+/// assert!(!is_open_file(&board, File::A));
+/// assert!(is_open_file(&board, File::C));
+/// ```
+pub fn is_open_file(board: &Board, file: File) -> bool {
+    board.pawns & constants::FILES[file.index() as usize] == 0
+}
+
+/// Returns true if the file has pawns of the opponent of `color`, but none of `color`
+///
+/// # Examples
+/// ```
+/// # use rust_chess::core::File;
+/// # use rust_chess::evaluation::is_semi_open_file;
+/// # use rust_chess::game_representation::{Board, Color};
+/// let board = Board::from_fen("4k3/pp6/8/8/8/8/1P6/4K3").unwrap();
+/// assert!(is_semi_open_file(&board, File::A, Color::White));
+/// assert!(!is_semi_open_file(&board, File::A, Color::Black));
+/// assert!(!is_semi_open_file(&board, File::B, Color::White));
+/// ```
+pub fn is_semi_open_file(board: &Board, file: File, color: Color) -> bool {
+    let pawns =
+        |color| board.pieces_of(PieceType::Pawn, color).0 & constants::FILES[file.index() as usize];
+    pawns(color) == 0 && pawns(color.get_opponent_color()) != 0
+}
+
+/// Returns the rooks of `color` on the second rank of the opponent
+///
+/// Queens are not counted as rooks.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::evaluation::rooks_on_seventh;
+/// # use rust_chess::game_representation::{Board, Color};
+/// let board = Board::from_fen("4k3/R6r/8/8/8/8/r7/4K3").unwrap();
+/// assert_eq!(rooks_on_seventh(&board, Color::White).count(), 1);
+/// assert_eq!(rooks_on_seventh(&board, Color::Black).count(), 1);
+/// ```
+pub fn rooks_on_seventh(board: &Board, color: Color) -> Bitboard {
+    let seventh = match color {
+        Color::White => constants::RANKS[6],
+        Color::Black => constants::RANKS[1],
+    };
+    board.pieces_of(PieceType::Rook, color) & Bitboard(seventh)
+}
+
+/// Returns true if two rooks of `color` stand on the same rank or file with no piece between
+///
+/// # Examples
+/// ```
+/// # use rust_chess::evaluation::has_connected_rooks;
+/// # use rust_chess::game_representation::{Board, Color};
+/// let board = Board::from_fen("r4rk1/8/8/8/8/8/8/R2QK2R").unwrap();
+/// assert!(has_connected_rooks(&board, Color::Black));
+/// assert!(!has_connected_rooks(&board, Color::White));
+/// ```
+pub fn has_connected_rooks(board: &Board, color: Color) -> bool {
+    let rooks = board.pieces_of(PieceType::Rook, color).0;
+    let occupied = board.occupancy().0;
+    Bitboard(rooks).any(|rook| movegen::rook_attacks(rook.bitboard(), occupied) & rooks != 0)
+}
+
+/// Returns the middlegame and endgame bonus for the placement of the rooks of one color
+pub(crate) fn rook_activity(board: &Board, color: Color) -> (i32, i32) {
+    let (mut middlegame, mut endgame) = (0, 0);
+    let mut add = |(mg, eg): (i32, i32)| {
+        middlegame += mg;
+        endgame += eg;
+    };
+    for rook in board.pieces_of(PieceType::Rook, color) {
+        let file = rook.file();
+        if is_open_file(board, file) {
+            add(OPEN_FILE);
+        } else if is_semi_open_file(board, file, color) {
+            add(SEMI_OPEN_FILE);
+        }
+    }
+    for _ in rooks_on_seventh(board, color) {
+        add(SEVENTH_RANK);
+    }
+    if has_connected_rooks(board, color) {
+        add(CONNECTED_ROOKS);
+    }
+    (middlegame, endgame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_representation::Game;
+
+    #[test]
+    fn active_rooks_score() {
+        let closed = Board::from_fen("4k3/pppppppp/8/8/8/8/PPPPPPPP/R3K3").unwrap();
+        assert_eq!(rook_activity(&closed, Color::White), (0, 0));
+        let open = Board::from_fen("4k3/1ppppppp/8/8/8/8/1PPPPPPP/R3K3").unwrap();
+        assert_eq!(rook_activity(&open, Color::White), OPEN_FILE);
+        let semi_open = Board::from_fen("4k3/pppppppp/8/8/8/8/1PPPPPPP/R3K3").unwrap();
+        assert_eq!(rook_activity(&semi_open, Color::White), SEMI_OPEN_FILE);
+
+        // doubled on the open file and one of them on the seventh rank
+        let active = Board::from_fen("4k3/R1pppppp/8/8/8/8/2PPPPPP/R3K3").unwrap();
+        assert_eq!(
+            rook_activity(&active, Color::White),
+            (
+                2 * OPEN_FILE.0 + SEVENTH_RANK.0 + CONNECTED_ROOKS.0,
+                2 * OPEN_FILE.1 + SEVENTH_RANK.1 + CONNECTED_ROOKS.1
+            )
+        );
+
+        // a rook on the open file is better than one behind its own pawn
+        let open = Game::from_fen("4k3/1p6/8/8/8/8/1P6/2R1K3 w - - 0 1").unwrap();
+        let closed = Game::from_fen("4k3/1p6/8/8/8/8/1P6/1R2K3 w - - 0 1").unwrap();
+        assert!(open.evaluate() > closed.evaluate());
+    }
+}
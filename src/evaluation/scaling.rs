@@ -0,0 +1,109 @@
+use crate::core::Square;
+use crate::game_representation::{Board, Color, PieceType};
+
+/// The scale factor of positions without a known drawing pattern
+pub const SCALE_NORMAL: i32 = 64;
+/// The scale factor of an ending with only opposite colored bishops and pawns
+const OPPOSITE_BISHOPS_ONLY: i32 = 24;
+/// The scale factor of opposite colored bishops with further pieces
+const OPPOSITE_BISHOPS: i32 = 48;
+/// The scale factor of a rook and pawn against a rook with the defending king in front of the pawn
+const ROOK_FORTRESS: i32 = 16;
+
+/// Returns the factor in 1/[`SCALE_NORMAL`] the score of the `strong` side is scaled by
+///
+/// Known drawish endings lower the factor: opposite colored bishops, where the defending bishop
+/// holds the other color of the board, and a rook and pawn against a rook with the defending
+/// king blocking the path of the pawn.
+///
+/// [`SCALE_NORMAL`]: constant.SCALE_NORMAL.html
+pub(crate) fn scale_factor(board: &Board, strong: Color) -> i32 {
+    let weak = strong.get_opponent_color();
+    let count = |piece, color| board.piece_count(piece, color);
+    let pieces = |color| {
+        [
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Rook,
+            PieceType::Queen,
+        ]
+        .iter()
+        .map(|&piece| count(piece, color))
+        .sum::<u32>()
+    };
+
+    if count(PieceType::Bishop, strong) == 1
+        && count(PieceType::Bishop, weak) == 1
+        && is_light(board, strong) != is_light(board, weak)
+    {
+        return if pieces(strong) == 1 && pieces(weak) == 1 {
+            OPPOSITE_BISHOPS_ONLY
+        } else {
+            OPPOSITE_BISHOPS
+        };
+    }
+
+    let rook_and_pawn = count(PieceType::Rook, strong) == 1
+        && pieces(strong) == 1
+        && count(PieceType::Pawn, strong) == 1;
+    let lone_rook =
+        count(PieceType::Rook, weak) == 1 && pieces(weak) == 1 && count(PieceType::Pawn, weak) == 0;
+    if rook_and_pawn && lone_rook && blocks_pawn(board, strong) {
+        return ROOK_FORTRESS;
+    }
+    SCALE_NORMAL
+}
+
+/// Returns whether the first bishop of the color stands on a light field, `None` without one
+fn is_light(board: &Board, color: Color) -> Option<bool> {
+    board
+        .pieces_of(PieceType::Bishop, color)
+        .first()
+        .map(|bishop| {
+            let (file, row) = bishop.coords();
+            (file + row) % 2 == 0
+        })
+}
+
+/// Returns true if the king of the opponent of `strong` stands in front of its single pawn
+fn blocks_pawn(board: &Board, strong: Color) -> bool {
+    let pawn = board.pieces_of(PieceType::Pawn, strong).first();
+    let king = board
+        .pieces_of(PieceType::King, strong.get_opponent_color())
+        .first();
+    match (pawn, king) {
+        (Some(pawn), Some(king)) => {
+            let ahead = |square: Square| match strong {
+                Color::White => square.rank() > pawn.rank(),
+                Color::Black => square.rank() < pawn.rank(),
+            };
+            king.file() == pawn.file() && ahead(king)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_representation::Game;
+
+    #[test]
+    fn drawish_endings() {
+        let scale = |fen| scale_factor(&Board::from_fen(fen).unwrap(), Color::White);
+        assert_eq!(scale("4k3/8/8/8/8/8/PP6/4K3"), SCALE_NORMAL);
+        assert_eq!(scale("2b1k3/8/8/8/8/8/PPP5/2B1K3"), OPPOSITE_BISHOPS_ONLY);
+        assert_eq!(scale("r1b1k3/8/8/8/8/8/PPP5/2BRK3"), OPPOSITE_BISHOPS);
+        // bishops on the same color
+        assert_eq!(scale("4kb2/8/8/8/8/8/PPP5/2B1K3"), SCALE_NORMAL);
+
+        assert_eq!(scale("r7/4k3/8/4P3/8/8/8/4K2R"), ROOK_FORTRESS);
+        assert_eq!(scale("r7/8/8/4P3/8/8/8/k3K2R"), SCALE_NORMAL);
+
+        let bishops = Game::from_fen("2b1k3/8/8/8/8/8/PPP5/2B1K3 w - - 0 1").unwrap();
+        let breakdown = bishops.evaluate_breakdown();
+        assert_eq!(breakdown.scale, OPPOSITE_BISHOPS_ONLY);
+        assert!(bishops.evaluate() > 0);
+        assert!(bishops.evaluate() < breakdown.unscaled());
+    }
+}
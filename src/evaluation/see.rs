@@ -0,0 +1,179 @@
+use crate::game_representation::{Board, Color, Game, PieceType};
+use crate::move_generation::movegen::attackers_of;
+use crate::move_generation::{Action, ActionType};
+
+/// Returns the static exchange value of an action in centipawns
+///
+/// Both sides keep capturing on the destination of the action with their least valuable
+/// attacker, and either side may stop whenever continuing would lose material. The result is
+/// the material the side to move wins with best play of this exchange, negative if the action
+/// loses material. Pieces behind other attackers join once the way is free, pins and checks
+/// are not taken into account. Non-capturing actions are valued by the exchange they allow.
+///
+/// The piece values are the ones of [`PieceType::get_value`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::evaluation::see;
+/// let game = Game::from_fen("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1").unwrap();
+/// let action = Action::from_san("Rxe5", &game).unwrap();
+/// assert_eq!(see(&game, &action), 100);
+///
+/// // the pawn on e5 is defended twice, taking it loses the knight for a pawn
+/// let game = Game::from_fen("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1").unwrap();
+/// let action = Action::from_san("Nxe5", &game).unwrap();
+/// assert_eq!(see(&game, &action), 100 - 320);
+/// ```
+///
+/// [`PieceType::get_value`]: ../game_representation/enum.PieceType.html#method.get_value
+pub fn see(state: &Game, action: &Action) -> i32 {
+    if let ActionType::Castling(_) = action.get_action_type() {
+        return 0;
+    }
+    let board = &state.board;
+    let from = action.get_from_index();
+    let to = action.get_to_index();
+    let target = 1u64 << to;
+    let mut occupied = state.occupancy().0 ^ (1 << from);
+    if action.get_capture_piece() == Some(PieceType::Pawn) && occupied & target == 0 {
+        // en passant, the captured pawn is behind the destination
+        occupied ^= match state.color_to_move {
+            Color::White => target << 8,
+            Color::Black => target >> 8,
+        };
+    }
+
+    let promotion = action
+        .get_promotion_piece()
+        .map_or(0, |piece| piece.get_value() - PieceType::Pawn.get_value());
+    // gains[i] is the material won by the side making the i-th capture if the exchange
+    // stopped right after it
+    let mut gains = [0; 32];
+    gains[0] = action.get_capture_piece().map_or(0, PieceType::get_value) + promotion;
+    let mut on_target = action
+        .get_promotion_piece()
+        .unwrap_or_else(|| action.get_piecetype())
+        .get_value();
+    let mut color = state.color_to_move.get_opponent_color();
+    let mut depth = 0;
+    loop {
+        let attackers = attackers_to(target, occupied, board) & occupied;
+        let own_attackers = attackers & board.occupancy_of(color).0;
+        let (square, piece) = match least_valuable(own_attackers, board) {
+            Some(attacker) => attacker,
+            None => break,
+        };
+        // the king can only capture if the opponent can not recapture
+        if piece == PieceType::King && attackers & !own_attackers != 0 {
+            break;
+        }
+        depth += 1;
+        gains[depth] = on_target - gains[depth - 1];
+        occupied ^= square;
+        on_target = piece.get_value();
+        color = color.get_opponent_color();
+    }
+    while depth > 0 {
+        gains[depth - 1] = -(-gains[depth - 1]).max(gains[depth]);
+        depth -= 1;
+    }
+    gains[0]
+}
+
+/// Returns all pieces of both colors attacking `target`, sliding pieces are blocked by `occupied`
+fn attackers_to(target: u64, occupied: u64, board: &Board) -> u64 {
+    // pawns attacking a field of one color are found from the point of view of that color
+    attackers_of(target, occupied, board, Color::White) & board.occupancy_of(Color::Black).0
+        | attackers_of(target, occupied, board, Color::Black) & board.occupancy_of(Color::White).0
+}
+
+/// Returns the field and type of the least valuable piece among `attackers`
+fn least_valuable(attackers: u64, board: &Board) -> Option<(u64, PieceType)> {
+    let queens = board.bishops & board.rooks;
+    [
+        (board.pawns, PieceType::Pawn),
+        (board.knights, PieceType::Knight),
+        (board.bishops & !queens, PieceType::Bishop),
+        (board.rooks & !queens, PieceType::Rook),
+        (queens, PieceType::Queen),
+        (board.kings, PieceType::King),
+    ]
+    .iter()
+    .find(|(pieces, _)| pieces & attackers != 0)
+    .map(|(pieces, piece)| {
+        let pieces = pieces & attackers;
+        (pieces & pieces.wrapping_neg(), *piece)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn see_of(fen: &str, san: &str) -> i32 {
+        let game = Game::from_fen(fen).unwrap();
+        let action = Action::from_san(san, &game).unwrap();
+        see(&game, &action)
+    }
+
+    #[test]
+    fn standard_positions() {
+        let (pawn, knight, bishop, rook, queen) = (100, 320, 330, 500, 900);
+        let cases = [
+            (
+                "1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1",
+                "Rxe5",
+                pawn,
+            ),
+            (
+                "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+                "Nxe5",
+                pawn - knight,
+            ),
+            // the second rook recaptures through the first one
+            ("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "Rxd5", pawn),
+            ("3rk3/8/8/3p4/8/8/3R4/4K3 w - - 0 1", "Rxd5", pawn - rook),
+            // the queen is attacked by the bishop, which is defended by the pawn
+            (
+                "4k3/8/8/3p4/2b5/3Q4/8/4K3 w - - 0 1",
+                "Qxc4",
+                bishop - queen,
+            ),
+            ("4k3/8/8/8/8/2p5/8/3NK3 w - - 0 1", "Nxc3", pawn),
+            // quiet actions to an attacked field lose the piece
+            ("4k3/8/3p4/8/4N3/8/8/4K3 w - - 0 1", "Nc5", -knight),
+            ("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1", "Nc5", 0),
+            (
+                "1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1",
+                "axb8=Q",
+                rook + queen - pawn,
+            ),
+            ("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a8=Q", -pawn),
+            ("4k3/2p5/8/3pP3/8/8/8/4K3 w - d6 0 2", "exd6", 0),
+            ("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", "exd6", pawn),
+            // the king may only recapture if nothing else attacks the field
+            ("8/8/3k4/4p3/8/5N2/8/5K2 w - - 0 1", "Nxe5", pawn - knight),
+            ("8/8/3k4/4p3/8/5N2/8/4RK2 w - - 0 1", "Nxe5", pawn),
+            ("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1", "Kxd2", pawn),
+            ("r3k3/8/8/8/8/8/8/R3K3 w - - 0 1", "Rxa8", rook),
+        ];
+        for (fen, san, expected) in cases.iter() {
+            assert_eq!(see_of(fen, san), *expected, "{} in {}", san, fen);
+        }
+    }
+
+    #[test]
+    fn symmetric_for_black() {
+        let white = see_of(
+            "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+            "Nxe5",
+        );
+        let black = see_of(
+            "2k1q3/1pp1r1bp/p2n2p1/8/4P3/P4B2/1PPN3P/1K1R3Q b - - 0 1",
+            "Nxe4",
+        );
+        assert_eq!(white, black);
+    }
+}
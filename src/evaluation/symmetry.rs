@@ -0,0 +1,106 @@
+use super::EvalBreakdown;
+use crate::game_representation::Game;
+
+/// A position evaluated differently than the same position with the colors flipped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsymmetricEval {
+    /// The FEN of the position
+    pub fen: String,
+    /// The evaluation of the position from the point of view of white
+    pub breakdown: EvalBreakdown,
+    /// The evaluation of the flipped position from the point of view of white
+    pub flipped: EvalBreakdown,
+}
+
+/// Checks that every position evaluates to the negated score of its color flipped position
+///
+/// The middlegame and endgame scores must be negated and the phase and scale factor equal, see
+/// [`Game::flip_colors`]. Evaluation terms looking at one color differently than the other
+/// break this.
+///
+/// # Errors
+/// * All positions evaluated asymmetrically, in the order they were given
+///
+/// # Examples
+/// ```
+/// # use rust_chess::evaluation::check_eval_symmetry;
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::movegen;
+/// let start = Game::startpos();
+/// let mut positions = vec![start.clone()];
+/// for action in movegen::legal_moves(&start) {
+///     let mut game = start.clone();
+///     game.execute_action(&action);
+///     positions.push(game);
+/// }
+/// assert!(check_eval_symmetry(&positions).is_ok());
+/// ```
+///
+/// [`Game::flip_colors`]: ../game_representation/struct.Game.html#method.flip_colors
+pub fn check_eval_symmetry<'a, I>(games: I) -> Result<(), Vec<AsymmetricEval>>
+where
+    I: IntoIterator<Item = &'a Game>,
+{
+    let asymmetric: Vec<AsymmetricEval> = games
+        .into_iter()
+        .filter_map(|game| {
+            let breakdown = game.evaluate_breakdown();
+            let flipped = game.flip_colors().evaluate_breakdown();
+            let symmetric = breakdown.middlegame == -flipped.middlegame
+                && breakdown.endgame == -flipped.endgame
+                && breakdown.phase == flipped.phase
+                && (breakdown.scale == flipped.scale || breakdown.unscaled() == 0);
+            if symmetric {
+                None
+            } else {
+                Some(AsymmetricEval {
+                    fen: game.to_fen(),
+                    breakdown,
+                    flipped,
+                })
+            }
+        })
+        .collect();
+    if asymmetric.is_empty() {
+        Ok(())
+    } else {
+        Err(asymmetric)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::move_generation::movegen;
+
+    /// Returns the position and all positions reachable by up to `depth` moves
+    fn walk(game: &Game, depth: u8, positions: &mut Vec<Game>) {
+        positions.push(game.clone());
+        if depth == 0 {
+            return;
+        }
+        for action in movegen::legal_moves(game) {
+            let mut next = game.clone();
+            next.execute_action(&action);
+            walk(&next, depth - 1, positions);
+        }
+    }
+
+    #[test]
+    fn evaluation_is_symmetric() {
+        let mut positions = Vec::new();
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r1b1k3/8/8/8/8/8/PPP5/2BRK3 b - - 0 1",
+            "r7/4k3/8/4P3/8/8/8/4K2R w - - 0 1",
+        ] {
+            walk(&Game::from_fen(fen).unwrap(), 2, &mut positions);
+        }
+        assert!(positions.len() > 2000);
+        assert_eq!(check_eval_symmetry(&positions), Ok(()));
+
+        let flipped: Vec<Game> = positions.iter().map(Game::flip_colors).collect();
+        assert_eq!(check_eval_symmetry(&flipped), Ok(()));
+    }
+}
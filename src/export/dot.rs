@@ -0,0 +1,356 @@
+use super::labels::move_label;
+use crate::game_representation::{Game, UndoToken};
+use crate::move_generation::Action;
+use crate::record::{GameTree, NodeId, RecordedGame, Repertoire};
+use crate::search::SearchTree;
+
+/// A tree that can be exported to the Graphviz DOT format
+///
+/// Nodes are identified by a cheap handle, so trees stored in arenas or reconstructed on the
+/// fly (like search trees) can be exported without building an owned node structure first.
+/// The export walks the tree once from the root and keeps a state for the node it is at, like
+/// the position of a game tree, which [`enter`] and [`leave`] update on every step.
+///
+/// [`enter`]: #method.enter
+/// [`leave`]: #method.leave
+pub trait DotTree {
+    type Node: Copy;
+
+    /// The state of the node the walk is at
+    type State;
+
+    /// Returns the handle of the root node and the state at the root
+    fn root(&self) -> (Self::Node, Self::State);
+
+    /// Returns the text shown inside the node, the state is the one of the node
+    fn node_label(&self, node: Self::Node, state: &Self::State) -> String;
+
+    /// Returns all children of the node together with the label of the connecting edge
+    fn children(&self, node: Self::Node, state: &Self::State) -> Vec<(String, Self::Node)>;
+
+    /// Changes the state of the parent into the state of its child `node`
+    fn enter(&self, _node: Self::Node, _state: &mut Self::State) {}
+
+    /// Changes the state of `node` back into the state of its parent
+    fn leave(&self, _node: Self::Node, _state: &mut Self::State) {}
+}
+
+/// Returns the DOT representation of the tree, cut off after `max_depth` edges from the root
+///
+/// The output can be rendered with the standard Graphviz tools, e.g. `dot -Tsvg tree.dot`.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::export::export_dot;
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::record::RecordedGame;
+/// let mut record = RecordedGame::new();
+/// record.push(Action::from_san("e4", &Game::startpos()).unwrap());
+/// let dot = export_dot(&record, 10);
+/// assert!(dot.starts_with("digraph tree {"));
+/// assert!(dot.contains("n0 -> n1 [label=\"e2-e4\"];"));
+/// ```
+pub fn export_dot<T: DotTree>(tree: &T, max_depth: usize) -> String {
+    let mut dot = String::from("digraph tree {\n    node [shape=box, fontname=\"monospace\"];\n");
+    let (root, mut state) = tree.root();
+    dot.push_str(&format!(
+        "    n0 [label=\"{}\"];\n",
+        escape(&tree.node_label(root, &state))
+    ));
+    let mut walk = Walk {
+        tree,
+        max_depth,
+        next_id: 1,
+        dot,
+    };
+    walk.visit(root, 0, 0, &mut state);
+    walk.dot.push_str("}\n");
+    walk.dot
+}
+
+/// The progress of [`export_dot`] through a tree
+///
+/// [`export_dot`]: fn.export_dot.html
+struct Walk<'a, T> {
+    tree: &'a T,
+    max_depth: usize,
+    next_id: usize,
+    dot: String,
+}
+
+impl<'a, T: DotTree> Walk<'a, T> {
+    /// Writes the children of the node with the given id and everything below them
+    fn visit(&mut self, node: T::Node, id: usize, depth: usize, state: &mut T::State) {
+        if depth >= self.max_depth {
+            return;
+        }
+        for (edge, child) in self.tree.children(node, state) {
+            let child_id = self.next_id;
+            self.next_id += 1;
+            self.tree.enter(child, state);
+            self.dot.push_str(&format!(
+                "    n{} [label=\"{}\"];\n    n{} -> n{} [label=\"{}\"];\n",
+                child_id,
+                escape(&self.tree.node_label(child, state)),
+                id,
+                child_id,
+                escape(&edge)
+            ));
+            self.visit(child, child_id, depth + 1, state);
+            self.tree.leave(child, state);
+        }
+    }
+}
+
+/// Escapes a string for use inside a quoted DOT id
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// A recorded game is exported as a single line of positions, labelled by their FEN
+///
+/// The walk never returns to a position of the line, so leaving a node keeps the position.
+impl DotTree for RecordedGame {
+    type Node = usize;
+    type State = Game;
+
+    fn root(&self) -> (usize, Game) {
+        (0, self.position_at(0))
+    }
+
+    fn node_label(&self, _ply: usize, game: &Game) -> String {
+        game.to_fen()
+    }
+
+    fn children(&self, ply: usize, _game: &Game) -> Vec<(String, usize)> {
+        match self.actions().get(ply) {
+            Some(action) => vec![(move_label(action), ply + 1)],
+            None => Vec::new(),
+        }
+    }
+
+    fn enter(&self, ply: usize, game: &mut Game) {
+        game.execute_action(&self.actions()[ply - 1]);
+    }
+}
+
+/// A game tree is exported with all of its variations, positions are labelled by their FEN
+impl DotTree for GameTree {
+    type Node = NodeId;
+    type State = (Game, Vec<UndoToken>);
+
+    fn root(&self) -> (NodeId, (Game, Vec<UndoToken>)) {
+        let game = Game::from_fen(self.start_fen()).expect("start fen was validated");
+        (GameTree::root(self), (game, Vec::new()))
+    }
+
+    fn node_label(&self, _node: NodeId, (game, _): &(Game, Vec<UndoToken>)) -> String {
+        game.to_fen()
+    }
+
+    fn children(&self, node: NodeId, _state: &(Game, Vec<UndoToken>)) -> Vec<(String, NodeId)> {
+        GameTree::children(self, node)
+            .iter()
+            .filter_map(|&child| Some((move_label(&self.action(child)?), child)))
+            .collect()
+    }
+
+    fn enter(&self, node: NodeId, state: &mut (Game, Vec<UndoToken>)) {
+        if let Some(action) = self.action(node) {
+            execute(state, &action);
+        }
+    }
+
+    fn leave(&self, node: NodeId, state: &mut (Game, Vec<UndoToken>)) {
+        if let Some(action) = self.action(node) {
+            undo(state, &action);
+        }
+    }
+}
+
+/// A repertoire is exported as the opening tree of its prepared moves
+///
+/// Nodes are the actions leading to the positions, so transpositions are exported once per
+/// line reaching them.
+impl DotTree for Repertoire {
+    type Node = Option<Action>;
+    type State = (Game, Vec<UndoToken>);
+
+    fn root(&self) -> (Option<Action>, (Game, Vec<UndoToken>)) {
+        let game = Game::from_fen(self.start_fen()).expect("start fen was validated");
+        (None, (game, Vec::new()))
+    }
+
+    fn node_label(&self, _node: Option<Action>, (game, _): &(Game, Vec<UndoToken>)) -> String {
+        game.to_fen()
+    }
+
+    fn children(
+        &self,
+        _node: Option<Action>,
+        (game, _): &(Game, Vec<UndoToken>),
+    ) -> Vec<(String, Option<Action>)> {
+        self.moves(game)
+            .iter()
+            .map(|action| (move_label(action), Some(*action)))
+            .collect()
+    }
+
+    fn enter(&self, node: Option<Action>, state: &mut (Game, Vec<UndoToken>)) {
+        if let Some(action) = node {
+            execute(state, &action);
+        }
+    }
+
+    fn leave(&self, node: Option<Action>, state: &mut (Game, Vec<UndoToken>)) {
+        if let Some(action) = node {
+            undo(state, &action);
+        }
+    }
+}
+
+/// Executes the action and keeps the token to take it back
+fn execute((game, tokens): &mut (Game, Vec<UndoToken>), action: &Action) {
+    tokens.push(game.execute_action(action));
+}
+
+/// Takes back the action executed last
+fn undo((game, tokens): &mut (Game, Vec<UndoToken>), action: &Action) {
+    let token = tokens.pop().expect("one token per executed action");
+    game.undo_action(action, token);
+}
+
+/// A search tree is exported with the score of every action, the root shows its FEN as well
+impl DotTree for SearchTree {
+    type Node = usize;
+    type State = ();
+
+    fn root(&self) -> (usize, ()) {
+        (SearchTree::root(self), ())
+    }
+
+    fn node_label(&self, node: usize, _state: &()) -> String {
+        let score = self.node(node).score;
+        if node == SearchTree::root(self) {
+            format!("{} ({})", self.start_fen(), score)
+        } else {
+            score.to_string()
+        }
+    }
+
+    fn children(&self, node: usize, _state: &()) -> Vec<(String, usize)> {
+        self.node(node)
+            .children
+            .iter()
+            .filter_map(|&child| Some((move_label(&self.node(child).action?), child)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A complete binary tree of the given height, nodes are numbered in heap order
+    struct BinaryTree(usize);
+
+    impl DotTree for BinaryTree {
+        type Node = usize;
+        type State = ();
+
+        fn root(&self) -> (usize, ()) {
+            (1, ())
+        }
+
+        fn node_label(&self, node: usize, _state: &()) -> String {
+            format!("node \"{}\"", node)
+        }
+
+        fn children(&self, node: usize, _state: &()) -> Vec<(String, usize)> {
+            if node >= 1 << self.0 {
+                return Vec::new();
+            }
+            vec![
+                (String::from("left"), node * 2),
+                (String::from("right"), node * 2 + 1),
+            ]
+        }
+    }
+
+    #[test]
+    fn depth_limit() {
+        let dot = export_dot(&BinaryTree(10), 2);
+        assert_eq!(dot.matches("->").count(), 6);
+        assert_eq!(dot.matches("[label=\"node").count(), 7);
+        assert!(dot.contains("label=\"node \\\"1\\\"\""));
+
+        let dot = export_dot(&BinaryTree(2), 10);
+        assert_eq!(dot.matches("->").count(), 6);
+        let dot = export_dot(&BinaryTree(2), 0);
+        assert_eq!(dot.matches("->").count(), 0);
+    }
+
+    #[test]
+    fn recorded_game_is_a_line() {
+        use crate::game_representation::Game;
+        use crate::move_generation::Action;
+
+        let mut record = RecordedGame::new();
+        let mut game = Game::startpos();
+        for san in &["e4", "e5", "Nf3", "Nc6"] {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+            record.push(action);
+        }
+        let dot = export_dot(&record, 3);
+        assert_eq!(dot.matches("->").count(), 3);
+        assert!(dot.contains("n2 -> n3 [label=\"Ng1-f3\"];"));
+        assert!(!dot.contains(&game.to_fen()));
+        assert!(export_dot(&record, 4).contains(&game.to_fen()));
+    }
+
+    #[test]
+    fn branching_trees() {
+        use crate::move_generation::Action;
+        use crate::search::{SearchLimits, Searcher};
+
+        let mut tree = GameTree::new();
+        for san in &["e4", "e5"] {
+            tree.play(Action::from_san(san, tree.position()).unwrap())
+                .unwrap();
+        }
+        tree.back();
+        tree.play(Action::from_san("c5", tree.position()).unwrap())
+            .unwrap();
+        let sicilian = tree.position().to_fen();
+        let dot = export_dot(&tree, 10);
+        assert_eq!(dot.matches("->").count(), 3);
+        assert!(dot.contains("n1 -> n2 [label=\"e7-e5\"];"));
+        assert!(dot.contains("n1 -> n3 [label=\"c7-c5\"];"));
+        assert!(dot.contains(&format!("n3 [label=\"{}\"];", sicilian)));
+
+        let repertoire = Repertoire::from_pgn("1. e4 e5 (1... c5 2. Nf3) 2. Nf3 *").unwrap();
+        let dot = export_dot(&repertoire, 10);
+        assert_eq!(dot.matches("->").count(), 5);
+        assert_eq!(dot.matches("[label=\"Ng1-f3\"]").count(), 2);
+        assert!(dot.contains(&sicilian));
+        assert_eq!(export_dot(&repertoire, 1).matches("->").count(), 1);
+
+        let game = Game::from_fen("4k3/8/8/3q4/4P3/2N5/8/4K3 w - - 0 1").unwrap();
+        let mut searcher = Searcher::new(SearchLimits::depth(3));
+        searcher.record_tree(2);
+        let result = searcher.search(&game);
+        let search_tree = searcher.search_tree().unwrap();
+        let root = search_tree.node(search_tree.root());
+        assert!(root.children.len() > 1);
+        assert!(root
+            .children
+            .iter()
+            .any(|&child| !search_tree.node(child).children.is_empty()));
+        let dot = export_dot(search_tree, 10);
+        assert_eq!(dot.matches("->").count(), search_tree.len());
+        assert!(dot.contains(&format!("({})", result.score)));
+        assert!(dot.contains("[label=\"e4xd5\"]"));
+    }
+}
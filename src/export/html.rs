@@ -0,0 +1,155 @@
+use super::labels::move_label;
+use crate::game_representation::{piece_glyph, Board, Color, Game};
+use crate::record::RecordedGame;
+
+const LIGHT_SQUARE: &str = "#f0d9b5";
+const DARK_SQUARE: &str = "#b58863";
+
+/// Returns a self-contained HTML page showing the given game
+///
+/// The page contains a move list and one inline SVG diagram for every position of the game,
+/// the move list links to the diagram of the position reached after that move. No scripts or
+/// external resources are used, so the page can be shared as a single file.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::export::export_html;
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::record::RecordedGame;
+/// let mut record = RecordedGame::new();
+/// record.push(Action::from_san("e4", &Game::startpos()).unwrap());
+/// let html = export_html(&record);
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// assert_eq!(html.matches("<svg").count(), 2);
+/// ```
+pub fn export_html(record: &RecordedGame) -> String {
+    let mut game = Game::from_fen(record.start_fen()).expect("start fen was validated");
+    let mut moves = String::new();
+    let mut diagrams = String::new();
+    diagrams.push_str(&position_html(0, "Starting position", &game));
+
+    for (index, action) in record.actions().iter().enumerate() {
+        let ply = index + 1;
+        let label = move_label(action);
+        let number = game.get_full_move_clock();
+        let white_to_move = game.color_to_move == Color::White;
+        // every move number gets its own list entry, a game may start with a move of black
+        if white_to_move || index == 0 {
+            if index > 0 {
+                moves.push_str("</li>\n");
+            }
+            moves.push_str(&format!("<li value=\"{}\">", number));
+        }
+        moves.push_str(&format!("<a href=\"#ply-{}\">{}</a> ", ply, label));
+
+        game.execute_action(action);
+        let title = if white_to_move {
+            format!("{}. {}", number, label)
+        } else {
+            format!("{}... {}", number, label)
+        };
+        diagrams.push_str(&position_html(ply, &title, &game));
+    }
+    if !record.is_empty() {
+        moves.push_str("</li>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Game viewer</title>\n\
+         <style>\n\
+         body {{ font-family: sans-serif; }}\n\
+         .position {{ display: inline-block; margin: 1em; }}\n\
+         .position svg {{ width: 320px; height: 320px; }}\n\
+         .fen {{ font-family: monospace; font-size: small; }}\n\
+         </style>\n</head>\n<body>\n<ol class=\"moves\">\n{}</ol>\n{}</body>\n</html>\n",
+        moves, diagrams
+    )
+}
+
+/// Returns the html block with a heading, the diagram and the FEN of a single position
+fn position_html(ply: usize, title: &str, game: &Game) -> String {
+    format!(
+        "<div class=\"position\" id=\"ply-{}\">\n<h3>{}</h3>\n{}<p class=\"fen\">{}</p>\n</div>\n",
+        ply,
+        title,
+        board_svg(&game.board),
+        game.to_fen()
+    )
+}
+
+/// Returns an inline SVG diagram of the board with white at the bottom
+fn board_svg(board: &Board) -> String {
+    let mut svg = String::from("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 8 8\">\n");
+    for index in 0..64u8 {
+        let (file, rank) = (index % 8, index / 8);
+        let color = if (file + rank) % 2 == 0 {
+            LIGHT_SQUARE
+        } else {
+            DARK_SQUARE
+        };
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"1\" height=\"1\" fill=\"{}\"/>\n",
+            file, rank, color
+        ));
+        if let Some(glyph) = piece_glyph(board.get_piecestr_at(index)) {
+            svg.push_str(&format!(
+                "<text x=\"{}.5\" y=\"{}.8\" font-size=\"0.85\" text-anchor=\"middle\">{}</text>\n",
+                file, rank, glyph
+            ));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::move_generation::Action;
+
+    #[test]
+    fn html_contains_moves_and_diagrams() {
+        let mut record = RecordedGame::new();
+        let mut game = Game::startpos();
+        for san in &["e4", "e5", "Nf3"] {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+            record.push(action);
+        }
+        let html = export_html(&record);
+        assert_eq!(html.matches("<svg").count(), 4);
+        assert!(html.contains("<a href=\"#ply-1\">e2-e4</a>"));
+        assert!(html.contains("<a href=\"#ply-3\">Ng1-f3</a>"));
+        assert!(html.contains("id=\"ply-3\""));
+        assert!(html.contains(&game.to_fen()));
+        assert!(!html.contains("<script"));
+        // 32 pieces in the starting position
+        let start = &html[html.find("id=\"ply-0\"").unwrap()..html.find("id=\"ply-1\"").unwrap()];
+        assert_eq!(start.matches("<text").count(), 32);
+    }
+
+    #[test]
+    fn black_moves_first() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 20";
+        let mut record = RecordedGame::from_fen(fen).unwrap();
+        let mut game = Game::from_fen(fen).unwrap();
+        for san in &["Kd7", "e4"] {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+            record.push(action);
+        }
+        let html = export_html(&record);
+        assert!(html.contains("<li value=\"20\"><a href=\"#ply-1\">"));
+        assert!(html.contains("<li value=\"21\"><a href=\"#ply-2\">e2-e4</a>"));
+        assert!(html.contains("<h3>20... "));
+        assert!(html.contains("<h3>21. e2-e4</h3>"));
+    }
+
+    #[test]
+    fn empty_game() {
+        let html = export_html(&RecordedGame::new());
+        assert_eq!(html.matches("<svg").count(), 1);
+        assert!(!html.contains("<li"));
+    }
+}
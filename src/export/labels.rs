@@ -0,0 +1,27 @@
+use crate::core::bitboard;
+use crate::game_representation::PieceType;
+use crate::move_generation::Action;
+
+/// Returns a long algebraic label like `Ng1-f3`, `e7xd8=Q` or `O-O-O` for the action
+pub(crate) fn move_label(action: &Action) -> String {
+    if action.is_castling() {
+        return if action.is_kingside_castling() {
+            String::from("O-O")
+        } else {
+            String::from("O-O-O")
+        };
+    }
+    let mut label = String::new();
+    if action.get_piecetype() != PieceType::Pawn {
+        label.push(bitboard::piecetype_to_char(action.get_piecetype()));
+    }
+    label
+        .push_str(&bitboard::index_to_field_repr(action.get_from_index()).expect("index is valid"));
+    label.push(if action.is_capture() { 'x' } else { '-' });
+    label.push_str(&bitboard::index_to_field_repr(action.get_to_index()).expect("index is valid"));
+    if let Some(piece) = action.get_promotion_piece() {
+        label.push('=');
+        label.push(bitboard::piecetype_to_char(piece));
+    }
+    label
+}
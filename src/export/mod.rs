@@ -0,0 +1,8 @@
+//! Exporting games and positions to formats meant for humans and external tools
+
+mod dot;
+mod html;
+mod labels;
+
+pub use dot::{export_dot, DotTree};
+pub use html::export_html;
@@ -0,0 +1,219 @@
+//! C bindings of games, enabled with the `ffi` feature
+//!
+//! Games are passed to C as opaque handles created by [`chess_game_new`] or
+//! [`chess_game_from_fen`] and released by [`chess_game_free`]. Strings returned by the
+//! bindings are owned by the caller and released by [`chess_string_free`]. Functions never
+//! unwind into C: invalid input and internal panics are reported by a null pointer or a
+//! negative status code.
+//!
+//! A shared library for C is built with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib`, the crate type is not set in
+//! the manifest as a `no_std` build can not produce a dynamic library.
+//!
+//! ```c
+//! ChessGame *game = chess_game_new();
+//! chess_game_push_uci(game, "e2e4");
+//! char *fen = chess_game_fen(game);
+//! chess_string_free(fen);
+//! chess_game_free(game);
+//! ```
+//!
+//! [`chess_game_new`]: fn.chess_game_new.html
+//! [`chess_game_from_fen`]: fn.chess_game_from_fen.html
+//! [`chess_game_free`]: fn.chess_game_free.html
+//! [`chess_string_free`]: fn.chess_string_free.html
+
+use crate::core::ParserError;
+use crate::game_representation::Game;
+use crate::move_generation::{movegen, Action, LegalMove};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// The status of a successful call
+pub const CHESS_OK: c_int = 0;
+/// The status of a call with a null pointer or a string that is not valid UTF-8
+pub const CHESS_INVALID_ARGUMENT: c_int = -1;
+/// The status of a call with a move that can not be parsed or is not legal
+pub const CHESS_ILLEGAL_MOVE: c_int = -2;
+/// The status of a call that panicked, the game is left unchanged
+pub const CHESS_INTERNAL_ERROR: c_int = -3;
+
+/// The opaque handle of a game
+pub struct ChessGame {
+    game: Game,
+}
+
+/// Returns a new game in the standard starting position
+#[no_mangle]
+pub extern "C" fn chess_game_new() -> *mut ChessGame {
+    Box::into_raw(Box::new(ChessGame {
+        game: Game::startpos(),
+    }))
+}
+
+/// Returns a new game of the Forsyth-Edwards Notation, or null if it can not be parsed
+///
+/// # Safety
+/// `fen` has to be null or point to a null terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_from_fen(fen: *const c_char) -> *mut ChessGame {
+    let fen = match to_str(fen) {
+        Some(fen) => fen,
+        None => return ptr::null_mut(),
+    };
+    match catch(|| Game::from_fen(fen)) {
+        Some(Ok(game)) => Box::into_raw(Box::new(ChessGame { game })),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Releases a game, doing nothing for null
+///
+/// # Safety
+/// `game` has to be null or a handle that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_free(game: *mut ChessGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Returns the Forsyth-Edwards Notation of the game, or null for a null handle
+///
+/// # Safety
+/// `game` has to be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_fen(game: *const ChessGame) -> *mut c_char {
+    match game.as_ref() {
+        Some(game) => to_c_string(catch(|| game.game.to_fen())),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Returns all legal moves in UCI notation separated by spaces, or null for a null handle
+///
+/// The string is empty if the game is over.
+///
+/// # Safety
+/// `game` has to be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_legal_moves(game: *const ChessGame) -> *mut c_char {
+    let game = match game.as_ref() {
+        Some(game) => game,
+        None => return ptr::null_mut(),
+    };
+    to_c_string(catch(|| {
+        let moves: Vec<String> = movegen::legal_moves(&game.game)
+            .iter()
+            .map(Action::to_uci)
+            .collect();
+        moves.join(" ")
+    }))
+}
+
+/// Executes a legal move given in UCI notation and returns the status
+///
+/// # Safety
+/// `game` has to be null or a valid handle, `uci` has to be null or point to a null terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_push_uci(game: *mut ChessGame, uci: *const c_char) -> c_int {
+    let (game, uci) = match (game.as_mut(), to_str(uci)) {
+        (Some(game), Some(uci)) => (game, uci),
+        _ => return CHESS_INVALID_ARGUMENT,
+    };
+    let result = catch(|| -> Result<Game, ParserError> {
+        let action = LegalMove::validate(Action::from_uci(uci, &game.game)?, &game.game)?;
+        let mut after = game.game.clone();
+        after.play(action)?;
+        Ok(after)
+    });
+    match result {
+        Some(Ok(after)) => {
+            game.game = after;
+            CHESS_OK
+        }
+        Some(Err(_)) => CHESS_ILLEGAL_MOVE,
+        None => CHESS_INTERNAL_ERROR,
+    }
+}
+
+/// Releases a string returned by the bindings, doing nothing for null
+///
+/// # Safety
+/// `string` has to be null or a string returned by the bindings that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn chess_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Returns the string behind the pointer, `None` for null or invalid UTF-8
+unsafe fn to_str<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+    CStr::from_ptr(string).to_str().ok()
+}
+
+/// Returns the result of the closure, `None` if it panicked
+fn catch<T>(f: impl FnOnce() -> T) -> Option<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).ok()
+}
+
+/// Returns the string as an owned C string, null if there is none
+fn to_c_string(string: Option<String>) -> *mut c_char {
+    string
+        .and_then(|string| CString::new(string).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the owned string and releases it
+    unsafe fn take(string: *mut c_char) -> String {
+        let owned = CStr::from_ptr(string).to_str().unwrap().to_string();
+        chess_string_free(string);
+        owned
+    }
+
+    #[test]
+    fn handles_and_strings() {
+        unsafe {
+            let game = chess_game_new();
+            assert_eq!(take(chess_game_legal_moves(game)).split(' ').count(), 20);
+            // the rook can not pass the pawns, and white may not move black's pieces
+            for uci in ["a1a8", "e7e5", "e1e8"] {
+                let uci = CString::new(uci).unwrap();
+                assert_eq!(chess_game_push_uci(game, uci.as_ptr()), CHESS_ILLEGAL_MOVE);
+            }
+            let e4 = CString::new("e2e4").unwrap();
+            assert_eq!(chess_game_push_uci(game, e4.as_ptr()), CHESS_OK);
+            assert_eq!(chess_game_push_uci(game, e4.as_ptr()), CHESS_ILLEGAL_MOVE);
+            assert_eq!(
+                chess_game_push_uci(game, ptr::null()),
+                CHESS_INVALID_ARGUMENT
+            );
+            let fen = take(chess_game_fen(game));
+            assert_eq!(
+                fen,
+                "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+            );
+            chess_game_free(game);
+
+            let fen = CString::new(fen).unwrap();
+            let game = chess_game_from_fen(fen.as_ptr());
+            assert!(!game.is_null());
+            chess_game_free(game);
+            let invalid = CString::new("8/8 w").unwrap();
+            assert!(chess_game_from_fen(invalid.as_ptr()).is_null());
+            assert!(chess_game_fen(ptr::null()).is_null());
+            chess_game_free(ptr::null_mut());
+        }
+    }
+}
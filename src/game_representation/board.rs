@@ -1,6 +1,10 @@
 use super::{Color, PieceType};
-use crate::core::{bitboard, ParserError};
+use crate::core::bitboard::{self, Bitboard};
+use crate::core::{FenField, ParserError, Square};
 use crate::move_generation::{Action, ActionType};
+use alloc::{format, string::String, string::ToString, vec::Vec};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// The board part of a chess game state
 ///
@@ -23,6 +27,27 @@ use crate::move_generation::{Action, ActionType};
 ///   +-------------------------+   +---------------------------------+
 ///      a  b  c  d  e  f  g  h        a   b   c   d   e   f   g   h
 /// ```
+///
+/// Two boards are equal if all of their bitboards are equal.
+///
+/// With the `mailbox` feature enabled, the board additionally keeps a 64 byte array with the
+/// piece on every field, so [`piece_at`] and [`get_piecetype_on`] are a single array read. The
+/// array is kept up to date by [`execute_action`] and [`undo_action`]; changing the public
+/// bitboards directly leaves it stale until [`sync_mailbox`] is called.
+///
+/// The number of pieces and the material of both colors are cached as well and kept up to date
+/// the same way, so [`piece_count`] and [`material`] do not count bits. After changing the
+/// bitboards directly, [`sync_piece_counts`] recomputes them.
+///
+/// [`piece_at`]: #method.piece_at
+/// [`get_piecetype_on`]: #method.get_piecetype_on
+/// [`execute_action`]: #method.execute_action
+/// [`undo_action`]: #method.undo_action
+/// [`sync_mailbox`]: #method.sync_mailbox
+/// [`piece_count`]: #method.piece_count
+/// [`material`]: #method.material
+/// [`sync_piece_counts`]: #method.sync_piece_counts
+#[derive(Clone, Debug)]
 pub struct Board {
     pub bishops: u64,
     pub rooks: u64,
@@ -30,13 +55,20 @@ pub struct Board {
     pub whites: u64,
     pub pawns: u64,
     pub kings: u64,
+    // piece on every field: 0 for empty, otherwise the piecetype with the color in bit 3
+    #[cfg(feature = "mailbox")]
+    mailbox: [u8; 64],
+    // pieces per color, indexed by the value of the piece type
+    counts: [[u8; 7]; 2],
+    // value of all pieces but the king per color
+    material: [i32; 2],
 }
 
 impl Board {
     /// Returns a board initialized with the standard chess starting position
     /// # Examples
     /// ```
-    /// # use core::game_representation::Board;
+    /// # use rust_chess::game_representation::Board;
     /// assert_eq!(&Board::startpos().to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR");
     /// ```
     pub fn startpos() -> Board {
@@ -52,14 +84,33 @@ impl Board {
             .expect("Error in parsing bishop position");
         let whites = bitboard::from_repr("8/8/8/8/8/8/00000000/00000000")
             .expect("Error in parsing white position");
-        Board {
+        Board::from_bitboards(pawns, rooks, knights, kings, bishops, whites)
+    }
+
+    /// Returns a board of the given bitboards, filling the mailbox if it is enabled
+    pub(crate) fn from_bitboards(
+        pawns: u64,
+        rooks: u64,
+        knights: u64,
+        kings: u64,
+        bishops: u64,
+        whites: u64,
+    ) -> Board {
+        let mut board = Board {
             pawns,
             rooks,
             knights,
             kings,
             bishops,
             whites,
-        }
+            #[cfg(feature = "mailbox")]
+            mailbox: [0; 64],
+            counts: [[0; 7]; 2],
+            material: [0; 2],
+        };
+        board.sync_mailbox();
+        board.sync_piece_counts();
+        board
     }
 
     /// This method will execute any action on the board.
@@ -69,10 +120,14 @@ impl Board {
     /// There is no checking if a check occurs through this action or king is captured or a king is even
     /// on the board.
     ///
+    /// The color is the color of the player executing the action, as actions do not store it.
+    /// Castling also moves the rook, promotions replace the pawn by the promoted piece and a
+    /// pawn capturing onto an empty field removes the pawn captured en passant.
+    ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::{Board, Color, PieceType};
-    /// # use core::move_generation::{Action, ActionType};
+    /// # use rust_chess::game_representation::{Board, Color, PieceType};
+    /// # use rust_chess::move_generation::{Action, ActionType};
     /// let mut b = Board::startpos();
     /// let a = Action::new((4, 6), (4, 4), PieceType::Pawn, ActionType::Quiet); // this is e2e4
     /// b.execute_action(&a, Color::White);
@@ -85,6 +140,10 @@ impl Board {
         let not_from_bit = !(1 << shift_from);
         let not_to_bit = !(1 << shift_to);
         let piecetype = action.get_piecetype();
+        // a pawn capturing onto an empty field can only be an en passant capture
+        let is_en_passant = piecetype == PieceType::Pawn
+            && action.get_action_type() == ActionType::Capture(PieceType::Pawn)
+            && self.get_piecetype_on(shift_to).is_none();
 
         let pawn_to_bit = ((piecetype == PieceType::Pawn) as u64) << shift_to;
         let knight_to_bit = ((piecetype == PieceType::Knight) as u64) << shift_to;
@@ -141,58 +200,282 @@ impl Board {
             }
             ActionType::Castling(is_kingside_castling) => {
                 // castling already has the king set correctly so only move the rook
-                // branching is fine, as this case is already so rare
-                match color {
-                    Color::White => {
-                        if is_kingside_castling {
-                            // rook is moved from h1 to f1 always
-                            let not_from_bit =
-                                !(1u64 << bitboard::field_repr_to_index("h1").expect("is checked"));
-                            let to_bit =
-                                1u64 << bitboard::field_repr_to_index("f1").expect("is checked");
-                            self.whites &= not_from_bit;
-                            self.rooks &= not_from_bit;
-                            self.whites |= to_bit;
-                            self.rooks |= to_bit;
-                        } else {
-                            // rook is moved from a1 to d1 always
-                            let not_from_bit =
-                                !(1u64 << bitboard::field_repr_to_index("a1").expect("is checked"));
-                            let to_bit =
-                                1u64 << bitboard::field_repr_to_index("d1").expect("is checked");
-                            self.whites &= not_from_bit;
-                            self.rooks &= not_from_bit;
-                            self.whites |= to_bit;
-                            self.rooks |= to_bit;
-                        }
-                    }
-                    Color::Black => {
-                        if is_kingside_castling {
-                            // rook is moved from h8 to f8 always
-                            let not_from_bit =
-                                !(1u64 << bitboard::field_repr_to_index("h8").expect("is checked"));
-                            let to_bit =
-                                1u64 << bitboard::field_repr_to_index("f8").expect("is checked");
-                            self.whites &= not_from_bit;
-                            self.rooks &= not_from_bit;
-                            self.rooks |= to_bit;
-                        } else {
-                            // rook is moved from a8 to d8 always
-                            let not_from_bit =
-                                !(1u64 << bitboard::field_repr_to_index("a8").expect("is checked"));
-                            let to_bit =
-                                1u64 << bitboard::field_repr_to_index("d8").expect("is checked");
-                            self.whites &= not_from_bit;
-                            self.rooks &= not_from_bit;
-                            self.rooks |= to_bit;
-                        }
-                    }
-                }
+                let (rook_from, rook_to) = castling_rook_fields(color, is_kingside_castling);
+                self.clear_field(rook_from);
+                self.set_field(rook_to, PieceType::Rook, color);
             }
             _ => {
                 // don't need to do anything for captures or quiet moves
             }
         };
+
+        if is_en_passant {
+            // the captured pawn stands behind the field the capturing pawn moved to
+            match color {
+                Color::White => self.clear_field(shift_to + 8),
+                Color::Black => self.clear_field(shift_to - 8),
+            }
+        }
+        self.count_action(action, color, 1);
+        self.sync_mailbox_after(action);
+        self.debug_validate();
+    }
+
+    /// Reverts an action previously executed with [`execute_action`]
+    ///
+    /// The color is the color of the player that executed the action. Just like
+    /// [`execute_action`] this does not check anything, undoing an action that was not the last
+    /// one executed on this board will corrupt the board.
+    /// The board does not know whether a pawn capture was en passant, so the captured pawn is
+    /// always restored on the destination field. [`Game::undo_action`] corrects this.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Board, Color, PieceType};
+    /// # use rust_chess::move_generation::{Action, ActionType};
+    /// let mut b = Board::startpos();
+    /// let a = Action::new((4, 6), (4, 4), PieceType::Pawn, ActionType::Quiet); // this is e2e4
+    /// b.execute_action(&a, Color::White);
+    /// b.undo_action(&a, Color::White);
+    /// assert_eq!("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", &b.to_fen());
+    /// ```
+    ///
+    /// [`execute_action`]: #method.execute_action
+    /// [`Game::undo_action`]: struct.Game.html#method.undo_action
+    pub fn undo_action(&mut self, action: &Action, color: Color) {
+        let shift_from = action.get_from_index();
+        let shift_to = action.get_to_index();
+
+        // promoted pieces turn back into pawns
+        let piecetype = if action.is_promotion() {
+            PieceType::Pawn
+        } else {
+            action.get_piecetype()
+        };
+        self.clear_field(shift_to);
+        self.set_field(shift_from, piecetype, color);
+
+        if let Some(captured) = action.get_capture_piece() {
+            self.set_field(shift_to, captured, color.get_opponent_color());
+        }
+
+        if let ActionType::Castling(is_kingside_castling) = action.get_action_type() {
+            // king is already back, the rook still has to be moved to its corner
+            let (rook_from, rook_to) = castling_rook_fields(color, is_kingside_castling);
+            self.clear_field(rook_to);
+            self.set_field(rook_from, PieceType::Rook, color);
+        }
+        self.count_action(action, color, -1);
+        self.debug_validate();
+    }
+
+    /// Checks the invariants of the bitboard encoding
+    ///
+    /// * No field holds more than one piece type, except queens set on the bishop and rook bitboards
+    /// * Only occupied fields are marked as white
+    /// * Every color has at most one king, unless the `analysis-board` feature is enabled
+    /// * With the `mailbox` feature, the mailbox matches the bitboards
+    /// * The cached piece counts match the bitboards
+    ///
+    /// With the `debug-validate` feature enabled, this is checked after every executed or undone
+    /// action and a violation panics.
+    ///
+    /// # Errors
+    /// * The first violated invariant together with the field it was found on
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Board;
+    /// assert!(Board::startpos().check_invariants().is_ok());
+    /// let mut b = Board::startpos();
+    /// b.knights |= b.pawns;
+    /// assert_eq!(b.check_invariants().unwrap_err().square.to_string(), "a7");
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let sliders = self.bishops | self.rooks;
+        let sets = [self.pawns, self.knights, self.kings, sliders];
+        for (i, first) in sets.iter().enumerate() {
+            for second in &sets[i + 1..] {
+                if let Some(square) = Bitboard(first & second).first() {
+                    return Err(InvariantViolation {
+                        square,
+                        reason: "Field holds more than one piece",
+                    });
+                }
+            }
+        }
+        if let Some(square) = Bitboard(self.whites & !self.occupancy().0).first() {
+            return Err(InvariantViolation {
+                square,
+                reason: "Empty field is marked as white",
+            });
+        }
+        for color in [Color::White, Color::Black] {
+            let kings = self.pieces_of(PieceType::King, color);
+            if kings.count() > 1 && !cfg!(feature = "analysis-board") {
+                return Err(InvariantViolation {
+                    square: kings.last().expect("more than one king"),
+                    reason: "More than one king of a color",
+                });
+            }
+        }
+        #[cfg(feature = "mailbox")]
+        {
+            if let Some(square) = Square::all()
+                .find(|s| self.mailbox[s.index() as usize] != self.mailbox_entry(s.index()))
+            {
+                return Err(InvariantViolation {
+                    square,
+                    reason: "Mailbox does not match the bitboards",
+                });
+            }
+        }
+        let mut counted = self.clone();
+        counted.sync_piece_counts();
+        if counted.counts != self.counts {
+            let square = Square::all()
+                .find(|s| {
+                    self.piece_at(*s).is_some_and(|(piece, color)| {
+                        counted.piece_count(piece, color) != self.piece_count(piece, color)
+                    })
+                })
+                .unwrap_or_else(|| Square::from_index(0).expect("a8 is on the board"));
+            return Err(InvariantViolation {
+                square,
+                reason: "Piece counts do not match the bitboards",
+            });
+        }
+        Ok(())
+    }
+
+    /// Recomputes the mailbox from the bitboards
+    ///
+    /// Only needed after changing the public bitboards directly, all other methods keep the
+    /// mailbox up to date. Does nothing without the `mailbox` feature.
+    pub fn sync_mailbox(&mut self) {
+        #[cfg(feature = "mailbox")]
+        {
+            for shift in 0..64 {
+                self.mailbox[shift as usize] = self.mailbox_entry(shift);
+            }
+        }
+    }
+
+    /// Recomputes the cached piece counts and material from the bitboards
+    ///
+    /// Only needed after changing the public bitboards directly, all other methods keep the
+    /// counts up to date.
+    pub fn sync_piece_counts(&mut self) {
+        self.counts = [[0; 7]; 2];
+        self.material = [0; 2];
+        for color in [Color::White, Color::Black] {
+            for piece in [
+                PieceType::King,
+                PieceType::Pawn,
+                PieceType::Knight,
+                PieceType::Rook,
+                PieceType::Queen,
+                PieceType::Bishop,
+            ] {
+                let count = self.pieces_of(piece, color).count() as i8;
+                self.adjust_count(piece, color, count);
+            }
+        }
+    }
+
+    /// Adds the pieces captured or promoted by an action to the counts, `sign` is -1 for undoing
+    #[inline(always)]
+    fn count_action(&mut self, action: &Action, color: Color, sign: i8) {
+        if let Some(captured) = action.get_capture_piece() {
+            self.adjust_count(captured, color.get_opponent_color(), -sign);
+        }
+        if let Some(promoted) = action.get_promotion_piece() {
+            self.adjust_count(PieceType::Pawn, color, -sign);
+            self.adjust_count(promoted, color, sign);
+        }
+    }
+
+    #[inline(always)]
+    fn adjust_count(&mut self, piece: PieceType, color: Color, change: i8) {
+        let count = &mut self.counts[color as usize][piece as usize];
+        *count = count.wrapping_add(change as u8);
+        if piece != PieceType::King {
+            self.material[color as usize] += i32::from(change) * piece.get_value();
+        }
+    }
+
+    /// Updates the mailbox entries of the source and destination field of an executed action
+    #[inline(always)]
+    fn sync_mailbox_after(&mut self, action: &Action) {
+        #[cfg(feature = "mailbox")]
+        {
+            let shift_from = action.get_from_index();
+            let shift_to = action.get_to_index();
+            self.mailbox[shift_from as usize] = self.mailbox_entry(shift_from);
+            self.mailbox[shift_to as usize] = self.mailbox_entry(shift_to);
+        }
+        #[cfg(not(feature = "mailbox"))]
+        {
+            let _ = action;
+        }
+    }
+
+    /// Returns the mailbox encoding of the field computed from the bitboards
+    #[cfg(feature = "mailbox")]
+    fn mailbox_entry(&self, shift: u8) -> u8 {
+        match self.piecetype_from_bitboards(shift) {
+            Some(piece) => piece as u8 | ((self.whites >> shift & 1) as u8) << 3,
+            None => 0,
+        }
+    }
+
+    /// Panics on a violated invariant if the `debug-validate` feature is enabled
+    #[inline(always)]
+    fn debug_validate(&self) {
+        #[cfg(feature = "debug-validate")]
+        {
+            if let Err(violation) = self.check_invariants() {
+                panic!("Board invariant violated: {}", violation);
+            }
+        }
+    }
+
+    /// Removes any piece from the field with the given shift index
+    pub(crate) fn clear_field(&mut self, shift: u8) {
+        let not_bit = !(1u64 << shift);
+        self.rooks &= not_bit;
+        self.pawns &= not_bit;
+        self.kings &= not_bit;
+        self.bishops &= not_bit;
+        self.knights &= not_bit;
+        self.whites &= not_bit;
+        #[cfg(feature = "mailbox")]
+        {
+            self.mailbox[shift as usize] = 0;
+        }
+    }
+
+    /// Places a piece on the empty field with the given shift index
+    pub(crate) fn set_field(&mut self, shift: u8, piece: PieceType, color: Color) {
+        let bit = 1u64 << shift;
+        match piece {
+            PieceType::Pawn => self.pawns |= bit,
+            PieceType::Knight => self.knights |= bit,
+            PieceType::King => self.kings |= bit,
+            PieceType::Bishop => self.bishops |= bit,
+            PieceType::Rook => self.rooks |= bit,
+            PieceType::Queen => {
+                self.bishops |= bit;
+                self.rooks |= bit;
+            }
+        }
+        if color == Color::White {
+            self.whites |= bit;
+        }
+        #[cfg(feature = "mailbox")]
+        {
+            self.mailbox[shift as usize] = self.mailbox_entry(shift);
+        }
     }
 
     /// Returns the board-part of a FEN-string
@@ -206,7 +489,7 @@ impl Board {
             let mut files_skipped = 0;
             for file in 0..8 {
                 let piece_on = self.get_piecestr_on(file, rank);
-                if piece_on == "" {
+                if piece_on.is_empty() {
                     files_skipped += 1;
                     continue;
                 }
@@ -220,7 +503,7 @@ impl Board {
                 res_str.push_str(&format!("{}", files_skipped));
             }
             if rank != 7 {
-                res_str.push_str("/");
+                res_str.push('/');
             }
         }
         res_str
@@ -228,29 +511,82 @@ impl Board {
 
     /// Constructs a new Board from only the board-part of a FEN
     ///
+    /// # Errors
+    /// * The FEN does not consist of 8 ranks of 8 files each
+    /// * The FEN contains a character other than a piece letter or a digit from 1 to 8
+    /// * A color has more than one king, unless the `analysis-board` feature is enabled
+    ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::Board;
+    /// # use rust_chess::game_representation::Board;
     /// let b = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR").unwrap();
     /// assert_eq!(&b.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR");
+    /// assert!(Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP").is_err());
+    /// assert!(Board::from_fen("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR").is_err());
+    /// assert!(Board::from_fen("rnbqkbnr/pppppppp/44/8/8/8/PPPPPPPP/RNBQKBNR").is_ok());
+    /// assert!(Board::from_fen("rnbqkbnr/pppppppp/54/8/8/8/PPPPPPPP/RNBQKBNR").is_err());
     /// ```
     pub fn from_fen(fen: &str) -> Result<Board, ParserError> {
+        Board::parse_fen(fen, true)
+    }
+
+    /// Constructs a new Board from a hand-typed board-part of a FEN
+    ///
+    /// Works like [`from_fen`], but ranks with less than 8 files are filled up with empty fields
+    /// and a color may have more than one king. Use [`check_invariants`] or [`Game::validate`]
+    /// before searching such a board.
+    ///
+    /// # Errors
+    /// * The FEN has more than 8 ranks, less than 8 ranks or a rank with more than 8 files
+    /// * The FEN contains a character other than a piece letter or a digit from 1 to 8
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Board;
+    /// let b = Board::from_fen_lenient("4k/8/8/8/8/8/4P/4K2K").unwrap();
+    /// assert_eq!(&b.to_fen(), "4k3/8/8/8/8/8/4P3/4K2K");
+    /// assert!(Board::from_fen("4k/8/8/8/8/8/4P/4K3").is_err());
+    /// assert!(Board::from_fen_lenient("4k3/8/8/8/8/8/4P3/4K3/8").is_err());
+    /// ```
+    ///
+    /// [`from_fen`]: #method.from_fen
+    /// [`check_invariants`]: #method.check_invariants
+    /// [`Game::validate`]: struct.Game.html#method.validate
+    pub fn from_fen_lenient(fen: &str) -> Result<Board, ParserError> {
+        Board::parse_fen(fen, false)
+    }
+
+    fn parse_fen(fen: &str, strict: bool) -> Result<Board, ParserError> {
         let mut pawns = 0;
         let mut whites = 0;
         let mut knights = 0;
         let mut bishops = 0;
         let mut rooks = 0;
         let mut kings = 0;
-        for (rank, rank_str) in fen.split('/').enumerate() {
+        let error = |offset, reason| ParserError::BadFenField {
+            field: FenField::Board,
+            token: fen.to_string(),
+            offset,
+            reason,
+        };
+        let ranks: Vec<&str> = fen.split('/').collect();
+        if ranks.len() < 8 {
+            return Err(error(fen.len(), "Board fen has less than 8 ranks"));
+        }
+        let mut rank_offset = 0;
+        for (rank, rank_str) in ranks.into_iter().enumerate() {
+            if rank == 8 {
+                return Err(error(rank_offset, "Board fen has more than 8 ranks"));
+            }
             let mut file = 0;
-            for c in rank_str.chars() {
-                let shift = file + rank * 8;
-                if shift > 63 {
-                    panic!(format!(
-                        "shift is too high with file {} and rank {} fen {}",
-                        file, rank, fen
+            for (i, c) in rank_str.char_indices() {
+                if file >= 8 {
+                    return Err(error(
+                        rank_offset + i,
+                        "Rank of board fen has more than 8 files",
                     ));
                 }
+                let shift = file + rank * 8;
                 match c {
                     'p' => {
                         pawns |= 0b1 << shift;
@@ -269,6 +605,9 @@ impl Board {
                         file += 1;
                     }
                     'k' => {
+                        if strict && kings & !whites != 0 && !cfg!(feature = "analysis-board") {
+                            return Err(error(rank_offset + i, "Black has more than one king"));
+                        }
                         kings |= 0b1 << shift;
                         file += 1;
                     }
@@ -298,6 +637,9 @@ impl Board {
                         file += 1;
                     }
                     'K' => {
+                        if strict && kings & whites != 0 && !cfg!(feature = "analysis-board") {
+                            return Err(error(rank_offset + i, "White has more than one king"));
+                        }
                         kings |= 0b1 << shift;
                         whites |= 0b1 << shift;
                         file += 1;
@@ -333,30 +675,283 @@ impl Board {
                         file += 8;
                     }
                     _ => {
-                        panic!("Illegal character in board fen");
+                        return Err(error(rank_offset + i, "Illegal character in board fen"));
                     }
                 }
+                if file > 8 {
+                    return Err(error(
+                        rank_offset + i,
+                        "Rank of board fen has more than 8 files",
+                    ));
+                }
+            }
+            if strict && file != 8 {
+                return Err(error(
+                    rank_offset + rank_str.len(),
+                    "Rank of board fen has less than 8 files",
+                ));
             }
+            rank_offset += rank_str.len() + 1;
+        }
+        Ok(Board::from_bitboards(
+            pawns, rooks, knights, kings, bishops, whites,
+        ))
+    }
+
+    /// Returns the piece type and color of the piece on the given field
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::core::Square;
+    /// # use rust_chess::game_representation::{Board, Color, PieceType};
+    /// let b = Board::startpos();
+    /// let e8: Square = "e8".parse().unwrap();
+    /// assert_eq!(b.piece_at(e8), Some((PieceType::King, Color::Black)));
+    /// assert_eq!(b.piece_at("e4".parse().unwrap()), None);
+    /// ```
+    pub fn piece_at(&self, square: Square) -> Option<(PieceType, Color)> {
+        let piece = self.get_piecetype_on(square.index())?;
+        if self.whites & square.bitboard() != 0 {
+            Some((piece, Color::White))
+        } else {
+            Some((piece, Color::Black))
+        }
+    }
+
+    /// Returns the color of the piece on the given field, `None` for an empty field
+    pub fn color_at(&self, square: Square) -> Option<Color> {
+        if !self.occupancy().contains(square) {
+            None
+        } else if self.whites & square.bitboard() != 0 {
+            Some(Color::White)
+        } else {
+            Some(Color::Black)
         }
-        Ok(Board {
-            pawns,
-            rooks,
-            knights,
-            kings,
-            bishops,
-            whites,
-        })
+    }
+
+    /// Returns a bitboard of all occupied fields
+    #[inline(always)]
+    pub fn occupancy(&self) -> Bitboard {
+        Bitboard(self.pawns | self.knights | self.bishops | self.rooks | self.kings)
+    }
+
+    /// Returns a bitboard of all fields occupied by pieces of the color
+    #[inline(always)]
+    pub fn occupancy_of(&self, color: Color) -> Bitboard {
+        match color {
+            Color::White => self.occupancy() & Bitboard(self.whites),
+            Color::Black => self.occupancy() & !Bitboard(self.whites),
+        }
+    }
+
+    /// Returns the board mirrored along the middle between the d-file and the e-file
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Board;
+    /// let b = Board::from_fen("4k3/8/8/8/8/8/1P6/R3K3").unwrap();
+    /// assert_eq!(b.mirror_horizontal().to_fen(), "3k4/8/8/8/8/8/6P1/3K3R");
+    /// ```
+    pub fn mirror_horizontal(&self) -> Board {
+        self.transform(|bitboard| bitboard.reverse_bits().swap_bytes())
+    }
+
+    /// Returns the board mirrored along the middle between the fourth and fifth rank
+    ///
+    /// The pieces keep their colors, see [`flip_colors`] to swap them as well.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Board;
+    /// let b = Board::from_fen("4k3/8/8/8/8/8/1P6/R3K3").unwrap();
+    /// assert_eq!(b.flip_vertical().to_fen(), "R3K3/1P6/8/8/8/8/8/4k3");
+    /// ```
+    ///
+    /// [`flip_colors`]: #method.flip_colors
+    pub fn flip_vertical(&self) -> Board {
+        self.transform(u64::swap_bytes)
+    }
+
+    /// Returns the board rotated by 180 degrees
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Board;
+    /// let b = Board::from_fen("4k3/8/8/8/8/8/1P6/R3K3").unwrap();
+    /// assert_eq!(b.rotate(), b.flip_vertical().mirror_horizontal());
+    /// assert_eq!(b.rotate().to_fen(), "3K3R/6P1/8/8/8/8/8/3k4");
+    /// ```
+    pub fn rotate(&self) -> Board {
+        self.transform(u64::reverse_bits)
+    }
+
+    /// Returns the board with the ranks mirrored and the colors of all pieces swapped
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Board;
+    /// let b = Board::from_fen("4k3/8/8/8/8/8/4P3/4K2R").unwrap();
+    /// assert_eq!(b.flip_colors().to_fen(), "4k2r/4p3/8/8/8/8/8/4K3");
+    /// assert_eq!(Board::startpos().flip_colors(), Board::startpos());
+    /// ```
+    pub fn flip_colors(&self) -> Board {
+        let board = self.flip_vertical();
+        Board::from_bitboards(
+            board.pawns,
+            board.rooks,
+            board.knights,
+            board.kings,
+            board.bishops,
+            board.occupancy().0 & !board.whites,
+        )
+    }
+
+    /// Returns the board with the fields of every bitboard moved by `f`
+    fn transform(&self, f: impl Fn(u64) -> u64) -> Board {
+        Board::from_bitboards(
+            f(self.pawns),
+            f(self.rooks),
+            f(self.knights),
+            f(self.kings),
+            f(self.bishops),
+            f(self.whites),
+        )
+    }
+
+    /// Returns all pieces on the board with their fields, in the order from a8 to h1
+    ///
+    /// Fields are visited rank by rank starting with the eighth rank, each rank from the a-file
+    /// to the h-file, like in a FEN.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Board, Color, PieceType};
+    /// let b = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3").unwrap();
+    /// let pieces: Vec<String> = b
+    ///     .iter()
+    ///     .map(|(square, piece, color)| format!("{:?} {:?} {}", color, piece, square))
+    ///     .collect();
+    /// assert_eq!(pieces, vec!["Black King e8", "White Pawn e2", "White King e1"]);
+    /// assert_eq!(Board::startpos().iter().count(), 32);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Square, PieceType, Color)> + '_ {
+        self.occupancy().map(move |square| self.entry(square))
+    }
+
+    /// Returns the pieces of one color with their fields, in the same order as [`iter`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Board, Color, PieceType};
+    /// let b = Board::startpos();
+    /// let material: i32 = b
+    ///     .pieces(Color::Black)
+    ///     .filter(|(_, piece, _)| *piece != PieceType::King)
+    ///     .map(|(_, piece, _)| piece.get_value())
+    ///     .sum();
+    /// assert_eq!(material, 4000);
+    /// assert!(b.pieces(Color::White).all(|(_, _, color)| color == Color::White));
+    /// ```
+    ///
+    /// [`iter`]: #method.iter
+    pub fn pieces(&self, color: Color) -> impl Iterator<Item = (Square, PieceType, Color)> + '_ {
+        self.occupancy_of(color)
+            .map(move |square| self.entry(square))
+    }
+
+    /// Returns the piece on an occupied field
+    fn entry(&self, square: Square) -> (Square, PieceType, Color) {
+        let (piece, color) = self.piece_at(square).expect("field is occupied");
+        (square, piece, color)
+    }
+
+    /// Returns a bitboard of all pieces of the given type and color
+    ///
+    /// Bishops and rooks do not include queens, even though queens are stored on both of their
+    /// bitboards.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::core::bitboard::Bitboard;
+    /// # use rust_chess::game_representation::{Board, Color, PieceType};
+    /// let b = Board::startpos();
+    /// assert_eq!(b.pieces_of(PieceType::Queen, Color::White), Bitboard(1 << 59));
+    /// let rooks: Vec<String> = b.pieces_of(PieceType::Rook, Color::Black).map(|s| s.to_string()).collect();
+    /// assert_eq!(rooks, vec!["a8", "h8"]);
+    /// ```
+    pub fn pieces_of(&self, piece: PieceType, color: Color) -> Bitboard {
+        let pieces = match piece {
+            PieceType::Pawn => self.pawns,
+            PieceType::Knight => self.knights,
+            PieceType::King => self.kings,
+            PieceType::Bishop => self.bishops & !self.rooks,
+            PieceType::Rook => self.rooks & !self.bishops,
+            PieceType::Queen => self.bishops & self.rooks,
+        };
+        Bitboard(pieces) & self.occupancy_of(color)
+    }
+
+    /// Returns the number of pieces of the given type and color
+    ///
+    /// Queens are only counted as queens, like by [`pieces_of`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Board, Color, PieceType};
+    /// let b = Board::startpos();
+    /// assert_eq!(b.piece_count(PieceType::Pawn, Color::White), 8);
+    /// assert_eq!(b.piece_count(PieceType::Queen, Color::Black), 1);
+    /// ```
+    ///
+    /// [`pieces_of`]: #method.pieces_of
+    #[inline(always)]
+    pub fn piece_count(&self, piece: PieceType, color: Color) -> u32 {
+        u32::from(self.counts[color as usize][piece as usize])
+    }
+
+    /// Returns the value of all pieces of the color except the king in centipawns
+    ///
+    /// The piece values are the ones of [`PieceType::get_value`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Board, Color};
+    /// let b = Board::startpos();
+    /// assert_eq!(b.material(Color::White), 8 * 100 + 2 * 320 + 2 * 330 + 2 * 500 + 900);
+    /// assert_eq!(b.material(Color::White), b.material(Color::Black));
+    /// ```
+    ///
+    /// [`PieceType::get_value`]: enum.PieceType.html#method.get_value
+    #[inline(always)]
+    pub fn material(&self, color: Color) -> i32 {
+        self.material[color as usize]
     }
 
     /// Returns the piecetype of the given index
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::{Board, PieceType};
+    /// # use rust_chess::game_representation::{Board, PieceType};
     /// let b = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR").unwrap();
     /// assert_eq!(b.get_piecetype_on(59), Some(PieceType::Queen));
     /// ```
     pub fn get_piecetype_on(&self, index: u8) -> Option<PieceType> {
+        #[cfg(feature = "mailbox")]
+        return match self.mailbox[index as usize] & 7 {
+            0 => None,
+            1 => Some(PieceType::King),
+            2 => Some(PieceType::Pawn),
+            3 => Some(PieceType::Knight),
+            4 => Some(PieceType::Rook),
+            5 => Some(PieceType::Queen),
+            _ => Some(PieceType::Bishop),
+        };
+        #[cfg(not(feature = "mailbox"))]
+        return self.piecetype_from_bitboards(index);
+    }
+
+    /// Returns the piecetype of the given index by testing every bitboard
+    fn piecetype_from_bitboards(&self, index: u8) -> Option<PieceType> {
         if self.pawns >> index & 1 == 1 {
             return Some(PieceType::Pawn);
         }
@@ -394,7 +989,7 @@ impl Board {
     /// * Bishop: b/B
     /// * Rook: r/R
     /// * Queen: q/Q
-    fn get_piecestr_at(&self, shift: u8) -> &str {
+    pub(crate) fn get_piecestr_at(&self, shift: u8) -> &str {
         if self.pawns >> shift & 1 == 1 {
             if self.whites >> shift & 1 == 1 {
                 return "P";
@@ -435,6 +1030,81 @@ impl Board {
     }
 }
 
+/// A violated invariant of the board encoding, see [`Board::check_invariants`]
+///
+/// [`Board::check_invariants`]: struct.Board.html#method.check_invariants
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvariantViolation {
+    /// The field the violation was found on
+    pub square: Square,
+    /// Description of the violated invariant
+    pub reason: &'static str,
+}
+
+impl core::fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} on {}", self.reason, self.square)
+    }
+}
+
+/// Returns the field the rook leaves and the field it moves to when castling
+fn castling_rook_fields(color: Color, kingside: bool) -> (u8, u8) {
+    let (from, to) = match (color, kingside) {
+        (Color::White, true) => ("h1", "f1"),
+        (Color::White, false) => ("a1", "d1"),
+        (Color::Black, true) => ("h8", "f8"),
+        (Color::Black, false) => ("a8", "d8"),
+    };
+    (
+        bitboard::field_repr_to_index(from).expect("is checked"),
+        bitboard::field_repr_to_index(to).expect("is checked"),
+    )
+}
+
+impl Board {
+    /// Returns the bitboards boards are compared and hashed by, leaving out the caches
+    fn bitboards(&self) -> [u64; 6] {
+        [
+            self.bishops,
+            self.rooks,
+            self.knights,
+            self.whites,
+            self.pawns,
+            self.kings,
+        ]
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Board) -> bool {
+        self.bitboards() == other.bitboards()
+    }
+}
+
+impl Eq for Board {}
+
+impl core::hash::Hash for Board {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.bitboards().hash(state);
+    }
+}
+
+/// Boards are serialized as the board-part of a FEN
+#[cfg(feature = "serde")]
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_fen())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Board, D::Error> {
+        let fen = String::deserialize(deserializer)?;
+        Board::from_fen(&fen).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -461,6 +1131,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn special_actions() {
+        let mut b = Board::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R").unwrap();
+        let castle = Action::new((4, 0), (2, 0), PieceType::King, ActionType::Castling(false));
+        b.execute_action(&castle, Color::Black);
+        assert_eq!(b.to_fen(), "2kr3r/1P6/8/3pP3/8/8/8/R3K2R");
+        let castle = Action::new((4, 7), (6, 7), PieceType::King, ActionType::Castling(true));
+        b.execute_action(&castle, Color::White);
+        assert_eq!(b.to_fen(), "2kr3r/1P6/8/3pP3/8/8/8/R4RK1");
+        b.undo_action(&castle, Color::White);
+        assert_eq!(b.to_fen(), "2kr3r/1P6/8/3pP3/8/8/8/R3K2R");
+
+        let promotion = Action::new(
+            (1, 1),
+            (1, 0),
+            PieceType::Pawn,
+            ActionType::Promotion(PieceType::Knight),
+        );
+        b.execute_action(&promotion, Color::White);
+        assert_eq!(b.to_fen(), "1Nkr3r/8/8/3pP3/8/8/8/R3K2R");
+        let en_passant = Action::new(
+            (4, 3),
+            (3, 2),
+            PieceType::Pawn,
+            ActionType::Capture(PieceType::Pawn),
+        );
+        b.execute_action(&en_passant, Color::White);
+        assert_eq!(b.to_fen(), "1Nkr3r/8/3P4/8/8/8/8/R3K2R");
+        assert!(b.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn piece_counts_follow_actions() {
+        use crate::game_representation::Game;
+        use crate::move_generation::movegen;
+
+        fn walk(game: &mut Game, depth: u8) {
+            let mut counted = game.board.clone();
+            counted.sync_piece_counts();
+            for color in [Color::White, Color::Black] {
+                for piece in [
+                    PieceType::Pawn,
+                    PieceType::Knight,
+                    PieceType::Bishop,
+                    PieceType::Rook,
+                    PieceType::Queen,
+                    PieceType::King,
+                ] {
+                    assert_eq!(
+                        counted.piece_count(piece, color),
+                        game.board.piece_count(piece, color),
+                        "{}",
+                        game.to_fen()
+                    );
+                }
+                assert_eq!(counted.material(color), game.board.material(color));
+            }
+            if depth == 0 {
+                return;
+            }
+            for action in movegen::legal_moves(game) {
+                let token = game.execute_action(&action);
+                walk(game, depth - 1);
+                game.undo_action(&action, token);
+            }
+        }
+        // promotions with and without capture, en passant and castling
+        let mut game = Game::from_fen("r3k2r/1P6/8/3pP3/8/8/6p1/R3K2R w KQkq d6 0 1").unwrap();
+        walk(&mut game, 3);
+
+        let mut board = Board::startpos();
+        assert_eq!(board.material(Color::White), 4000);
+        board.pawns &= !(1 << 52);
+        board.whites &= !(1 << 52);
+        board.sync_mailbox();
+        // stale counts do not take part in the comparison
+        let stale = board.clone();
+        assert_eq!(
+            board.check_invariants().unwrap_err().reason,
+            "Piece counts do not match the bitboards"
+        );
+        board.sync_piece_counts();
+        assert_eq!(board.piece_count(PieceType::Pawn, Color::White), 7);
+        assert_eq!(board.material(Color::White), 3900);
+        assert!(board.check_invariants().is_ok());
+        assert_eq!(stale, board);
+    }
+
     #[test]
     fn fen_io_test() {
         assert_eq!(
@@ -590,4 +1348,154 @@ mod tests {
             "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR"
         );
     }
+
+    #[test]
+    fn piece_queries() {
+        let board = Board::from_fen("4k3/8/8/3q4/4B3/8/8/R3K3").unwrap();
+        let square = |index| Square::from_index(index).unwrap();
+        assert_eq!(
+            board.piece_at(square(27)),
+            Some((PieceType::Queen, Color::Black))
+        );
+        assert_eq!(
+            board.piece_at(square(36)),
+            Some((PieceType::Bishop, Color::White))
+        );
+        assert_eq!(board.color_at(square(56)), Some(Color::White));
+        assert_eq!(board.color_at(square(0)), None);
+        let pieces = |piece, color| board.pieces_of(piece, color).0;
+        assert_eq!(board.occupancy().count(), 5);
+        assert_eq!(board.occupancy_of(Color::Black).0, 1 << 4 | 1 << 27);
+        assert_eq!(pieces(PieceType::Bishop, Color::White), 1 << 36);
+        assert_eq!(pieces(PieceType::Bishop, Color::Black), 0);
+        assert_eq!(pieces(PieceType::Rook, Color::White), 1 << 56);
+        assert_eq!(pieces(PieceType::Queen, Color::Black), 1 << 27);
+    }
+
+    #[test]
+    fn strict_and_lenient_fen() {
+        use crate::game_representation::Game;
+
+        let reason = |fen| match Board::from_fen(fen) {
+            Err(ParserError::BadFenField { offset, reason, .. }) => (offset, reason),
+            other => panic!("{:?} parsed as {:?}", fen, other),
+        };
+        assert_eq!(
+            reason("8/8/8/8/8/8/8/8/8"),
+            (16, "Board fen has more than 8 ranks")
+        );
+        assert_eq!(
+            reason("8/8/8/8/8/8/8/4k1K1N"),
+            (19, "Rank of board fen has more than 8 files")
+        );
+        assert_eq!(
+            reason("8/8/8/8/8/8/8/4k4"),
+            (16, "Rank of board fen has more than 8 files")
+        );
+        assert_eq!(
+            reason("8/8/8/8/8/8/8/4k2"),
+            (17, "Rank of board fen has less than 8 files")
+        );
+        if !cfg!(feature = "analysis-board") {
+            assert_eq!(
+                reason("k7/8/8/8/8/8/8/k3K3"),
+                (15, "Black has more than one king")
+            );
+        }
+
+        let board = Board::from_fen_lenient("k/8/8/8/8/8/8/k3K").unwrap();
+        assert_eq!(board.to_fen(), "k7/8/8/8/8/8/8/k3K3");
+        assert!(Board::from_fen_lenient("8/8/8/8/8/8/8/4k4").is_err());
+        assert!(Board::from_fen_lenient("8/8/8/8/8/8/8/8/8").is_err());
+        assert!(Game::from_fen_lenient("k/8/8/8/8/8/8/4K w - - 0 1").is_ok());
+    }
+
+    #[test]
+    fn transformations() {
+        use crate::game_representation::Game;
+
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let mut game = Game::from_fen(fen).unwrap();
+        let board = game.board.clone();
+        for transformed in [
+            board.mirror_horizontal(),
+            board.flip_vertical(),
+            board.rotate(),
+            board.flip_colors(),
+        ] {
+            transformed.check_invariants().unwrap();
+            assert_eq!(transformed.iter().count(), board.iter().count());
+        }
+        assert_eq!(board.mirror_horizontal().mirror_horizontal(), board);
+        assert_eq!(board.flip_vertical().flip_vertical(), board);
+        assert_eq!(board.rotate().rotate(), board);
+        assert_eq!(board.flip_colors().flip_colors(), board);
+
+        // the same moves are possible in the transformed positions
+        assert_eq!(game.flip_colors().perft(3), game.perft(3));
+        let mut game = Game::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+        assert_eq!(game.mirror_horizontal().perft(4), game.perft(4));
+    }
+
+    #[test]
+    fn invariants() {
+        let board = Board::from_fen_lenient("4k3/8/8/8/8/8/8/4K2k").unwrap();
+        if cfg!(feature = "analysis-board") {
+            assert!(board.check_invariants().is_ok());
+        } else {
+            let violation = board.check_invariants().unwrap_err();
+            assert_eq!(violation.to_string(), "More than one king of a color on h1");
+        }
+
+        let mut board = Board::startpos();
+        board.whites |= 1 << 36;
+        assert_eq!(
+            board.check_invariants().unwrap_err().square.to_string(),
+            "e4"
+        );
+    }
+
+    #[cfg(feature = "mailbox")]
+    #[test]
+    fn mailbox_follows_actions() {
+        use crate::game_representation::Game;
+        use crate::move_generation::movegen;
+
+        fn walk(game: &mut Game, depth: u8) {
+            for action in movegen::legal_moves(game) {
+                let token = game.execute_action(&action);
+                game.board.check_invariants().unwrap();
+                if depth > 1 {
+                    walk(game, depth - 1);
+                }
+                game.undo_action(&action, token);
+                game.board.check_invariants().unwrap();
+            }
+        }
+
+        // castling both ways, en passant and promotions with and without capture
+        let mut game = Game::from_fen("r3k2r/1P4P1/8/3pP3/8/8/1p4p1/R3K2R w KQkq d6 0 1").unwrap();
+        walk(&mut game, 2);
+
+        let mut board = Board::startpos();
+        board.pawns &= !(1 << 52);
+        board.whites &= !(1 << 52);
+        assert!(board.check_invariants().is_err());
+        // the stale mailbox does not take part in the comparison
+        let stale = board.clone();
+        board.sync_mailbox();
+        assert_eq!(board.piece_at("e2".parse().unwrap()), None);
+        assert_eq!(stale, board);
+    }
+
+    // a second king is allowed on analysis boards
+    #[cfg(all(feature = "debug-validate", not(feature = "analysis-board")))]
+    #[test]
+    #[should_panic(expected = "Board invariant violated")]
+    fn corrupting_actions_panic() {
+        let mut board = Board::startpos();
+        // a king moving away from a field the king does not stand on
+        let action = Action::new((4, 6), (4, 4), PieceType::King, ActionType::Quiet);
+        board.execute_action(&action, Color::White);
+    }
 }
@@ -0,0 +1,222 @@
+use super::{Board, Castling, Color, Game, PieceType, PositionIssue};
+use crate::core::Square;
+use alloc::{format, string::String, vec::Vec};
+
+/// Sets up a position piece by piece instead of writing a FEN by hand
+///
+/// The builder starts with an empty board, white to move, no castling rights, no en passant
+/// field and the clocks of a new game. The position is only checked once it is built.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::core::Square;
+/// # use rust_chess::game_representation::{Color, PieceType, PositionBuilder};
+/// let square = |name: &str| name.parse::<Square>().unwrap();
+/// let game = PositionBuilder::new()
+///     .place(PieceType::King, Color::White, square("e1"))
+///     .place(PieceType::Rook, Color::White, square("h1"))
+///     .place(PieceType::King, Color::Black, square("e8"))
+///     .castling_right(Color::White, true)
+///     .side_to_move(Color::Black)
+///     .full_move_number(40)
+///     .build()
+///     .unwrap();
+/// assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/4K2R b K - 0 40");
+/// ```
+#[derive(Clone, Debug)]
+pub struct PositionBuilder {
+    board: Board,
+    color_to_move: Color,
+    castling: Castling,
+    en_passant: Option<Square>,
+    half_move_clock: u8,
+    full_move_number: u32,
+}
+
+impl PositionBuilder {
+    /// Returns a builder of an empty board
+    pub fn new() -> PositionBuilder {
+        PositionBuilder {
+            board: Board::from_bitboards(0, 0, 0, 0, 0, 0),
+            color_to_move: Color::White,
+            castling: Castling::from_raw(0),
+            en_passant: None,
+            half_move_clock: 0,
+            full_move_number: 1,
+        }
+    }
+
+    /// Puts a piece on the square, replacing the piece standing there
+    pub fn place(mut self, piece: PieceType, color: Color, square: Square) -> PositionBuilder {
+        self.board.clear_field(square.index());
+        self.board.set_field(square.index(), piece, color);
+        self
+    }
+
+    /// Sets the color to move
+    pub fn side_to_move(mut self, color: Color) -> PositionBuilder {
+        self.color_to_move = color;
+        self
+    }
+
+    /// Allows the color to castle to the given side
+    pub fn castling_right(mut self, color: Color, kingside: bool) -> PositionBuilder {
+        let right = match (color, kingside) {
+            (Color::White, true) => Castling::get_white_kingside(),
+            (Color::White, false) => Castling::get_white_queenside(),
+            (Color::Black, true) => Castling::get_black_kingside(),
+            (Color::Black, false) => Castling::get_black_queenside(),
+        };
+        self.castling = Castling::from_raw(self.castling.get_raw() | right);
+        self
+    }
+
+    /// Sets the field a pawn that was just pushed by two fields can be captured on
+    pub fn en_passant(mut self, square: Square) -> PositionBuilder {
+        self.en_passant = Some(square);
+        self
+    }
+
+    /// Sets the number of half moves since the last capture or pawn move
+    pub fn half_move_clock(mut self, half_moves: u8) -> PositionBuilder {
+        self.half_move_clock = half_moves;
+        self
+    }
+
+    /// Sets the number of the move, starting at 1 and increased after every move of black
+    pub fn full_move_number(mut self, number: u32) -> PositionBuilder {
+        self.full_move_number = number;
+        self
+    }
+
+    /// Returns the game of the position
+    ///
+    /// # Errors
+    /// * All issues of the position found by [`Game::validate`], followed by
+    ///   [`PositionIssue::InvalidEnPassant`] if the en passant field can not follow a double pawn
+    ///   push
+    /// * [`PositionIssue::InvalidMoveNumber`] if the full move number is 0
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::core::Square;
+    /// # use rust_chess::game_representation::{Color, PieceType, PositionBuilder, PositionIssue};
+    /// let e4: Square = "e4".parse().unwrap();
+    /// let builder = PositionBuilder::new().place(PieceType::Pawn, Color::White, e4);
+    /// assert_eq!(
+    ///     builder.build().unwrap_err(),
+    ///     vec![PositionIssue::MissingKing(Color::White), PositionIssue::MissingKing(Color::Black)]
+    /// );
+    /// ```
+    ///
+    /// [`Game::validate`]: struct.Game.html#method.validate
+    /// [`PositionIssue::InvalidEnPassant`]: enum.PositionIssue.html#variant.InvalidEnPassant
+    /// [`PositionIssue::InvalidMoveNumber`]: enum.PositionIssue.html#variant.InvalidMoveNumber
+    pub fn build(&self) -> Result<Game, Vec<PositionIssue>> {
+        let game = Game::from_fen_lenient(&self.to_fen()).expect("builder writes valid FENs");
+        let mut issues = game.validate().err().unwrap_or_default();
+        match self.en_passant {
+            Some(square) if game.get_en_passant() != Some(square) => {
+                issues.push(PositionIssue::InvalidEnPassant(square))
+            }
+            _ => {}
+        }
+        if self.full_move_number == 0 {
+            issues.push(PositionIssue::InvalidMoveNumber);
+        }
+        if issues.is_empty() {
+            Ok(game)
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Returns the FEN of the position, read leniently so that `build` can report its issues
+    fn to_fen(&self) -> String {
+        let color = match self.color_to_move {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+        let mut castling = String::new();
+        for (right, letter) in [
+            (Castling::get_white_kingside(), 'K'),
+            (Castling::get_white_queenside(), 'Q'),
+            (Castling::get_black_kingside(), 'k'),
+            (Castling::get_black_queenside(), 'q'),
+        ] {
+            if self.castling.is_available(right) {
+                castling.push(letter);
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        let en_passant = self
+            .en_passant
+            .map_or(String::from("-"), |square| format!("{}", square));
+        format!(
+            "{} {} {} {} {} {}",
+            self.board.to_fen(),
+            color,
+            castling,
+            en_passant,
+            self.half_move_clock,
+            self.full_move_number
+        )
+    }
+}
+
+impl Default for PositionBuilder {
+    fn default() -> Self {
+        PositionBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_like_fen() {
+        let square = |name: &str| name.parse::<Square>().unwrap();
+        let builder = PositionBuilder::new()
+            .place(PieceType::King, Color::White, square("e1"))
+            .place(PieceType::King, Color::Black, square("e8"))
+            .place(PieceType::Pawn, Color::White, square("d5"))
+            .place(PieceType::Knight, Color::Black, square("e5"))
+            .place(PieceType::Pawn, Color::Black, square("e5"))
+            .en_passant(square("e6"))
+            .half_move_clock(0)
+            .full_move_number(12);
+        let game = builder.build().unwrap();
+        assert_eq!(
+            game,
+            Game::from_fen("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 12").unwrap()
+        );
+
+        let builder = builder
+            .en_passant(square("c6"))
+            .castling_right(Color::Black, false);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            vec![
+                PositionIssue::InvalidCastlingRight {
+                    color: Color::Black,
+                    kingside: false
+                },
+                PositionIssue::InvalidEnPassant(square("c6")),
+            ]
+        );
+
+        // a FEN with the move number 0 is not built
+        assert_eq!(
+            PositionBuilder::new()
+                .place(PieceType::King, Color::White, square("e1"))
+                .place(PieceType::King, Color::Black, square("e8"))
+                .full_move_number(0)
+                .build()
+                .unwrap_err(),
+            vec![PositionIssue::InvalidMoveNumber]
+        );
+    }
+}
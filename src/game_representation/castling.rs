@@ -5,6 +5,7 @@
 /// * Bit 1 is WHITE_QUEENSIDE
 /// * Bit 2 is BLACK_KINGSIDE
 /// * Bit 3 is BLACK_QUEENSIDE
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Castling {
     data: u8,
 }
@@ -28,6 +29,14 @@ impl Castling {
         Castling { data }
     }
 
+    /// Returns the raw data byte, the inverse of [`from_raw`]
+    ///
+    /// [`from_raw`]: #method.from_raw
+    #[inline(always)]
+    pub fn get_raw(&self) -> u8 {
+        self.data
+    }
+
     /// Compares with the given data and returns true if this is set
     #[inline(always)]
     pub fn is_available(&self, data: u8) -> bool {
@@ -2,7 +2,7 @@
 ///
 /// Has an internal representation as a single byte with `White = 0` and `Black = 1`
 #[repr(u8)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
 pub enum Color {
     White = 0,
     Black = 1,
@@ -13,11 +13,11 @@ impl Color {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::Color;
+    /// # use rust_chess::game_representation::Color;
     /// assert_eq!(Color::White.get_opponent_color(), Color::Black);
     /// assert_eq!(Color::Black.get_opponent_color(), Color::White);
     /// ```
     pub fn get_opponent_color(self) -> Color {
-        unsafe { std::mem::transmute(1 - (self as u8)) }
+        unsafe { core::mem::transmute(1 - (self as u8)) }
     }
 }
@@ -0,0 +1,139 @@
+use super::{Game, GameResult, UndoToken};
+use crate::core::ParserError;
+use crate::move_generation::{movegen, Action};
+use alloc::string::String;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The change between two consecutive states of a game
+///
+/// A delta stores the played action together with the clocks, the result and the hash of the
+/// state after it. Keeping two copies of a game in sync, e.g. on a server and its clients, only
+/// needs the deltas instead of full FENs, and the hash detects copies that diverged. With the
+/// `serde` feature deltas can be serialized.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::{Game, GameDelta};
+/// # use rust_chess::move_generation::Action;
+/// let mut server = Game::startpos();
+/// let mut client = server.clone();
+/// let action = Action::from_san("e4", &server).unwrap();
+/// let delta = GameDelta::new(&server, &action);
+/// server.execute_action(&action);
+/// assert_eq!(delta.action, "e2e4");
+///
+/// client.apply_delta(&delta).unwrap();
+/// assert_eq!(client.to_fen(), server.to_fen());
+/// assert!(client.apply_delta(&delta).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameDelta {
+    /// The played action in UCI notation
+    pub action: String,
+    /// The half move clock after the action
+    pub half_move_clock: u8,
+    /// The full move clock after the action
+    pub full_move_clock: u32,
+    /// The result of the game after the action
+    pub result: GameResult,
+    /// The zobrist hash of the state after the action
+    pub hash: u64,
+}
+
+impl GameDelta {
+    /// Returns the delta of executing the legal action in the given state
+    pub fn new(before: &Game, action: &Action) -> GameDelta {
+        let mut after = before.clone();
+        after.execute_action(action);
+        GameDelta::of(action, &after)
+    }
+
+    /// Returns the delta leading from one state to the other, `None` if no legal action does
+    pub fn between(before: &Game, after: &Game) -> Option<GameDelta> {
+        let mut state = before.clone();
+        movegen::legal_moves(before).into_iter().find_map(|action| {
+            let token = state.execute_action(&action);
+            let delta = if state.get_hash() == after.get_hash() && state.to_fen() == after.to_fen()
+            {
+                Some(GameDelta::of(&action, &state))
+            } else {
+                None
+            };
+            state.undo_action(&action, token);
+            delta
+        })
+    }
+
+    fn of(action: &Action, after: &Game) -> GameDelta {
+        GameDelta {
+            action: action.to_uci(),
+            half_move_clock: after.get_half_move_clock(),
+            full_move_clock: after.get_full_move_clock(),
+            result: after.result(),
+            hash: after.get_hash(),
+        }
+    }
+}
+
+impl Game {
+    /// Executes the action of the delta and checks that the resulting state matches it
+    ///
+    /// The state is left unchanged if the delta can not be applied.
+    ///
+    /// # Errors
+    /// * The action of the delta is not legal in the state
+    /// * The clocks, the result or the hash of the resulting state differ from the delta
+    pub fn apply_delta(&mut self, delta: &GameDelta) -> Result<UndoToken, ParserError> {
+        let action = Action::from_uci(&delta.action, self)?;
+        let token = self.execute_action(&action);
+        let matches = self.get_half_move_clock() == delta.half_move_clock
+            && self.get_full_move_clock() == delta.full_move_clock
+            && self.get_hash() == delta.hash
+            && self.result() == delta.result;
+        if matches {
+            Ok(token)
+        } else {
+            self.undo_action(&action, token);
+            Err(ParserError::InvalidParameter(
+                "Delta does not match the resulting state",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deltas_replay_a_game() {
+        let mut server = Game::startpos();
+        let mut client = server.clone();
+        for san in &["f3", "e5", "g4", "Qh4#"] {
+            let before = server.clone();
+            let action = Action::from_san(san, &server).unwrap();
+            server.execute_action(&action);
+            let delta = GameDelta::between(&before, &server).unwrap();
+            assert_eq!(delta, GameDelta::new(&before, &action));
+            #[cfg(feature = "serde")]
+            let delta: GameDelta =
+                serde_json::from_str(&serde_json::to_string(&delta).unwrap()).unwrap();
+            client.apply_delta(&delta).unwrap();
+            assert_eq!(client.to_fen(), server.to_fen());
+        }
+        assert_eq!(client.result(), GameResult::BlackWins);
+        assert_eq!(GameDelta::between(&Game::startpos(), &server), None);
+
+        // a delta of a diverged state is rejected without changing the state
+        let mut delta = GameDelta::new(
+            &Game::startpos(),
+            &Action::from_uci("e2e4", &Game::startpos()).unwrap(),
+        );
+        delta.hash ^= 1;
+        let mut state = Game::startpos();
+        assert!(state.apply_delta(&delta).is_err());
+        assert_eq!(state.to_fen(), Game::startpos().to_fen());
+    }
+}
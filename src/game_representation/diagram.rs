@@ -0,0 +1,164 @@
+use super::{Board, Color, Game};
+use alloc::{format, string::String, string::ToString, vec::Vec};
+
+/// Options for the text diagrams of [`Board::to_ascii`] and [`Game::to_ascii`]
+///
+/// The default is a plain ASCII diagram with white at the bottom and rank and file labels.
+///
+/// [`Board::to_ascii`]: struct.Board.html#method.to_ascii
+/// [`Game::to_ascii`]: struct.Game.html#method.to_ascii
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiagramOptions {
+    /// The color whose first rank is printed at the bottom
+    pub bottom: Color,
+    /// Use unicode chess glyphs instead of FEN piece letters
+    pub unicode: bool,
+    /// Print rank numbers to the left and file letters below the board
+    pub labels: bool,
+}
+
+impl Default for DiagramOptions {
+    fn default() -> Self {
+        DiagramOptions {
+            bottom: Color::White,
+            unicode: false,
+            labels: true,
+        }
+    }
+}
+
+impl Board {
+    /// Returns an 8x8 text diagram of the board
+    ///
+    /// Every field is printed as its FEN piece letter or unicode glyph, empty fields as `.`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Board, Color, DiagramOptions};
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3").unwrap();
+    /// let options = DiagramOptions { bottom: Color::Black, unicode: true, labels: false };
+    /// assert_eq!(
+    ///     board.to_ascii(options),
+    ///     ". . . ♔ . . . .\n. . . ♙ . . . .\n. . . . . . . .\n. . . . . . . .\n\
+    ///      . . . . . . . .\n. . . . . . . .\n. . . . . . . .\n. . . ♚ . . . .\n"
+    /// );
+    /// ```
+    pub fn to_ascii(&self, options: DiagramOptions) -> String {
+        let mut diagram = String::new();
+        for row in 0..8 {
+            let rank = match options.bottom {
+                Color::White => row,
+                Color::Black => 7 - row,
+            };
+            if options.labels {
+                diagram.push_str(&format!("{} ", 8 - rank));
+            }
+            let fields: Vec<String> = (0..8)
+                .map(|column| {
+                    let file = match options.bottom {
+                        Color::White => column,
+                        Color::Black => 7 - column,
+                    };
+                    let piece = self.get_piecestr_at(rank * 8 + file);
+                    match (piece, options.unicode) {
+                        ("", _) => String::from("."),
+                        (piece, true) => piece_glyph(piece).expect("piece string").to_string(),
+                        (piece, false) => piece.to_string(),
+                    }
+                })
+                .collect();
+            diagram.push_str(&fields.join(" "));
+            diagram.push('\n');
+        }
+        if options.labels {
+            let files = match options.bottom {
+                Color::White => "a b c d e f g h",
+                Color::Black => "h g f e d c b a",
+            };
+            diagram.push_str("  ");
+            diagram.push_str(files);
+            diagram.push('\n');
+        }
+        diagram
+    }
+}
+
+impl Game {
+    /// Returns an 8x8 text diagram of the board followed by the FEN of the position
+    ///
+    /// See [`Board::to_ascii`] for the diagram itself.
+    ///
+    /// [`Board::to_ascii`]: struct.Board.html#method.to_ascii
+    pub fn to_ascii(&self, options: DiagramOptions) -> String {
+        format!("{}{}\n", self.board.to_ascii(options), self.to_fen())
+    }
+}
+
+/// Prints the default diagram of [`Board::to_ascii`]
+///
+/// [`Board::to_ascii`]: struct.Board.html#method.to_ascii
+impl core::fmt::Display for Board {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.to_ascii(DiagramOptions::default()))
+    }
+}
+
+/// Prints the default diagram of [`Game::to_ascii`]
+///
+/// [`Game::to_ascii`]: struct.Game.html#method.to_ascii
+impl core::fmt::Display for Game {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.to_ascii(DiagramOptions::default()))
+    }
+}
+
+/// Returns the unicode chess glyph for a FEN piece character
+pub(crate) fn piece_glyph(piece: &str) -> Option<char> {
+    match piece {
+        "K" => Some('♔'),
+        "Q" => Some('♕'),
+        "R" => Some('♖'),
+        "B" => Some('♗'),
+        "N" => Some('♘'),
+        "P" => Some('♙'),
+        "k" => Some('♚'),
+        "q" => Some('♛'),
+        "r" => Some('♜'),
+        "b" => Some('♝'),
+        "n" => Some('♞'),
+        "p" => Some('♟'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_display() {
+        let game = Game::startpos();
+        assert_eq!(
+            game.to_string(),
+            "8 r n b q k b n r\n7 p p p p p p p p\n6 . . . . . . . .\n5 . . . . . . . .\n\
+             4 . . . . . . . .\n3 . . . . . . . .\n2 P P P P P P P P\n1 R N B Q K B N R\n\
+             \x20 a b c d e f g h\n\
+             rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\n"
+        );
+        assert!(game.to_string().starts_with(&game.board.to_string()));
+    }
+
+    #[test]
+    fn flipped_labels() {
+        let board = Board::from_fen("7k/8/8/8/8/8/8/K7").unwrap();
+        let options = DiagramOptions {
+            bottom: Color::Black,
+            ..DiagramOptions::default()
+        };
+        let diagram = board.to_ascii(options);
+        let lines: Vec<&str> = diagram.lines().collect();
+        assert_eq!(lines[0], "1 . . . . . . . K");
+        assert_eq!(lines[7], "8 k . . . . . . .");
+        assert_eq!(lines[8], "  h g f e d c b a");
+    }
+}
@@ -0,0 +1,142 @@
+use super::Game;
+use crate::move_generation::{movegen, Action};
+use alloc::vec::Vec;
+
+/// A draw the color to move may claim, but which does not end the game by itself
+///
+/// Following the FIDE laws, a player claims a repetition or the fifty move rule either in the
+/// current position or by announcing the action that leads to it. The game only ends once the
+/// claim is made, see [`Game::claimable_draws`].
+///
+/// [`Game::claimable_draws`]: struct.Game.html#method.claimable_draws
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DrawClaim {
+    /// The current position occurred for at least the third time
+    ThreefoldRepetition,
+    /// No capture or pawn move happened in the last fifty moves of both players
+    FiftyMoveRule,
+    /// Executing the action repeats a position for at least the third time
+    ThreefoldRepetitionAfter(Action),
+    /// Executing the action completes fifty moves without a capture or pawn move
+    FiftyMoveRuleAfter(Action),
+}
+
+impl Game {
+    /// Returns the draws the color to move can claim, the current ones first
+    ///
+    /// Claims announcing an action are only listed if the action is legal and does not mate, as
+    /// checkmate ends the game before the claim. Games that are already over have no claims.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{DrawClaim, Game};
+    /// # use rust_chess::move_generation::Action;
+    /// let mut g = Game::startpos();
+    /// for san in &["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1"] {
+    ///     let a = Action::from_san(san, &g).unwrap();
+    ///     g.execute_action(&a);
+    /// }
+    /// let back = Action::from_san("Ng8", &g).unwrap();
+    /// assert_eq!(g.claimable_draws(), vec![DrawClaim::ThreefoldRepetitionAfter(back)]);
+    /// g.execute_action(&back);
+    /// assert_eq!(g.claimable_draws()[0], DrawClaim::ThreefoldRepetition);
+    /// assert!(!g.is_automatic_draw());
+    /// ```
+    pub fn claimable_draws(&self) -> Vec<DrawClaim> {
+        let actions = movegen::legal_moves(self);
+        let mut claims = Vec::new();
+        if actions.is_empty() || self.is_automatic_draw() {
+            return claims;
+        }
+        if self.repetition_count() >= 3 {
+            claims.push(DrawClaim::ThreefoldRepetition);
+        }
+        if self.is_draw_by_fifty_move_rule() {
+            claims.push(DrawClaim::FiftyMoveRule);
+        }
+        let mut state = self.clone();
+        for action in actions {
+            let token = state.execute_action(&action);
+            if !state.is_checkmate() {
+                if state.repetition_count() >= 3 {
+                    claims.push(DrawClaim::ThreefoldRepetitionAfter(action));
+                }
+                if state.is_draw_by_fifty_move_rule() && !self.is_draw_by_fifty_move_rule() {
+                    claims.push(DrawClaim::FiftyMoveRuleAfter(action));
+                }
+            }
+            state.undo_action(&action, token);
+        }
+        claims
+    }
+
+    /// Returns true if the game is drawn without a claim
+    ///
+    /// This is the case for stalemate, a position occurring for the fifth time and seventy-five
+    /// moves of both players without a capture or pawn move, unless the last one mated.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// assert!(Game::from_fen("8/8/4k3/8/8/4K3/8/7R w - - 150 100").unwrap().is_automatic_draw());
+    /// assert!(!Game::from_fen("8/8/4k3/8/8/4K3/8/7R w - - 149 100").unwrap().is_automatic_draw());
+    /// ```
+    pub fn is_automatic_draw(&self) -> bool {
+        if self.repetition_count() >= 5 || self.is_stalemate() {
+            return true;
+        }
+        self.get_half_move_clock() >= 150 && !self.is_checkmate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claims_and_automatic_draws() {
+        let mut game = Game::from_fen("8/8/4k3/8/8/4K3/8/7R w - - 99 80").unwrap();
+        let claims = game.claimable_draws();
+        assert!(claims.len() > 10);
+        assert!(claims
+            .iter()
+            .all(|claim| matches!(claim, DrawClaim::FiftyMoveRuleAfter(_))));
+
+        let action = Action::from_san("Rh2", &game).unwrap();
+        game.execute_action(&action);
+        assert_eq!(game.claimable_draws()[0], DrawClaim::FiftyMoveRule);
+        assert!(!game
+            .claimable_draws()
+            .contains(&DrawClaim::FiftyMoveRuleAfter(action)));
+
+        // the fifth occurrence ends the game without a claim
+        let mut game = Game::startpos();
+        for _ in 0..4 {
+            assert!(!game.is_automatic_draw());
+            for san in &["Nf3", "Nf6", "Ng1", "Ng8"] {
+                let action = Action::from_san(san, &game).unwrap();
+                game.execute_action(&action);
+            }
+        }
+        assert!(game.is_automatic_draw());
+        assert!(game.claimable_draws().is_empty());
+
+        // the position after a double push repeats if no pawn can capture en passant
+        let mut game = Game::startpos();
+        for san in &["e4", "Nf6", "Nf3", "Ng8", "Ng1", "Nf6", "Nf3", "Ng8", "Ng1"] {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+        }
+        let forward = Action::from_san("Nf6", &game).unwrap();
+        let claims = game.claimable_draws();
+        assert_eq!(claims[0], DrawClaim::ThreefoldRepetition);
+        assert!(claims.contains(&DrawClaim::ThreefoldRepetitionAfter(forward)));
+
+        // a move completing fifty moves does not draw if it mates
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 99 80").unwrap();
+        let mate = Action::from_san("Rd8#", &game).unwrap();
+        assert!(!game
+            .claimable_draws()
+            .contains(&DrawClaim::FiftyMoveRuleAfter(mate)));
+    }
+}
@@ -0,0 +1,427 @@
+use super::Game;
+use crate::core::ParserError;
+use crate::move_generation::Action;
+use alloc::collections::BTreeMap;
+use alloc::{format, string::String, string::ToString, vec::Vec};
+
+/// The operations of an Extended Position Description, mapping opcodes to their operands
+///
+/// Operands are stored as written, without the quotes around strings. The standard opcodes of
+/// test suites can be read with typed accessors, moves are given in Standard Algebraic Notation
+/// of the position the EPD describes.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// let (game, operations) =
+///     Game::from_epd_with_operations("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - bm Rd8#; id \"mate\";")
+///         .unwrap();
+/// assert_eq!(operations.id(), Some("mate"));
+/// let best = operations.best_moves(&game).unwrap();
+/// assert_eq!(best[0].to_san(&game), "Rd8#");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EpdOperations {
+    operations: BTreeMap<String, Vec<String>>,
+}
+
+impl EpdOperations {
+    /// Returns an empty set of operations
+    pub fn new() -> EpdOperations {
+        EpdOperations::default()
+    }
+
+    /// Returns the operands of the opcode
+    pub fn get(&self, opcode: &str) -> Option<&[String]> {
+        self.operations.get(opcode).map(Vec::as_slice)
+    }
+
+    /// Sets the operands of the opcode and returns the ones it replaces
+    pub fn insert(&mut self, opcode: &str, operands: Vec<String>) -> Option<Vec<String>> {
+        self.operations.insert(opcode.to_string(), operands)
+    }
+
+    /// Removes the opcode and returns its operands
+    pub fn remove(&mut self, opcode: &str) -> Option<Vec<String>> {
+        self.operations.remove(opcode)
+    }
+
+    /// Returns all opcodes with their operands, ordered by opcode
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.operations
+            .iter()
+            .map(|(opcode, operands)| (opcode.as_str(), operands.as_slice()))
+    }
+
+    /// Returns the number of operations
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Returns true if there are no operations
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Returns the identifier of the position, the `id` operation
+    pub fn id(&self) -> Option<&str> {
+        self.get("id")?.first().map(String::as_str)
+    }
+
+    /// Returns the centipawn evaluation of the position, the `ce` operation
+    ///
+    /// # Errors
+    /// * `ce` is not followed by exactly one number
+    pub fn centipawn_evaluation(&self) -> Result<Option<i32>, ParserError> {
+        match self.get("ce") {
+            None => Ok(None),
+            Some([score]) => score
+                .parse()
+                .map(Some)
+                .map_err(|_| ParserError::InvalidParameter("EPD evaluation is not a number")),
+            Some(_) => Err(ParserError::WrongParameterNumber),
+        }
+    }
+
+    /// Returns the best moves of the position, the `bm` operation
+    ///
+    /// Returns no moves if the operation is missing.
+    ///
+    /// # Errors
+    /// * A move can not be read by [`Action::from_san`]
+    ///
+    /// [`Action::from_san`]: ../move_generation/struct.Action.html#method.from_san
+    pub fn best_moves(&self, game: &Game) -> Result<Vec<Action>, ParserError> {
+        self.moves("bm", game)
+    }
+
+    /// Returns the moves to avoid in the position, the `am` operation
+    ///
+    /// Returns no moves if the operation is missing.
+    ///
+    /// # Errors
+    /// * A move can not be read by [`Action::from_san`]
+    ///
+    /// [`Action::from_san`]: ../move_generation/struct.Action.html#method.from_san
+    pub fn avoid_moves(&self, game: &Game) -> Result<Vec<Action>, ParserError> {
+        self.moves("am", game)
+    }
+
+    /// Returns the principal variation starting in the position, the `pv` operation
+    ///
+    /// Every move is read in the position after the moves in front of it. Returns no moves if
+    /// the operation is missing.
+    ///
+    /// # Errors
+    /// * A move can not be read by [`Action::from_san`]
+    ///
+    /// [`Action::from_san`]: ../move_generation/struct.Action.html#method.from_san
+    pub fn principal_variation(&self, game: &Game) -> Result<Vec<Action>, ParserError> {
+        let mut state = game.clone();
+        let mut line = Vec::new();
+        for san in self.get("pv").unwrap_or(&[]) {
+            let action = Action::from_san(san, &state)?;
+            state.execute_action(&action);
+            line.push(action);
+        }
+        Ok(line)
+    }
+
+    /// Sets the operands of the opcode to the actions written in the position
+    ///
+    /// For the `pv` opcode the actions are a line played one after another, for all other
+    /// opcodes they are alternatives in the position.
+    pub fn insert_moves(&mut self, opcode: &str, game: &Game, actions: &[Action]) {
+        let mut state = game.clone();
+        let mut operands = Vec::with_capacity(actions.len());
+        for action in actions {
+            operands.push(action.to_san(&state));
+            if opcode == "pv" {
+                state.execute_action(action);
+            }
+        }
+        self.insert(opcode, operands);
+    }
+
+    /// Returns the moves of an opcode listing alternatives in the position
+    fn moves(&self, opcode: &str, game: &Game) -> Result<Vec<Action>, ParserError> {
+        self.get(opcode)
+            .unwrap_or(&[])
+            .iter()
+            .map(|san| Action::from_san(san, game))
+            .collect()
+    }
+}
+
+impl Game {
+    /// Returns the game described by an Extended Position Description
+    ///
+    /// An EPD starts with the first four fields of a FEN, followed by operations like
+    /// `bm Nf3;` consisting of an opcode and its operands. The clocks are read from the `hmvc`
+    /// (halfmove clock) and `fmvn` (fullmove number) operations and default to 0 and 1. All
+    /// other operations are ignored, use [`from_epd_with_operations`] to read them.
+    ///
+    /// # Errors
+    /// * There are less than four fields
+    /// * The fields are not a valid FEN position
+    /// * `hmvc` or `fmvn` is not followed by exactly one number
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let game = Game::from_epd("8/8/4k3/8/8/4K3/8/7R w - - hmvc 99; fmvn 80; id \"clock\";")
+    ///     .unwrap();
+    /// assert_eq!(game.to_fen(), "8/8/4k3/8/8/4K3/8/7R w - - 99 80");
+    /// ```
+    ///
+    /// [`from_epd_with_operations`]: #method.from_epd_with_operations
+    pub fn from_epd(epd: &str) -> Result<Game, ParserError> {
+        Game::from_epd_with_operations(epd).map(|(game, _)| game)
+    }
+
+    /// Returns the game described by an Extended Position Description and its operations
+    ///
+    /// Works like [`from_epd`]. The `hmvc` and `fmvn` operations are part of the game, all
+    /// other operations are returned by their opcode. If an opcode is repeated, its last
+    /// operation is kept.
+    ///
+    /// # Errors
+    /// * All errors of [`from_epd`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let epd = "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";";
+    /// let (game, operations) = Game::from_epd_with_operations(epd).unwrap();
+    /// assert_eq!(operations.id(), Some("WAC.001"));
+    /// assert_eq!(operations.get("bm"), Some(&[String::from("Qg6")][..]));
+    /// assert_eq!(operations.get("hmvc"), None);
+    /// assert!(game.to_epd_with_operations(&operations).ends_with("fmvn 1; bm Qg6; id \"WAC.001\";"));
+    /// ```
+    ///
+    /// [`from_epd`]: #method.from_epd
+    pub fn from_epd_with_operations(epd: &str) -> Result<(Game, EpdOperations), ParserError> {
+        let mut fields = epd.trim_start().splitn(5, ' ');
+        let mut position = Vec::with_capacity(4);
+        for _ in 0..4 {
+            match fields.next() {
+                Some(field) if !field.is_empty() => position.push(field),
+                _ => return Err(ParserError::WrongParameterNumber),
+            }
+        }
+        let (mut half_move, mut full_move) = (0, 1);
+        let mut operations = EpdOperations::new();
+        for mut operation in epd_operations(fields.next().unwrap_or(""))? {
+            let clock = match operation[0].as_str() {
+                "hmvc" => &mut half_move,
+                "fmvn" => &mut full_move,
+                _ => {
+                    let opcode = operation.remove(0);
+                    operations.insert(&opcode, operation);
+                    continue;
+                }
+            };
+            *clock = match &operation[1..] {
+                [number] => number.parse().map_err(|_| {
+                    ParserError::InvalidParameter("EPD clock operand is not a number")
+                })?,
+                _ => return Err(ParserError::WrongParameterNumber),
+            };
+        }
+        let game = Game::from_fen(&format!(
+            "{} {} {}",
+            position.join(" "),
+            half_move,
+            full_move
+        ))?;
+        Ok((game, operations))
+    }
+
+    /// Returns the position as an Extended Position Description
+    ///
+    /// The clocks are written as `hmvc` and `fmvn` operations, so the EPD describes the same
+    /// game as [`to_fen`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// assert_eq!(
+    ///     Game::startpos().to_epd(),
+    ///     "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - hmvc 0; fmvn 1;"
+    /// );
+    /// ```
+    ///
+    /// [`to_fen`]: #method.to_fen
+    pub fn to_epd(&self) -> String {
+        self.to_epd_with_operations(&EpdOperations::new())
+    }
+
+    /// Returns the position as an Extended Position Description with the given operations
+    ///
+    /// The operations follow the clocks in the order of their opcodes, `hmvc` and `fmvn`
+    /// operations are replaced by the clocks of the game. Operands containing spaces or
+    /// semicolons and the operands of string opcodes like `id` and `c0` are quoted.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{EpdOperations, Game};
+    /// let game = Game::startpos();
+    /// let mut operations = EpdOperations::new();
+    /// operations.insert("id", vec![String::from("start")]);
+    /// operations.insert("ce", vec![String::from("20")]);
+    /// assert_eq!(
+    ///     game.to_epd_with_operations(&operations),
+    ///     "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - hmvc 0; fmvn 1; ce 20; id \"start\";"
+    /// );
+    /// ```
+    pub fn to_epd_with_operations(&self, operations: &EpdOperations) -> String {
+        let fen = self.to_fen();
+        let fields: Vec<&str> = fen.split(' ').collect();
+        let mut epd = format!(
+            "{} hmvc {}; fmvn {};",
+            fields[..4].join(" "),
+            self.get_half_move_clock(),
+            self.get_full_move_clock()
+        );
+        for (opcode, operands) in operations.iter() {
+            if opcode == "hmvc" || opcode == "fmvn" {
+                continue;
+            }
+            epd.push(' ');
+            epd.push_str(opcode);
+            for operand in operands {
+                epd.push(' ');
+                if is_string_opcode(opcode) || operand.is_empty() || operand.contains([' ', ';']) {
+                    epd.push('"');
+                    epd.push_str(operand);
+                    epd.push('"');
+                } else {
+                    epd.push_str(operand);
+                }
+            }
+            epd.push(';');
+        }
+        epd
+    }
+}
+
+/// Returns true if the operands of the opcode are strings, which are always quoted
+fn is_string_opcode(opcode: &str) -> bool {
+    let bytes = opcode.as_bytes();
+    match bytes {
+        [b'c', digit] | [b'v', digit] => digit.is_ascii_digit(),
+        _ => matches!(opcode, "id" | "eco" | "nic"),
+    }
+}
+
+/// Splits the operations of an EPD into their opcode and operands
+///
+/// Operations end with a `;`, operands in double quotes may contain spaces and semicolons.
+///
+/// # Errors
+/// * A quoted operand is not closed
+/// * The last operation is not terminated by a `;`
+fn epd_operations(operations: &str) -> Result<Vec<Vec<String>>, ParserError> {
+    let mut parsed = Vec::new();
+    let mut operation: Vec<String> = Vec::new();
+    let mut chars = operations.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                if !operation.is_empty() {
+                    parsed.push(core::mem::take(&mut operation));
+                }
+            }
+            '"' => {
+                let mut operand = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => operand.push(c),
+                        None => {
+                            return Err(ParserError::InvalidParameter(
+                                "EPD string operand is not closed",
+                            ))
+                        }
+                    }
+                }
+                operation.push(operand);
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut token = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || next == ';' || next == '"' {
+                        break;
+                    }
+                    token.push(next);
+                    chars.next();
+                }
+                operation.push(token);
+            }
+        }
+    }
+    if !operation.is_empty() {
+        return Err(ParserError::InvalidParameter(
+            "EPD operation is not terminated",
+        ));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clocks_round_trip() {
+        let epd =
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - hmvc 7; fmvn 21;";
+        let game = Game::from_epd(epd).unwrap();
+        assert_eq!(game.get_half_move_clock(), 7);
+        assert_eq!(game.get_full_move_clock(), 21);
+        assert_eq!(game.to_epd(), epd);
+
+        let game = Game::from_epd("4k3/8/8/8/8/8/8/4K3 b - - c0 \"a; b\"; bm Kd7;").unwrap();
+        assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+
+        assert!(Game::from_epd("4k3/8/8/8/8/8/8/4K3 w -").is_err());
+        assert!(Game::from_epd("4k3/8/8/8/8/8/8/4K3 w - - hmvc x;").is_err());
+        assert!(Game::from_epd("4k3/8/8/8/8/8/8/4K3 w - - hmvc 1 2;").is_err());
+        assert!(Game::from_epd("4k3/8/8/8/8/8/8/4K3 w - - hmvc 1").is_err());
+        assert!(Game::from_epd("4k3/8/8/8/8/8/8/4K3 w - - c0 \"open;").is_err());
+    }
+
+    #[test]
+    fn test_suite_operations() {
+        let epd = "r1b1k2r/ppppnppp/2n2q2/2b5/3NP3/2P1B3/PP3PPP/RN1QKB1R w KQkq - \
+                   bm Nxc6; am Bg5 Qh5; ce +45; pv Nxc6 Bxe3 Nxe7; id \"STS(v1.0) 001\";";
+        let (game, operations) = Game::from_epd_with_operations(epd).unwrap();
+        assert_eq!(operations.len(), 5);
+        assert_eq!(operations.id(), Some("STS(v1.0) 001"));
+        assert_eq!(operations.centipawn_evaluation(), Ok(Some(45)));
+        let sans =
+            |actions: Vec<Action>| -> Vec<String> { actions.iter().map(|a| a.to_uci()).collect() };
+        assert_eq!(sans(operations.best_moves(&game).unwrap()), vec!["d4c6"]);
+        assert_eq!(
+            sans(operations.avoid_moves(&game).unwrap()),
+            vec!["e3g5", "d1h5"]
+        );
+        let pv = operations.principal_variation(&game).unwrap();
+        assert_eq!(sans(pv.clone()), vec!["d4c6", "c5e3", "c6e7"]);
+
+        let mut written = EpdOperations::new();
+        written.insert_moves("pv", &game, &pv);
+        written.insert_moves("bm", &game, &pv[..1]);
+        assert_eq!(written.get("pv").unwrap(), ["Nxc6", "Bxe3", "Nxe7"]);
+        let (_, read) =
+            Game::from_epd_with_operations(&game.to_epd_with_operations(&written)).unwrap();
+        assert_eq!(read, written);
+        assert!(EpdOperations::new().best_moves(&game).unwrap().is_empty());
+
+        let (game, operations) =
+            Game::from_epd_with_operations("4k3/8/8/8/8/8/8/4K3 w - - bm Kd9; ce x;").unwrap();
+        assert!(operations.best_moves(&game).is_err());
+        assert!(operations.centipawn_evaluation().is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use super::{Board, Color, PieceType};
+use crate::core::{bitboard, ParserError};
+use alloc::{string::String, vec::Vec};
+
+/// The pieces counted by a material key, in the order they appear in a signature
+const SIGNATURE_PIECES: [PieceType; 5] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+/// The material balance of a position, identifying endings such as KRPvKR
+///
+/// Counts every piece except the kings for both colors. Two positions have equal keys if both
+/// players have the same number of pieces of every type, regardless of where they stand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MaterialKey {
+    // indexed by color, then by the position of the piece in SIGNATURE_PIECES
+    counts: [[u8; 5]; 2],
+}
+
+impl MaterialKey {
+    /// Returns the material key of all pieces on the board
+    pub fn from_board(board: &Board) -> MaterialKey {
+        let mut counts = [[0u8; 5]; 2];
+        for index in 0..64 {
+            if let Some(piece) = board.get_piecetype_on(index) {
+                if let Some(slot) = signature_slot(piece) {
+                    let color = if board.whites >> index & 1 == 1 {
+                        Color::White
+                    } else {
+                        Color::Black
+                    };
+                    counts[color as usize][slot] += 1;
+                }
+            }
+        }
+        MaterialKey { counts }
+    }
+
+    /// Returns the material key described by a signature like `KRPvKR`
+    ///
+    /// The white pieces come first, both sides start with their king and are separated by a
+    /// `v`. The order of the other pieces does not matter.
+    ///
+    /// # Errors
+    /// * The signature does not consist of exactly two sides separated by `v`
+    /// * A side does not start with exactly one king
+    /// * A side contains a character other than `QRBNP`
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Color, MaterialKey, PieceType};
+    /// let key = MaterialKey::from_signature("KRPvKR").unwrap();
+    /// assert_eq!(key.count(Color::White, PieceType::Pawn), 1);
+    /// assert_eq!(key.count(Color::Black, PieceType::Rook), 1);
+    /// assert_eq!(key.to_signature(), "KRPvKR");
+    /// ```
+    pub fn from_signature(signature: &str) -> Result<MaterialKey, ParserError> {
+        let sides: Vec<&str> = signature.split('v').collect();
+        if sides.len() != 2 {
+            return Err(ParserError::WrongParameterNumber);
+        }
+        let mut counts = [[0u8; 5]; 2];
+        for (side, pieces) in sides.iter().enumerate() {
+            let mut chars = pieces.chars();
+            if chars.next() != Some('K') {
+                return Err(ParserError::InvalidParameter(
+                    "Material signature side does not start with a king",
+                ));
+            }
+            for c in chars {
+                let piece = match c {
+                    'P' => PieceType::Pawn,
+                    'K' => {
+                        return Err(ParserError::InvalidParameter(
+                            "Material signature contains more than one king per side",
+                        ))
+                    }
+                    _ => bitboard::char_to_piecetype(c)?,
+                };
+                let slot = signature_slot(piece).expect("kings are handled above");
+                counts[side][slot] = match counts[side][slot].checked_add(1) {
+                    Some(count) => count,
+                    None => {
+                        return Err(ParserError::InvalidParameter(
+                            "Material signature has too many pieces of one type",
+                        ))
+                    }
+                };
+            }
+        }
+        Ok(MaterialKey { counts })
+    }
+
+    /// Returns the signature of the key, strongest pieces first, e.g. `KQvKRP`
+    pub fn to_signature(&self) -> String {
+        let mut signature = String::new();
+        for (side, counts) in self.counts.iter().enumerate() {
+            if side == 1 {
+                signature.push('v');
+            }
+            signature.push('K');
+            for (piece, count) in SIGNATURE_PIECES.iter().zip(counts.iter()) {
+                let c = match piece {
+                    PieceType::Pawn => 'P',
+                    _ => bitboard::piecetype_to_char(*piece),
+                };
+                for _ in 0..*count {
+                    signature.push(c);
+                }
+            }
+        }
+        signature
+    }
+
+    /// Returns how many pieces of the given type the color has
+    ///
+    /// Kings are not part of the key, so their count is always 0.
+    pub fn count(&self, color: Color, piece: PieceType) -> u8 {
+        match signature_slot(piece) {
+            Some(slot) => self.counts[color as usize][slot],
+            None => 0,
+        }
+    }
+
+    /// Returns the key with the material of both colors swapped
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::MaterialKey;
+    /// let key = MaterialKey::from_signature("KRPvKR").unwrap();
+    /// assert_eq!(key.flipped().to_signature(), "KRvKRP");
+    /// ```
+    pub fn flipped(&self) -> MaterialKey {
+        MaterialKey {
+            counts: [self.counts[1], self.counts[0]],
+        }
+    }
+}
+
+/// Returns the position of the piece in a signature, kings have none
+fn signature_slot(piece: PieceType) -> Option<usize> {
+    SIGNATURE_PIECES.iter().position(|p| *p == piece)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_of_startpos() {
+        let key = MaterialKey::from_board(&Board::startpos());
+        assert_eq!(key.to_signature(), "KQRRBBNNPPPPPPPPvKQRRBBNNPPPPPPPP");
+        assert_eq!(key, key.flipped());
+    }
+
+    #[test]
+    fn signatures_are_normalized() {
+        let key = MaterialKey::from_signature("KPRvKR").unwrap();
+        assert_eq!(key.to_signature(), "KRPvKR");
+        assert_eq!(
+            key,
+            MaterialKey::from_board(&Board::from_fen("8/8/3k4/8/2r5/8/4PR2/4K3").unwrap())
+        );
+    }
+
+    #[test]
+    fn invalid_signatures() {
+        assert!(MaterialKey::from_signature("KRP").is_err());
+        assert!(MaterialKey::from_signature("RPvKR").is_err());
+        assert!(MaterialKey::from_signature("KRKvK").is_err());
+        assert!(MaterialKey::from_signature("KXvK").is_err());
+        assert!(MaterialKey::from_signature(&format!("K{}vK", "Q".repeat(256))).is_err());
+    }
+}
@@ -1,13 +1,31 @@
 //! All code related to representing the game of chess
 
 mod board;
+mod builder;
 mod castling;
 mod color;
+mod delta;
+mod diagram;
+mod draws;
+mod epd;
+mod material;
 mod piecetype;
+mod result;
 mod state;
+mod validation;
 
-pub use board::Board;
+pub use board::{Board, InvariantViolation};
+pub use builder::PositionBuilder;
 pub use castling::Castling;
 pub use color::Color;
+pub use delta::GameDelta;
+#[cfg(feature = "std")]
+pub(crate) use diagram::piece_glyph;
+pub use diagram::DiagramOptions;
+pub use draws::DrawClaim;
+pub use epd::EpdOperations;
+pub use material::MaterialKey;
 pub use piecetype::PieceType;
-pub use state::Game;
+pub use result::GameResult;
+pub use state::{Game, UndoToken};
+pub use validation::PositionIssue;
@@ -1,3 +1,6 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Type of chess piece
 ///
 /// A simple enum containing only un-colored chess piece types. It is represented as a byte
@@ -8,7 +11,8 @@
 /// * Rook = 4
 /// * Queen = 5
 /// * Bishop = 6
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u8)]
 pub enum PieceType {
     King = 1,
@@ -18,3 +22,26 @@ pub enum PieceType {
     Queen = 5,
     Bishop = 6,
 }
+
+impl PieceType {
+    /// Returns the usual material value of the piece in centipawns
+    ///
+    /// The king can never be traded, it is valued higher than all other pieces together.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::PieceType;
+    /// assert_eq!(PieceType::Pawn.get_value(), 100);
+    /// assert!(PieceType::Knight.get_value() < PieceType::Bishop.get_value());
+    /// ```
+    pub const fn get_value(self) -> i32 {
+        match self {
+            PieceType::Pawn => 100,
+            PieceType::Knight => 320,
+            PieceType::Bishop => 330,
+            PieceType::Rook => 500,
+            PieceType::Queen => 900,
+            PieceType::King => 20000,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::Color;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The outcome of a game, as far as it can be decided from the current position
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Ongoing,
+}
+
+impl GameResult {
+    /// Returns the result of a game won by the given color
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Color, GameResult};
+    /// assert_eq!(GameResult::win_for(Color::Black), GameResult::BlackWins);
+    /// ```
+    pub fn win_for(color: Color) -> GameResult {
+        match color {
+            Color::White => GameResult::WhiteWins,
+            Color::Black => GameResult::BlackWins,
+        }
+    }
+}
@@ -1,10 +1,42 @@
-use super::{Board, Castling, Color, PieceType};
-use crate::core::{bitboard, ParserError};
-use crate::move_generation::{Action, ActionType};
+use super::{Board, Castling, Color, GameResult, PieceType};
+use crate::core::bitboard::{self, Bitboard};
+use crate::core::{zobrist, FenField, ParserError, Square};
+use crate::move_generation::{movegen, Action, ActionType, CheckInfo};
+#[cfg(feature = "std")]
+use crate::pgn::PgnGame;
+use alloc::{format, string::String, string::ToString, vec::Vec};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Basic representation of a chess game
 ///
-/// Holds all information needed for a chess game except for repetition information.
+/// Holds all information needed for a chess game including the hashes of all positions reached
+/// by executing actions, which are needed to detect repetitions.
+/// The zobrist hash and the occupied fields of both colors are updated incrementally by
+/// [`execute_action`] and [`undo_action`], changing `board` or `color_to_move` directly does
+/// not update them.
+///
+/// Two games are equal if their FEN is equal. The history of earlier positions is ignored, so
+/// the same position reached by different move orders compares and hashes equally.
+///
+/// Games only hold plain data, so they are `Send` and `Sync` and can be cloned into other
+/// threads, as done by [`perft_parallel`]. With the `serde` feature games are serialized as
+/// their FEN.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// // the knights moved out and back, so the clocks advanced
+/// let game = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 4 3").unwrap();
+/// assert_ne!(game, Game::startpos());
+/// let game = Game::from_fen(&game.to_fen()).unwrap();
+/// assert_eq!(game.clone(), game);
+/// ```
+///
+/// [`execute_action`]: #method.execute_action
+/// [`undo_action`]: #method.undo_action
+/// [`perft_parallel`]: #method.perft_parallel
+#[derive(Clone, Debug)]
 pub struct Game {
     // 50 move rule
     half_move_clock: u8,
@@ -14,25 +46,147 @@ pub struct Game {
     // shift index of en_passant square, if available; 255 otherwise
     en_passant: u8,
     castling: Castling,
+    // zobrist hash of the current position
+    hash: u64,
+    // zobrist hashes of all positions before the current one, oldest first
+    history: Vec<u64>,
+    // occupied fields, cached so move generation does not combine all bitboards every time
+    all_pieces: u64,
+    white_pieces: u64,
+    black_pieces: u64,
+}
+
+/// Information needed to revert an executed action
+///
+/// Returned by [`Game::execute_action`] and consumed by [`Game::undo_action`]. It stores only the
+/// parts of the state that can not be reconstructed from the action itself. Null moves use the
+/// same token, see [`Game::make_null_move`].
+///
+/// [`Game::execute_action`]: struct.Game.html#method.execute_action
+/// [`Game::undo_action`]: struct.Game.html#method.undo_action
+/// [`Game::make_null_move`]: struct.Game.html#method.make_null_move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UndoToken {
+    en_passant: u8,
+    castling: u8,
+    half_move_clock: u8,
+    hash: u64,
 }
 
 impl Game {
     /// Returns a game struct containing the canonical starting position of chess
     pub fn startpos() -> Game {
-        Game {
+        let mut game = Game {
             half_move_clock: 0,
             full_move_clock: 1,
             color_to_move: Color::White,
             board: Board::startpos(),
             en_passant: 255,
             castling: Castling::new(),
-        }
+            hash: 0,
+            history: Vec::new(),
+            all_pieces: 0,
+            white_pieces: 0,
+            black_pieces: 0,
+        };
+        game.hash = game.compute_hash();
+        game.refresh_occupancy();
+        game
+    }
+
+    /// Returns true if the game is in the standard starting position with the clocks of a new game
+    ///
+    /// The history is ignored, so returning to the starting position by moving the knights out
+    /// and back is not standard, as the clocks advanced.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// assert!(Game::startpos().is_standard_startpos());
+    /// let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    /// assert!(Game::from_fen(fen).unwrap().is_standard_startpos());
+    /// let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 4 3";
+    /// assert!(!Game::from_fen(fen).unwrap().is_standard_startpos());
+    /// ```
+    pub fn is_standard_startpos(&self) -> bool {
+        *self == Game::startpos()
+    }
+
+    /// Returns the same position seen from the other side
+    ///
+    /// The ranks are mirrored and the colors of all pieces, the color to move, the castling
+    /// rights and the en passant field are swapped. The clocks are kept, the history of earlier
+    /// positions is dropped as it can not be flipped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let game = Game::from_fen("4k3/8/8/3pP3/8/8/8/R3K3 w Q d6 0 30").unwrap();
+    /// assert_eq!(game.flip_colors().to_fen(), "r3k3/8/8/8/3Pp3/8/8/4K3 b q d3 0 30");
+    /// # #[cfg(feature = "std")]
+    /// assert_eq!(game.evaluate(), game.flip_colors().evaluate());
+    /// ```
+    pub fn flip_colors(&self) -> Game {
+        let castling = self.castling.get_raw();
+        let mut game = Game {
+            board: self.board.flip_colors(),
+            castling: Castling::from_raw((castling & 0b11) << 2 | (castling >> 2) & 0b11),
+            en_passant: match self.get_en_passant() {
+                Some(square) => square.flip_rank().index(),
+                None => 255,
+            },
+            half_move_clock: self.half_move_clock,
+            full_move_clock: self.full_move_clock,
+            color_to_move: self.color_to_move.get_opponent_color(),
+            hash: 0,
+            history: Vec::new(),
+            all_pieces: 0,
+            white_pieces: 0,
+            black_pieces: 0,
+        };
+        game.hash = game.compute_hash();
+        game.refresh_occupancy();
+        game
+    }
+
+    /// Returns the position mirrored along the middle between the d-file and the e-file
+    ///
+    /// Castling rights are dropped, as the mirrored kings and rooks can not castle. The en
+    /// passant field is mirrored, the color to move and the clocks are kept and the history of
+    /// earlier positions is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let game = Game::from_fen("4k3/8/8/3pP3/8/8/8/R3K3 w Q d6 0 30").unwrap();
+    /// assert_eq!(game.mirror_horizontal().to_fen(), "3k4/8/8/3Pp3/8/8/8/3K3R w - e6 0 30");
+    /// ```
+    pub fn mirror_horizontal(&self) -> Game {
+        let mut game = Game {
+            board: self.board.mirror_horizontal(),
+            castling: Castling::from_raw(0),
+            en_passant: match self.get_en_passant() {
+                Some(square) => square.index() ^ 7,
+                None => 255,
+            },
+            half_move_clock: self.half_move_clock,
+            full_move_clock: self.full_move_clock,
+            color_to_move: self.color_to_move,
+            hash: 0,
+            history: Vec::new(),
+            all_pieces: 0,
+            white_pieces: 0,
+            black_pieces: 0,
+        };
+        game.hash = game.compute_hash();
+        game.refresh_occupancy();
+        game
     }
 
     /// Returns the Forsyth-Edwards Notation representation of the given struct
     pub fn to_fen(&self) -> String {
         let mut ret = self.board.to_fen();
-        ret.push_str(" ");
+        ret.push(' ');
         match self.color_to_move {
             Color::White => {
                 ret.push_str("w ");
@@ -46,24 +200,24 @@ impl Game {
         let mut any_castle = false;
         if self.castling.is_available(Castling::get_white_kingside()) {
             any_castle = true;
-            ret.push_str("K");
+            ret.push('K');
         }
         if self.castling.is_available(Castling::get_white_queenside()) {
             any_castle = true;
-            ret.push_str("Q");
+            ret.push('Q');
         }
         if self.castling.is_available(Castling::get_black_kingside()) {
             any_castle = true;
-            ret.push_str("k");
+            ret.push('k');
         }
         if self.castling.is_available(Castling::get_black_queenside()) {
             any_castle = true;
-            ret.push_str("q");
+            ret.push('q');
         }
         if !any_castle {
-            ret.push_str("-");
+            ret.push('-');
         }
-        ret.push_str(" ");
+        ret.push(' ');
 
         // en passant information
         if self.en_passant < 255 {
@@ -71,7 +225,7 @@ impl Game {
                 &bitboard::index_to_field_repr(self.en_passant)
                     .expect("Index is wrong and could not be converted"),
             );
-            ret.push_str(" ");
+            ret.push(' ');
         } else {
             ret.push_str("- ");
         }
@@ -82,13 +236,325 @@ impl Game {
         ret
     }
 
+    /// Returns the number of the current full move, starting at 1 and incremented after black moved
+    pub fn get_full_move_clock(&self) -> u32 {
+        self.full_move_clock
+    }
+
+    /// Returns the number of halfmoves since the last capture or pawn move
+    pub fn get_half_move_clock(&self) -> u8 {
+        self.half_move_clock
+    }
+
+    /// Returns the castling rights of both players
+    pub fn get_castling(&self) -> &Castling {
+        &self.castling
+    }
+
+    /// Returns the field a pawn can be captured on en passant, if any
+    ///
+    /// The field is set after every double pawn push, whether a capturing pawn exists or not.
+    pub fn get_en_passant(&self) -> Option<Square> {
+        Square::from_index(self.en_passant).ok()
+    }
+
+    /// Returns the en passant field if a pawn of the color to move stands next to the pushed pawn
+    ///
+    /// Only then does the en passant field change the position in the sense of the repetition
+    /// rules, so only this field is part of the hash. Pins are not considered.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let g = Game::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+    /// assert_eq!(g.capturable_en_passant().map(|s| s.to_string()), Some(String::from("e3")));
+    /// let g = Game::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+    /// assert_eq!(g.capturable_en_passant(), None);
+    /// ```
+    pub fn capturable_en_passant(&self) -> Option<Square> {
+        Square::from_index(self.hashed_en_passant()).ok()
+    }
+
+    /// Returns the en passant field if a pawn can capture on it and 255 otherwise
+    fn hashed_en_passant(&self) -> u8 {
+        if self.en_passant > 63 {
+            return 255;
+        }
+        let victim = en_passant_victim(self.en_passant, self.color_to_move);
+        let file = victim % 8;
+        let mut neighbours = 0u64;
+        if file > 0 {
+            neighbours |= 1 << (victim - 1);
+        }
+        if file < 7 {
+            neighbours |= 1 << (victim + 1);
+        }
+        let capturers = self.board.pieces_of(PieceType::Pawn, self.color_to_move).0 & neighbours;
+        if capturers != 0 {
+            self.en_passant
+        } else {
+            255
+        }
+    }
+
+    /// Returns the zobrist hash of the current position
+    ///
+    /// The hash covers the board, the side to move, castling rights and the en passant field if
+    /// a pawn can capture on it, see [`capturable_en_passant`], but not the clocks. Equal
+    /// positions in the sense of the repetition rules hash equally.
+    ///
+    /// [`capturable_en_passant`]: #method.capturable_en_passant
+    pub fn get_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns a bitboard of all occupied fields
+    ///
+    /// Unlike [`Board::occupancy`] this is cached and does not combine the bitboards.
+    ///
+    /// [`Board::occupancy`]: struct.Board.html#method.occupancy
+    #[inline(always)]
+    pub fn occupancy(&self) -> Bitboard {
+        Bitboard(self.all_pieces)
+    }
+
+    /// Returns a bitboard of all fields occupied by pieces of the color
+    ///
+    /// Unlike [`Board::occupancy_of`] this is cached and does not combine the bitboards.
+    ///
+    /// [`Board::occupancy_of`]: struct.Board.html#method.occupancy_of
+    #[inline(always)]
+    pub fn occupancy_of(&self, color: Color) -> Bitboard {
+        match color {
+            Color::White => Bitboard(self.white_pieces),
+            Color::Black => Bitboard(self.black_pieces),
+        }
+    }
+
+    /// Recomputes the cached occupied fields from the board
+    ///
+    /// Only needed after changing `board` directly.
+    pub fn refresh_occupancy(&mut self) {
+        self.white_pieces = self.board.occupancy_of(Color::White).0;
+        self.black_pieces = self.board.occupancy_of(Color::Black).0;
+        self.all_pieces = self.white_pieces | self.black_pieces;
+    }
+
+    /// Toggles the fields changed by the action of the color to move in the occupancy cache
+    ///
+    /// Toggling twice restores the cache, so this is used for both executing and undoing.
+    fn toggle_occupancy(&mut self, action: &Action, en_passant: u8) {
+        let color = self.color_to_move;
+        let to = action.get_to_index();
+        let mut own = 1u64 << action.get_from_index() ^ 1u64 << to;
+        if let ActionType::Castling(is_kingside_castling) = action.get_action_type() {
+            own ^= match (color, is_kingside_castling) {
+                (Color::White, true) => 1 << 63 | 1 << 61,
+                (Color::White, false) => 1 << 56 | 1 << 59,
+                (Color::Black, true) => 1 << 7 | 1 << 5,
+                (Color::Black, false) => 1 | 1 << 3,
+            };
+        }
+        let opponent = if !action.is_capture() {
+            0
+        } else if is_en_passant_capture(action, en_passant) {
+            1u64 << en_passant_victim(to, color)
+        } else {
+            1u64 << to
+        };
+        let (white, black) = match color {
+            Color::White => (own, opponent),
+            Color::Black => (opponent, own),
+        };
+        self.white_pieces ^= white;
+        self.black_pieces ^= black;
+        self.all_pieces = self.white_pieces | self.black_pieces;
+        #[cfg(feature = "debug-validate")]
+        {
+            assert_eq!(
+                self.all_pieces,
+                self.board.occupancy().0,
+                "Occupancy cache does not match the board"
+            );
+        }
+    }
+
+    /// Panics if the incremental hash differs from a recomputed one with the `verify-hash` feature
+    #[inline(always)]
+    fn verify_hash(&self) {
+        #[cfg(feature = "verify-hash")]
+        {
+            let expected = self.compute_hash();
+            assert!(
+                self.hash == expected,
+                "Incremental hash {:016x} does not match the recomputed hash {:016x} of {}",
+                self.hash,
+                expected,
+                self.to_fen()
+            );
+        }
+    }
+
+    /// Computes the zobrist hash of the current position from scratch
+    fn compute_hash(&self) -> u64 {
+        let mut hash = 0;
+        for index in 0..64 {
+            if let Some(piece) = self.board.get_piecetype_on(index) {
+                let color = if self.board.whites >> index & 1 == 1 {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                hash ^= zobrist::piece_key(color, piece, index);
+            }
+        }
+        hash ^= zobrist::castling_key(self.castling.get_raw());
+        hash ^= zobrist::en_passant_key(self.hashed_en_passant());
+        if self.color_to_move == Color::Black {
+            hash ^= zobrist::side_key();
+        }
+        hash
+    }
+
+    /// Returns true if the current position occurred at least three times
+    ///
+    /// Only positions reached since the last capture or pawn move are considered, as no earlier
+    /// position can be repeated. Positions before the game was constructed are unknown.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// let mut g = Game::startpos();
+    /// for _ in 0..2 {
+    ///     assert!(!g.is_draw_by_repetition());
+    ///     for san in &["Nf3", "Nf6", "Ng1", "Ng8"] {
+    ///         let a = Action::from_san(san, &g).unwrap();
+    ///         g.execute_action(&a);
+    ///     }
+    /// }
+    /// assert!(g.is_draw_by_repetition());
+    /// ```
+    pub fn is_draw_by_repetition(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    /// Returns how often the current position occurred, including the current occurrence
+    pub fn repetition_count(&self) -> usize {
+        let reversible = (self.half_move_clock as usize).min(self.history.len());
+        1 + self.history[self.history.len() - reversible..]
+            .iter()
+            .rev()
+            .skip(1)
+            .step_by(2)
+            .filter(|hash| **hash == self.hash)
+            .count()
+    }
+
+    /// Returns true if no capture or pawn move happened in the last fifty moves of both players
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// assert!(!Game::from_fen("8/8/4k3/8/8/4K3/8/7R w - - 99 80").unwrap().is_draw_by_fifty_move_rule());
+    /// assert!(Game::from_fen("8/8/4k3/8/8/4K3/8/7R b - - 100 80").unwrap().is_draw_by_fifty_move_rule());
+    /// ```
+    pub fn is_draw_by_fifty_move_rule(&self) -> bool {
+        self.half_move_clock >= 100
+    }
+
+    /// Returns true if the king of the color to move is attacked
+    pub fn is_in_check(&self) -> bool {
+        movegen::is_in_check(self)
+    }
+
+    /// Returns the checkers, pinned pieces and discovered check candidates of the position
+    ///
+    /// See [`CheckInfo`] for sharing it between move generation and other code looking at the
+    /// same position.
+    ///
+    /// [`CheckInfo`]: ../move_generation/struct.CheckInfo.html
+    pub fn check_info(&self) -> CheckInfo {
+        CheckInfo::new(self)
+    }
+
+    /// Returns true if the color to move is in check and has no legal action
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let g = Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+    /// assert!(g.is_checkmate());
+    /// assert!(!Game::startpos().is_checkmate());
+    /// ```
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check() && movegen::legal_moves(self).is_empty()
+    }
+
+    /// Returns true if the color to move is not in check but has no legal action
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let g = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    /// assert!(g.is_stalemate());
+    /// assert!(!g.is_checkmate());
+    /// ```
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_in_check() && movegen::legal_moves(self).is_empty()
+    }
+
+    /// Returns the result of the game in the current position
+    ///
+    /// Checkmate wins for the opponent of the color to move. Stalemate, threefold repetition and
+    /// the fifty move rule are draws, in this order of precedence after checkmate. Repetitions
+    /// and the fifty move rule are treated as claimed, see [`claimable_draws`] and
+    /// [`is_automatic_draw`] to offer the claim to the player instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Game, GameResult};
+    /// assert_eq!(Game::startpos().result(), GameResult::Ongoing);
+    /// let g = Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+    /// assert_eq!(g.result(), GameResult::BlackWins);
+    /// ```
+    ///
+    /// [`claimable_draws`]: #method.claimable_draws
+    /// [`is_automatic_draw`]: #method.is_automatic_draw
+    pub fn result(&self) -> GameResult {
+        if movegen::legal_moves(self).is_empty() {
+            if self.is_in_check() {
+                GameResult::win_for(self.color_to_move.get_opponent_color())
+            } else {
+                GameResult::Draw
+            }
+        } else if self.is_draw_by_repetition() || self.is_draw_by_fifty_move_rule() {
+            GameResult::Draw
+        } else {
+            GameResult::Ongoing
+        }
+    }
+
     /// Executes the given action on the state
     ///
     /// Does not check if the action is legal or sensible. Corrupt game states can be provoked
     /// by executing this method with non-legal actions.
-    pub fn execute_action(&mut self, action: &Action) {
-        self.half_move_clock += 1;
+    /// The returned token can be passed to [`undo_action`] to revert the action again.
+    ///
+    /// [`undo_action`]: #method.undo_action
+    pub fn execute_action(&mut self, action: &Action) -> UndoToken {
+        let token = UndoToken {
+            en_passant: self.en_passant,
+            castling: self.castling.get_raw(),
+            half_move_clock: self.half_move_clock,
+            hash: self.hash,
+        };
+        let hashed_en_passant = self.hashed_en_passant();
+        self.history.push(self.hash);
+        self.hash ^= self.action_hash_delta(action);
+        self.half_move_clock = self.half_move_clock.saturating_add(1);
         self.board.execute_action(action, self.color_to_move);
+        self.toggle_occupancy(action, token.en_passant);
 
         match action.get_action_type() {
             ActionType::Castling(_) => match self.color_to_move {
@@ -157,101 +623,313 @@ impl Game {
             _ => {}
         };
 
+        // a rook captured on its initial field can no longer castle
+        match action.get_to_index() {
+            0 => self.castling.remove(Castling::get_black_queenside()),
+            7 => self.castling.remove(Castling::get_black_kingside()),
+            56 => self.castling.remove(Castling::get_white_queenside()),
+            63 => self.castling.remove(Castling::get_white_kingside()),
+            _ => {}
+        };
+
+        self.full_move_clock += self.color_to_move as u32;
+        self.color_to_move = self.color_to_move.get_opponent_color();
+        self.hash ^= zobrist::castling_key(token.castling)
+            ^ zobrist::castling_key(self.castling.get_raw())
+            ^ zobrist::en_passant_key(hashed_en_passant)
+            ^ zobrist::en_passant_key(self.hashed_en_passant())
+            ^ zobrist::side_key();
+        self.verify_hash();
+        token
+    }
+
+    /// Returns the change of the hash caused by the pieces moved by the action
+    fn action_hash_delta(&self, action: &Action) -> u64 {
+        let color = self.color_to_move;
+        let piece = action.get_piecetype();
+        let from = action.get_from_index();
+        let to = action.get_to_index();
+        let mut delta = zobrist::piece_key(color, piece, from)
+            ^ zobrist::piece_key(color, action.get_promotion_piece().unwrap_or(piece), to);
+        if let Some(captured) = action.get_capture_piece() {
+            let captured_at = if is_en_passant_capture(action, self.en_passant) {
+                en_passant_victim(to, color)
+            } else {
+                to
+            };
+            delta ^= zobrist::piece_key(color.get_opponent_color(), captured, captured_at);
+        }
+        if let ActionType::Castling(is_kingside_castling) = action.get_action_type() {
+            let (rook_from, rook_to) = match (color, is_kingside_castling) {
+                (Color::White, true) => (63, 61),
+                (Color::White, false) => (56, 59),
+                (Color::Black, true) => (7, 5),
+                (Color::Black, false) => (0, 3),
+            };
+            delta ^= zobrist::piece_key(color, PieceType::Rook, rook_from)
+                ^ zobrist::piece_key(color, PieceType::Rook, rook_to);
+        }
+        delta
+    }
+
+    /// Reverts the last executed action
+    ///
+    /// The token has to be the one returned when executing the action. Undoing any action other
+    /// than the last executed one leads to a corrupt game state.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// let mut g = Game::startpos();
+    /// let a = Action::from_san("e4", &g).unwrap();
+    /// let token = g.execute_action(&a);
+    /// g.undo_action(&a, token);
+    /// assert_eq!(g.to_fen(), Game::startpos().to_fen());
+    /// ```
+    pub fn undo_action(&mut self, action: &Action, token: UndoToken) {
+        self.color_to_move = self.color_to_move.get_opponent_color();
+        self.full_move_clock -= self.color_to_move as u32;
+        self.board.undo_action(action, self.color_to_move);
+        if is_en_passant_capture(action, token.en_passant) {
+            let to = action.get_to_index();
+            self.board.clear_field(to);
+            self.board.set_field(
+                en_passant_victim(to, self.color_to_move),
+                PieceType::Pawn,
+                self.color_to_move.get_opponent_color(),
+            );
+        }
+        self.toggle_occupancy(action, token.en_passant);
+        self.en_passant = token.en_passant;
+        self.castling = Castling::from_raw(token.castling);
+        self.half_move_clock = token.half_move_clock;
+        self.hash = token.hash;
+        self.history.pop();
+        self.verify_hash();
+    }
+
+    /// Passes the turn to the opponent without moving a piece
+    ///
+    /// The en passant field is cleared and the hash is updated, the null move counts like a
+    /// quiet move for the clocks and the repetition history. Search code uses this for null
+    /// move pruning, which must not pass while the color to move is in check.
+    /// The returned token can be passed to [`undo_null_move`] to take the null move back.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Color, Game};
+    /// let mut g = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+    /// let token = g.make_null_move();
+    /// assert_eq!(g.to_fen(), "4k3/8/8/3pP3/8/8/8/4K3 b - - 1 2");
+    /// assert_eq!(g.get_hash(), Game::from_fen(&g.to_fen()).unwrap().get_hash());
+    /// g.undo_null_move(token);
+    /// assert_eq!(g.to_fen(), "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2");
+    /// ```
+    ///
+    /// [`undo_null_move`]: #method.undo_null_move
+    pub fn make_null_move(&mut self) -> UndoToken {
+        let token = UndoToken {
+            en_passant: self.en_passant,
+            castling: self.castling.get_raw(),
+            half_move_clock: self.half_move_clock,
+            hash: self.hash,
+        };
+        let hashed_en_passant = self.hashed_en_passant();
+        self.history.push(self.hash);
+        self.half_move_clock = self.half_move_clock.saturating_add(1);
+        self.en_passant = 255;
         self.full_move_clock += self.color_to_move as u32;
         self.color_to_move = self.color_to_move.get_opponent_color();
+        self.hash ^= zobrist::en_passant_key(hashed_en_passant) ^ zobrist::side_key();
+        self.verify_hash();
+        token
+    }
+
+    /// Takes back the last null move
+    ///
+    /// The token has to be the one returned by [`make_null_move`]. Undoing a null move that was
+    /// not the last executed move leads to a corrupt game state.
+    ///
+    /// [`make_null_move`]: #method.make_null_move
+    pub fn undo_null_move(&mut self, token: UndoToken) {
+        self.color_to_move = self.color_to_move.get_opponent_color();
+        self.full_move_clock -= self.color_to_move as u32;
+        self.en_passant = token.en_passant;
+        self.half_move_clock = token.half_move_clock;
+        self.hash = token.hash;
+        self.history.pop();
+        self.verify_hash();
     }
 
     /// Returns a game struct from a Forsyth-Edwards Notation representation
     ///
+    /// Positions that can not arise in a game are accepted as long as the board encoding stays
+    /// valid, use [`validate`] to find them.
+    ///
     /// # Errors
     /// * There are not exactly 6 parts split by spaces
     /// * The supplied color is not 'w' or 'b'
-    /// * The supplied board representation is not valid
+    /// * The supplied board representation is not valid, see [`Board::from_fen`]
     /// * The en passant information can not be parsed
     /// * The castling information contains any character other than 'K', 'Q', 'k', 'q' or '-'
     /// * The full move or half move is not a number
+    /// * The en passant field can not follow a double pawn push of the opponent, see
+    ///   [`from_fen_lenient`] to ignore such a field instead
+    ///
+    /// [`validate`]: #method.validate
+    /// [`Board::from_fen`]: struct.Board.html#method.from_fen
+    /// [`from_fen_lenient`]: #method.from_fen_lenient
     pub fn from_fen(fen: &str) -> Result<Game, ParserError> {
+        Game::parse_fen(fen, true)
+    }
+
+    /// Returns a game struct from a FEN, dropping an invalid en passant field
+    ///
+    /// Works like [`from_fen`], but an en passant field that can not follow a double pawn push
+    /// of the opponent is replaced with `-` instead of returning an error. The board is read by
+    /// [`Board::from_fen_lenient`], so short ranks and several kings of a color are accepted,
+    /// see [`validate`] for positions that can not be searched.
+    ///
+    /// # Errors
+    /// * The FEN can not be parsed for any other reason listed in [`from_fen`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e6 0 1";
+    /// assert!(Game::from_fen(fen).is_err());
+    /// let game = Game::from_fen_lenient(fen).unwrap();
+    /// assert_eq!(game.get_en_passant(), None);
+    /// ```
+    ///
+    /// [`from_fen`]: #method.from_fen
+    /// [`Board::from_fen_lenient`]: struct.Board.html#method.from_fen_lenient
+    /// [`validate`]: #method.validate
+    pub fn from_fen_lenient(fen: &str) -> Result<Game, ParserError> {
+        Game::parse_fen(fen, false)
+    }
+
+    fn parse_fen(fen: &str, strict: bool) -> Result<Game, ParserError> {
         // parts: 0|board 1|color 2|castling 3|en_passant 4|half_move 5|full_move
         let parts: Vec<&str> = fen.split(' ').collect();
         if parts.len() != 6 {
             return Err(ParserError::WrongParameterNumber);
         }
-        let board = Board::from_fen(parts[0])?;
+        let mut offsets = [0; 6];
+        for i in 1..6 {
+            offsets[i] = offsets[i - 1] + parts[i - 1].len() + 1;
+        }
+        let error = |field: FenField, offset: usize, reason| {
+            let index = field as usize;
+            ParserError::BadFenField {
+                field,
+                token: parts[index].to_string(),
+                offset: offsets[index] + offset,
+                reason,
+            }
+        };
+        let board = if strict {
+            Board::from_fen(parts[0])?
+        } else {
+            Board::from_fen_lenient(parts[0])?
+        };
 
         let color_to_move = match parts[1] {
             "w" => Color::White,
             "b" => Color::Black,
-            _ => return Err(ParserError::InvalidParameter("Color information is wrong")),
+            _ => return Err(error(FenField::Color, 0, "Color information is wrong")),
         };
 
         let mut castling = 0;
-        let chars: Vec<char> = parts[2].chars().collect();
-        if chars[0] == '-' {
-            castling = 0;
-        } else if chars.len() > 4 {
-            return Err(ParserError::WrongParameterNumber);
-        } else {
-            for c in chars {
-                match c {
-                    'K' => {
-                        castling |= Castling::get_white_kingside();
-                    }
-                    'Q' => {
-                        castling |= Castling::get_white_queenside();
-                    }
-                    'k' => {
-                        castling |= Castling::get_black_kingside();
-                    }
-                    'q' => {
-                        castling |= Castling::get_black_queenside();
-                    }
+        if parts[2].is_empty() {
+            return Err(error(FenField::Castling, 0, "Castling field is empty"));
+        } else if parts[2] != "-" {
+            for (i, c) in parts[2].char_indices() {
+                castling |= match c {
+                    'K' => Castling::get_white_kingside(),
+                    'Q' => Castling::get_white_queenside(),
+                    'k' => Castling::get_black_kingside(),
+                    'q' => Castling::get_black_queenside(),
                     _ => {
-                        return Err(ParserError::InvalidParameter(
+                        return Err(error(
+                            FenField::Castling,
+                            i,
                             "Castling information is wrong",
-                        ));
+                        ))
                     }
+                };
+                if i >= 4 {
+                    return Err(error(
+                        FenField::Castling,
+                        i,
+                        "More than four castling rights",
+                    ));
                 }
             }
         }
         let castling = Castling::from_raw(castling);
 
-        let en_passant = if parts[3] == "-" {
+        let mut en_passant = if parts[3] == "-" {
             255
         } else {
-            bitboard::field_repr_to_index(parts[3])?
+            bitboard::field_repr_to_index(parts[3])
+                .map_err(|e| e.in_fen_field(FenField::EnPassant, parts[3], offsets[3]))?
         };
+        if en_passant != 255 && !is_valid_en_passant(&board, color_to_move, en_passant) {
+            if strict {
+                return Err(error(
+                    FenField::EnPassant,
+                    0,
+                    "En passant field does not follow a double pawn push",
+                ));
+            }
+            en_passant = 255;
+        }
 
-        let half_move_clock = if let Ok(x) = parts[4].parse() {
-            x
-        } else {
-            return Err(ParserError::InvalidParameter(
-                "Full move clock is not a number",
-            ));
-        };
-        let full_move_clock = if let Ok(x) = parts[5].parse() {
-            x
-        } else {
-            return Err(ParserError::InvalidParameter(
-                "Full move clock is not a number",
-            ));
-        };
+        let half_move_clock = parts[4].parse().map_err(|_| {
+            error(
+                FenField::HalfMoveClock,
+                0,
+                "Half move clock is not a number",
+            )
+        })?;
+        let full_move_clock = parts[5].parse().map_err(|_| {
+            error(
+                FenField::FullMoveNumber,
+                0,
+                "Full move number is not a number",
+            )
+        })?;
 
-        Ok(Game {
+        let mut game = Game {
             board,
             castling,
             en_passant,
             half_move_clock,
             full_move_clock,
             color_to_move,
-        })
+            hash: 0,
+            history: Vec::new(),
+            all_pieces: 0,
+            white_pieces: 0,
+            black_pieces: 0,
+        };
+        game.hash = game.compute_hash();
+        game.refresh_occupancy();
+        Ok(game)
     }
 
-    /// Returns game from a given pgn string
+    /// Returns the position at the end of the main line of the first game in the pgn string
     ///
-    /// is very naive
+    /// Variations, comments and annotations are skipped. Use [`PgnGame`] to access them.
+    ///
+    /// # Errors
+    /// * The pgn can not be parsed by [`PgnGame::parse`]
+    /// * Any move of the main line can not be parsed in its position
     /// # Examples
     /// ```
-    /// # use core::game_representation::Game;
+    /// # use rust_chess::game_representation::Game;
     /// assert_eq!(
     ///     Game::from_pgn(
     ///         r#"[Event "?"]
@@ -269,26 +947,89 @@ impl Game {
     ///     "r1bq1rk1/pp2ppbp/2np1np1/8/3NP3/2N1BP2/PPPQ2PP/R3KB1R w KQ - 3 9"
     /// );
     /// ```
+    ///
+    /// [`PgnGame`]: ../pgn/struct.PgnGame.html
+    /// [`PgnGame::parse`]: ../pgn/struct.PgnGame.html#method.parse
+    #[cfg(feature = "std")]
     pub fn from_pgn(pgn_string: &str) -> Result<Game, ParserError> {
-        let mut g = Game::startpos();
-        // discard everything before first move
-        let parts = pgn_string.split("]").collect::<Vec<_>>();
-        let pgn_string = parts[parts.len() - 1];
+        PgnGame::parse(pgn_string)?.to_game()
+    }
+}
 
-        let full_moves = pgn_string.split(".").skip(1);
-        for full_move in full_moves {
-            let half_moves: Vec<_> = full_move.split(" ").skip(1).collect();
+impl PartialEq for Game {
+    fn eq(&self, other: &Game) -> bool {
+        self.board == other.board
+            && self.color_to_move == other.color_to_move
+            && self.castling == other.castling
+            && self.en_passant == other.en_passant
+            && self.half_move_clock == other.half_move_clock
+            && self.full_move_clock == other.full_move_clock
+    }
+}
 
-            if half_moves.len() > 0 {
-                let a = Action::from_san(half_moves[0], &g)?;
-                g.execute_action(&a);
-            }
-            if half_moves.len() > 1 {
-                let a = Action::from_san(half_moves[1], &g)?;
-                g.execute_action(&a);
-            }
-        }
-        Ok(g)
+impl Eq for Game {}
+
+impl core::hash::Hash for Game {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.board.hash(state);
+        self.color_to_move.hash(state);
+        self.castling.hash(state);
+        self.en_passant.hash(state);
+        self.half_move_clock.hash(state);
+        self.full_move_clock.hash(state);
+    }
+}
+
+/// Returns true if the action captures en passant on the given en passant field
+fn is_en_passant_capture(action: &Action, en_passant: u8) -> bool {
+    action.get_piecetype() == PieceType::Pawn
+        && action.get_action_type() == ActionType::Capture(PieceType::Pawn)
+        && action.get_to_index() == en_passant
+}
+
+/// Returns true if the opponent of `color` could just have double pushed a pawn over the field
+///
+/// The field has to be on the sixth rank for white to move and on the third rank for black to
+/// move, with the pushed pawn right in front of it and the field the pawn came from empty.
+fn is_valid_en_passant(board: &Board, color: Color, en_passant: u8) -> bool {
+    let rank = match color {
+        Color::White => bitboard::constants::RANKS[5],
+        Color::Black => bitboard::constants::RANKS[2],
+    };
+    if rank & (1 << en_passant) == 0 {
+        return false;
+    }
+    // the pawn moved away from the opponent
+    let (pushed, origin) = match color {
+        Color::White => (en_passant + 8, en_passant - 8),
+        Color::Black => (en_passant - 8, en_passant + 8),
+    };
+    let pushed = Square::from_index(pushed).expect("field is on the board");
+    board.piece_at(pushed) == Some((PieceType::Pawn, color.get_opponent_color()))
+        && board.occupancy().0 & (1 << en_passant | 1 << origin) == 0
+}
+
+/// Returns the shift index of the pawn captured by an en passant capture onto `to`
+fn en_passant_victim(to: u8, color: Color) -> u8 {
+    match color {
+        Color::White => to + 8,
+        Color::Black => to - 8,
+    }
+}
+
+/// Games are serialized as their FEN, so the history of earlier positions is not kept
+#[cfg(feature = "serde")]
+impl Serialize for Game {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_fen())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Game {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Game, D::Error> {
+        let fen = String::deserialize(deserializer)?;
+        Game::from_fen(&fen).map_err(serde::de::Error::custom)
     }
 }
 
@@ -412,6 +1153,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn test_pgn_reading() {
         assert_eq!(
             Game::from_pgn(
@@ -626,6 +1368,182 @@ mod tests {
         assert_eq!(state.to_fen(), "4k3/p7/8/P6p/8/3PP2P/4K2P/1n6 w - - 0 32");
     }
 
+    #[test]
+    fn undo_restores_state() {
+        let cases: [(&str, &str, &str, PieceType, ActionType); 7] = [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                "e2",
+                "e4",
+                PieceType::Pawn,
+                ActionType::Quiet,
+            ),
+            (
+                "rnbqkbnr/pp2pppp/3p4/2p5/3PP3/5N2/PPP2PPP/RNBQKB1R b KQkq d3 0 3",
+                "c5",
+                "d4",
+                PieceType::Pawn,
+                ActionType::Capture(PieceType::Pawn),
+            ),
+            (
+                "1nbqkb1r/rpppp1pp/5n1B/p4p2/3P4/2NQ4/PPP1PPPP/R3KBNR w KQk - 2 5",
+                "e1",
+                "c1",
+                PieceType::King,
+                ActionType::Castling(false),
+            ),
+            (
+                "1nbqk2r/rppp2pp/3b1n1B/p2Ppp2/4N3/3Q4/PPP1PPPP/2KR1BNR b k - 2 7",
+                "e8",
+                "g8",
+                PieceType::King,
+                ActionType::Castling(true),
+            ),
+            (
+                "4k3/p1p5/8/7p/P7/3PP2P/4K1pP/1R6 b - - 1 26",
+                "g2",
+                "g1",
+                PieceType::Pawn,
+                ActionType::Promotion(PieceType::Queen),
+            ),
+            (
+                "4k3/p7/8/P6p/8/3PP2P/2p1K2P/1R6 b - - 0 31",
+                "c2",
+                "b1",
+                PieceType::Pawn,
+                ActionType::PromotionCapture(PieceType::Knight, PieceType::Rook),
+            ),
+            (
+                "r3k2r/8/3Q4/8/8/5q2/8/R3K2R w KQkq - 5 20",
+                "d6",
+                "d8",
+                PieceType::Queen,
+                ActionType::Quiet,
+            ),
+        ];
+        for (fen, from, to, piece, actiontype) in cases.iter() {
+            let mut state = Game::from_fen(fen).unwrap();
+            let action = Action::new(
+                bitboard::field_repr_to_coords(from).unwrap(),
+                bitboard::field_repr_to_coords(to).unwrap(),
+                *piece,
+                *actiontype,
+            );
+            let token = state.execute_action(&action);
+            assert_ne!(&state.to_fen(), fen);
+            state.undo_action(&action, token);
+            assert_eq!(&state.to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn incremental_hash_matches_fen() {
+        let mut state = Game::startpos();
+        for san in &[
+            "e4", "c5", "Nf3", "d6", "d4", "cxd4", "Nxd4", "Nf6", "Nc3", "g6", "Be3", "Bg7", "f3",
+            "O-O", "Qd2", "Nc6", "O-O-O", "a5",
+        ] {
+            let action = Action::from_san(san, &state).unwrap();
+            let before = state.get_hash();
+            let token = state.execute_action(&action);
+            assert_eq!(
+                state.get_hash(),
+                Game::from_fen(&state.to_fen()).unwrap().get_hash()
+            );
+            assert_ne!(state.get_hash(), before);
+            state.undo_action(&action, token);
+            assert_eq!(state.get_hash(), before);
+            state.execute_action(&action);
+        }
+    }
+
+    #[cfg(feature = "verify-hash")]
+    #[test]
+    #[should_panic(expected = "does not match the recomputed hash")]
+    fn stale_hashes_panic() {
+        let mut state = Game::startpos();
+        // removing a piece directly leaves the incremental hash behind
+        state.board.knights &= !(1 << 57);
+        state.board.whites &= !(1 << 57);
+        state.board.sync_mailbox();
+        state.board.sync_piece_counts();
+        state.refresh_occupancy();
+        let action = Action::from_san("e4", &state).unwrap();
+        state.execute_action(&action);
+    }
+
+    #[test]
+    fn transpositions_hash_equally() {
+        let mut a = Game::startpos();
+        let mut b = Game::startpos();
+        for san in &["Nf3", "Nf6", "Nc3", "Nc6"] {
+            let action = Action::from_san(san, &a).unwrap();
+            a.execute_action(&action);
+        }
+        for san in &["Nc3", "Nc6", "Nf3", "Nf6"] {
+            let action = Action::from_san(san, &b).unwrap();
+            b.execute_action(&action);
+        }
+        assert_eq!(a.get_hash(), b.get_hash());
+
+        // same pieces but a different en passant field
+        let c =
+            Game::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        let d =
+            Game::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_ne!(c.get_hash(), d.get_hash());
+        // an en passant field no pawn can capture on does not change the position
+        let e =
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        let f =
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_eq!(e.get_hash(), f.get_hash());
+    }
+
+    #[test]
+    fn repetition_needs_reversible_moves() {
+        let mut state = Game::startpos();
+        for san in &["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1"] {
+            let action = Action::from_san(san, &state).unwrap();
+            state.execute_action(&action);
+        }
+        assert_eq!(state.repetition_count(), 2);
+        let action = Action::from_san("Ng8", &state).unwrap();
+        let token = state.execute_action(&action);
+        assert_eq!(state.repetition_count(), 3);
+        assert!(state.is_draw_by_repetition());
+        state.undo_action(&action, token);
+        assert!(!state.is_draw_by_repetition());
+
+        // a pawn move makes every earlier position unreachable
+        let mut state = Game::startpos();
+        for san in &[
+            "Nf3", "Nf6", "Ng1", "Ng8", "e3", "e6", "Nf3", "Nf6", "Ng1", "Ng8",
+        ] {
+            let action = Action::from_san(san, &state).unwrap();
+            state.execute_action(&action);
+        }
+        assert_eq!(state.repetition_count(), 2);
+
+        // the en passant field after the double push can not be used, so the position repeats
+        let mut state = Game::startpos();
+        for san in &["e4", "Nf6", "Nf3", "Ng8", "Ng1", "Nf6", "Nf3", "Ng8", "Ng1"] {
+            let action = Action::from_san(san, &state).unwrap();
+            state.execute_action(&action);
+        }
+        assert_eq!(state.repetition_count(), 3);
+        assert!(state.is_draw_by_repetition());
+
+        // a capturable en passant field only exists right after the double push
+        let mut state =
+            Game::from_fen("rnbqkbnr/ppp1pppp/8/8/3p4/5N2/PPPPPPPP/RNBQKB1R w KQkq - 0 1").unwrap();
+        for san in &["e4", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8", "Nf3"] {
+            let action = Action::from_san(san, &state).unwrap();
+            state.execute_action(&action);
+        }
+        assert_eq!(state.repetition_count(), 2);
+    }
+
     fn do_action(state: &mut Game, from: &str, to: &str, piece: PieceType, actiontype: ActionType) {
         let action = Action::new(
             bitboard::field_repr_to_coords(from).expect("could not convert repr"),
@@ -823,4 +1741,153 @@ mod tests {
             "r2qrbk1/1b1n1p2/3p1np1/p1pPp2p/1pP1P3/PP2BN1P/2BQ1PP1/R3RNK1 w - - 0 21"
         );
     }
+
+    #[test]
+    fn null_moves_between_actions() {
+        let mut g = Game::startpos();
+        let e4 = Action::from_san("e4", &g).unwrap();
+        let first = g.execute_action(&e4);
+        let null = g.make_null_move();
+        assert_eq!(g.color_to_move, Color::White);
+        assert_eq!(g.get_en_passant(), None);
+        assert_eq!(g.get_full_move_clock(), 2);
+        let d4 = Action::from_san("d4", &g).unwrap();
+        let second = g.execute_action(&d4);
+        assert_eq!(g.get_hash(), g.compute_hash());
+        assert_eq!(
+            g.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq d3 0 2"
+        );
+        g.undo_action(&d4, second);
+        g.undo_null_move(null);
+        g.undo_action(&e4, first);
+        assert_eq!(g, Game::startpos());
+    }
+
+    #[test]
+    fn en_passant_execute_and_undo() {
+        let mut state = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+        let action = Action::from_san("exd6", &state).unwrap();
+        let token = state.execute_action(&action);
+        assert_eq!(state.to_fen(), "4k3/8/3P4/8/8/8/8/4K3 b - - 0 2");
+        assert_eq!(
+            state.get_hash(),
+            Game::from_fen(&state.to_fen()).unwrap().get_hash()
+        );
+        state.undo_action(&action, token);
+        assert_eq!(state.to_fen(), "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2");
+    }
+
+    #[test]
+    fn occupancy_cache_follows_actions() {
+        let mut state = Game::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 2").unwrap();
+        let mut tokens = Vec::new();
+        let moves = ["exd6", "O-O", "bxa8=Q", "Kg7", "O-O-O"];
+        for san in &moves {
+            let action = Action::from_san(san, &state).unwrap();
+            tokens.push((action, state.execute_action(&action)));
+            assert_eq!(state.occupancy(), state.board.occupancy());
+            assert_eq!(
+                state.occupancy_of(Color::White),
+                state.board.occupancy_of(Color::White)
+            );
+        }
+        while let Some((action, token)) = tokens.pop() {
+            state.undo_action(&action, token);
+            assert_eq!(
+                state.occupancy_of(Color::Black),
+                state.board.occupancy_of(Color::Black)
+            );
+        }
+        assert_eq!(state.occupancy().count(), 9);
+    }
+
+    #[test]
+    fn en_passant_validation() {
+        let valid = [
+            "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2",
+            "4k3/8/8/8/3P4/8/8/4K3 b - d3 0 1",
+            // no pawn can capture, but the field is still valid
+            "4k3/8/8/p7/8/8/8/4K3 w - a6 0 2",
+        ];
+        for fen in &valid {
+            assert!(
+                Game::from_fen(fen).unwrap().get_en_passant().is_some(),
+                "{}",
+                fen
+            );
+        }
+        let invalid = [
+            // wrong rank for the side to move
+            "4k3/8/8/8/3P4/8/8/4K3 w - d3 0 1",
+            "4k3/8/8/8/8/8/8/4K3 w - d4 0 1",
+            // no pushed pawn, or a pawn of the wrong color
+            "4k3/8/8/8/8/8/8/4K3 w - d6 0 2",
+            "4k3/8/8/3P4/8/8/8/4K3 w - d6 0 2",
+            // the pawn could not have passed the field
+            "4k3/3n4/8/3p4/8/8/8/4K3 w - d6 0 2",
+            "4k3/8/3b4/3p4/8/8/8/4K3 w - d6 0 2",
+        ];
+        for fen in &invalid {
+            assert!(Game::from_fen(fen).is_err(), "{}", fen);
+            let game = Game::from_fen_lenient(fen).unwrap();
+            assert_eq!(game.get_en_passant(), None);
+            assert_eq!(
+                game.get_hash(),
+                Game::from_fen(&game.to_fen()).unwrap().get_hash()
+            );
+        }
+    }
+
+    #[test]
+    fn capturing_rook_removes_castling() {
+        let mut state = Game::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let action = Action::from_san("Rxa8", &state).unwrap();
+        state.execute_action(&action);
+        assert_eq!(state.to_fen(), "R3k2r/8/8/8/8/8/8/4K2R b Kk - 0 1");
+    }
+
+    #[test]
+    fn checkmate_takes_precedence() {
+        let state = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 100 60").unwrap();
+        assert!(state.is_checkmate());
+        assert_eq!(state.result(), GameResult::WhiteWins);
+        let state = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 b - - 100 60").unwrap();
+        assert_eq!(state.result(), GameResult::Draw);
+    }
+
+    #[test]
+    fn equality_ignores_history() {
+        use std::collections::HashSet;
+
+        let mut game = Game::startpos();
+        for (from, to) in &[("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")] {
+            do_action(&mut game, from, to, PieceType::Knight, ActionType::Quiet);
+        }
+        let fresh = Game::from_fen(&game.to_fen()).unwrap();
+        assert_eq!(game, fresh);
+        assert!(game.repetition_count() > fresh.repetition_count());
+
+        let mut positions = HashSet::new();
+        positions.insert(game.clone());
+        assert!(positions.contains(&fresh));
+        assert!(!positions.contains(&Game::startpos()));
+
+        let mut moved = fresh.clone();
+        do_action(&mut moved, "e2", "e4", PieceType::Pawn, ActionType::Quiet);
+        assert_ne!(moved, fresh);
+        assert_eq!(fresh.board, game.board);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "serde"))]
+    fn serialized_as_fen() {
+        let game = Game::from_pgn("1. e4 c5 2. Nf3").unwrap();
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(json, format!("\"{}\"", game.to_fen()));
+        assert_eq!(serde_json::from_str::<Game>(&json).unwrap(), game);
+        let json = serde_json::to_string(&game.board).unwrap();
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), game.board);
+        assert!(serde_json::from_str::<Game>("\"8/8/8 w - - 0 1\"").is_err());
+    }
 }
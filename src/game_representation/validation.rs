@@ -0,0 +1,291 @@
+use super::{Castling, Color, Game, PieceType};
+use crate::core::bitboard::constants;
+use crate::core::bitboard::Bitboard;
+use crate::core::Square;
+use alloc::vec::Vec;
+
+/// A reason why a position can not arise in a game of chess, see [`Game::validate`]
+///
+/// [`Game::validate`]: struct.Game.html#method.validate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionIssue {
+    /// The color has no king
+    MissingKing(Color),
+    /// The color has more than one king
+    TooManyKings(Color),
+    /// A pawn stands on the first or the last rank
+    PawnOnBackRank(Square),
+    /// The color has more than 8 pawns or more promoted pieces than missing pawns
+    TooManyPieces(Color),
+    /// The king of the side that just moved is attacked
+    OpponentInCheck,
+    /// The side to move is checked by more than two pieces
+    TooManyCheckers,
+    /// The castling right is set although the king or the rook left its starting field
+    InvalidCastlingRight { color: Color, kingside: bool },
+    /// The en passant field can not follow a double pawn push of the opponent
+    ///
+    /// Games never hold such a field, it is only reported by [`PositionBuilder::build`].
+    ///
+    /// [`PositionBuilder::build`]: struct.PositionBuilder.html#method.build
+    InvalidEnPassant(Square),
+    /// The full move number is 0, the first move of a game has the number 1
+    ///
+    /// It is only reported by [`PositionBuilder::build`]. Many FENs found in the wild use 0, so
+    /// [`Game::from_fen`] and [`Game::validate`] accept it.
+    ///
+    /// [`PositionBuilder::build`]: struct.PositionBuilder.html#method.build
+    /// [`Game::from_fen`]: struct.Game.html#method.from_fen
+    /// [`Game::validate`]: struct.Game.html#method.validate
+    InvalidMoveNumber,
+}
+
+impl core::fmt::Display for PositionIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PositionIssue::MissingKing(color) => write!(f, "{:?} has no king", color),
+            PositionIssue::TooManyKings(color) => write!(f, "{:?} has more than one king", color),
+            PositionIssue::PawnOnBackRank(square) => write!(f, "Pawn on back rank on {}", square),
+            PositionIssue::TooManyPieces(color) => write!(f, "{:?} has too many pieces", color),
+            PositionIssue::OpponentInCheck => write!(f, "Side not to move is in check"),
+            PositionIssue::TooManyCheckers => write!(f, "Side to move is checked too often"),
+            PositionIssue::InvalidCastlingRight { color, kingside } => write!(
+                f,
+                "{:?} can not castle {}",
+                color,
+                if *kingside { "kingside" } else { "queenside" }
+            ),
+            PositionIssue::InvalidEnPassant(square) => {
+                write!(
+                    f,
+                    "En passant on {} does not follow a double pawn push",
+                    square
+                )
+            }
+            PositionIssue::InvalidMoveNumber => write!(f, "Full move number is 0"),
+        }
+    }
+}
+
+impl Game {
+    /// Checks whether the position can arise in a game of chess
+    ///
+    /// [`from_fen_lenient`] accepts every position it can represent, this reports the positions that
+    /// are not reachable from the starting position. Only the conditions listed as
+    /// [`PositionIssue`] are checked, a position passing them may still be unreachable.
+    ///
+    /// # Errors
+    /// * All issues found, in the order of the [`PositionIssue`] variants
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Color, Game, PositionIssue};
+    /// assert!(Game::startpos().validate().is_ok());
+    /// let game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 b KQ - 0 1").unwrap();
+    /// assert_eq!(
+    ///     game.validate(),
+    ///     Err(vec![PositionIssue::InvalidCastlingRight {
+    ///         color: Color::White,
+    ///         kingside: true,
+    ///     }])
+    /// );
+    /// ```
+    ///
+    /// [`from_fen_lenient`]: #method.from_fen_lenient
+    /// [`PositionIssue`]: enum.PositionIssue.html
+    pub fn validate(&self) -> Result<(), Vec<PositionIssue>> {
+        let board = &self.board;
+        let mut issues = Vec::new();
+        for color in [Color::White, Color::Black] {
+            match board.pieces_of(PieceType::King, color).count() {
+                0 => issues.push(PositionIssue::MissingKing(color)),
+                1 => {}
+                _ => issues.push(PositionIssue::TooManyKings(color)),
+            }
+        }
+        let back_ranks = constants::RANKS[0] | constants::RANKS[7];
+        issues.extend(Bitboard(board.pawns & back_ranks).map(PositionIssue::PawnOnBackRank));
+        for color in [Color::White, Color::Black] {
+            let pawns = board.pieces_of(PieceType::Pawn, color).count();
+            if pawns > 8 || promoted_pieces(self, color) > 8 - pawns {
+                issues.push(PositionIssue::TooManyPieces(color));
+            }
+        }
+
+        let opponent = self.color_to_move.get_opponent_color();
+        if let Some(king) = board.pieces_of(PieceType::King, opponent).first() {
+            if board.attackers_to(king, self.color_to_move).count() > 0 {
+                issues.push(PositionIssue::OpponentInCheck);
+            }
+        }
+        if let Some(king) = board.pieces_of(PieceType::King, self.color_to_move).first() {
+            if board.attackers_to(king, opponent).count() > 2 {
+                issues.push(PositionIssue::TooManyCheckers);
+            }
+        }
+
+        for color in [Color::White, Color::Black] {
+            let (kingside, queenside, rank) = match color {
+                Color::White => (
+                    Castling::get_white_kingside(),
+                    Castling::get_white_queenside(),
+                    7,
+                ),
+                Color::Black => (
+                    Castling::get_black_kingside(),
+                    Castling::get_black_queenside(),
+                    0,
+                ),
+            };
+            let king = Square::from_coords((4, rank)).expect("field is on the board");
+            for (right, is_kingside, rook_file) in [(kingside, true, 7), (queenside, false, 0)] {
+                let rook = Square::from_coords((rook_file, rank)).expect("field is on the board");
+                if self.get_castling().is_available(right)
+                    && (board.piece_at(king) != Some((PieceType::King, color))
+                        || board.piece_at(rook) != Some((PieceType::Rook, color)))
+                {
+                    issues.push(PositionIssue::InvalidCastlingRight {
+                        color,
+                        kingside: is_kingside,
+                    });
+                }
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+/// Returns the number of pieces that exceed the initial set and must have been promoted
+///
+/// Bishops are counted per field color, as a promotion is needed for a second bishop on the
+/// same color.
+fn promoted_pieces(game: &Game, color: Color) -> u32 {
+    let board = &game.board;
+    let count = |piece| board.pieces_of(piece, color).count();
+    let light_bishops = board
+        .pieces_of(PieceType::Bishop, color)
+        .filter(|square| {
+            let (file, rank) = square.coords();
+            (file + rank) % 2 == 0
+        })
+        .count() as u32;
+    let dark_bishops = count(PieceType::Bishop) - light_bishops;
+    count(PieceType::Queen).saturating_sub(1)
+        + count(PieceType::Rook).saturating_sub(2)
+        + count(PieceType::Knight).saturating_sub(2)
+        + light_bishops.saturating_sub(1)
+        + dark_bishops.saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{FenField, ParserError};
+
+    fn issues(fen: &str) -> Vec<PositionIssue> {
+        Game::from_fen_lenient(fen)
+            .unwrap()
+            .validate()
+            .err()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn reachable_positions() {
+        assert!(issues("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").is_empty());
+        // four queens after promoting four pawns
+        assert!(issues("QQQQk3/8/8/8/8/8/4PPPP/4K3 b - - 0 1").is_empty());
+        // double check
+        assert!(issues("4k3/8/8/8/8/3n4/8/2r1K3 w - - 0 1").is_empty());
+    }
+
+    #[test]
+    fn unreachable_positions() {
+        assert_eq!(
+            issues("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+            vec![PositionIssue::MissingKing(Color::Black)]
+        );
+        assert_eq!(
+            issues("4k3/8/8/8/8/8/8/K3K3 b - - 0 1"),
+            vec![PositionIssue::TooManyKings(Color::White)]
+        );
+        assert_eq!(
+            issues("P3k3/8/8/8/8/8/8/4K2p w - - 0 1"),
+            vec![
+                PositionIssue::PawnOnBackRank("a8".parse().unwrap()),
+                PositionIssue::PawnOnBackRank("h1".parse().unwrap()),
+            ]
+        );
+        assert_eq!(
+            issues("4k3/pppppppp/p7/8/8/8/8/4K3 w - - 0 1"),
+            vec![PositionIssue::TooManyPieces(Color::Black)]
+        );
+        assert_eq!(
+            issues("QQk5/8/8/8/8/8/PPPPPPPP/4K3 w - - 0 1"),
+            vec![
+                PositionIssue::TooManyPieces(Color::White),
+                PositionIssue::OpponentInCheck,
+            ]
+        );
+        assert_eq!(
+            issues("4k3/8/8/8/8/3n1n2/8/2r1K3 w - - 0 1"),
+            vec![PositionIssue::TooManyCheckers]
+        );
+        assert_eq!(
+            issues("rk5r/8/8/8/8/8/8/4K3 w kq - 0 1"),
+            vec![
+                PositionIssue::InvalidCastlingRight {
+                    color: Color::Black,
+                    kingside: true
+                },
+                PositionIssue::InvalidCastlingRight {
+                    color: Color::Black,
+                    kingside: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_fens_are_errors() {
+        for fen in [
+            "",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNRR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR/8 w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN9 w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w  - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w -K - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1",
+        ] {
+            assert!(Game::from_fen(fen).is_err(), "{}", fen);
+        }
+        let error = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1");
+        assert!(matches!(
+            error,
+            Err(ParserError::BadFenField {
+                field: FenField::Board,
+                offset: 42,
+                ..
+            })
+        ));
+        let error = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 x");
+        assert!(matches!(
+            error,
+            Err(ParserError::BadFenField {
+                field: FenField::FullMoveNumber,
+                offset: 55,
+                ..
+            })
+        ));
+    }
+}
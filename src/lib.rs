@@ -1,3 +1,69 @@
+//! Chess rules, notation and analysis tools, meant as the basis for chess engines and GUIs
+//!
+//! The library used to be named `core`, which clashed with Rust's own `core` library and
+//! forced users into `extern crate core as ...` workarounds. All module paths stayed the same
+//! with the rename, so `core::game_representation::Game` is now
+//! `rust_chess::game_representation::Game`. Code written against the old name can keep its
+//! paths by aliasing the crate once:
+//!
+//! ```
+//! use rust_chess as chess_core;
+//!
+//! let game = chess_core::game_representation::Game::startpos();
+//! assert_eq!(chess_core::Game::startpos(), game);
+//! ```
+//!
+//! The central types are re-exported from the crate root, so `rust_chess::Game` and
+//! `rust_chess::game_representation::Game` are the same type.
+//!
+//! Without the default `std` feature the crate is `no_std` and only needs `alloc`. It then
+//! consists of the [`core`], [`game_representation`] and [`move_generation`] modules, which
+//! is all an embedded trainer or a WASM board needs to play by the rules.
+//!
+//! [`core`]: core/index.html
+//! [`game_representation`]: game_representation/index.html
+//! [`move_generation`]: move_generation/index.html
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod analysis;
 pub mod core;
+#[cfg(feature = "std")]
+pub mod encoding;
+#[cfg(feature = "std")]
+pub mod evaluation;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod game_representation;
 pub mod move_generation;
+#[cfg(feature = "std")]
+pub mod opening_book;
+#[cfg(feature = "std")]
+pub mod pgn;
+#[cfg(feature = "std")]
+pub mod prelude;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "std")]
+pub mod record;
+#[cfg(feature = "std")]
+pub mod sanity;
+#[cfg(feature = "std")]
+pub mod search;
+#[cfg(feature = "std")]
+pub mod tablebase;
+#[cfg(feature = "std")]
+pub mod uci;
+#[cfg(feature = "std")]
+pub mod ui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use crate::core::{ParserError, Square};
+pub use crate::game_representation::{Board, Color, Game, PieceType};
+pub use crate::move_generation::{Action, ActionType};
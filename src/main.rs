@@ -1,7 +1,5 @@
-extern crate core;
-
 fn main() {
-    let g = core::game_representation::Game::from_pgn(
+    let g = rust_chess::game_representation::Game::from_pgn(
         r#"[Event "?"]
            [Site "?"]
            [Date "????.??.??"]
@@ -16,8 +14,8 @@ fn main() {
     println!("{}", g.to_fen());
     println!(
         "{:?}",
-        core::move_generation::movegen::all_moves::<
-            core::move_generation::core::WhiteMoveGenColor,
-        >(0, false, &core::game_representation::Game::startpos())
+        rust_chess::move_generation::movegen::all_moves::<
+            rust_chess::move_generation::core::WhiteMoveGenColor,
+        >(0, false, &rust_chess::game_representation::Game::startpos())
     );
 }
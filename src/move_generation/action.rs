@@ -1,7 +1,10 @@
-pub use crate::game_representation::{Color, Game, PieceType};
+pub use crate::game_representation::{Game, PieceType};
+use alloc::{format, string::String, string::ToString, vec::Vec};
 
-use crate::core::{bitboard, ParserError};
+use crate::core::{bitboard, ParserError, Square};
 use crate::move_generation::movegen;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// A standard chess halfmove action.
 ///
@@ -22,7 +25,15 @@ use crate::move_generation::movegen;
 /// bit 1: is_promotion
 /// bit 2-4: capture_type, if capture, else is_kingside_castling in bit 2
 /// bit 5-7: promotion_type
-#[derive(PartialEq)]
+///
+/// With the `serde` feature actions are serialized as a struct of the squares by name, the
+/// moved piece and the action type, which does not depend on this internal structure.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "SerializedAction", into = "SerializedAction")
+)]
 pub struct Action {
     from: u8,
     to: u8,
@@ -36,7 +47,8 @@ pub struct Action {
 /// * Capture: The captured piece
 /// * Promotion: The type that is promoted to
 /// * PromotionCapture: The type that is promoted to and the captured piece
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ActionType {
     Quiet,
     Capture(PieceType),
@@ -50,8 +62,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -66,15 +78,15 @@ impl Action {
         assert!(to_x < 8);
         assert!(from_y < 8);
         assert!(to_y < 8);
-        return Action::new_from_index(from_x + 8 * from_y, to_x + 8 * to_y, piece, actiontype);
+        Action::new_from_index(from_x + 8 * from_y, to_x + 8 * to_y, piece, actiontype)
     }
 
     /// Returns a new Action struct with the corresponding values
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new_from_index(
     ///     8,
     ///     0,
@@ -120,193 +132,509 @@ impl Action {
         }
     }
 
+    /// Returns a new Action struct moving between the given fields
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::core::Square;
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
+    /// let from: Square = "e2".parse().unwrap();
+    /// let to: Square = "e4".parse().unwrap();
+    /// let action = Action::new_from_squares(from, to, PieceType::Pawn, ActionType::Quiet);
+    /// assert_eq!(action.get_from(), (4, 6));
+    /// ```
+    pub fn new_from_squares(
+        from: Square,
+        to: Square,
+        piece: PieceType,
+        actiontype: ActionType,
+    ) -> Action {
+        Action::new_from_index(from.index(), to.index(), piece, actiontype)
+    }
+
     /// Returns an action for the given SAN string
     ///
+    /// The action is looked up among the legal actions of the position, so pinned pieces are no
+    /// candidates for the source square. A given file, rank or square narrows the candidates
+    /// down, a fully specified source square like `e2e4` is accepted as well. A missing capture
+    /// marker is tolerated, a capture marker on a quiet move is not.
+    ///
+    /// Trailing annotations as found in PGN files are skipped: check `+` and mate `#` suffixes,
+    /// suffix glyphs like `!?` and an `e.p.` marker, with or without a space in front of it.
+    /// The check and mate suffixes are not verified, see [`from_san_checked`] for that. A move
+    /// marked `e.p.` has to be an en passant capture.
+    ///
+    /// # Errors
+    /// * [`BadSanToken`] if the string is not well formed, pointing at the offending character
+    /// * [`IllegalMove`] if no legal action matches
+    /// * [`AmbiguousMove`] if more than one legal action matches
+    ///
     /// # Examples
-    /// # use core::game_representation::{Action, Game};
-    /// let a = Action::from_san("e2e4", &Game::startpos());
-    /// assert_eq!(a.get_from(), (4, 6));
+    /// ```
+    /// # use rust_chess::core::ParserError;
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// let game = Game::startpos();
+    /// assert_eq!(Action::from_san("e2e4", &game).unwrap().get_from(), (4, 6));
+    /// assert!(matches!(
+    ///     Action::from_san("Nz3", &game),
+    ///     Err(ParserError::BadSanToken { offset: 1, .. })
+    /// ));
+    /// assert!(matches!(Action::from_san("Nd4", &game), Err(ParserError::IllegalMove { .. })));
+    ///
+    /// let game = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+    /// let en_passant = Action::from_san("exd6 e.p.", &game).unwrap();
+    /// assert_eq!(Action::from_san("exd6", &game).unwrap(), en_passant);
+    /// assert_eq!(Action::from_san("Kd2+?!", &game).unwrap().to_uci(), "e1d2");
+    /// ```
+    ///
+    /// [`from_san_checked`]: #method.from_san_checked
+    /// [`BadSanToken`]: ../core/enum.ParserError.html#variant.BadSanToken
+    /// [`IllegalMove`]: ../core/enum.ParserError.html#variant.IllegalMove
+    /// [`AmbiguousMove`]: ../core/enum.ParserError.html#variant.AmbiguousMove
     pub fn from_san(pgn_string: &str, state: &Game) -> Result<Action, ParserError> {
-        if pgn_string == "0-0" || pgn_string == "O-O" {
-            // kingside castling
-            let color = state.color_to_move as u8;
-            return Ok(Action::new_from_index(
-                60 - color * 56,
-                62 - color * 56,
-                PieceType::King,
-                ActionType::Castling(true),
-            ));
-        }
-        if pgn_string == "0-0-0" || pgn_string == "O-O-O" {
-            // queenside castling
-            let color = state.color_to_move as u8;
-            return Ok(Action::new_from_index(
-                60 - color * 56,
-                58 - color * 56,
-                PieceType::King,
-                ActionType::Castling(true),
-            ));
+        let suffix = SanSuffix::split(pgn_string);
+        let san = suffix.san;
+        let illegal = |reason| ParserError::IllegalMove {
+            token: pgn_string.to_string(),
+            reason,
+        };
+        let legal_moves = movegen::legal_moves(state);
+        let kingside = match san {
+            "0-0" | "O-O" => Some(true),
+            "0-0-0" | "O-O-O" => Some(false),
+            _ => None,
+        };
+        if let Some(kingside) = kingside {
+            return legal_moves
+                .into_iter()
+                .find(|a| a.get_action_type() == ActionType::Castling(kingside))
+                .ok_or_else(|| illegal("Castling is not legal"));
         }
-        if pgn_string.len() == 2 {
-            // simple pawn push
-            let to_index = bitboard::field_repr_to_index(pgn_string)?;
-            let color_sign = (-(state.color_to_move as i8)) * 2 + 1;
-            let mut index_delta = 8 * color_sign;
-            if (1 << (to_index as i8 + index_delta)) & state.board.pawns == 0 {
-                index_delta *= 2;
-            }
-            let from_index = (to_index as i8 + index_delta) as u8;
-            return Ok(Action::new_from_index(
-                from_index,
-                to_index,
-                PieceType::Pawn,
-                ActionType::Quiet,
-            ));
+        let bad = |offset, reason| ParserError::BadSanToken {
+            token: pgn_string.to_string(),
+            offset,
+            reason,
+        };
+        if let Some((offset, _)) = san.char_indices().find(|(_, c)| !c.is_ascii()) {
+            return Err(bad(offset, "Move contains a non-ASCII character"));
         }
-        if pgn_string.len() < 2 {
-            return Err(ParserError::InvalidParameter("Wrong length of pgn action"));
-        }
-        let mut chars = pgn_string.chars().collect::<Vec<_>>();
-        let piece;
-        if chars[0].is_uppercase() {
-            piece = bitboard::char_to_piecetype(chars[0])?;
-            chars.remove(0);
+        let bytes = san.as_bytes();
+        let file_at = |offset: usize| {
+            bitboard::str_to_file(bytes[offset] as char)
+                .map_err(|e| e.in_san_token(pgn_string, offset))
+        };
+        let rank_at = |offset: usize| {
+            bitboard::str_to_rank(&san[offset..=offset])
+                .map_err(|e| e.in_san_token(pgn_string, offset))
+        };
+        let piece_at = |offset: usize| {
+            bitboard::char_to_piecetype(bytes[offset] as char)
+                .map_err(|e| e.in_san_token(pgn_string, offset))
+        };
+
+        let mut start = 0;
+        let mut end = bytes.len();
+        let piece = if end > 0 && bytes[0].is_ascii_uppercase() {
+            start = 1;
+            piece_at(0)?
         } else {
-            piece = PieceType::Pawn;
-        }
-        if chars.len() < 2 {
-            return Err(ParserError::InvalidParameter("Wrong length of pgn action"));
+            PieceType::Pawn
+        };
+        let promotion_piece = if end >= start + 2 && bytes[end - 2] == b'=' {
+            end -= 2;
+            Some(piece_at(end + 1)?)
+        } else {
+            None
+        };
+        if end < start + 2 {
+            return Err(bad(start, "Destination square is missing"));
         }
+        end -= 2;
+        let to_index = file_at(end)? + rank_at(end + 1)? * 8;
 
-        // promotion
-        let promotion_piece;
-        if chars[chars.len() - 2] == '=' {
-            promotion_piece = Some(bitboard::char_to_piecetype(chars[chars.len() - 1])?);
-            chars.remove(chars.len() - 1);
-            chars.remove(chars.len() - 1);
-        } else {
-            promotion_piece = None;
+        // the first 'x' marks a capture, all other characters specify the source square
+        let mut is_capture = false;
+        let mut specifiers = Vec::new();
+        for (offset, &byte) in bytes.iter().enumerate().take(end).skip(start) {
+            if byte == b'x' && !is_capture {
+                is_capture = true;
+            } else {
+                specifiers.push(offset);
+            }
         }
 
-        let to_rank = bitboard::str_to_rank(&chars[chars.len() - 1].to_string())?;
-        let to_file = bitboard::str_to_file(chars[chars.len() - 2])?;
-        chars.remove(chars.len() - 1);
-        chars.remove(chars.len() - 1);
-
-        let capture_index = chars
-            .iter()
-            .enumerate()
-            .map(|(i, c)| if *c == 'x' { Some(i) } else { None })
-            .fold(None, |a, b| if a.is_none() { b } else { a });
-        let is_capture = capture_index.is_some();
-        if is_capture {
-            chars.remove(capture_index.expect("Was checked, can't happen"));
+        let (from_file, from_rank) = match specifiers[..] {
+            [file, rank] => (Some(file_at(file)?), Some(rank_at(rank)?)),
+            [rank] if bytes[rank].is_ascii_digit() => (None, Some(rank_at(rank)?)),
+            [file] => (Some(file_at(file)?), None),
+            // pawns only change their file when capturing, which names the file
+            [] if piece == PieceType::Pawn => (Some(to_index % 8), None),
+            [] => (None, None),
+            [_, _, extra, ..] => {
+                return Err(bad(
+                    extra,
+                    "Source square is specified by too many characters",
+                ))
+            }
+        };
+        let mut candidates = legal_moves.into_iter().filter(|a| {
+            a.get_piecetype() == piece
+                && a.get_to_index() == to_index
+                && !a.is_castling()
+                && a.get_promotion_piece() == promotion_piece
+                && (a.is_capture() || !is_capture)
+                && from_file.is_none_or(|file| a.get_from_index() % 8 == file)
+                && from_rank.is_none_or(|rank| a.get_from_index() / 8 == rank)
+        });
+        let action = match (candidates.next(), candidates.next()) {
+            (Some(action), None) => action,
+            (None, _) => return Err(illegal("No legal move matches")),
+            (Some(_), Some(_)) => {
+                return Err(ParserError::AmbiguousMove {
+                    token: pgn_string.to_string(),
+                })
+            }
+        };
+        let is_en_passant = action.is_capture()
+            && piece == PieceType::Pawn
+            && state.board.piece_at(action.get_to_square()).is_none();
+        if suffix.en_passant && !is_en_passant {
+            return Err(illegal("Move is not an en passant capture"));
         }
+        Ok(action)
+    }
 
-        let from_rank;
-        let from_file;
-        if chars.len() == 2 {
-            // fully specified
-            from_file = bitboard::str_to_file(chars[0])?;
-            from_rank = bitboard::str_to_rank(&chars[1].to_string())?;
-        } else if chars.len() == 1 {
-            if chars[0].is_numeric() {
-                // rank specified
-                from_rank = bitboard::str_to_rank(&chars[0].to_string())?;
-                let to_index = to_file + to_rank * 8;
-                let destination = 1 << (to_index);
-                let mask = movegen::can_be_attacked_from(destination, piece, state)
-                    & bitboard::constants::RANKS[from_rank as usize];
-                if mask.count_ones() != 1 {
-                    return Err(ParserError::InvalidParameter(
-                        "Multiple options for source square found",
-                    ));
-                }
-                let from_index = mask.trailing_zeros() as u8;
-                if from_rank != from_index / 8 {
-                    return Err(ParserError::InvalidParameter(
-                        "Source square is not on same rank as specified",
-                    ));
-                }
-                from_file = from_index % 8;
+    /// Returns an action for the given SAN string and verifies its check or mate suffix
+    ///
+    /// Works like [`from_san`], but a move marked with `+` has to give check and a move marked
+    /// with `#` has to checkmate. Moves without a suffix are accepted as they are.
+    ///
+    /// # Errors
+    /// * All errors of [`from_san`]
+    /// * [`IllegalMove`] if the suffix does not match the position after the move
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::core::ParserError;
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    /// assert!(Action::from_san_checked("Ra8#", &game).is_ok());
+    /// assert!(Action::from_san_checked("Ra8", &game).is_ok());
+    /// assert!(matches!(
+    ///     Action::from_san_checked("Ra7+", &game),
+    ///     Err(ParserError::IllegalMove { .. })
+    /// ));
+    /// ```
+    ///
+    /// [`from_san`]: #method.from_san
+    /// [`IllegalMove`]: ../core/enum.ParserError.html#variant.IllegalMove
+    pub fn from_san_checked(pgn_string: &str, state: &Game) -> Result<Action, ParserError> {
+        let action = Action::from_san(pgn_string, state)?;
+        let mut after = state.clone();
+        after.execute_action(&action);
+        let reason = match SanSuffix::split(pgn_string).check {
+            Some('#') if !after.is_checkmate() => "Move is not checkmate",
+            Some('+') if !after.is_in_check() => "Move does not give check",
+            _ => return Ok(action),
+        };
+        Err(ParserError::IllegalMove {
+            token: pgn_string.to_string(),
+            reason,
+        })
+    }
+
+    /// Returns the Standard Algebraic Notation of the action, played in the given position
+    ///
+    /// The moved piece is only disambiguated by file, rank or both if another legal action of
+    /// the same piece type reaches the same field. Check is marked by `+` and checkmate by `#`.
+    /// The action is expected to be legal in the position.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// let g = Game::from_fen("r3k2r/8/8/8/3p4/8/2P1P3/R3K2R w KQkq - 0 1").unwrap();
+    /// assert_eq!(Action::from_san("O-O-O", &g).unwrap().to_san(&g), "O-O-O");
+    /// // the rook on h1 can not reach b1, so no disambiguation is needed
+    /// assert_eq!(Action::from_san("Rab1", &g).unwrap().to_san(&g), "Rb1");
+    /// assert_eq!(Action::from_san("Rxa8", &g).unwrap().to_san(&g), "Rxa8+");
+    /// ```
+    pub fn to_san(&self, state: &Game) -> String {
+        let mut san = String::new();
+        if self.is_castling() {
+            san.push_str(if self.is_kingside_castling() {
+                "O-O"
             } else {
-                // file specified
-                from_file = bitboard::str_to_file(chars[0])?;
-                let to_index = to_file + to_rank * 8;
-                let destination = 1 << (to_index);
-                let mask = movegen::can_be_attacked_from(destination, piece, state)
-                    & bitboard::constants::FILES[from_file as usize];
-                if mask.count_ones() != 1 {
-                    return Err(ParserError::InvalidParameter(
-                        "Multiple options for source square found",
-                    ));
+                "O-O-O"
+            });
+        } else {
+            let (from_file, from_rank) = self.get_from();
+            let piece = self.get_piecetype();
+            if piece == PieceType::Pawn {
+                if self.is_capture() {
+                    san.push_str(bitboard::file_to_str(from_file).expect("file is valid"));
                 }
-                let from_index = mask.trailing_zeros() as u8;
-                if from_file != from_index % 8 {
-                    return Err(ParserError::InvalidParameter(
-                        "Source square is not on same file as specified",
-                    ));
+            } else {
+                san.push(bitboard::piecetype_to_char(piece));
+                let rivals: Vec<(u8, u8)> = movegen::legal_moves(state)
+                    .iter()
+                    .filter(|a| {
+                        a.get_piecetype() == piece
+                            && a.get_to_index() == self.get_to_index()
+                            && a.get_from_index() != self.get_from_index()
+                    })
+                    .map(|a| a.get_from())
+                    .collect();
+                if !rivals.is_empty() {
+                    let file = bitboard::file_to_str(from_file).expect("file is valid");
+                    let rank = bitboard::rank_to_str(from_rank).expect("rank is valid");
+                    if rivals.iter().all(|(file, _)| *file != from_file) {
+                        san.push_str(file);
+                    } else if rivals.iter().all(|(_, rank)| *rank != from_rank) {
+                        san.push_str(rank);
+                    } else {
+                        san.push_str(file);
+                        san.push_str(rank);
+                    }
                 }
-                from_rank = from_index / 8;
             }
-        } else {
-            // no specification
-            let to_index = to_file + to_rank * 8;
-            let destination = 1 << (to_index);
-            let mask = movegen::can_be_attacked_from(destination, piece, state);
-            if mask.count_ones() != 1 {
-                return Err(ParserError::InvalidParameter(
-                    "Multiple options for source square found",
-                ));
+            if self.is_capture() {
+                san.push('x');
+            }
+            san.push_str(
+                &bitboard::index_to_field_repr(self.get_to_index()).expect("index is valid"),
+            );
+            if let Some(promoted) = self.get_promotion_piece() {
+                san.push('=');
+                san.push(bitboard::piecetype_to_char(promoted));
             }
-            let from_index = mask.trailing_zeros() as u8;
-            from_rank = from_index / 8;
-            from_file = from_index % 8;
         }
 
-        let action_type;
-        if promotion_piece.is_some() && is_capture {
-            // promotion capture
-            let capture_piece = state.board.get_piecetype_on(to_rank * 8 + to_file);
-            if capture_piece.is_none() {
+        let mut after = state.clone();
+        after.execute_action(self);
+        if after.is_checkmate() {
+            san.push('#');
+        } else if after.is_in_check() {
+            san.push('+');
+        }
+        san
+    }
+
+    /// Returns an action for the given UCI coordinate notation like `e2e4` or `e7e8q`
+    ///
+    /// The moved and captured pieces are taken from the position. A king on its home field
+    /// moving to the g or c file is castling, as is the king moving onto its own rook in the
+    /// corner, which is how UCI writes castling in Chess960. A pawn moving diagonally onto the
+    /// en passant field captures en passant.
+    ///
+    /// Only the notation is checked, not whether the action is legal in the position. Use
+    /// [`LegalMove::validate`] before executing actions from untrusted input.
+    ///
+    /// # Errors
+    /// * The string is not 4 or 5 characters long
+    /// * A field can not be parsed
+    /// * There is no piece on the origin field
+    /// * The promotion piece is not one of `q`, `r`, `b` or `n`
+    /// * A promotion piece is given for an action that does not promote
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Game, PieceType};
+    /// # use rust_chess::move_generation::{Action, ActionType};
+    /// let a = Action::from_uci("g1f3", &Game::startpos()).unwrap();
+    /// assert_eq!(a.get_piecetype(), PieceType::Knight);
+    /// let g = Game::from_fen("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    /// let a = Action::from_uci("a7b8n", &g).unwrap();
+    /// assert_eq!(a.get_action_type(), ActionType::PromotionCapture(PieceType::Knight, PieceType::Knight));
+    /// let g = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    /// assert_eq!(Action::from_uci("e1h1", &g).unwrap().to_uci(), "e1g1");
+    /// assert!(Action::from_uci("e2e4q", &Game::startpos()).is_err());
+    /// ```
+    ///
+    /// [`LegalMove::validate`]: struct.LegalMove.html#method.validate
+    pub fn from_uci(uci: &str, state: &Game) -> Result<Action, ParserError> {
+        if !uci.is_ascii() || (uci.len() != 4 && uci.len() != 5) {
+            return Err(ParserError::InvalidParameter("Wrong length of uci action"));
+        }
+        let from = bitboard::field_repr_to_index(&uci[0..2])?;
+        let to = bitboard::field_repr_to_index(&uci[2..4])?;
+        let promotion = match uci.get(4..) {
+            Some("") | None => None,
+            Some("q") => Some(PieceType::Queen),
+            Some("r") => Some(PieceType::Rook),
+            Some("b") => Some(PieceType::Bishop),
+            Some("n") => Some(PieceType::Knight),
+            Some(_) => return Err(ParserError::InvalidParameter("Promotion piece is invalid")),
+        };
+        let action = Action::from_fields(from, to, promotion, state)?;
+        if promotion.is_some() && !action.is_promotion() {
+            return Err(ParserError::InvalidParameter(
+                "Promotion piece given for an action that does not promote",
+            ));
+        }
+        Ok(action)
+    }
+
+    /// Returns the legal action of a piece dragged from one field to another, as done in GUIs
+    ///
+    /// Castling is accepted as the king moving two fields or as the king dropped onto its own
+    /// rook. The promotion piece is only used for pawns reaching the last rank.
+    ///
+    /// # Errors
+    /// * An index is bigger than 63
+    /// * There is no piece on the origin field
+    /// * The resulting action is not legal in the position
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Game, PieceType};
+    /// # use rust_chess::move_generation::Action;
+    /// let g = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+    /// let drag = Action::from_fields_checked(60, 58, None, &g).unwrap();
+    /// assert_eq!(Action::from_fields_checked(60, 56, None, &g).unwrap(), drag);
+    /// assert!(drag.is_castling());
+    /// assert!(Action::from_fields_checked(60, 44, None, &g).is_err());
+    /// ```
+    pub fn from_fields_checked(
+        from: u8,
+        to: u8,
+        promotion: Option<PieceType>,
+        state: &Game,
+    ) -> Result<Action, ParserError> {
+        if from > 63 || to > 63 {
+            return Err(ParserError::InvalidParameter("index too high"));
+        }
+        let action = Action::from_fields(from, to, promotion, state)?;
+        if !movegen::legal_moves(state).contains(&action) {
+            return Err(ParserError::InvalidParameter("Action is not legal"));
+        }
+        Ok(action)
+    }
+
+    /// Returns the action moving the piece on `from` to `to`, without checking its legality
+    fn from_fields(
+        from: u8,
+        to: u8,
+        promotion: Option<PieceType>,
+        state: &Game,
+    ) -> Result<Action, ParserError> {
+        let piece = match state.board.get_piecetype_on(from) {
+            Some(piece) => piece,
+            None => {
                 return Err(ParserError::InvalidParameter(
-                    "No piece to capture on destination",
-                ));
+                    "No piece on the origin field",
+                ))
             }
-            action_type = ActionType::PromotionCapture(
-                promotion_piece.expect("Cannot happen, checked"),
-                capture_piece.expect("Cannot happend, checked"),
-            );
-        } else if promotion_piece.is_some() {
-            // promotion
-            action_type = ActionType::Promotion(promotion_piece.expect("Cannot happen, checked"));
-        } else if is_capture {
-            // capture
-            let capture_piece = state.board.get_piecetype_on(to_rank * 8 + to_file);
-            if capture_piece.is_none() {
-                return Err(ParserError::InvalidParameter(
-                    "No piece to capture on destination",
+        };
+        if piece == PieceType::King {
+            if let Some(kingside) = castling_side(from, to, state) {
+                let target = if kingside { from + 2 } else { from - 2 };
+                return Ok(Action::new_from_index(
+                    from,
+                    target,
+                    piece,
+                    ActionType::Castling(kingside),
                 ));
             }
-            action_type = ActionType::Capture(capture_piece.expect("Was checked, can't happen"));
+        }
+        let promotion = if piece == PieceType::Pawn && !(8..56).contains(&to) {
+            promotion
         } else {
-            // quiet
-            action_type = ActionType::Quiet;
+            None
+        };
+        let captured = match state.board.get_piecetype_on(to) {
+            Some(captured) => Some(captured),
+            None if piece == PieceType::Pawn
+                && from % 8 != to % 8
+                && state.get_en_passant().map(Square::index) == Some(to) =>
+            {
+                Some(PieceType::Pawn)
+            }
+            None => None,
+        };
+        let action_type = match (promotion, captured) {
+            (Some(promoted), Some(captured)) => ActionType::PromotionCapture(promoted, captured),
+            (Some(promoted), None) => ActionType::Promotion(promoted),
+            (None, Some(captured)) => ActionType::Capture(captured),
+            (None, None) => ActionType::Quiet,
+        };
+        Ok(Action::new_from_index(from, to, piece, action_type))
+    }
+
+    /// Returns the action in UCI coordinate notation like `e2e4`, `e1g1` or `e7e8q`
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Game, PieceType};
+    /// # use rust_chess::move_generation::{Action, ActionType};
+    /// let a = Action::from_san("O-O", &Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap()).unwrap();
+    /// assert_eq!(a.to_uci(), "e1g1");
+    /// let a = Action::new((0, 1), (0, 0), PieceType::Pawn, ActionType::Promotion(PieceType::Queen));
+    /// assert_eq!(a.to_uci(), "a7a8q");
+    /// ```
+    pub fn to_uci(&self) -> String {
+        let mut uci = bitboard::index_to_field_repr(self.get_from_index()).expect("index is valid");
+        uci.push_str(&bitboard::index_to_field_repr(self.get_to_index()).expect("index is valid"));
+        if let Some(promoted) = self.get_promotion_piece() {
+            uci.push(bitboard::piecetype_to_char(promoted).to_ascii_lowercase());
+        }
+        uci
+    }
+
+    /// Returns the action in long algebraic notation with the piece letter, like `Ng1-f3`
+    ///
+    /// Pawns are written with a `P`, captures with an `x` instead of the dash and promotions
+    /// with the promoted piece after a `=`. Castling is written as `O-O` or `O-O-O`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Game, PieceType};
+    /// # use rust_chess::move_generation::{Action, ActionType};
+    /// let a = Action::from_san("Nf3", &Game::startpos()).unwrap();
+    /// assert_eq!(a.pretty(), "Ng1-f3");
+    /// let a = Action::new(
+    ///     (4, 1),
+    ///     (3, 0),
+    ///     PieceType::Pawn,
+    ///     ActionType::PromotionCapture(PieceType::Queen, PieceType::Rook));
+    /// assert_eq!(a.pretty(), "Pe7xd8=Q");
+    /// ```
+    pub fn pretty(&self) -> String {
+        if self.is_castling() {
+            return String::from(if self.is_kingside_castling() {
+                "O-O"
+            } else {
+                "O-O-O"
+            });
         }
-        Ok(Action::new(
-            (from_file, from_rank),
-            (to_file, to_rank),
+        let piece = match self.get_piecetype() {
+            PieceType::Pawn => 'P',
+            piece => bitboard::piecetype_to_char(piece),
+        };
+        let separator = if self.is_capture() { 'x' } else { '-' };
+        let mut pretty = format!(
+            "{}{}{}{}",
             piece,
-            action_type,
-        ))
+            self.get_from_square(),
+            separator,
+            self.get_to_square()
+        );
+        if let Some(promoted) = self.get_promotion_piece() {
+            pretty.push('=');
+            pretty.push(bitboard::piecetype_to_char(promoted));
+        }
+        pretty
     }
 
     /// Returns the coordinates moved from
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -323,8 +651,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -341,8 +669,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -355,12 +683,33 @@ impl Action {
         self.from & 0b11_1111
     }
 
+    /// Returns the field moved from
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// let action = Action::from_san("Nf3", &Game::startpos()).unwrap();
+    /// assert_eq!(action.get_from_square().to_string(), "g1");
+    /// assert_eq!(action.get_to_square().to_string(), "f3");
+    /// ```
+    #[inline(always)]
+    pub fn get_from_square(&self) -> Square {
+        Square::from_index(self.get_from_index()).expect("index has six bits")
+    }
+
+    /// Returns the field moved to
+    #[inline(always)]
+    pub fn get_to_square(&self) -> Square {
+        Square::from_index(self.get_to_index()).expect("index has six bits")
+    }
+
     /// Returns the index moved to
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -377,8 +726,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -389,7 +738,7 @@ impl Action {
     #[inline(always)]
     pub fn get_piecetype(&self) -> PieceType {
         let piece = (self.from >> 6) | ((self.to >> 5) & 0b100);
-        unsafe { std::mem::transmute(piece) }
+        unsafe { core::mem::transmute(piece) }
     }
 
     /// Returns a fully filled ActionType enum for the action
@@ -404,8 +753,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (4,7),
     ///     (2,7),
@@ -444,8 +793,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (4,7),
     ///     (2,7),
@@ -464,8 +813,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (4,7),
     ///     (2,7),
@@ -488,8 +837,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -505,8 +854,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -525,8 +874,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,6),
     ///     (0,7),
@@ -539,7 +888,7 @@ impl Action {
         if !self.is_promotion() {
             return None;
         }
-        Some(unsafe { std::mem::transmute((self.special >> 5) & 0b111) })
+        Some(unsafe { core::mem::transmute::<u8, PieceType>((self.special >> 5) & 0b111) })
     }
 
     /// Returns the captured piece if it is a capture, else None
@@ -548,8 +897,8 @@ impl Action {
     ///
     /// # Examples
     /// ```
-    /// # use core::game_representation::PieceType;
-    /// # use core::move_generation::{ActionType, Action};
+    /// # use rust_chess::game_representation::PieceType;
+    /// # use rust_chess::move_generation::{ActionType, Action};
     /// let action = Action::new(
     ///     (0,0),
     ///     (7,7),
@@ -562,26 +911,146 @@ impl Action {
         if !self.is_capture() {
             return None;
         }
-        Some(unsafe { std::mem::transmute((self.special >> 2) & 0b111) })
+        Some(unsafe { core::mem::transmute::<u8, PieceType>((self.special >> 2) & 0b111) })
     }
 }
 
-impl std::fmt::Debug for Action {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = format!(
-            "{}{}{}",
-            bitboard::piecetype_to_char(self.get_piecetype()),
-            bitboard::index_to_field_repr(self.get_from_index()).unwrap(),
-            bitboard::index_to_field_repr(self.get_to_index()).unwrap()
-        );
-        f.write_str(&s)
+/// Shows the squares, the moved piece and the action type instead of the encoded bytes
+impl core::fmt::Debug for Action {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Action")
+            .field("from", &format_args!("{}", self.get_from_square()))
+            .field("to", &format_args!("{}", self.get_to_square()))
+            .field("piece", &self.get_piecetype())
+            .field("action_type", &self.get_action_type())
+            .finish()
+    }
+}
+
+/// Shows the action in long algebraic notation like `e2e4` or `e7e8=Q`
+///
+/// Unlike [`to_uci`] the promoted piece follows a `=` in upper case. See [`pretty`] for a form
+/// including the moved piece.
+///
+/// [`to_uci`]: struct.Action.html#method.to_uci
+/// [`pretty`]: struct.Action.html#method.pretty
+impl core::fmt::Display for Action {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", self.get_from_square(), self.get_to_square())?;
+        match self.get_promotion_piece() {
+            Some(promoted) => write!(f, "={}", bitboard::piecetype_to_char(promoted)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns the side a king on `from` castles to when moved to `to`, if the move is castling
+///
+/// Only a king on its home field castles, either by moving to the g or c file or by being
+/// dropped onto its own rook on the h or a file.
+fn castling_side(from: u8, to: u8, state: &Game) -> Option<bool> {
+    let board = &state.board;
+    let own_color = |index: u8| board.whites >> index & 1;
+    let home = if own_color(from) == 1 { 60 } else { 4 };
+    if from != home {
+        return None;
+    }
+    let own_rook = |index: u8| {
+        board.get_piecetype_on(index) == Some(PieceType::Rook)
+            && own_color(index) == own_color(from)
+    };
+    if to == home + 2 || (to == home + 3 && own_rook(to)) {
+        Some(true)
+    } else if to == home - 2 || (to == home - 4 && own_rook(to)) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// The annotations following the move of a SAN string
+struct SanSuffix<'a> {
+    /// The move without its annotations
+    san: &'a str,
+    /// The check `+` or mate `#` marker
+    check: Option<char>,
+    en_passant: bool,
+}
+
+impl<'a> SanSuffix<'a> {
+    /// Splits the trailing check, mate, en passant and glyph annotations off a SAN string
+    fn split(token: &'a str) -> SanSuffix<'a> {
+        let mut suffix = SanSuffix {
+            san: token,
+            check: None,
+            en_passant: false,
+        };
+        loop {
+            let trimmed = suffix.san.trim_end_matches(['!', '?']).trim_end();
+            if let Some(rest) = trimmed.strip_suffix("e.p.") {
+                suffix.en_passant = true;
+                suffix.san = rest;
+            } else if let (None, Some(mark @ ('+' | '#'))) = (suffix.check, trimmed.chars().last())
+            {
+                suffix.check = Some(mark);
+                suffix.san = &trimmed[..trimmed.len() - 1];
+            } else {
+                suffix.san = trimmed;
+                return suffix;
+            }
+        }
+    }
+}
+
+/// The structured form actions are serialized in, as the compact one is subject to change
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct SerializedAction {
+    from: Square,
+    to: Square,
+    piece: PieceType,
+    action_type: ActionType,
+}
+
+#[cfg(feature = "serde")]
+impl From<Action> for SerializedAction {
+    fn from(action: Action) -> SerializedAction {
+        SerializedAction {
+            from: Square::from_index(action.get_from_index()).expect("index is on the board"),
+            to: Square::from_index(action.get_to_index()).expect("index is on the board"),
+            piece: action.get_piecetype(),
+            action_type: action.get_action_type(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedAction> for Action {
+    fn from(action: SerializedAction) -> Action {
+        Action::new_from_squares(action.from, action.to, action.piece, action.action_type)
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn formatting() {
+        let game = Game::from_fen("r3k3/1P6/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let action = |san| Action::from_san(san, &game).unwrap();
+        assert_eq!(action("Rh2").to_string(), "h1h2");
+        assert_eq!(action("bxa8=N").to_string(), "b7a8=N");
+        assert_eq!(action("b8=Q").pretty(), "Pb7-b8=Q");
+        assert_eq!(action("O-O").pretty(), "O-O");
+        assert_eq!(action("O-O").to_string(), "e1g1");
+        assert_eq!(
+            format!("{:?}", action("bxa8=N")),
+            "Action { from: b7, to: a8, piece: Pawn, action_type: PromotionCapture(Knight, Rook) }"
+        );
+    }
+
     #[test]
     fn test_in_out() {
         let action = Action::new((0, 1), (2, 3), PieceType::Queen, ActionType::Quiet);
@@ -644,4 +1113,288 @@ mod tests {
             )
         );
     }
+
+    fn san_of(fen: &str, from: &str, to: &str) -> String {
+        let game = Game::from_fen(fen).unwrap();
+        let from = bitboard::field_repr_to_index(from).unwrap();
+        let to = bitboard::field_repr_to_index(to).unwrap();
+        movegen::legal_moves(&game)
+            .into_iter()
+            .find(|a| {
+                a.get_from_index() == from
+                    && a.get_to_index() == to
+                    && a.get_promotion_piece().unwrap_or(PieceType::Queen) == PieceType::Queen
+            })
+            .unwrap()
+            .to_san(&game)
+    }
+
+    #[test]
+    fn san_disambiguation() {
+        // knights on b1 and f1 reach d2, rooks on a1 and a5 reach a3
+        let fen = "1k6/8/8/R6Q/8/8/8/RN2KN1Q w - - 0 1";
+        assert_eq!(san_of(fen, "b1", "d2"), "Nbd2");
+        assert_eq!(san_of(fen, "a5", "a3"), "R5a3");
+        assert_eq!(san_of(fen, "h5", "h3"), "Q5h3");
+        assert_eq!(san_of(fen, "b1", "c3"), "Nc3");
+        // three queens reach e1, only file and rank together identify the one on h4
+        let fen = "1k6/8/8/8/4Q2Q/8/K7/7Q w - - 0 1";
+        assert_eq!(san_of(fen, "h4", "e1"), "Qh4e1");
+        assert_eq!(san_of(fen, "e4", "e1"), "Qee1");
+        // a pinned knight is no rival
+        assert_eq!(
+            san_of("4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1", "b1", "d2"),
+            "Nbd2"
+        );
+        assert_eq!(
+            san_of("4k3/8/8/8/8/8/8/1N2KN1r w - - 0 1", "b1", "d2"),
+            "Nd2"
+        );
+    }
+
+    #[test]
+    fn tricky_san_corpus() {
+        let queens = "1k6/8/8/8/4Q2Q/8/K7/4r2Q w - - 0 1";
+        let knights = "1k6/8/8/8/8/8/8/RN2KN1Q w - - 0 1";
+        let pawns = "1r2k3/P1P5/8/3pP3/8/8/8/4K3 w - d6 0 1";
+        for (fen, san, uci) in &[
+            (queens, "Qh4e1", "h4e1"),
+            (queens, "Qh4xe1", "h4e1"),
+            (queens, "Q1xe1", "h1e1"),
+            (queens, "Qe4xe1", "e4e1"),
+            (knights, "Nb1d2", "b1d2"),
+            (knights, "Nbd2", "b1d2"),
+            (knights, "Nf1d2", "f1d2"),
+            (knights, "Nb1c3", "b1c3"),
+            (pawns, "axb8=Q", "a7b8q"),
+            (pawns, "a7xb8=N", "a7b8n"),
+            (pawns, "c8=R", "c7c8r"),
+            (pawns, "c7c8=Q", "c7c8q"),
+            (pawns, "exd6", "e5d6"),
+            (pawns, "e5xd6", "e5d6"),
+            (pawns, "e6", "e5e6"),
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            let action = Action::from_san(san, &game).unwrap();
+            assert_eq!(&action.to_uci(), uci, "{}", san);
+        }
+        for (fen, san) in &[
+            // no queen stands on the given square
+            (queens, "Qa1e1"),
+            (queens, "Qh4xd1"),
+            // a knight is on b1, but no knight stands on the given square
+            (knights, "Nc1d2"),
+            // ambiguous without the file or rank
+            (knights, "Nd2"),
+            (queens, "Qxe1"),
+            (queens, "Qhxe1"),
+            (queens, "Q4xe1"),
+            (pawns, "c6c8=Q"),
+            (queens, "Qh4h4e1"),
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            assert!(Action::from_san(san, &game).is_err(), "{}", san);
+        }
+    }
+
+    #[test]
+    fn san_suffixes() {
+        assert_eq!(
+            san_of("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7", "b8"),
+            "b8=Q+"
+        );
+        assert_eq!(
+            san_of("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "a1", "a8"),
+            "Ra8#"
+        );
+        assert_eq!(
+            san_of("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", "e5", "d6"),
+            "exd6"
+        );
+        assert_eq!(
+            san_of("r3k3/8/8/8/8/8/8/4K3 b q - 0 1", "e8", "c8"),
+            "O-O-O"
+        );
+    }
+
+    #[test]
+    fn san_annotations() {
+        let game = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+        for san in &[
+            "exd6",
+            "exd6e.p.",
+            "exd6 e.p.",
+            "exd6 e.p.!",
+            "e5xd6+",
+            "exd6 e.p.+",
+        ] {
+            let action = Action::from_san(san, &game).unwrap();
+            assert_eq!(&action.to_uci(), "e5d6", "{}", san);
+        }
+        assert_eq!(
+            Action::from_san("Kd1 e.p.", &game),
+            Err(ParserError::IllegalMove {
+                token: String::from("Kd1 e.p."),
+                reason: "Move is not an en passant capture",
+            })
+        );
+        // the offset points into the whole token
+        assert!(matches!(
+            Action::from_san("Kz1+", &game),
+            Err(ParserError::BadSanToken { offset: 1, .. })
+        ));
+
+        let game = Game::from_fen("r3k2r/8/8/8/8/8/8/3K4 b kq - 0 1").unwrap();
+        assert!(Action::from_san("O-O-O+", &game).unwrap().is_castling());
+        assert!(Action::from_san_checked("O-O-O+", &game).is_ok());
+        assert!(Action::from_san_checked("O-O+", &game).is_err());
+        assert!(Action::from_san_checked("O-O#", &game).is_err());
+        assert!(Action::from_san_checked("O-O", &game).is_ok());
+
+        let game = Game::from_fen("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2")
+            .unwrap();
+        assert!(Action::from_san_checked("Qh4#", &game).is_ok());
+        assert!(Action::from_san_checked("Qh4+", &game).is_ok());
+        assert!(Action::from_san_checked("Qg5+", &game).is_err());
+    }
+
+    #[test]
+    fn uci_round_trip() {
+        for fen in &[
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1",
+            "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2",
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            for action in movegen::legal_moves(&game) {
+                let parsed = Action::from_uci(&action.to_uci(), &game).unwrap();
+                assert_eq!(parsed, action, "{} in {}", action.to_uci(), fen);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_uci() {
+        let game = Game::startpos();
+        assert!(Action::from_uci("e2e", &game).is_err());
+        assert!(Action::from_uci("e3e4", &game).is_err());
+        assert!(Action::from_uci("e2e4k", &game).is_err());
+        assert!(Action::from_uci("i2e4", &game).is_err());
+    }
+
+    #[test]
+    fn castling_gestures() {
+        let game = Game::from_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
+        let kingside = Action::from_san("O-O", &game).unwrap();
+        let queenside = Action::from_san("O-O-O", &game).unwrap();
+        assert_eq!(Action::from_uci("e1g1", &game).unwrap(), kingside);
+        assert_eq!(Action::from_uci("e1h1", &game).unwrap(), kingside);
+        assert_eq!(Action::from_uci("e1a1", &game).unwrap(), queenside);
+        assert_eq!(
+            Action::from_fields_checked(60, 63, None, &game).unwrap(),
+            kingside
+        );
+        assert_eq!(
+            Action::from_fields_checked(60, 56, None, &game).unwrap(),
+            queenside
+        );
+
+        // dropping the king onto a rook of the opponent is a capture, not castling
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        assert!(!Action::from_uci("e1h1", &game).unwrap().is_castling());
+        // castling without the right or through an attacked field is not legal
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/R3K2R w K - 0 1").unwrap();
+        assert!(Action::from_fields_checked(60, 56, None, &game).is_err());
+        let game = Game::from_fen("5r1k/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        assert!(Action::from_fields_checked(60, 63, None, &game).is_err());
+    }
+
+    #[test]
+    fn king_moves_off_the_home_field_are_not_castling() {
+        // the king next to its own rook in the corner must not wrap around the board
+        let game = Game::from_fen("rk6/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+        let action = Action::from_uci("b8a8", &game).unwrap();
+        assert!(!action.is_castling());
+        assert_eq!(action.to_uci(), "b8a8");
+        assert!(Action::from_fields_checked(1, 0, None, &game).is_err());
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/6KR w - - 0 1").unwrap();
+        let action = Action::from_uci("g1h1", &game).unwrap();
+        assert!(!action.is_castling());
+        assert_eq!(action.to_uci(), "g1h1");
+        assert!(Action::from_fields_checked(62, 63, None, &game).is_err());
+        // two files away from anywhere but the home field is a normal, illegal king move
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/1K6 w - - 0 1").unwrap();
+        let action = Action::from_uci("b1d1", &game).unwrap();
+        assert!(!action.is_castling());
+        assert_eq!(action.to_uci(), "b1d1");
+        assert!(Action::from_fields_checked(57, 59, None, &game).is_err());
+    }
+
+    #[test]
+    fn san_errors() {
+        let game = Game::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+        let error = Action::from_san("Nd2", &game).unwrap_err();
+        assert_eq!(
+            error,
+            ParserError::AmbiguousMove {
+                token: String::from("Nd2")
+            }
+        );
+        assert_eq!(Action::from_san("Nbd2", &game).unwrap().get_from(), (1, 7));
+        assert_eq!(Action::from_san("N1d2", &game).unwrap().get_from(), (1, 7));
+        assert!(matches!(
+            Action::from_san("Nbxd2", &game),
+            Err(ParserError::IllegalMove { .. })
+        ));
+        assert!(matches!(
+            Action::from_san("e4", &game),
+            Err(ParserError::IllegalMove { .. })
+        ));
+        for (san, offset) in [
+            ("Nd9", 2),
+            ("Xd2", 0),
+            ("Nbbd2", 2),
+            ("Nb1bd2", 3),
+            ("d8=X", 3),
+            ("N", 1),
+        ] {
+            match Action::from_san(san, &game) {
+                Err(ParserError::BadSanToken {
+                    token, offset: at, ..
+                }) => {
+                    assert_eq!((token.as_str(), at), (san, offset))
+                }
+                other => panic!("{} parsed as {:?}", san, other),
+            }
+        }
+    }
+
+    #[test]
+    fn san_resolution_uses_legal_moves() {
+        // the knight on f3 is pinned by the bishop, so Nd2 can only come from b1
+        let game = Game::from_fen("4k3/8/8/3b4/8/5N2/8/1N5K w - - 0 1").unwrap();
+        assert_eq!(Action::from_san("Nd2", &game).unwrap().get_from(), (1, 7));
+        assert!(matches!(
+            Action::from_san("Nfd2", &game),
+            Err(ParserError::IllegalMove { .. })
+        ));
+        // castling requires the right, a missing capture marker is tolerated
+        let game = Game::from_fen("4k3/8/8/3p4/4P3/8/8/4K2R w - - 0 1").unwrap();
+        assert!(matches!(
+            Action::from_san("O-O", &game),
+            Err(ParserError::IllegalMove { .. })
+        ));
+        assert_eq!(
+            Action::from_san("ed5", &game).unwrap(),
+            Action::from_san("exd5", &game).unwrap()
+        );
+        assert!(matches!(
+            Action::from_san("d5", &game),
+            Err(ParserError::IllegalMove { .. })
+        ));
+        // a pawn reaching the last rank has to name its promotion piece
+        let game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(Action::from_san("a8", &game).is_err());
+        assert!(Action::from_san("a8=N", &game).unwrap().is_promotion());
+    }
 }
@@ -0,0 +1,132 @@
+use super::core::{BlackMoveGenColor, WhiteMoveGenColor};
+use super::movegen::{
+    attackers_of, bishop_attacks, king_attacks, pawn_attacks_east, pawn_attacks_west, rook_attacks,
+};
+use crate::core::bitboard::{constants, Bitboard};
+use crate::core::Square;
+use crate::game_representation::{Board, Color, PieceType};
+
+impl Board {
+    /// Returns all fields attacked by the pieces of the given color
+    ///
+    /// A field is attacked if a piece could capture an opposing piece standing on it, so pawns
+    /// attack diagonally only and fields occupied by own pieces count as attacked too. Sliding
+    /// pieces are blocked by the first piece in their way.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Board, Color};
+    /// let b = Board::startpos();
+    /// let attacked = b.attacks_by(Color::White);
+    /// assert_eq!(attacked.count(), 22);
+    /// assert!(attacked.contains("f3".parse().unwrap()));
+    /// assert!(!attacked.contains("e4".parse().unwrap()));
+    /// ```
+    pub fn attacks_by(&self, color: Color) -> Bitboard {
+        let occupied = self.occupancy().0;
+        let own = self.occupancy_of(color).0;
+        let pawns = self.pawns & own;
+        let pawn_attacks = match color {
+            Color::White => {
+                pawn_attacks_west::<WhiteMoveGenColor>(pawns)
+                    | pawn_attacks_east::<WhiteMoveGenColor>(pawns)
+            }
+            Color::Black => {
+                pawn_attacks_west::<BlackMoveGenColor>(pawns)
+                    | pawn_attacks_east::<BlackMoveGenColor>(pawns)
+            }
+        };
+        let knight_attacks = Bitboard(self.knights & own)
+            .map(|square| constants::KNIGHT_MASKS[square.index() as usize])
+            .fold(0, |attacks, mask| attacks | mask);
+        Bitboard(
+            pawn_attacks
+                | knight_attacks
+                | king_attacks(self.kings & own)
+                | bishop_attacks(self.bishops & own, occupied)
+                | rook_attacks(self.rooks & own, occupied),
+        )
+    }
+
+    /// Returns the pieces of the given color attacking the field
+    ///
+    /// The field may be empty or occupied by a piece of either color.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Board, Color};
+    /// let b = Board::from_fen("4k3/8/8/3p4/4P3/2N5/8/4K3").unwrap();
+    /// let attackers: Vec<String> = b
+    ///     .attackers_to("d5".parse().unwrap(), Color::White)
+    ///     .map(|square| square.to_string())
+    ///     .collect();
+    /// assert_eq!(attackers, vec!["e4", "c3"]);
+    /// ```
+    pub fn attackers_to(&self, square: Square, color: Color) -> Bitboard {
+        let attackers = attackers_of(
+            square.bitboard(),
+            self.occupancy().0,
+            self,
+            color.get_opponent_color(),
+        );
+        Bitboard(attackers) & self.occupancy_of(color)
+    }
+
+    /// Returns true if a piece of the given color attacks the field
+    pub fn is_attacked_by(&self, square: Square, color: Color) -> bool {
+        !self.attackers_to(square, color).is_empty()
+    }
+
+    /// Returns the king of the given color, `None` if it has none
+    pub fn king_square(&self, color: Color) -> Option<Square> {
+        self.pieces_of(PieceType::King, color).first()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attackers_of_both_colors() {
+        let board = Board::from_fen("3r2k1/8/8/1b1q4/8/5n2/4P3/3RK3").unwrap();
+        let e2: Square = "e2".parse().unwrap();
+        let black: Vec<String> = board
+            .attackers_to(e2, Color::Black)
+            .map(|square| square.to_string())
+            .collect();
+        assert_eq!(black, vec!["b5"]);
+        let white: Vec<String> = board
+            .attackers_to(e2, Color::White)
+            .map(|square| square.to_string())
+            .collect();
+        assert_eq!(white, vec!["e1"]);
+        // the rook on d8 is blocked by the queen on d5
+        let d1: Square = "d1".parse().unwrap();
+        let black: Vec<String> = board
+            .attackers_to(d1, Color::Black)
+            .map(|square| square.to_string())
+            .collect();
+        assert_eq!(black, vec!["d5"]);
+        assert!(board.is_attacked_by("e1".parse().unwrap(), Color::Black));
+        assert_eq!(board.king_square(Color::Black), "g8".parse().ok());
+    }
+
+    #[test]
+    fn attack_maps_match_attackers() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R").unwrap();
+        for color in [Color::White, Color::Black] {
+            let attacked = board.attacks_by(color);
+            for square in Square::all() {
+                assert_eq!(
+                    attacked.contains(square),
+                    board.is_attacked_by(square, color),
+                    "{} by {:?}",
+                    square,
+                    color
+                );
+            }
+        }
+    }
+}
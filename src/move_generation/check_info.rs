@@ -0,0 +1,138 @@
+use super::movegen;
+use crate::core::bitboard::Bitboard;
+use crate::game_representation::{Color, Game};
+
+/// The checks and pins of a position
+///
+/// Move generation needs to know whether the color to move is in check and which of its pieces
+/// may not leave the line to their king, a search additionally looks for actions giving
+/// check. All of this is found with one pass over the sliding pieces aiming at the kings, so it
+/// is computed once per position and handed around, see [`MovePicker::check_info`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::{Color, Game, PieceType};
+/// # use rust_chess::move_generation::core::WhiteMoveGenColor;
+/// # use rust_chess::move_generation::movegen;
+/// // the bishop on b4 pins the knight on c3, the bishop on g5 stands in front of the rook
+/// let game = Game::from_fen("6k1/8/8/6B1/1b6/2N5/8/4K1R1 w - - 0 1").unwrap();
+/// let info = game.check_info();
+/// assert!(!info.in_check());
+/// assert_eq!(info.pinned_of(Color::White).first(), "c3".parse().ok());
+/// assert_eq!(info.discovered_checks.first(), "g5".parse().ok());
+///
+/// let pinned = info.pinned_of(Color::White).0;
+/// let actions = movegen::all_moves::<WhiteMoveGenColor>(pinned, info.in_check(), &game);
+/// assert!(actions.iter().all(|action| action.get_piecetype() != PieceType::Knight));
+/// ```
+///
+/// [`MovePicker::check_info`]: struct.MovePicker.html#method.check_info
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CheckInfo {
+    /// The pieces of the opponent attacking the king of the color to move
+    pub checkers: Bitboard,
+    /// The pieces of each color that are the only piece between their king and an attacking
+    /// rook, bishop or queen of the opponent, indexed by the color
+    pub pinned: [Bitboard; 2],
+    /// The pieces of the color to move that give check by moving off the line between an own
+    /// rook, bishop or queen and the king of the opponent
+    pub discovered_checks: Bitboard,
+}
+
+impl CheckInfo {
+    /// Returns the checks and pins of the position
+    ///
+    /// Sides without a king have no pinned pieces, with the `analysis-board` feature enabled
+    /// the same holds for sides with more than one king.
+    pub fn new(state: &Game) -> CheckInfo {
+        let color = state.color_to_move;
+        let opponent = color.get_opponent_color();
+        let own_pieces = state.occupancy_of(color).0;
+        let king = movegen::royal_king(&state.board, own_pieces);
+        let occupied = state.occupancy().0;
+        let checkers = if king == 0 {
+            0
+        } else {
+            movegen::attackers_of(king, occupied, &state.board, color) & !own_pieces
+        };
+        let mut pinned = [Bitboard::EMPTY; 2];
+        pinned[color as usize] = Bitboard(blockers(state, color, own_pieces));
+        pinned[opponent as usize] = Bitboard(blockers(state, opponent, own_pieces ^ occupied));
+        CheckInfo {
+            checkers: Bitboard(checkers),
+            pinned,
+            discovered_checks: Bitboard(blockers(state, opponent, own_pieces)),
+        }
+    }
+
+    /// Returns true if the color to move is in check
+    pub fn in_check(&self) -> bool {
+        !self.checkers.is_empty()
+    }
+
+    /// Returns the pinned pieces of the color
+    pub fn pinned_of(&self, color: Color) -> Bitboard {
+        self.pinned[color as usize]
+    }
+}
+
+/// Returns the pieces in `candidates` that are the only piece between the king of `color` and
+/// a rook, bishop or queen of its opponent
+fn blockers(state: &Game, color: Color, candidates: u64) -> u64 {
+    let board = &state.board;
+    let kings = movegen::royal_king(board, state.occupancy_of(color).0);
+    let opponent = state.occupancy_of(color.get_opponent_color()).0;
+    let occupied = state.occupancy().0;
+    let mut blockers = 0;
+    for king in Bitboard(kings).map(|square| square.bitboard()) {
+        let (lines, diagonals) = (
+            movegen::rook_attacks(king, 0),
+            movegen::bishop_attacks(king, 0),
+        );
+        // sliders aiming at the king on an empty board
+        let snipers = (lines & board.rooks | diagonals & board.bishops) & opponent;
+        for sniper in Bitboard(snipers).map(|square| square.bitboard()) {
+            let ends = sniper | king;
+            let between = if lines & sniper != 0 {
+                movegen::rook_attacks(sniper, ends) & movegen::rook_attacks(king, ends)
+            } else {
+                movegen::bishop_attacks(sniper, ends) & movegen::bishop_attacks(king, ends)
+            } & occupied;
+            if between.count_ones() == 1 {
+                blockers |= between;
+            }
+        }
+    }
+    blockers & candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_and_pins() {
+        let square = |name: &str| name.parse::<crate::core::Square>().unwrap().bitboard();
+        // the bishop on b4 pins the knight on c3, the bishop on a2 pins the knight on f7 and
+        // the bishop on g5 uncovers the rook on g1
+        let game = Game::from_fen("6k1/5n2/8/6B1/1b6/2N5/B7/4K1R1 w - - 0 1").unwrap();
+        let info = game.check_info();
+        assert!(!info.in_check());
+        assert_eq!(info.pinned_of(Color::White).0, square("c3"));
+        assert_eq!(info.pinned_of(Color::Black).0, square("f7"));
+        assert_eq!(info.discovered_checks.0, square("g5"));
+
+        // the pins stay, but black has no discovered checks
+        let game = Game::from_fen("6k1/5n2/8/6B1/1b6/2N5/B7/4K1R1 b - - 0 1").unwrap();
+        let info = game.check_info();
+        assert_eq!(info.pinned_of(Color::White).0, square("c3"));
+        assert_eq!(info.pinned_of(Color::Black).0, square("f7"));
+        assert_eq!(info.discovered_checks.0, 0);
+
+        // a double check
+        let game = Game::from_fen("4k3/8/3N4/8/8/8/8/4RK2 b - - 0 1").unwrap();
+        let info = game.check_info();
+        assert!(info.in_check());
+        assert_eq!(info.checkers.0, square("d6") | square("e1"));
+    }
+}
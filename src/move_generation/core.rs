@@ -1,5 +1,7 @@
-use crate::game_representation::PieceType;
-use crate::move_generation::{Action, ActionType};
+use crate::core::bitboard::Bitboard;
+use crate::core::Square;
+use crate::game_representation::{Board, PieceType};
+use crate::move_generation::{Action, ActionType, PROMOTION_PIECES};
 
 pub trait MoveGenColor {
     fn is_white() -> bool;
@@ -8,43 +10,20 @@ pub trait MoveGenColor {
 pub struct WhiteMoveGenColor;
 impl MoveGenColor for WhiteMoveGenColor {
     fn is_white() -> bool {
-        return true;
+        true
     }
 }
 
 pub struct BlackMoveGenColor;
 impl MoveGenColor for BlackMoveGenColor {
     fn is_white() -> bool {
-        return false;
-    }
-}
-
-pub struct FieldIterator {
-    data: u64,
-}
-
-impl FieldIterator {
-    pub fn new(data: u64) -> Self {
-        FieldIterator { data }
-    }
-}
-
-impl Iterator for FieldIterator {
-    type Item = u8;
-
-    fn next(&mut self) -> Option<u8> {
-        if self.data == 0 {
-            return None;
-        }
-        let index = self.data.trailing_zeros();
-        self.data &= !(1 << index);
-        return Some(index as u8);
+        false
     }
 }
 
 pub struct PawnPushIterator {
-    single: FieldIterator,
-    double: FieldIterator,
+    single: Bitboard,
+    double: Bitboard,
     delta: i8,
 }
 
@@ -52,14 +31,14 @@ impl PawnPushIterator {
     pub fn new<T: MoveGenColor>(single: u64, double: u64) -> PawnPushIterator {
         if T::is_white() {
             PawnPushIterator {
-                single: FieldIterator::new(single),
-                double: FieldIterator::new(double),
+                single: Bitboard(single),
+                double: Bitboard(double),
                 delta: 8,
             }
         } else {
             PawnPushIterator {
-                single: FieldIterator::new(single),
-                double: FieldIterator::new(double),
+                single: Bitboard(single),
+                double: Bitboard(double),
                 delta: -8,
             }
         }
@@ -70,14 +49,14 @@ impl Iterator for PawnPushIterator {
     type Item = Action;
 
     fn next(&mut self) -> Option<Action> {
-        if let Some(to) = self.single.next() {
+        if let Some(to) = self.single.next().map(Square::index) {
             Some(Action::new_from_index(
                 (to as i8 + self.delta) as u8,
                 to,
                 PieceType::Pawn,
                 ActionType::Quiet,
             ))
-        } else if let Some(to) = self.double.next() {
+        } else if let Some(to) = self.double.next().map(Square::index) {
             Some(Action::new_from_index(
                 (to as i8 + 2 * self.delta) as u8,
                 to,
@@ -91,7 +70,7 @@ impl Iterator for PawnPushIterator {
 }
 
 pub struct QuietActionIterator {
-    fields: FieldIterator,
+    fields: Bitboard,
     piece: PieceType,
     from: u8,
 }
@@ -99,7 +78,7 @@ pub struct QuietActionIterator {
 impl QuietActionIterator {
     pub fn new(data: u64, piece: PieceType, from: u8) -> QuietActionIterator {
         QuietActionIterator {
-            fields: FieldIterator::new(data),
+            fields: Bitboard(data),
             piece,
             from,
         }
@@ -110,7 +89,7 @@ impl Iterator for QuietActionIterator {
     type Item = Action;
 
     fn next(&mut self) -> Option<Action> {
-        if let Some(to) = self.fields.next() {
+        if let Some(to) = self.fields.next().map(Square::index) {
             Some(Action::new_from_index(
                 self.from,
                 to,
@@ -122,3 +101,116 @@ impl Iterator for QuietActionIterator {
         }
     }
 }
+
+pub struct CaptureActionIterator<'a> {
+    fields: Bitboard,
+    piece: PieceType,
+    from: u8,
+    board: &'a Board,
+}
+
+impl<'a> CaptureActionIterator<'a> {
+    pub fn new(
+        data: u64,
+        piece: PieceType,
+        from: u8,
+        board: &'a Board,
+    ) -> CaptureActionIterator<'a> {
+        CaptureActionIterator {
+            fields: Bitboard(data),
+            piece,
+            from,
+            board,
+        }
+    }
+}
+
+impl<'a> Iterator for CaptureActionIterator<'a> {
+    type Item = Action;
+
+    fn next(&mut self) -> Option<Action> {
+        let to = self.fields.next().map(Square::index)?;
+        let captured = self
+            .board
+            .get_piecetype_on(to)
+            .expect("captures only target occupied fields");
+        Some(Action::new_from_index(
+            self.from,
+            to,
+            self.piece,
+            ActionType::Capture(captured),
+        ))
+    }
+}
+
+/// Iterates over pawn captures, the pawn moved from the destination field plus `delta`
+pub struct PawnCaptureIterator<'a> {
+    fields: Bitboard,
+    delta: i8,
+    board: &'a Board,
+}
+
+impl<'a> PawnCaptureIterator<'a> {
+    pub fn new(data: u64, delta: i8, board: &'a Board) -> PawnCaptureIterator<'a> {
+        PawnCaptureIterator {
+            fields: Bitboard(data),
+            delta,
+            board,
+        }
+    }
+}
+
+impl<'a> Iterator for PawnCaptureIterator<'a> {
+    type Item = Action;
+
+    fn next(&mut self) -> Option<Action> {
+        let to = self.fields.next().map(Square::index)?;
+        let captured = self
+            .board
+            .get_piecetype_on(to)
+            .expect("captures only target occupied fields");
+        Some(Action::new_from_index(
+            (to as i8 + self.delta) as u8,
+            to,
+            PieceType::Pawn,
+            ActionType::Capture(captured),
+        ))
+    }
+}
+
+pub struct PromotionIterator {
+    from: u8,
+    to: u8,
+    captured: Option<PieceType>,
+    index: usize,
+}
+
+impl PromotionIterator {
+    pub fn new(from: u8, to: u8, captured: Option<PieceType>) -> PromotionIterator {
+        PromotionIterator {
+            from,
+            to,
+            captured,
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for PromotionIterator {
+    type Item = Action;
+
+    fn next(&mut self) -> Option<Action> {
+        let promoted = *PROMOTION_PIECES.get(self.index)?;
+        self.index += 1;
+        let actiontype = match self.captured {
+            Some(captured) => ActionType::PromotionCapture(promoted, captured),
+            None => ActionType::Promotion(promoted),
+        };
+        Some(Action::new_from_index(
+            self.from,
+            self.to,
+            PieceType::Pawn,
+            actiontype,
+        ))
+    }
+}
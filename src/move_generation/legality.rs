@@ -0,0 +1,200 @@
+use super::core::{BlackMoveGenColor, WhiteMoveGenColor};
+use super::{movegen, Action};
+use crate::core::ParserError;
+use crate::game_representation::{Color, Game, UndoToken};
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+/// An action generated for a position without checking whether it leaves the king in check
+///
+/// The action is tied to the position it was generated in and can only be turned into a
+/// [`LegalMove`] for that position. It dereferences to the wrapped [`Action`].
+///
+/// [`LegalMove`]: struct.LegalMove.html
+/// [`Action`]: struct.Action.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PseudoLegalMove {
+    action: Action,
+    // zobrist hash of the position the action was generated for
+    hash: u64,
+}
+
+/// An action known to be legal in the position it was created for
+///
+/// Only move generation and [`LegalMove::validate`] create legal moves, so code holding one
+/// never executes an unchecked action by accident. [`Game::play`] refuses legal moves of other
+/// positions. It dereferences to the wrapped [`Action`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::{Action, LegalMove};
+/// let mut game = Game::startpos();
+/// let e4 = LegalMove::validate(Action::from_san("e4", &game).unwrap(), &game).unwrap();
+/// game.play(e4).unwrap();
+/// // the same move is not legal a second time
+/// assert!(game.play(e4).is_err());
+/// ```
+///
+/// [`LegalMove::validate`]: #method.validate
+/// [`Game::play`]: ../game_representation/struct.Game.html#method.play
+/// [`Action`]: struct.Action.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LegalMove {
+    action: Action,
+    hash: u64,
+}
+
+impl PseudoLegalMove {
+    /// Returns the wrapped action
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    /// Returns the action as a legal move if it does not leave the own king in check
+    ///
+    /// Returns `None` as well if `state` is not the position the action was generated for.
+    pub fn into_legal(self, state: &Game) -> Option<LegalMove> {
+        if self.hash != state.get_hash() || !movegen::is_legal(&self.action, state) {
+            return None;
+        }
+        Some(LegalMove {
+            action: self.action,
+            hash: self.hash,
+        })
+    }
+}
+
+impl LegalMove {
+    /// Returns the action as a legal move of the position
+    ///
+    /// # Errors
+    /// * The action is not one of the legal actions of the position
+    pub fn validate(action: Action, state: &Game) -> Result<LegalMove, ParserError> {
+        if !movegen::legal_moves(state).contains(&action) {
+            return Err(ParserError::InvalidParameter("Action is not legal"));
+        }
+        Ok(LegalMove {
+            action,
+            hash: state.get_hash(),
+        })
+    }
+
+    /// Returns the wrapped action
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    /// Returns true if the move was created for the given position
+    pub fn belongs_to(&self, state: &Game) -> bool {
+        self.hash == state.get_hash()
+    }
+}
+
+impl Deref for PseudoLegalMove {
+    type Target = Action;
+
+    fn deref(&self) -> &Action {
+        &self.action
+    }
+}
+
+impl Deref for LegalMove {
+    type Target = Action;
+
+    fn deref(&self) -> &Action {
+        &self.action
+    }
+}
+
+impl From<PseudoLegalMove> for Action {
+    fn from(action: PseudoLegalMove) -> Action {
+        action.action
+    }
+}
+
+impl From<LegalMove> for Action {
+    fn from(action: LegalMove) -> Action {
+        action.action
+    }
+}
+
+impl Game {
+    /// Returns all pseudo legal actions of the color to move
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// // the pinned knight can move, but none of its moves is legal
+    /// let game = Game::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+    /// let pseudo_legal = game.pseudo_legal_moves();
+    /// let legal: Vec<_> = pseudo_legal.iter().filter_map(|m| m.into_legal(&game)).collect();
+    /// assert_eq!(legal, game.legal_moves());
+    /// assert!(legal.len() < pseudo_legal.len());
+    /// ```
+    pub fn pseudo_legal_moves(&self) -> Vec<PseudoLegalMove> {
+        let in_check = self.is_in_check();
+        let actions = match self.color_to_move {
+            Color::White => movegen::all_moves::<WhiteMoveGenColor>(0, in_check, self),
+            Color::Black => movegen::all_moves::<BlackMoveGenColor>(0, in_check, self),
+        };
+        let hash = self.get_hash();
+        actions
+            .into_iter()
+            .map(|action| PseudoLegalMove { action, hash })
+            .collect()
+    }
+
+    /// Returns all legal actions of the color to move
+    pub fn legal_moves(&self) -> Vec<LegalMove> {
+        let hash = self.get_hash();
+        movegen::legal_moves(self)
+            .into_iter()
+            .map(|action| LegalMove { action, hash })
+            .collect()
+    }
+
+    /// Executes a legal move, see [`execute_action`]
+    ///
+    /// # Errors
+    /// * The move was created for a different position
+    ///
+    /// [`execute_action`]: #method.execute_action
+    pub fn play(&mut self, action: LegalMove) -> Result<UndoToken, ParserError> {
+        if !action.belongs_to(self) {
+            return Err(ParserError::InvalidParameter(
+                "Move belongs to a different position",
+            ));
+        }
+        Ok(self.execute_action(&action.action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legal_moves_match_movegen() {
+        let mut game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let legal: Vec<Action> = game.legal_moves().into_iter().map(Action::from).collect();
+        assert_eq!(legal, movegen::legal_moves(&game));
+        let from_pseudo: Vec<Action> = game
+            .pseudo_legal_moves()
+            .into_iter()
+            .filter_map(|action| action.into_legal(&game))
+            .map(|action| action.action())
+            .collect();
+        assert_eq!(from_pseudo, legal);
+
+        let pseudo = game.pseudo_legal_moves()[0];
+        let first = game.legal_moves()[0];
+        let token = game.play(first).unwrap();
+        assert_eq!(pseudo.into_legal(&game), None);
+        game.undo_action(&first, token);
+        assert!(pseudo.into_legal(&game).is_some());
+        assert!(LegalMove::validate(*pseudo, &game).is_ok());
+    }
+}
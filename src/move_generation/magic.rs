@@ -0,0 +1,228 @@
+/// Multipliers mapping the relevant bishop blockers of every field to a unique table index
+#[rustfmt::skip]
+const BISHOP_MAGICS: [u64; 64] = [
+    0x10102002004a1420, 0x8020040400584008, 0x10510800811201c8, 0x5204042080000088,
+    0x2204106880000002, 0x1401042004000000, 0x0400880410042004, 0x0028208200a02020,
+    0x1500241990010e00, 0x8001200182020a40, 0x40004101030b0000, 0x8002041042000100,
+    0x4010011041020038, 0x0000010421044000, 0x1500210808020a00, 0x8000088400880520,
+    0x0405004010040100, 0x1005823210040108, 0x2708008102040011, 0x4048200404009100,
+    0x0018104101400024, 0x0003000601190101, 0x8004803108491000, 0x8014241200820800,
+    0x0006e080100c3040, 0x0501044a11041800, 0x9020300008004045, 0x0894080000220040,
+    0x1001010083104000, 0x5004030040900080, 0x000400422c012400, 0x0002128698404812,
+    0x1010108404900440, 0x0928021182084100, 0x2006080409020024, 0x1010202020180080,
+    0xa010008200202200, 0x2098015100019004, 0x0002041440810811, 0x802a02020000b098,
+    0x0009015090004060, 0x4000821082081001, 0x0100210040420800, 0x0800004010488a00,
+    0x2000081104004040, 0x4c8e029015000082, 0x0420340322224842, 0x1298260043400210,
+    0x0000822802400008, 0x00008a0101600000, 0x3040003412080021, 0x3040290220884800,
+    0x4a1500401041004a, 0x8010200282020781, 0x0020203142209091, 0x0070300600902110,
+    0x0040808800b62048, 0x0000810400c44420, 0x00080400440c0441, 0x8340080020840411,
+    0x0000000104208200, 0x0000800810d00080, 0x0400530411080200, 0x4040702400932244,
+];
+
+/// Multipliers mapping the relevant rook blockers of every field to a unique table index
+#[rustfmt::skip]
+const ROOK_MAGICS: [u64; 64] = [
+    0x1080004008801020, 0x0840092002c03000, 0x1900200010400900, 0x0880100008000480,
+    0x4200100420080200, 0x8100020100080400, 0x0200040110886200, 0x0200008040220411,
+    0x0404800084400220, 0x0000401000402000, 0x0086001081220440, 0x0408800800100280,
+    0x000a001201040820, 0x8848800200840080, 0x4001000100040200, 0x0442000102105084,
+    0x9080010020804100, 0x0040404000201009, 0x0000808010002009, 0x2200090021d00100,
+    0x0008008008040080, 0x0004004002010040, 0x0011040008015042, 0x00000a0001768104,
+    0x0000800080204009, 0x2010004140002001, 0x9800200280100080, 0x1000100080080080,
+    0x0442000a00049020, 0x2100040080020080, 0x0800120400900148, 0x0010040a00128541,
+    0x2800804000800030, 0x1010002000400041, 0x4000200011004100, 0x0610008410800800,
+    0x0400802402800800, 0xc100020080800400, 0x0002000802000401, 0x0182085882000401,
+    0x0220204000808000, 0x2860100040024022, 0x0001002004110040, 0x99101042000a0020,
+    0x0004080004008080, 0x0010040002008080, 0x2012004881020004, 0x8300842444820011,
+    0x0088403882010200, 0x0820400080210100, 0x0110910040a00300, 0x0801100280080480,
+    0x0242009008200600, 0x1002000489500200, 0x0040800200010080, 0x0091800041000080,
+    0x0000209300488001, 0x04c1002414824001, 0x020020000b001041, 0x7000100004200901,
+    0x8002002004100802, 0x30010002084c0007, 0x0888221800813004, 0x4000002840840112,
+];
+
+/// The directions of bishop moves as file and row steps
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+/// The directions of rook moves as file and row steps
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// The lookup parameters of a single field for one kind of sliding piece
+#[derive(Clone, Copy)]
+struct Magic {
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    #[inline(always)]
+    const fn index(&self, occupied: u64) -> usize {
+        self.offset + ((occupied & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+const BISHOPS: [Magic; 64] = magics(&BISHOP_MAGICS, &BISHOP_DIRECTIONS, 0);
+const ROOKS: [Magic; 64] = magics(&ROOK_MAGICS, &ROOK_DIRECTIONS, BISHOP_TABLE_SIZE);
+const BISHOP_TABLE_SIZE: usize = table_size(&BISHOP_DIRECTIONS);
+const TABLE_SIZE: usize = BISHOP_TABLE_SIZE + table_size(&ROOK_DIRECTIONS);
+
+/// Precomputed attacks of bishops and rooks for every field and every relevant blocker set
+///
+/// See [magic bitboards](https://www.chessprogramming.org/Magic_Bitboards) for the idea. The
+/// table is about 840 KiB and is computed at compile time, so lookups need neither an
+/// initialization nor any synchronization and work the same on every thread and target.
+static ATTACKS: [u64; TABLE_SIZE] = attacks();
+
+/// Returns the lookup parameters of all fields, with the tables starting at `offset`
+const fn magics(magics: &[u64; 64], directions: &[(i8, i8); 4], offset: usize) -> [Magic; 64] {
+    let mut entries = [Magic {
+        mask: 0,
+        magic: 0,
+        shift: 0,
+        offset: 0,
+    }; 64];
+    let mut offset = offset;
+    let mut index = 0;
+    while index < 64 {
+        let mask = relevant_blockers(index as u8, directions);
+        entries[index] = Magic {
+            mask,
+            magic: magics[index],
+            shift: 64 - mask.count_ones(),
+            offset,
+        };
+        offset += 1 << mask.count_ones();
+        index += 1;
+    }
+    entries
+}
+
+/// Returns the number of table entries of all fields for one kind of sliding piece
+const fn table_size(directions: &[(i8, i8); 4]) -> usize {
+    let mut size = 0;
+    let mut index = 0;
+    while index < 64 {
+        size += 1 << relevant_blockers(index, directions).count_ones();
+        index += 1;
+    }
+    size
+}
+
+/// Returns the attacks of every blocker subset of every field for both kinds of sliders
+const fn attacks() -> [u64; TABLE_SIZE] {
+    let mut attacks = [0; TABLE_SIZE];
+    let mut index = 0;
+    while index < 64 {
+        fill(
+            &mut attacks,
+            index as u8,
+            &BISHOPS[index],
+            &BISHOP_DIRECTIONS,
+        );
+        fill(&mut attacks, index as u8, &ROOKS[index], &ROOK_DIRECTIONS);
+        index += 1;
+    }
+    attacks
+}
+
+/// Writes the attacks of every blocker subset of the mask of the field to the table
+const fn fill(
+    attacks: &mut [u64; TABLE_SIZE],
+    index: u8,
+    entry: &Magic,
+    directions: &[(i8, i8); 4],
+) {
+    // enumerate all subsets of the mask with the carry-rippler trick
+    let mut blockers = 0u64;
+    loop {
+        attacks[entry.index(blockers)] = slide(index, blockers, directions, false);
+        blockers = blockers.wrapping_sub(entry.mask) & entry.mask;
+        if blockers == 0 {
+            break;
+        }
+    }
+}
+
+/// Returns the fields whose occupation changes the attacks of a slider on the field
+///
+/// The last field of every ray is always attacked, so the board edges are left out.
+const fn relevant_blockers(index: u8, directions: &[(i8, i8); 4]) -> u64 {
+    slide(index, 0, directions, true)
+}
+
+/// Returns the fields reached from the field in the directions until a blocker is hit
+///
+/// With `skip_last`, the last field of every ray is left out.
+const fn slide(index: u8, occupied: u64, directions: &[(i8, i8); 4], skip_last: bool) -> u64 {
+    let mut attacks = 0;
+    let mut direction = 0;
+    while direction < 4 {
+        let (file_step, row_step) = directions[direction];
+        let mut file = (index % 8) as i8 + file_step;
+        let mut row = (index / 8) as i8 + row_step;
+        while file >= 0 && file < 8 && row >= 0 && row < 8 {
+            let next_file = file + file_step;
+            let next_row = row + row_step;
+            let last = next_file < 0 || next_file >= 8 || next_row < 0 || next_row >= 8;
+            if skip_last && last {
+                break;
+            }
+            let field = 1u64 << (row * 8 + file);
+            attacks |= field;
+            if occupied & field != 0 {
+                break;
+            }
+            file = next_file;
+            row = next_row;
+        }
+        direction += 1;
+    }
+    attacks
+}
+
+/// Returns the fields attacked by a bishop on the field with the given shift index
+#[inline(always)]
+pub(crate) fn bishop_attacks_from(index: u8, occupied: u64) -> u64 {
+    ATTACKS[BISHOPS[index as usize].index(occupied)]
+}
+
+/// Returns the fields attacked by a rook on the field with the given shift index
+#[inline(always)]
+pub(crate) fn rook_attacks_from(index: u8, occupied: u64) -> u64 {
+    ATTACKS[ROOKS[index as usize].index(occupied)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bitboard::constants::{FILES, RANKS};
+    use crate::move_generation::movegen::{bishop_rays, rook_rays};
+
+    #[test]
+    fn tables_match_rays() {
+        // a cheap generator for varied blocker sets
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..200 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let occupied = state & (state >> 3);
+            for index in 0..64 {
+                let field = 1u64 << index;
+                assert_eq!(
+                    bishop_attacks_from(index, occupied),
+                    bishop_rays(field, occupied)
+                );
+                assert_eq!(
+                    rook_attacks_from(index, occupied),
+                    rook_rays(field, occupied)
+                );
+            }
+        }
+        assert_eq!(TABLE_SIZE, 5248 + 102_400);
+        // the masks leave out the board edges
+        let edges = RANKS[0] | RANKS[7] | FILES[0] | FILES[7];
+        assert_eq!(BISHOPS[27].mask, bishop_rays(1 << 27, 0) & !edges);
+        assert_eq!(ROOKS[0].mask, rook_rays(1, 0) & !(1 << 7) & !(1 << 56));
+    }
+}
@@ -1,7 +1,22 @@
 //! All code related to move generation and representation
 
 mod action;
+mod attacks;
+mod check_info;
 pub mod core;
+mod legality;
+mod magic;
+mod move_list;
 pub mod movegen;
+mod perft;
+mod picker;
+mod premove;
+mod promotion;
 
 pub use action::{Action, ActionType};
+pub use check_info::CheckInfo;
+pub use legality::{LegalMove, PseudoLegalMove};
+pub use move_list::{MoveList, MAX_MOVES};
+pub use picker::{MovePicker, MoveStage};
+pub use premove::Premoves;
+pub use promotion::PROMOTION_PIECES;
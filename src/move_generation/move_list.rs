@@ -0,0 +1,147 @@
+use super::{Action, ActionType};
+use crate::game_representation::PieceType;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+/// The maximum number of actions a [`MoveList`] can hold
+///
+/// No reachable chess position has more than 218 legal actions, so this leaves room for pseudo
+/// legal actions as well.
+///
+/// [`MoveList`]: struct.MoveList.html
+pub const MAX_MOVES: usize = 256;
+
+/// A list of actions with a fixed capacity that lives on the stack
+///
+/// Generating actions into a reused list avoids allocating a vector for every position, which
+/// matters when traversing large move trees. The list dereferences to a slice of the stored
+/// actions.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::{movegen, MoveList};
+/// let game = Game::startpos();
+/// let mut moves = MoveList::new();
+/// movegen::generate_moves_into(&game, &mut moves);
+/// assert_eq!(moves.len(), 20);
+/// assert!(moves.iter().all(|action| movegen::is_legal(action, &game)));
+/// ```
+#[derive(Clone)]
+pub struct MoveList {
+    actions: [Action; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    /// Returns an empty list
+    pub fn new() -> MoveList {
+        // the unused entries are never read, any action does as a placeholder
+        let placeholder = Action::new_from_index(0, 0, PieceType::King, ActionType::Quiet);
+        MoveList {
+            actions: [placeholder; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// Appends an action to the end of the list
+    ///
+    /// # Panics
+    /// * if the list already holds [`MAX_MOVES`] actions
+    ///
+    /// [`MAX_MOVES`]: constant.MAX_MOVES.html
+    #[inline(always)]
+    pub fn push(&mut self, action: Action) {
+        self.actions[self.len] = action;
+        self.len += 1;
+    }
+
+    /// Removes all actions from the list
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Keeps only the actions the predicate returns true for, preserving their order
+    pub fn retain(&mut self, mut predicate: impl FnMut(&Action) -> bool) {
+        let mut kept = 0;
+        for index in 0..self.len {
+            if predicate(&self.actions[index]) {
+                self.actions[kept] = self.actions[index];
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        MoveList::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Action];
+
+    fn deref(&self) -> &[Action] {
+        &self.actions[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Action] {
+        &mut self.actions[..self.len]
+    }
+}
+
+impl Extend<Action> for MoveList {
+    fn extend<I: IntoIterator<Item = Action>>(&mut self, iter: I) {
+        for action in iter {
+            self.push(action);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Action;
+    type IntoIter = core::slice::Iter<'a, Action>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_retain_and_clear() {
+        let mut list = MoveList::new();
+        assert!(list.is_empty());
+        list.extend((0..8).map(|to| {
+            Action::new_from_index(48 + to, 40 + to, PieceType::Pawn, ActionType::Quiet)
+        }));
+        assert_eq!(list.len(), 8);
+        list.retain(|action| action.get_to_index() % 2 == 0);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list[1].get_to_index(), 42);
+        assert_eq!((&list).into_iter().count(), 4);
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn overflowing_panics() {
+        let mut list = MoveList::new();
+        let action = Action::new_from_index(0, 1, PieceType::Rook, ActionType::Quiet);
+        list.extend(core::iter::repeat_n(action, MAX_MOVES + 1));
+    }
+}
@@ -1,62 +1,468 @@
-use crate::core::bitboard;
-use crate::game_representation::{Color, Game, PieceType};
-use crate::move_generation::core::MoveGenColor;
-use crate::move_generation::Action;
-use crate::move_generation::core::{FieldIterator, QuietActionIterator, PawnPushIterator};
+use crate::core::bitboard::{self, Bitboard};
+use crate::core::Square;
+use crate::game_representation::{Board, Castling, Color, Game, PieceType};
+use crate::move_generation::core::{
+    BlackMoveGenColor, CaptureActionIterator, MoveGenColor, PawnCaptureIterator, PawnPushIterator,
+    PromotionIterator, QuietActionIterator, WhiteMoveGenColor,
+};
+use crate::move_generation::magic;
+use crate::move_generation::{Action, ActionType, CheckInfo, MoveList};
+use alloc::vec::Vec;
 
+/// Returns all pseudo legal actions for the color `T`
+///
+/// Pieces in `pinned` are not moved at all, see [`Game::check_info`] for the pinned pieces of
+/// a position. The generated actions may leave the own king in check, [`legal_moves`] filters
+/// those out. Castling is only generated if `in_check` is false, the fields the king passes are
+/// checked for attacks.
+///
+/// [`Game::check_info`]: ../../game_representation/struct.Game.html#method.check_info
+/// [`legal_moves`]: fn.legal_moves.html
 pub fn all_moves<T: MoveGenColor>(pinned: u64, in_check: bool, state: &Game) -> Vec<Action> {
-    // missing: captures, king, en passant, promotion
-    if in_check {
-        unimplemented!();
-    }
+    let mut moves = Vec::new();
+    generate::<T, _>(pinned, in_check, state, &mut moves, true, true);
+    moves
+}
+
+/// [`all_moves`] appending to the given list instead of allocating a vector
+///
+/// [`all_moves`]: fn.all_moves.html
+pub fn all_moves_into<T: MoveGenColor>(
+    pinned: u64,
+    in_check: bool,
+    state: &Game,
+    moves: &mut MoveList,
+) {
+    generate::<T, _>(pinned, in_check, state, moves, true, true);
+}
 
-    let all_pieces = state.board.bishops
-        | state.board.rooks
-        | state.board.pawns
-        | state.board.knights
-        | state.board.kings;
+/// Appends the pseudo legal actions of the color `T` to the list, see [`all_moves`]
+///
+/// Tactical actions are captures and promotions, all other actions are quiet. Only the kinds
+/// enabled by `tactical` and `quiet` are generated. The actions can be collected into a
+/// [`MoveList`] as well as into a vector.
+///
+/// [`all_moves`]: fn.all_moves.html
+/// [`MoveList`]: ../struct.MoveList.html
+fn generate<T: MoveGenColor, M: Extend<Action>>(
+    pinned: u64,
+    in_check: bool,
+    state: &Game,
+    moves: &mut M,
+    tactical: bool,
+    quiet: bool,
+) {
+    let board = &state.board;
+    let all_pieces = state.occupancy().0;
     let own_pieces;
     let other_pieces;
     let last_rank;
+    let en_passant_rank;
     if T::is_white() {
-        own_pieces = all_pieces & state.board.whites;
-        other_pieces = all_pieces & !state.board.whites;
+        own_pieces = state.occupancy_of(Color::White).0;
+        other_pieces = state.occupancy_of(Color::Black).0;
         last_rank = bitboard::constants::RANKS[7];
+        en_passant_rank = bitboard::constants::RANKS[5];
     } else {
-        own_pieces = all_pieces & !state.board.whites;
-        other_pieces = all_pieces & state.board.whites;
+        own_pieces = state.occupancy_of(Color::Black).0;
+        other_pieces = state.occupancy_of(Color::White).0;
         last_rank = bitboard::constants::RANKS[0];
+        en_passant_rank = bitboard::constants::RANKS[2];
+    }
+    let movable = own_pieces & !pinned;
+    // fields the generated actions may move to
+    let empty = if quiet { !all_pieces } else { 0 };
+    let other_pieces = if tactical { other_pieces } else { 0 };
+
+    let pushed_pawns = single_pawn_pushes::<T>(board.pawns & movable, !all_pieces);
+    if quiet {
+        let double_pawns = double_pawn_pushes::<T>(pushed_pawns, !all_pieces);
+        moves.extend(PawnPushIterator::new::<T>(
+            pushed_pawns & !last_rank,
+            double_pawns,
+        ));
+    }
+
+    // promotions, both quiet and capturing, always generate all four choices
+    let promotions = if tactical {
+        pushed_pawns & last_rank
+    } else {
+        0
+    };
+    let pawn_delta: i8 = if T::is_white() { 8 } else { -8 };
+    for to in Bitboard(promotions).map(Square::index) {
+        moves.extend(PromotionIterator::new(
+            (to as i8 + pawn_delta) as u8,
+            to,
+            None,
+        ));
+    }
+    let west_captures = pawn_attacks_west::<T>(board.pawns & movable) & other_pieces;
+    let east_captures = pawn_attacks_east::<T>(board.pawns & movable) & other_pieces;
+    for (captures, delta) in [
+        (west_captures, pawn_delta + 1),
+        (east_captures, pawn_delta - 1),
+    ] {
+        for to in Bitboard(captures & last_rank).map(Square::index) {
+            let captured = board
+                .get_piecetype_on(to)
+                .expect("other_pieces only contains occupied fields");
+            moves.extend(PromotionIterator::new(
+                (to as i8 + delta) as u8,
+                to,
+                Some(captured),
+            ));
+        }
+        moves.extend(PawnCaptureIterator::new(
+            captures & !last_rank,
+            delta,
+            board,
+        ));
+    }
+
+    // en passant, the captured pawn has to stand right behind the en passant field
+    if let Some(en_passant) = state.get_en_passant().filter(|_| tactical) {
+        let target = en_passant.bitboard() & en_passant_rank;
+        let victim = if T::is_white() {
+            bitboard::bitboard_south(target, 1)
+        } else {
+            bitboard::bitboard_north(target, 1)
+        };
+        if victim & other_pieces & board.pawns != 0 {
+            for (attacks, delta) in [
+                (
+                    pawn_attacks_west::<T>(board.pawns & movable),
+                    pawn_delta + 1,
+                ),
+                (
+                    pawn_attacks_east::<T>(board.pawns & movable),
+                    pawn_delta - 1,
+                ),
+            ] {
+                if attacks & target != 0 {
+                    moves.extend(Some(Action::new_from_index(
+                        (en_passant.index() as i8 + delta) as u8,
+                        en_passant.index(),
+                        PieceType::Pawn,
+                        ActionType::Capture(PieceType::Pawn),
+                    )));
+                }
+            }
+        }
+    }
+
+    let sliders = [
+        (board.bishops & !board.rooks, PieceType::Bishop),
+        (board.rooks & !board.bishops, PieceType::Rook),
+        (board.bishops & board.rooks, PieceType::Queen),
+    ];
+    for (pieces, piece) in sliders {
+        for index in Bitboard(pieces & movable).map(Square::index) {
+            let from = 1 << index;
+            let attacks = match piece {
+                PieceType::Bishop => bishop_attacks(from, all_pieces),
+                PieceType::Rook => rook_attacks(from, all_pieces),
+                _ => bishop_attacks(from, all_pieces) | rook_attacks(from, all_pieces),
+            };
+            moves.extend(QuietActionIterator::new(attacks & empty, piece, index));
+            moves.extend(CaptureActionIterator::new(
+                attacks & other_pieces,
+                piece,
+                index,
+                board,
+            ));
+        }
+    }
+
+    for knight_index in Bitboard(board.knights & movable).map(Square::index) {
+        let pos = bitboard::constants::KNIGHT_MASKS[knight_index as usize];
+        moves.extend(QuietActionIterator::new(
+            pos & empty,
+            PieceType::Knight,
+            knight_index,
+        ));
+        moves.extend(CaptureActionIterator::new(
+            pos & other_pieces,
+            PieceType::Knight,
+            knight_index,
+            board,
+        ));
+    }
+
+    for king_index in Bitboard(board.kings & movable).map(Square::index) {
+        let pos = bitboard::constants::KING_MASKS[king_index as usize];
+        moves.extend(QuietActionIterator::new(
+            pos & empty,
+            PieceType::King,
+            king_index,
+        ));
+        moves.extend(CaptureActionIterator::new(
+            pos & other_pieces,
+            PieceType::King,
+            king_index,
+            board,
+        ));
+    }
+
+    if quiet && !in_check {
+        castling_moves::<T, _>(state, own_pieces, all_pieces & !own_pieces, moves);
+    }
+}
+
+/// Appends the castling actions available to the color `T`, assuming it is not in check
+fn castling_moves<T: MoveGenColor, M: Extend<Action>>(
+    state: &Game,
+    own_pieces: u64,
+    other_pieces: u64,
+    moves: &mut M,
+) {
+    let board = &state.board;
+    let all_pieces = own_pieces | other_pieces;
+    let (color, king_field, kingside, queenside) = if T::is_white() {
+        (
+            Color::White,
+            60,
+            Castling::get_white_kingside(),
+            Castling::get_white_queenside(),
+        )
+    } else {
+        (
+            Color::Black,
+            4,
+            Castling::get_black_kingside(),
+            Castling::get_black_queenside(),
+        )
+    };
+    let rooks = board.rooks & !board.bishops & own_pieces;
+    let attacked = |field: u8| is_attacked(1 << field, all_pieces, other_pieces, board, color);
+
+    if board.kings & own_pieces & (1 << king_field) == 0 {
+        return;
+    }
+    if state.get_castling().is_available(kingside)
+        && rooks & (1 << (king_field + 3)) != 0
+        && all_pieces & (0b11 << (king_field + 1)) == 0
+        && !attacked(king_field + 1)
+        && !attacked(king_field + 2)
+    {
+        moves.extend(Some(Action::new_from_index(
+            king_field,
+            king_field + 2,
+            PieceType::King,
+            ActionType::Castling(true),
+        )));
     }
-    let empty = !all_pieces;
+    if state.get_castling().is_available(queenside)
+        && rooks & (1 << (king_field - 4)) != 0
+        && all_pieces & (0b111 << (king_field - 3)) == 0
+        && !attacked(king_field - 1)
+        && !attacked(king_field - 2)
+    {
+        moves.extend(Some(Action::new_from_index(
+            king_field,
+            king_field - 2,
+            PieceType::King,
+            ActionType::Castling(false),
+        )));
+    }
+}
 
-    let pushed_pawns = single_pawn_pushes::<T>(state.board.pawns & own_pieces & !pinned, empty);
-    let double_pawns = double_pawn_pushes::<T>(pushed_pawns, empty);
-    let mut iter: Box<dyn Iterator<Item = Action>> = Box::new(PawnPushIterator::new::<T>(pushed_pawns & !last_rank, double_pawns));
+/// Returns all legal actions for the color to move
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::movegen::legal_moves;
+/// assert_eq!(legal_moves(&Game::startpos()).len(), 20);
+/// let g = Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+/// assert!(legal_moves(&g).is_empty());
+/// ```
+pub fn legal_moves(state: &Game) -> Vec<Action> {
+    let mut moves = MoveList::new();
+    generate_moves_into(state, &mut moves);
+    moves.to_vec()
+}
+
+/// Replaces the content of the list with all legal actions for the color to move
+///
+/// This is [`legal_moves`] without allocating, the list can be reused for every position.
+///
+/// [`legal_moves`]: fn.legal_moves.html
+pub fn generate_moves_into(state: &Game, moves: &mut MoveList) {
+    generate_legal(state, moves, true, true);
+}
 
-    for bishop_index in FieldIterator::new(state.board.bishops & own_pieces & !pinned & !state.board.rooks) {
-        let bishop = 1 << bishop_index;
-        let rays = bishop_rays(bishop, own_pieces, other_pieces);
-        iter = Box::new(iter.chain(QuietActionIterator::new(rays & !other_pieces, PieceType::Bishop, bishop_index)));
+/// Replaces the content of the list with all legal captures and promotions
+///
+/// Together with [`generate_quiets_into`] this generates the same actions as
+/// [`generate_moves_into`], which allows searching the tactical actions first without
+/// generating the quiet ones.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::{movegen, MoveList};
+/// let g = Game::from_fen("4k3/1P6/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+/// let mut moves = MoveList::new();
+/// movegen::generate_captures_into(&g, &mut moves);
+/// // exd5 and four promotions
+/// assert_eq!(moves.len(), 5);
+/// movegen::generate_quiets_into(&g, &mut moves);
+/// assert_eq!(moves.len(), 6);
+/// ```
+///
+/// [`generate_quiets_into`]: fn.generate_quiets_into.html
+/// [`generate_moves_into`]: fn.generate_moves_into.html
+pub fn generate_captures_into(state: &Game, moves: &mut MoveList) {
+    generate_legal(state, moves, true, false);
+}
+
+/// Replaces the content of the list with all legal actions that neither capture nor promote
+///
+/// See [`generate_captures_into`] for the other actions.
+///
+/// [`generate_captures_into`]: fn.generate_captures_into.html
+pub fn generate_quiets_into(state: &Game, moves: &mut MoveList) {
+    generate_legal(state, moves, false, true);
+}
+
+fn generate_legal(state: &Game, moves: &mut MoveList, tactical: bool, quiet: bool) {
+    generate_legal_with(state, &CheckInfo::new(state), moves, tactical, quiet);
+}
+
+/// Replaces the content of the list with the legal actions of the enabled kinds
+///
+/// Only actions of the king, of pinned pieces, en passant captures and actions out of check
+/// can leave the own king in check, all other pseudo legal actions are legal right away.
+pub(crate) fn generate_legal_with(
+    state: &Game,
+    info: &CheckInfo,
+    moves: &mut MoveList,
+    tactical: bool,
+    quiet: bool,
+) {
+    moves.clear();
+    let in_check = info.in_check();
+    match state.color_to_move {
+        Color::White => {
+            generate::<WhiteMoveGenColor, _>(0, in_check, state, moves, tactical, quiet)
+        }
+        Color::Black => {
+            generate::<BlackMoveGenColor, _>(0, in_check, state, moves, tactical, quiet)
+        }
+    };
+    let pinned = info.pinned_of(state.color_to_move).0;
+    moves.retain(|action| {
+        let safe = !in_check
+            && action.get_piecetype() != PieceType::King
+            && pinned & (1u64 << action.get_from_index()) == 0
+            && !(action.get_piecetype() == PieceType::Pawn
+                && state.get_en_passant() == Some(action.get_to_square()));
+        safe || is_legal(action, state)
+    });
+}
+
+/// Returns true if the king of the color to move is attacked
+///
+/// Positions without a king of the color to move are never in check. With the
+/// `analysis-board` feature enabled, the same holds for positions with more than one king of
+/// the color to move.
+pub fn is_in_check(state: &Game) -> bool {
+    let board = &state.board;
+    let all_pieces = state.occupancy().0;
+    let own_pieces = state.occupancy_of(state.color_to_move).0;
+    let king = royal_king(board, own_pieces);
+    king != 0
+        && is_attacked(
+            king,
+            all_pieces,
+            all_pieces & !own_pieces,
+            board,
+            state.color_to_move,
+        )
+}
+
+/// Returns true if the pseudo legal action does not leave the own king in check
+///
+/// Castling is always considered legal here, as [`all_moves`] only generates castling through
+/// unattacked fields.
+///
+/// [`all_moves`]: fn.all_moves.html
+pub fn is_legal(action: &Action, state: &Game) -> bool {
+    if let ActionType::Castling(_) = action.get_action_type() {
+        return true;
     }
+    let board = &state.board;
+    let color = state.color_to_move;
+    let all_pieces = state.occupancy().0;
+    let own_pieces = state.occupancy_of(color).0;
+    let from = 1u64 << action.get_from_index();
+    let to = 1u64 << action.get_to_index();
 
-    for rook_index in FieldIterator::new(state.board.rooks & own_pieces & !pinned & !state.board.bishops) {
-        let rook = 1 << rook_index;
-        let rays = rook_rays(rook, own_pieces, other_pieces);
-        iter = Box::new(iter.chain(QuietActionIterator::new(rays & !other_pieces, PieceType::Rook, rook_index)));
+    let mut captured = if action.is_capture() { to } else { 0 };
+    if action.get_piecetype() == PieceType::Pawn
+        && action.is_capture()
+        && state.get_en_passant() == Some(action.get_to_square())
+    {
+        captured = match color {
+            Color::White => bitboard::bitboard_south(to, 1),
+            Color::Black => bitboard::bitboard_north(to, 1),
+        };
     }
 
-    for queen_index in FieldIterator::new(state.board.rooks & state.board.bishops & own_pieces & !pinned) {
-        let queen = 1 << queen_index;
-        let rays = rook_rays(queen, own_pieces, other_pieces) | bishop_rays(queen, own_pieces, other_pieces);
-        iter = Box::new(iter.chain(QuietActionIterator::new(rays & !other_pieces, PieceType::Queen, queen_index)));
+    let king = royal_king(board, own_pieces);
+    if king == 0 {
+        return true;
     }
+    let king = if action.get_piecetype() == PieceType::King {
+        to
+    } else {
+        king
+    };
+    let occupied = (all_pieces & !from & !captured) | to;
+    let attackers = all_pieces & !own_pieces & !captured;
+    !is_attacked(king, occupied, attackers, board, color)
+}
 
-    for knight_index in FieldIterator::new(state.board.knights & own_pieces & !pinned) {
-        let pos = bitboard::constants::KNIGHT_MASKS[knight_index as usize] & !own_pieces;
-        iter = Box::new(iter.chain(QuietActionIterator::new(pos & !other_pieces, PieceType::Knight, knight_index)));
+/// Returns the kings of the pieces that have to be kept out of check
+///
+/// Without the `analysis-board` feature these are all kings among the pieces. With it, a side
+/// with more than one king is treated like a side without a king, check is ignored.
+#[inline(always)]
+pub(crate) fn royal_king(board: &Board, own_pieces: u64) -> u64 {
+    let kings = board.kings & own_pieces;
+    if cfg!(feature = "analysis-board") && kings.count_ones() > 1 {
+        return 0;
     }
+    kings
+}
 
-    return iter.collect();
+/// Returns true if any of the `attackers` attacks a field in `target`
+///
+/// `defender` is the color owning the target, which decides the direction pawns attack from.
+/// The attacking pieces are looked up on the board, restricted to `attackers`, while sliding
+/// pieces are blocked by `occupied`. This allows checking attacks after a hypothetical action.
+fn is_attacked(target: u64, occupied: u64, attackers: u64, board: &Board, defender: Color) -> bool {
+    attackers_of(target, occupied, board, defender) & attackers != 0
+}
+
+/// Returns all pieces of the board attacking a field in `target`, regardless of their color
+///
+/// `defender` is the color owning the target, which decides the direction pawns attack from.
+/// Sliding pieces are blocked by `occupied`.
+pub(crate) fn attackers_of(target: u64, occupied: u64, board: &Board, defender: Color) -> u64 {
+    let mut knight_fields = 0;
+    for index in Bitboard(target).map(Square::index) {
+        knight_fields |= bitboard::constants::KNIGHT_MASKS[index as usize];
+    }
+    let pawn_fields = Bitboard(target)
+        .map(|square| bitboard::constants::PAWN_ATTACKS[defender as usize][square.index() as usize])
+        .fold(0, |fields, attacks| fields | attacks);
+    knight_fields & board.knights
+        | king_attacks(target) & board.kings
+        | pawn_fields & board.pawns
+        | bishop_attacks(target, occupied) & board.bishops
+        | rook_attacks(target, occupied) & board.rooks
 }
 
 pub fn single_pawn_pushes<T: MoveGenColor>(pawns: u64, empty: u64) -> u64 {
@@ -75,156 +481,317 @@ pub fn double_pawn_pushes<T: MoveGenColor>(pushed_pawns: u64, empty: u64) -> u64
     }
 }
 
+/// Returns all fields attacked by the given pawns towards the west (a-file)
+pub fn pawn_attacks_west<T: MoveGenColor>(pawns: u64) -> u64 {
+    if T::is_white() {
+        bitboard::bitboard_west_one(bitboard::bitboard_north(pawns, 1))
+    } else {
+        bitboard::bitboard_west_one(bitboard::bitboard_south(pawns, 1))
+    }
+}
+
+/// Returns all fields attacked by the given pawns towards the east (h-file)
+pub fn pawn_attacks_east<T: MoveGenColor>(pawns: u64) -> u64 {
+    if T::is_white() {
+        bitboard::bitboard_east_one(bitboard::bitboard_north(pawns, 1))
+    } else {
+        bitboard::bitboard_east_one(bitboard::bitboard_south(pawns, 1))
+    }
+}
+
+/// Returns the pieces of the given type and of the color to move that attack `destination`
+///
+/// Sliding pieces are blocked by all pieces on the board. Rooks and bishops do not include
+/// queens, a queen is only returned for [`PieceType::Queen`], so the piece letter of a SAN
+/// action is enough to tell the candidates of "Rxd4" and "Qxd4" apart. This does not check
+/// whether the found pieces are pinned.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::{Game, PieceType};
+/// # use rust_chess::move_generation::movegen::can_be_attacked_from;
+/// let g = Game::from_fen("4k3/8/8/8/8/8/8/Q2RK3 w - - 0 1").unwrap();
+/// let d4 = 1 << 35;
+/// assert_eq!(can_be_attacked_from(d4, PieceType::Rook, &g), 1 << 59);
+/// assert_eq!(can_be_attacked_from(d4, PieceType::Queen, &g), 1 << 56);
+/// ```
+///
+/// [`PieceType::Queen`]: ../../game_representation/enum.PieceType.html#variant.Queen
 pub fn can_be_attacked_from(destination: u64, piece: PieceType, state: &Game) -> u64 {
+    let board = &state.board;
+    let occupied = state.occupancy().0;
     let attacked = match piece {
+        // a pawn attacks the destination from where an opposing pawn on it would attack
         PieceType::Pawn => {
-            (if state.color_to_move == Color::White {
-                let rank_shifted = bitboard::bitboard_south(destination, 1);
-                bitboard::bitboard_east_one(rank_shifted)
-                    | bitboard::bitboard_west_one(rank_shifted)
-            } else {
-                let rank_shifted = bitboard::bitboard_north(destination, 1);
-                bitboard::bitboard_east_one(rank_shifted)
-                    | bitboard::bitboard_west_one(rank_shifted)
-            }) & state.board.pawns
-        }
-        PieceType::King => {
-            let left_right = destination
-                | bitboard::bitboard_west_one(destination)
-                | bitboard::bitboard_east_one(destination);
-            (left_right
-                | bitboard::bitboard_north(left_right, 1)
-                | bitboard::bitboard_south(left_right, 1))
-                & state.board.kings
+            let opponent = state.color_to_move.get_opponent_color();
+            Bitboard(destination)
+                .map(|square| {
+                    bitboard::constants::PAWN_ATTACKS[opponent as usize][square.index() as usize]
+                })
+                .fold(0, |fields, attacks| fields | attacks)
+                & board.pawns
         }
+        PieceType::King => king_attacks(destination) & board.kings,
         PieceType::Knight => {
-            let index = destination.trailing_zeros();
-            bitboard::constants::KNIGHT_MASKS[index as usize] & state.board.knights
+            Bitboard(destination)
+                .map(|square| bitboard::constants::KNIGHT_MASKS[square.index() as usize])
+                .fold(0, |fields, knight_fields| fields | knight_fields)
+                & board.knights
         }
-        PieceType::Rook => rays_to_rooks(destination, state) & !state.board.bishops,
-        PieceType::Bishop => rays_to_bishops(destination, state) & !&state.board.rooks,
+        PieceType::Rook => rook_attacks(destination, occupied) & board.rooks & !board.bishops,
+        PieceType::Bishop => bishop_attacks(destination, occupied) & board.bishops & !board.rooks,
         PieceType::Queen => {
-            let queens = state.board.bishops & state.board.rooks;
-            (rays_to_bishops(destination, state) | rays_to_rooks(destination, state)) & queens
+            (bishop_attacks(destination, occupied) | rook_attacks(destination, occupied))
+                & board.bishops
+                & board.rooks
         }
     };
-    if state.color_to_move == Color::White {
-        attacked & state.board.whites
-    } else {
-        attacked & !state.board.whites
-    }
-}
-
-fn bishop_rays(bishop: u64, own_pieces: u64, other_pieces: u64) -> u64 {
-    let empty = !(own_pieces | other_pieces);
-    let mut mask = 0;
-    let mut fill = bishop;
-    while fill != mask {
-        mask |= fill;
-        let left_right = bitboard::bitboard_east_one(mask) | bitboard::bitboard_west_one(mask);
-        fill = (bitboard::bitboard_north(left_right, 1)
-            | bitboard::bitboard_south(left_right, 1)
-            | mask)
-            & (empty | bishop);
-    }
-    let left_right = bitboard::bitboard_east_one(mask) | bitboard::bitboard_west_one(mask);
-    fill = (bitboard::bitboard_north(left_right, 1) | bitboard::bitboard_south(left_right, 1))
-        & other_pieces; // captures
-    mask |= fill;
-    mask & !bishop
-}
-
-fn rook_rays(rook: u64, own_pieces: u64, other_pieces: u64) -> u64 {
-    let empty = !(own_pieces | other_pieces);
-    let mut mask = 0;
-    let mut fill = rook;
-    while fill != mask {
-        mask |= fill;
-        fill = (bitboard::bitboard_north(mask, 1) | bitboard::bitboard_south(mask, 1) | mask)
-            & (empty | rook);
-    }
-    fill = (bitboard::bitboard_north(mask, 1) | bitboard::bitboard_south(mask, 1)) & other_pieces;
-    mask |= fill;
-
-    let mut lr_mask = 0;
-    let mut fill = rook;
-    while fill != lr_mask {
-        lr_mask |= fill;
-        fill =
-            (bitboard::bitboard_east_one(lr_mask) | bitboard::bitboard_west_one(lr_mask) | lr_mask)
-                & (empty | rook);
-    }
-    fill = (bitboard::bitboard_east_one(lr_mask) | bitboard::bitboard_west_one(lr_mask))
-        & other_pieces;
-    lr_mask |= fill;
-
-    (mask | lr_mask) & !rook
-}
-
-fn rays_to_bishops(field: u64, state: &Game) -> u64 {
-    let all_pieces = state.board.bishops
-        | state.board.rooks
-        | state.board.pawns
-        | state.board.knights
-        | state.board.kings;
-    let own_pieces;
-    if state.color_to_move == Color::White {
-        own_pieces = all_pieces & state.board.whites;
-    } else {
-        own_pieces = all_pieces & !state.board.whites;
-    }
-    let empty = !all_pieces;
-    let mut mask = 0;
-    let mut fill = field;
-    while fill != mask {
-        mask |= fill;
-        let left_right = bitboard::bitboard_east_one(mask) | bitboard::bitboard_west_one(mask);
-        fill = (bitboard::bitboard_north(left_right, 1)
-            | bitboard::bitboard_south(left_right, 1)
-            | mask)
-            & (empty | field);
-    }
-    let left_right = bitboard::bitboard_east_one(mask) | bitboard::bitboard_west_one(mask);
-    fill = (bitboard::bitboard_north(left_right, 1) | bitboard::bitboard_south(left_right, 1))
-        & own_pieces;
-    mask |= fill;
-    mask & state.board.bishops
-}
-
-fn rays_to_rooks(field: u64, state: &Game) -> u64 {
-    let all_pieces = state.board.bishops
-        | state.board.rooks
-        | state.board.pawns
-        | state.board.knights
-        | state.board.kings;
-    let own_pieces;
-    if state.color_to_move == Color::White {
-        own_pieces = all_pieces & state.board.whites;
-    } else {
-        own_pieces = all_pieces & !state.board.whites;
-    }
-    let empty = !all_pieces;
-    let mut mask = 0;
-    let mut fill = field;
-    while fill != mask {
-        mask |= fill;
-        fill = (bitboard::bitboard_north(mask, 1) | bitboard::bitboard_south(mask, 1) | mask)
-            & (empty | field);
-    }
-    fill = (bitboard::bitboard_north(mask, 1) | bitboard::bitboard_south(mask, 1)) & own_pieces;
-    mask |= fill;
-
-    let mut lr_mask = 0;
-    let mut fill = field;
-    while fill != lr_mask {
-        lr_mask |= fill;
-        fill =
-            (bitboard::bitboard_east_one(lr_mask) | bitboard::bitboard_west_one(lr_mask) | lr_mask)
-                & (empty | field);
-    }
-    fill =
-        (bitboard::bitboard_east_one(lr_mask) | bitboard::bitboard_west_one(lr_mask)) & own_pieces;
-    lr_mask |= fill;
-
-    (mask | lr_mask) & state.board.rooks
+    attacked & state.occupancy_of(state.color_to_move).0
+}
+
+/// Returns all fields attacked by the given kings
+pub fn king_attacks(kings: u64) -> u64 {
+    Bitboard(kings)
+        .map(|square| bitboard::constants::KING_MASKS[square.index() as usize])
+        .fold(0, |attacks, king_attacks| attacks | king_attacks)
+}
+
+/// Returns all fields attacked diagonally by the given pieces
+///
+/// Every ray stops at the first field contained in `occupied`, which is attacked as well. The
+/// attacks of every piece are looked up in precomputed magic bitboard tables.
+pub fn bishop_attacks(pieces: u64, occupied: u64) -> u64 {
+    Bitboard(pieces)
+        .map(|square| magic::bishop_attacks_from(square.index(), occupied))
+        .fold(0, |attacks, piece_attacks| attacks | piece_attacks)
+}
+
+/// Returns all fields attacked along ranks and files by the given pieces
+///
+/// Every ray stops at the first field contained in `occupied`, which is attacked as well. The
+/// attacks of every piece are looked up in precomputed magic bitboard tables.
+pub fn rook_attacks(pieces: u64, occupied: u64) -> u64 {
+    Bitboard(pieces)
+        .map(|square| magic::rook_attacks_from(square.index(), occupied))
+        .fold(0, |attacks, piece_attacks| attacks | piece_attacks)
+}
+
+/// [`bishop_attacks`] computed by flooding along the rays
+///
+/// This is much slower than the table lookup and is only used to build the tables.
+///
+/// [`bishop_attacks`]: fn.bishop_attacks.html
+pub fn bishop_rays(pieces: u64, occupied: u64) -> u64 {
+    ray_attacks(pieces, occupied, |b| {
+        bitboard::bitboard_east_one(bitboard::bitboard_north(b, 1))
+    }) | ray_attacks(pieces, occupied, |b| {
+        bitboard::bitboard_west_one(bitboard::bitboard_north(b, 1))
+    }) | ray_attacks(pieces, occupied, |b| {
+        bitboard::bitboard_east_one(bitboard::bitboard_south(b, 1))
+    }) | ray_attacks(pieces, occupied, |b| {
+        bitboard::bitboard_west_one(bitboard::bitboard_south(b, 1))
+    })
+}
+
+/// [`rook_attacks`] computed by flooding along the rays
+///
+/// This is much slower than the table lookup and is only used to build the tables.
+///
+/// [`rook_attacks`]: fn.rook_attacks.html
+pub fn rook_rays(pieces: u64, occupied: u64) -> u64 {
+    ray_attacks(pieces, occupied, |b| bitboard::bitboard_north(b, 1))
+        | ray_attacks(pieces, occupied, |b| bitboard::bitboard_south(b, 1))
+        | ray_attacks(pieces, occupied, bitboard::bitboard_east_one)
+        | ray_attacks(pieces, occupied, bitboard::bitboard_west_one)
+}
+
+/// Floods from the pieces into one direction until a field in `occupied` is hit
+fn ray_attacks(pieces: u64, occupied: u64, step: fn(u64) -> u64) -> u64 {
+    let mut attacks = 0;
+    let mut flood = step(pieces);
+    while flood != 0 {
+        attacks |= flood;
+        flood = step(flood & !occupied);
+    }
+    attacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::move_generation::core::{BlackMoveGenColor, WhiteMoveGenColor};
+    use crate::move_generation::ActionType;
+
+    fn filter_promotions(actions: &[Action]) -> Vec<&Action> {
+        actions.iter().filter(|a| a.is_promotion()).collect()
+    }
+
+    #[test]
+    fn quiet_promotions() {
+        let g = Game::from_fen("4k3/1P6/8/8/8/8/K7/8 w - - 0 1").unwrap();
+        let actions = all_moves::<WhiteMoveGenColor>(0, false, &g);
+        let promotions = filter_promotions(&actions);
+        assert_eq!(promotions.len(), 4);
+        for piece in &[
+            PieceType::Queen,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+        ] {
+            let expected = Action::new(
+                bitboard::field_repr_to_coords("b7").unwrap(),
+                bitboard::field_repr_to_coords("b8").unwrap(),
+                PieceType::Pawn,
+                ActionType::Promotion(*piece),
+            );
+            assert!(promotions.contains(&&expected));
+        }
+    }
+
+    #[test]
+    fn promotion_captures() {
+        let g = Game::from_fen("r1n1k3/1P6/8/8/8/8/K7/8 w - - 0 1").unwrap();
+        let actions = all_moves::<WhiteMoveGenColor>(0, false, &g);
+        let promotions = filter_promotions(&actions);
+        assert_eq!(promotions.len(), 12);
+        assert!(promotions.contains(&&Action::new(
+            bitboard::field_repr_to_coords("b7").unwrap(),
+            bitboard::field_repr_to_coords("a8").unwrap(),
+            PieceType::Pawn,
+            ActionType::PromotionCapture(PieceType::Knight, PieceType::Rook),
+        )));
+        assert!(promotions.contains(&&Action::new(
+            bitboard::field_repr_to_coords("b7").unwrap(),
+            bitboard::field_repr_to_coords("c8").unwrap(),
+            PieceType::Pawn,
+            ActionType::PromotionCapture(PieceType::Queen, PieceType::Knight),
+        )));
+
+        // blocked pawns can still promote by capturing
+        let g = Game::from_fen("4k3/8/8/8/8/8/1p6/RN2K3 b - - 0 1").unwrap();
+        let actions = all_moves::<BlackMoveGenColor>(0, false, &g);
+        let promotions = filter_promotions(&actions);
+        assert_eq!(promotions.len(), 4);
+        assert!(promotions.iter().all(|a| a.get_action_type()
+            == ActionType::PromotionCapture(a.get_promotion_piece().unwrap(), PieceType::Rook)));
+    }
+
+    #[test]
+    fn pinned_pawns_do_not_promote() {
+        let g = Game::from_fen("r1n1k3/1P6/8/8/8/8/K7/8 w - - 0 1").unwrap();
+        let pinned = 1 << bitboard::field_repr_to_index("b7").unwrap();
+        let actions = all_moves::<WhiteMoveGenColor>(pinned, false, &g);
+        assert!(filter_promotions(&actions).is_empty());
+    }
+
+    #[test]
+    fn legal_move_counts() {
+        for (fen, count) in &[
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                20,
+            ),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                48,
+            ),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 14),
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                6,
+            ),
+        ] {
+            let g = Game::from_fen(fen).unwrap();
+            assert_eq!(legal_moves(&g).len(), *count, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn slider_rays_do_not_turn_corners() {
+        let a1 = 1 << bitboard::field_repr_to_index("a1").unwrap();
+        assert_eq!(bishop_attacks(a1, a1).count_ones(), 7);
+        assert_eq!(rook_attacks(a1, a1).count_ones(), 14);
+        let d4 = 1 << bitboard::field_repr_to_index("d4").unwrap();
+        let blocker = 1 << bitboard::field_repr_to_index("f6").unwrap();
+        assert_eq!(bishop_attacks(d4, blocker).count_ones(), 11);
+        assert!(bishop_attacks(d4, blocker) & blocker != 0);
+    }
+
+    #[test]
+    fn attackers_separate_queens_from_rooks() {
+        let state = Game::from_fen("3rk3/8/8/1n1p4/8/4P3/6B1/Q2RK3 b - - 0 1").unwrap();
+        let d4 = 1 << bitboard::field_repr_to_index("d4").unwrap();
+        // only black pieces are candidates for black to move
+        assert_eq!(can_be_attacked_from(d4, PieceType::Rook, &state), 0);
+        assert_eq!(can_be_attacked_from(d4, PieceType::Queen, &state), 0);
+        assert_eq!(can_be_attacked_from(d4, PieceType::Knight, &state), 1 << 25);
+
+        let state = Game::from_fen("3rk3/8/8/1n1p4/8/4P3/6B1/Q2RK3 w - - 0 1").unwrap();
+        assert_eq!(can_be_attacked_from(d4, PieceType::Rook, &state), 1 << 59);
+        assert_eq!(can_be_attacked_from(d4, PieceType::Queen, &state), 1 << 56);
+        assert_eq!(can_be_attacked_from(d4, PieceType::Bishop, &state), 0);
+        assert_eq!(can_be_attacked_from(d4, PieceType::Pawn, &state), 1 << 44);
+        let rook = Action::from_san("Rd4", &state).unwrap();
+        let queen = Action::from_san("Qd4", &state).unwrap();
+        assert_eq!(rook.get_from_square().to_string(), "d1");
+        assert_eq!(queen.get_from_square().to_string(), "a1");
+    }
+
+    #[test]
+    fn castling_respects_attacks() {
+        // f1 is attacked, so only queenside castling is possible
+        let g = Game::from_fen("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1").unwrap();
+        let castles: Vec<_> = legal_moves(&g)
+            .into_iter()
+            .filter(|a| matches!(a.get_action_type(), ActionType::Castling(_)))
+            .collect();
+        assert_eq!(castles.len(), 1);
+        assert_eq!(castles[0].get_action_type(), ActionType::Castling(false));
+
+        // no castling out of check
+        let g = Game::from_fen("4k3/8/8/8/8/8/4r3/R3K2R w KQ - 0 1").unwrap();
+        assert!(legal_moves(&g)
+            .iter()
+            .all(|a| !matches!(a.get_action_type(), ActionType::Castling(_))));
+    }
+
+    #[test]
+    fn en_passant_captures() {
+        let g = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2").unwrap();
+        let en_passant = Action::new(
+            bitboard::field_repr_to_coords("e5").unwrap(),
+            bitboard::field_repr_to_coords("d6").unwrap(),
+            PieceType::Pawn,
+            ActionType::Capture(PieceType::Pawn),
+        );
+        assert!(legal_moves(&g).contains(&en_passant));
+
+        // capturing would expose the king on the rank
+        let g = Game::from_fen("8/8/8/K2pP2r/8/8/8/4k3 w - d6 0 2").unwrap();
+        assert!(!legal_moves(&g).contains(&en_passant));
+    }
+
+    #[test]
+    fn positions_without_kings() {
+        let mut g = Game::from_fen("8/8/8/3r4/8/8/3P4/R7 w - - 0 1").unwrap();
+        assert!(!is_in_check(&g));
+        assert_eq!(legal_moves(&g).len(), 16);
+        assert_eq!(g.perft(2), 205);
+    }
+
+    #[cfg(feature = "analysis-board")]
+    #[test]
+    fn positions_with_several_kings() {
+        // both white kings are attacked, but with two kings neither is royal
+        let mut g = Game::from_fen("4k3/8/8/8/8/8/8/r2K1K2 w - - 0 1").unwrap();
+        assert!(!is_in_check(&g));
+        let moves = legal_moves(&g);
+        assert!(moves.iter().any(|a| a.to_uci() == "d1d2"));
+        assert!(moves.iter().any(|a| a.to_uci() == "f1f2"));
+        assert!(g.perft(3) > 0);
+
+        // a single king is still kept out of check
+        let g = Game::from_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1").unwrap();
+        assert!(is_in_check(&g));
+    }
 }
@@ -0,0 +1,179 @@
+use crate::game_representation::Game;
+use crate::move_generation::{movegen, Action, MoveList};
+use alloc::vec::Vec;
+
+impl Game {
+    /// Returns the number of leaf nodes of the legal move tree with the given depth
+    ///
+    /// Counting the nodes and comparing them to known values is the standard way of validating
+    /// a move generator. The game is left in the same state it was in before.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let mut g = Game::startpos();
+    /// assert_eq!(g.perft(1), 20);
+    /// assert_eq!(g.perft(3), 8902);
+    /// ```
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut actions = MoveList::new();
+        movegen::generate_moves_into(self, &mut actions);
+        if depth == 1 {
+            return actions.len() as u64;
+        }
+        let mut nodes = 0;
+        for action in &actions {
+            let token = self.execute_action(action);
+            nodes += self.perft(depth - 1);
+            self.undo_action(action, token);
+        }
+        nodes
+    }
+
+    /// Returns the perft node count below every legal root action
+    ///
+    /// Comparing the divided counts with another move generator narrows a wrong perft result
+    /// down to the root action whose subtree differs. The sum of all counts equals
+    /// [`perft`] of the same depth. A depth of 0 returns no actions.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let mut g = Game::startpos();
+    /// let divided = g.perft_divide(2);
+    /// assert_eq!(divided.len(), 20);
+    /// assert!(divided.iter().all(|(_, nodes)| *nodes == 20));
+    /// ```
+    ///
+    /// [`perft`]: #method.perft
+    pub fn perft_divide(&mut self, depth: u32) -> Vec<(Action, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+        movegen::legal_moves(self)
+            .into_iter()
+            .map(|action| {
+                let token = self.execute_action(&action);
+                let nodes = self.perft(depth - 1);
+                self.undo_action(&action, token);
+                (action, nodes)
+            })
+            .collect()
+    }
+
+    /// Returns the same node count as [`perft`], splitting the root actions across threads
+    ///
+    /// Every thread counts the nodes below its share of the root actions on its own copy of
+    /// the game, so the speedup grows with the number of threads up to the number of cores.
+    /// A thread count of 0 is treated as 1.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let game = Game::startpos();
+    /// assert_eq!(game.perft_parallel(3, 4), 8902);
+    /// ```
+    ///
+    /// [`perft`]: #method.perft
+    #[cfg(feature = "std")]
+    pub fn perft_parallel(&self, depth: u32, threads: usize) -> u64 {
+        if depth <= 1 {
+            return self.clone().perft(depth);
+        }
+        let actions = movegen::legal_moves(self);
+        let threads = threads.clamp(1, actions.len().max(1));
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|thread| {
+                    let mut game = self.clone();
+                    let actions = &actions;
+                    scope.spawn(move || {
+                        let mut nodes = 0;
+                        for action in actions.iter().skip(thread).step_by(threads) {
+                            let token = game.execute_action(action);
+                            nodes += game.perft(depth - 1);
+                            game.undo_action(action, token);
+                        }
+                        nodes
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("perft threads do not panic"))
+                .sum()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_perft_results() {
+        for (epd, depth, nodes) in &[
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - hmvc 0; fmvn 1;",
+                3,
+                8902,
+            ),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - hmvc 0; fmvn 1;",
+                2,
+                2039,
+            ),
+            (
+                "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - hmvc 0; fmvn 1;",
+                4,
+                43238,
+            ),
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - hmvc 0; fmvn 1;",
+                3,
+                9467,
+            ),
+            (
+                "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - hmvc 1; fmvn 8;",
+                2,
+                1486,
+            ),
+        ] {
+            let mut game = Game::from_epd(epd).unwrap();
+            assert_eq!(game.perft(*depth), *nodes, "{}", epd);
+            assert_eq!(game.to_epd(), *epd);
+        }
+    }
+
+    #[test]
+    fn divide_sums_to_perft() {
+        let mut game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let divided = game.perft_divide(2);
+        assert_eq!(divided.len(), 48);
+        assert_eq!(divided.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2039);
+        assert!(game.perft_divide(0).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn parallel_perft() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Game>();
+        assert_send_sync::<crate::game_representation::Board>();
+
+        let mut game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        for threads in 0..4 {
+            assert_eq!(game.perft_parallel(3, threads), 97862);
+        }
+        assert_eq!(game.perft_parallel(1, 8), 48);
+        assert_eq!(game.perft_parallel(0, 8), 1);
+        assert_eq!(game.perft(3), 97862);
+    }
+}
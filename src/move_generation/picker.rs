@@ -0,0 +1,281 @@
+use super::{movegen, Action, CheckInfo, MoveList};
+use crate::game_representation::{Game, PieceType};
+
+/// The stages of a [`MovePicker`], in the order they are passed
+///
+/// [`MovePicker`]: struct.MovePicker.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MoveStage {
+    /// The action given to [`MovePicker::with_hash_move`]
+    ///
+    /// [`MovePicker::with_hash_move`]: struct.MovePicker.html#method.with_hash_move
+    HashMove,
+    /// Promotions and captures of a piece at least as valuable as the capturing one
+    GoodCaptures,
+    /// Actions that neither capture nor promote
+    Quiets,
+    /// Captures of a piece less valuable than the capturing one
+    BadCaptures,
+    /// All actions were returned
+    Done,
+}
+
+/// Returns the legal actions of a position in an order suited for searching
+///
+/// The captures and promotions are generated right away and ordered by the value of the
+/// captured piece first and the value of the capturing piece second. The quiet actions are only
+/// generated once all good captures were returned, so a search cutting off early never pays for
+/// them. Losing captures come last.
+///
+/// The picker does not borrow the game, so the caller can execute and undo actions between
+/// two calls of [`next`]. The game has to be in the position the picker was created for
+/// whenever [`next`] is called.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::{MovePicker, MoveStage};
+/// let game = Game::from_fen("4k3/8/8/3q4/4P3/2N5/8/4K3 w - - 0 1").unwrap();
+/// let mut picker = MovePicker::new(&game);
+/// let first = picker.next(&game).unwrap();
+/// assert_eq!(first.to_uci(), "e4d5");
+/// assert_eq!(picker.stage(), MoveStage::GoodCaptures);
+/// let mut count = 1;
+/// while picker.next(&game).is_some() {
+///     count += 1;
+/// }
+/// assert_eq!(count, 12);
+/// ```
+///
+/// [`next`]: #method.next
+#[derive(Clone, Debug)]
+pub struct MovePicker {
+    stage: MoveStage,
+    // actions of the current stage, best first
+    current: MoveList,
+    bad_captures: MoveList,
+    index: usize,
+    skip_quiets: bool,
+    hash_move: Option<Action>,
+    check_info: CheckInfo,
+}
+
+impl MovePicker {
+    /// Returns a picker for the legal actions of the position
+    pub fn new(state: &Game) -> MovePicker {
+        MovePicker::with_hash_move(state, None)
+    }
+
+    /// Returns a picker returning `hash_move` first, if it is legal in the position
+    ///
+    /// Searches usually remember the best action of a position, for example in a
+    /// [`TranspositionTable`], and try it before generating any other action. The hash move is
+    /// checked against the generated actions, so a wrong one is ignored.
+    ///
+    /// [`TranspositionTable`]: ../search/struct.TranspositionTable.html
+    pub fn with_hash_move(state: &Game, hash_move: Option<Action>) -> MovePicker {
+        let check_info = CheckInfo::new(state);
+        let mut current = MoveList::new();
+        movegen::generate_legal_with(state, &check_info, &mut current, true, false);
+        let mut bad_captures = current.clone();
+        current.retain(is_good_capture);
+        bad_captures.retain(|action| !is_good_capture(action));
+        current.sort_unstable_by_key(|action| -capture_score(action));
+        bad_captures.sort_unstable_by_key(|action| -capture_score(action));
+        let hash_move = hash_move.filter(|action| {
+            if action.is_capture() || action.is_promotion() {
+                current.contains(action) || bad_captures.contains(action)
+            } else {
+                let mut quiets = MoveList::new();
+                movegen::generate_legal_with(state, &check_info, &mut quiets, false, true);
+                quiets.contains(action)
+            }
+        });
+        MovePicker {
+            stage: match hash_move {
+                Some(_) => MoveStage::HashMove,
+                None => MoveStage::GoodCaptures,
+            },
+            current,
+            bad_captures,
+            index: 0,
+            skip_quiets: false,
+            hash_move,
+            check_info,
+        }
+    }
+
+    /// Returns the stage of the action returned last
+    pub fn stage(&self) -> MoveStage {
+        self.stage
+    }
+
+    /// Returns the checks and pins of the position, computed once for all stages
+    pub fn check_info(&self) -> &CheckInfo {
+        &self.check_info
+    }
+
+    /// Makes the picker leave out all quiet actions, as needed by a quiescence search
+    ///
+    /// Has no effect on quiet actions already returned.
+    pub fn skip_quiets(&mut self) {
+        self.skip_quiets = true;
+    }
+
+    /// Returns the next action, `None` once all actions were returned
+    pub fn next(&mut self, state: &Game) -> Option<Action> {
+        if self.stage == MoveStage::HashMove {
+            // the index counts the returned hash moves until the good captures start
+            if self.index == 0 {
+                self.index = 1;
+                return self.hash_move;
+            }
+            self.stage = MoveStage::GoodCaptures;
+            self.index = 0;
+        }
+        loop {
+            if let Some(&action) = self.current.get(self.index) {
+                self.index += 1;
+                if Some(action) == self.hash_move {
+                    continue;
+                }
+                return Some(action);
+            }
+            self.index = 0;
+            match self.stage {
+                MoveStage::HashMove | MoveStage::GoodCaptures => {
+                    self.stage = MoveStage::Quiets;
+                    if self.skip_quiets {
+                        self.current.clear();
+                    } else {
+                        movegen::generate_legal_with(
+                            state,
+                            &self.check_info,
+                            &mut self.current,
+                            false,
+                            true,
+                        );
+                    }
+                }
+                MoveStage::Quiets => {
+                    self.stage = MoveStage::BadCaptures;
+                    core::mem::swap(&mut self.current, &mut self.bad_captures);
+                }
+                MoveStage::BadCaptures | MoveStage::Done => {
+                    self.stage = MoveStage::Done;
+                    self.current.clear();
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Returns true for promotions and captures not giving away material at first sight
+fn is_good_capture(action: &Action) -> bool {
+    let piece = action.get_piecetype();
+    match action.get_capture_piece() {
+        _ if action.is_promotion() => true,
+        // legal king captures can not be answered
+        Some(_) if piece == PieceType::King => true,
+        Some(captured) => captured.get_value() >= piece.get_value(),
+        None => true,
+    }
+}
+
+/// Scores a capture by the most valuable victim first and the least valuable attacker second
+fn capture_score(action: &Action) -> i32 {
+    let victim = action.get_capture_piece().map_or(0, PieceType::get_value);
+    let promotion = action
+        .get_promotion_piece()
+        .map_or(0, |piece| piece.get_value() - PieceType::Pawn.get_value());
+    let attacker = match action.get_piecetype() {
+        PieceType::King => 0,
+        piece => piece.get_value(),
+    };
+    (victim + promotion) * 16 - attacker / 16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_representation::Game;
+
+    fn picked(game: &Game, skip_quiets: bool) -> Vec<(String, MoveStage)> {
+        picked_with(MovePicker::new(game), game, skip_quiets)
+    }
+
+    fn picked_with(
+        mut picker: MovePicker,
+        game: &Game,
+        skip_quiets: bool,
+    ) -> Vec<(String, MoveStage)> {
+        if skip_quiets {
+            picker.skip_quiets();
+        }
+        let mut picked = Vec::new();
+        while let Some(action) = picker.next(game) {
+            picked.push((action.to_uci(), picker.stage()));
+        }
+        assert_eq!(picker.stage(), MoveStage::Done);
+        assert!(picker.next(game).is_none());
+        picked
+    }
+
+    #[test]
+    fn stages_cover_all_legal_moves() {
+        let game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let picked = picked(&game, false);
+        let mut legal: Vec<String> = movegen::legal_moves(&game)
+            .iter()
+            .map(Action::to_uci)
+            .collect();
+        let mut uci: Vec<String> = picked.iter().map(|(uci, _)| uci.clone()).collect();
+        legal.sort();
+        uci.sort();
+        assert_eq!(uci, legal);
+        // stages never go back
+        assert!(picked.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        // the queen takes the defended pawn on h3 last
+        assert_eq!(
+            picked.last().unwrap(),
+            &(String::from("f3h3"), MoveStage::BadCaptures)
+        );
+    }
+
+    #[test]
+    fn captures_are_ordered() {
+        let game = Game::from_fen("4k3/1P6/8/2r1q3/3P4/8/8/7K w - - 0 1").unwrap();
+        let picked = picked(&game, true);
+        let uci: Vec<&str> = picked.iter().map(|(uci, _)| uci.as_str()).collect();
+        // the queen is worth more than promoting
+        assert_eq!(uci[0], "d4e5");
+        assert_eq!(uci[1], "b7b8q");
+        assert_eq!(uci[2], "d4c5");
+        assert_eq!(uci.len(), 6);
+    }
+
+    #[test]
+    fn hash_move_comes_first() {
+        let game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let all = picked(&game, false);
+        for uci in ["a2a3", "f3h3", "e5f7"] {
+            let hash_move = Action::from_uci(uci, &game).unwrap();
+            let picker = MovePicker::with_hash_move(&game, Some(hash_move));
+            let picked = picked_with(picker, &game, false);
+            assert_eq!(picked[0], (String::from(uci), MoveStage::HashMove));
+            assert_eq!(picked.len(), all.len());
+            assert!(picked[1..].iter().all(|(other, _)| other != uci));
+        }
+
+        // actions of other positions are ignored
+        let illegal = Action::from_uci("e1e2", &Game::startpos()).unwrap();
+        let picker = MovePicker::with_hash_move(&game, Some(illegal));
+        assert_eq!(picker.stage(), MoveStage::GoodCaptures);
+        assert_eq!(picked_with(picker, &game, false), all);
+    }
+}
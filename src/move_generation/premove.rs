@@ -0,0 +1,207 @@
+use crate::game_representation::{Color, Game};
+use crate::move_generation::{movegen, Action};
+use alloc::vec::Vec;
+
+/// The actions the player waiting for the opponent may queue as a premove
+///
+/// Online servers let the player who is waiting for the opponent queue a premove, which is
+/// played if it is legal once the opponent moved. The premoves are the union of the legal
+/// actions after every legal reply of the color to move. The union is generated once, so any
+/// number of actions can be checked against it, for example while the player drags a piece.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::{Action, Premoves};
+/// let mut g = Game::startpos();
+/// g.execute_action(&Action::from_san("e4", &g).unwrap());
+/// let premoves = Premoves::new(&g);
+/// let uci: Vec<String> = premoves.actions().iter().map(Action::to_uci).collect();
+/// // the pawn on e4 captures on d5 if black pushes a pawn there, but never moves two fields
+/// assert!(uci.contains(&String::from("e4d5")));
+/// assert!(!uci.contains(&String::from("e4e6")));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Premoves {
+    actions: Vec<Action>,
+}
+
+impl Premoves {
+    /// Returns the premoves of the opponent of the color to move
+    pub fn new(state: &Game) -> Premoves {
+        let mut actions: Vec<Action> = Vec::new();
+        let mut game = state.clone();
+        for reply in movegen::legal_moves(&game) {
+            let token = game.execute_action(&reply);
+            for action in movegen::legal_moves(&game) {
+                if !actions.contains(&action) {
+                    actions.push(action);
+                }
+            }
+            game.undo_action(&reply, token);
+        }
+        Premoves { actions }
+    }
+
+    /// Returns true if the action is legal after some reply of the color to move
+    ///
+    /// Capture information of the action has to match the position after the reply, just like
+    /// for any other action.
+    pub fn contains(&self, action: &Action) -> bool {
+        self.actions.contains(action)
+    }
+
+    /// Returns every action that is legal after some reply, each one once
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+}
+
+impl Game {
+    /// Returns the actions the opponent of the color to move may queue as a premove
+    ///
+    /// See [`Premoves`] for checking several actions against the same position.
+    ///
+    /// [`Premoves`]: ../move_generation/struct.Premoves.html
+    pub fn premoves(&self) -> Premoves {
+        Premoves::new(self)
+    }
+
+    /// Returns true if the action could be legal after some reply of the color to move
+    ///
+    /// This stops at the first reply after which the action is legal. To check many actions
+    /// against the same position, generate the [`premoves`] once instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// let mut g = Game::startpos();
+    /// let e4 = Action::from_san("e4", &g).unwrap();
+    /// let e5 = Action::from_san("e5", &Game::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap()).unwrap();
+    /// assert!(g.is_premove_plausible(&e5));
+    /// g.execute_action(&e4);
+    /// // white may premove e4 before black replied, but not e5
+    /// assert!(!g.is_premove_plausible(&e5));
+    /// ```
+    ///
+    /// [`premoves`]: #method.premoves
+    pub fn is_premove_plausible(&self, action: &Action) -> bool {
+        let premover = self.color_to_move.get_opponent_color();
+        let own = match premover {
+            Color::White => self.board.whites,
+            Color::Black => !self.board.whites,
+        };
+        // the moved piece can not appear from nowhere, it can only be captured by the reply
+        if self.board.get_piecetype_on(action.get_from_index()) != Some(action.get_piecetype())
+            || own >> action.get_from_index() & 1 == 0
+        {
+            return false;
+        }
+
+        let mut game = self.clone();
+        for reply in movegen::legal_moves(&game) {
+            let token = game.execute_action(&reply);
+            let plausible = movegen::legal_moves(&game).contains(action);
+            game.undo_action(&reply, token);
+            if plausible {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bitboard;
+    use crate::game_representation::PieceType;
+    use crate::move_generation::ActionType;
+
+    fn action(from: &str, to: &str, piece: PieceType, actiontype: ActionType) -> Action {
+        Action::new(
+            bitboard::field_repr_to_coords(from).unwrap(),
+            bitboard::field_repr_to_coords(to).unwrap(),
+            piece,
+            actiontype,
+        )
+    }
+
+    #[test]
+    fn premove_capturing_a_piece_that_may_arrive() {
+        // black to move, white wants to take whatever lands on d5
+        let g = Game::from_fen("4k3/2n5/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+        let exd5 = action(
+            "e4",
+            "d5",
+            PieceType::Pawn,
+            ActionType::Capture(PieceType::Knight),
+        );
+        assert!(g.is_premove_plausible(&exd5));
+        let exd5 = action(
+            "e4",
+            "d5",
+            PieceType::Pawn,
+            ActionType::Capture(PieceType::Queen),
+        );
+        assert!(!g.is_premove_plausible(&exd5));
+    }
+
+    #[test]
+    fn premove_of_a_pinned_piece() {
+        // the rook can unpin the knight by leaving the file
+        let g = Game::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 b - - 0 1").unwrap();
+        let nc3 = action("e2", "c3", PieceType::Knight, ActionType::Quiet);
+        assert!(g.is_premove_plausible(&nc3));
+        // here it can not, every reply keeps the pin or captures the knight
+        let g = Game::from_fen("k2rrr2/8/8/8/8/8/4N3/4K3 b - - 0 1").unwrap();
+        assert!(!g.is_premove_plausible(&nc3));
+    }
+
+    #[test]
+    fn premove_of_the_king() {
+        // the blocked pawn keeps attacking d2 whatever black plays
+        let g = Game::from_fen("6k1/8/8/8/8/4p3/4N3/4K3 b - - 0 1").unwrap();
+        let kd2 = action("e1", "d2", PieceType::King, ActionType::Quiet);
+        assert!(!g.is_premove_plausible(&kd2));
+        let kd1 = action("e1", "d1", PieceType::King, ActionType::Quiet);
+        assert!(g.is_premove_plausible(&kd1));
+        // the own pieces can not be moved
+        let g = Game::from_fen("6k1/8/8/8/8/4p3/4N3/4K3 w - - 0 1").unwrap();
+        assert!(!g.is_premove_plausible(&kd1));
+    }
+
+    #[test]
+    fn premoves_are_the_union_of_the_replies() {
+        // only a reply of the knight to d5 allows the capture
+        let g = Game::from_fen("4k3/2n5/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+        let premoves = g.premoves();
+        let mut uci: Vec<String> = premoves.actions().iter().map(Action::to_uci).collect();
+        uci.sort();
+        assert_eq!(
+            uci,
+            ["e1d1", "e1d2", "e1e2", "e1f1", "e1f2", "e4d5", "e4e5"]
+        );
+        let exd5 = action(
+            "e4",
+            "d5",
+            PieceType::Pawn,
+            ActionType::Capture(PieceType::Knight),
+        );
+        assert!(premoves.contains(&exd5));
+        for (from, to, piece, actiontype) in [
+            ("e4", "d5", PieceType::Pawn, ActionType::Quiet),
+            (
+                "e4",
+                "f5",
+                PieceType::Pawn,
+                ActionType::Capture(PieceType::Knight),
+            ),
+            ("e4", "e6", PieceType::Pawn, ActionType::Quiet),
+            ("c7", "d5", PieceType::Knight, ActionType::Quiet),
+        ] {
+            assert!(!premoves.contains(&action(from, to, piece, actiontype)));
+        }
+    }
+}
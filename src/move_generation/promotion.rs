@@ -0,0 +1,116 @@
+use crate::core::ParserError;
+use crate::game_representation::{Game, PieceType};
+use crate::move_generation::Action;
+use alloc::vec::Vec;
+
+/// The pieces a pawn can promote to, in the order GUIs usually offer them and move generation
+/// generates them
+pub const PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+impl Action {
+    /// Returns the legal action of a piece dragged from one field to another, promoting a pawn
+    /// reaching the last rank to `default`
+    ///
+    /// Online GUIs usually promote to a queen right away and only ask for the piece if the
+    /// player opted out of auto-queening. Passing [`PieceType::Queen`] as `default` gives that
+    /// behaviour, [`promotion_choices`] returns all actions to offer otherwise. For every other
+    /// drag this is the same as [`from_fields_checked`].
+    ///
+    /// # Errors
+    /// * `default` is not a piece a pawn can promote to
+    /// * Any error of [`from_fields_checked`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Game, PieceType};
+    /// # use rust_chess::move_generation::Action;
+    /// let g = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    /// let a7 = 8;
+    /// let a8 = 0;
+    /// let action = Action::from_fields_with_default(a7, a8, PieceType::Queen, &g).unwrap();
+    /// assert_eq!(action.to_uci(), "a7a8q");
+    /// let action = Action::from_fields_with_default(a7, a8, PieceType::Knight, &g).unwrap();
+    /// assert_eq!(action.to_uci(), "a7a8n");
+    /// ```
+    ///
+    /// [`PieceType::Queen`]: ../game_representation/enum.PieceType.html#variant.Queen
+    /// [`promotion_choices`]: #method.promotion_choices
+    /// [`from_fields_checked`]: #method.from_fields_checked
+    pub fn from_fields_with_default(
+        from: u8,
+        to: u8,
+        default: PieceType,
+        state: &Game,
+    ) -> Result<Action, ParserError> {
+        if !PROMOTION_PIECES.contains(&default) {
+            return Err(ParserError::InvalidParameter(
+                "Pawns can not promote to the default piece",
+            ));
+        }
+        Action::from_fields_checked(from, to, Some(default), state)
+    }
+
+    /// Returns all legal promotions of a pawn dragged from one field to another
+    ///
+    /// The actions are in the order of [`PROMOTION_PIECES`]. The result is empty if the drag
+    /// is legal but does not promote, so GUIs can use it to decide whether to show a
+    /// promotion dialog.
+    ///
+    /// # Errors
+    /// * Any error of [`from_fields_checked`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// let g = Game::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    /// let choices = Action::promotion_choices(8, 1, &g).unwrap();
+    /// let uci: Vec<String> = choices.iter().map(Action::to_uci).collect();
+    /// assert_eq!(uci, ["a7b8q", "a7b8r", "a7b8b", "a7b8n"]);
+    /// assert!(Action::promotion_choices(60, 61, &g).unwrap().is_empty());
+    /// ```
+    ///
+    /// [`PROMOTION_PIECES`]: constant.PROMOTION_PIECES.html
+    /// [`from_fields_checked`]: #method.from_fields_checked
+    pub fn promotion_choices(from: u8, to: u8, state: &Game) -> Result<Vec<Action>, ParserError> {
+        let action = Action::from_fields_checked(from, to, Some(PieceType::Queen), state)?;
+        if !action.is_promotion() {
+            return Ok(Vec::new());
+        }
+        PROMOTION_PIECES
+            .iter()
+            .map(|&piece| Action::from_fields_checked(from, to, Some(piece), state))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_and_choices() {
+        let g = Game::from_fen("4k3/8/8/8/8/8/p7/1N2K3 b - - 0 1").unwrap();
+        let (a2, a1, b1) = (48, 56, 57);
+        for &piece in PROMOTION_PIECES.iter() {
+            let action = Action::from_fields_with_default(a2, a1, piece, &g).unwrap();
+            assert_eq!(action.get_promotion_piece(), Some(piece));
+            let capture = Action::from_fields_with_default(a2, b1, piece, &g).unwrap();
+            assert_eq!(capture.get_capture_piece(), Some(PieceType::Knight));
+        }
+        assert!(Action::from_fields_with_default(a2, a1, PieceType::King, &g).is_err());
+        assert!(Action::from_fields_with_default(a2, a1, PieceType::Pawn, &g).is_err());
+        // the default is ignored for actions that do not promote
+        let king = Action::from_fields_with_default(4, 5, PieceType::Knight, &g).unwrap();
+        assert_eq!(king.get_promotion_piece(), None);
+
+        assert_eq!(Action::promotion_choices(a2, b1, &g).unwrap().len(), 4);
+        assert!(Action::promotion_choices(a2, 49, &g).is_err());
+        assert!(Action::promotion_choices(a2, 40, &g).is_err());
+    }
+}
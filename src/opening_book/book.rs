@@ -0,0 +1,254 @@
+use super::PolyglotKeys;
+use crate::core::ParserError;
+use crate::game_representation::Game;
+use crate::move_generation::Action;
+use std::convert::TryInto;
+
+/// The size of an entry of a Polyglot book in bytes
+const ENTRY_SIZE: usize = 16;
+
+/// A single entry of a Polyglot book, a move of the position with the given key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookEntry {
+    /// The Polyglot hash of the position, see [`PolyglotKeys`]
+    ///
+    /// [`PolyglotKeys`]: struct.PolyglotKeys.html
+    pub key: u64,
+    /// The move in the Polyglot encoding: the destination file and rank in the lowest six bits,
+    /// then the source file and rank and the promotion piece from 1 (knight) to 4 (queen)
+    pub raw_move: u16,
+    /// How often the move should be played relative to the other moves of the position
+    pub weight: u16,
+    /// Data used by learning engines, not interpreted
+    pub learn: u32,
+}
+
+impl BookEntry {
+    /// Returns the entry stored in 16 big endian bytes
+    pub fn from_bytes(bytes: [u8; ENTRY_SIZE]) -> BookEntry {
+        let field = |start: usize, end: usize| {
+            bytes[start..end]
+                .iter()
+                .fold(0u64, |value, byte| value << 8 | u64::from(*byte))
+        };
+        BookEntry {
+            key: field(0, 8),
+            raw_move: field(8, 10) as u16,
+            weight: field(10, 12) as u16,
+            learn: field(12, 16) as u32,
+        }
+    }
+
+    /// Returns the 16 big endian bytes storing the entry
+    pub fn to_bytes(&self) -> [u8; ENTRY_SIZE] {
+        let mut bytes = [0; ENTRY_SIZE];
+        bytes[..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.raw_move.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes[12..].copy_from_slice(&self.learn.to_be_bytes());
+        bytes
+    }
+
+    /// Returns the move of the entry in the position it belongs to
+    ///
+    /// Polyglot writes castling as the king capturing its own rook, like `e1h1`, which is
+    /// understood by [`Action::from_uci`] as well.
+    ///
+    /// # Errors
+    /// * The move is not legal in the position
+    ///
+    /// [`Action::from_uci`]: ../move_generation/struct.Action.html#method.from_uci
+    pub fn action(&self, game: &Game) -> Result<Action, ParserError> {
+        let field = |bits: u16| {
+            let file = (b'a' + (bits & 0b111) as u8) as char;
+            let rank = (b'1' + (bits >> 3 & 0b111) as u8) as char;
+            format!("{}{}", file, rank)
+        };
+        let mut uci = field(self.raw_move >> 6) + &field(self.raw_move);
+        match self.raw_move >> 12 & 0b111 {
+            0 => {}
+            promotion @ 1..=4 => uci.push(b"nbrq"[promotion as usize - 1] as char),
+            _ => return Err(ParserError::InvalidParameter("Promotion piece is invalid")),
+        }
+        Action::from_uci(&uci, game)
+    }
+}
+
+/// An opening book in the Polyglot format
+///
+/// A Polyglot book is a sequence of 16 byte entries sorted by the hash of their position, so
+/// all moves of a position are found by a binary search. Positions are hashed with the
+/// Polyglot key convention implemented by [`PolyglotKeys`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::opening_book::{BookEntry, PolyglotBook, PolyglotKeys};
+/// let keys = PolyglotKeys::standard();
+/// let game = Game::startpos();
+/// let key = keys.key(&game);
+/// // e2e4 and d2d4, with e2e4 played twice as often
+/// let bytes: Vec<u8> = [(0x031c, 2), (0x02db, 1)]
+///     .iter()
+///     .map(|&(raw_move, weight)| BookEntry { key, raw_move, weight, learn: 0 })
+///     .flat_map(|entry| entry.to_bytes().to_vec())
+///     .collect();
+/// let book = PolyglotBook::from_bytes(&bytes).unwrap();
+/// let moves = book.moves(&game, &keys);
+/// assert_eq!(moves.len(), 2);
+/// assert_eq!((moves[0].0.to_uci(), moves[0].1), (String::from("e2e4"), 2));
+/// assert_eq!(book.best_move(&game, &keys).unwrap().to_uci(), "e2e4");
+/// assert_eq!(book.weighted_move(&game, &keys, 2).unwrap().to_uci(), "d2d4");
+/// ```
+///
+/// [`PolyglotKeys`]: struct.PolyglotKeys.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PolyglotBook {
+    entries: Vec<BookEntry>,
+}
+
+impl PolyglotBook {
+    /// Returns the book stored in the bytes of a `.bin` file
+    ///
+    /// Entries that are not sorted by their key are sorted, keeping the order of the moves of
+    /// every position.
+    ///
+    /// # Errors
+    /// * The length of the data is not a multiple of 16 bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<PolyglotBook, ParserError> {
+        if bytes.len() % ENTRY_SIZE != 0 {
+            return Err(ParserError::WrongParameterNumber);
+        }
+        let mut entries: Vec<BookEntry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| BookEntry::from_bytes(chunk.try_into().expect("chunks have 16 bytes")))
+            .collect();
+        entries.sort_by_key(|entry| entry.key);
+        Ok(PolyglotBook { entries })
+    }
+
+    /// Returns the bytes of the book in the format of a `.bin` file
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.entries.iter().flat_map(BookEntry::to_bytes).collect()
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the book has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns all entries of the position with the given Polyglot hash
+    pub fn entries(&self, key: u64) -> &[BookEntry] {
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = self.entries.partition_point(|entry| entry.key <= key);
+        &self.entries[start..end]
+    }
+
+    /// Returns the legal book moves of the position with their weights, the heaviest first
+    ///
+    /// Entries with moves that are not legal in the position, for example because of a hash
+    /// collision, are skipped.
+    pub fn moves(&self, game: &Game, keys: &PolyglotKeys) -> Vec<(Action, u16)> {
+        let mut moves: Vec<(Action, u16)> = self
+            .entries(keys.key(game))
+            .iter()
+            .filter_map(|entry| Some((entry.action(game).ok()?, entry.weight)))
+            .collect();
+        moves.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+        moves
+    }
+
+    /// Returns the book move with the highest weight, `None` if the position is not in the book
+    pub fn best_move(&self, game: &Game, keys: &PolyglotKeys) -> Option<Action> {
+        self.moves(game, keys).first().map(|(action, _)| *action)
+    }
+
+    /// Returns a book move picked by its weight using the given random number
+    ///
+    /// The number is reduced modulo the sum of all weights, so every move is picked with a
+    /// probability proportional to its weight if the number is uniformly distributed. Returns
+    /// `None` if the position is not in the book or all its moves have a weight of 0.
+    pub fn weighted_move(&self, game: &Game, keys: &PolyglotKeys, random: u64) -> Option<Action> {
+        let moves = self.moves(game, keys);
+        let total: u64 = moves.iter().map(|(_, weight)| u64::from(*weight)).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = random % total;
+        for (action, weight) in moves {
+            if roll < u64::from(weight) {
+                return Some(action);
+            }
+            roll -= u64::from(weight);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opening_book::RANDOM_COUNT;
+
+    fn entry(key: u64, uci: &str, weight: u16) -> BookEntry {
+        let bytes = uci.as_bytes();
+        let field = |offset: usize| {
+            u16::from(bytes[offset] - b'a') | u16::from(bytes[offset + 1] - b'1') << 3
+        };
+        let promotion = bytes.get(4).map_or(0, |piece| {
+            b"nbrq".iter().position(|p| p == piece).unwrap() as u16 + 1
+        });
+        BookEntry {
+            key,
+            raw_move: promotion << 12 | field(0) << 6 | field(2),
+            weight,
+            learn: 0,
+        }
+    }
+
+    #[test]
+    fn probing_positions() {
+        let randoms: Vec<u64> = (0..RANDOM_COUNT as u64)
+            .map(|i| (i + 1).wrapping_mul(0x2545_f491_4f6c_dd1d))
+            .collect();
+        let keys = PolyglotKeys::from_randoms(&randoms).unwrap();
+        let castle = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let promote = Game::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let entries = [
+            entry(keys.key(&promote), "b7b8n", 1),
+            entry(keys.key(&castle), "e1h1", 5),
+            entry(keys.key(&castle), "e1e2", 0),
+            // not legal, as if the hash collided
+            entry(keys.key(&castle), "a1a2", 9),
+            entry(keys.key(&promote), "b7b8q", 7),
+        ];
+        let bytes: Vec<u8> = entries.iter().flat_map(BookEntry::to_bytes).collect();
+        let book = PolyglotBook::from_bytes(&bytes).unwrap();
+        assert_eq!(book.len(), 5);
+        assert_eq!(PolyglotBook::from_bytes(&book.to_bytes()), Ok(book.clone()));
+        assert!(PolyglotBook::from_bytes(&bytes[1..]).is_err());
+
+        let moves = book.moves(&castle, &keys);
+        assert_eq!(moves.len(), 2);
+        assert!(moves[0].0.is_castling());
+        assert_eq!(book.weighted_move(&castle, &keys, 4), Some(moves[0].0));
+
+        let uci: Vec<String> = book
+            .moves(&promote, &keys)
+            .iter()
+            .map(|(action, _)| action.to_uci())
+            .collect();
+        assert_eq!(uci, vec!["b7b8q", "b7b8n"]);
+        assert_eq!(
+            book.weighted_move(&promote, &keys, 7).unwrap().to_uci(),
+            "b7b8n"
+        );
+        assert_eq!(book.best_move(&Game::startpos(), &keys), None);
+        assert_eq!(book.entries(keys.key(&castle)).len(), 3);
+    }
+}
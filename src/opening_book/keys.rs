@@ -0,0 +1,221 @@
+use super::random64::RANDOM64;
+use crate::core::{ParserError, Square};
+use crate::game_representation::{Castling, Color, Game, PieceType};
+use std::convert::TryInto;
+
+/// The number of random numbers of the Polyglot hash: 768 for pieces, 4 for castling rights,
+/// 8 for en passant files and 1 for the side to move
+pub const RANDOM_COUNT: usize = 781;
+
+const CASTLING_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+/// Computes the hash Polyglot books are keyed by
+///
+/// The hash of the crate, see [`Game::get_hash`], uses its own random numbers, so book lookups
+/// need this separate hash. It follows the Polyglot key convention: the xor of the random
+/// numbers of every piece, every castling right, the en passant file if a pawn of the side to
+/// move can capture en passant, and the turn if white is to move.
+///
+/// Books created by Polyglot and compatible tools use the `Random64` array of the Polyglot
+/// sources, which [`standard`] returns the keys of. Books keyed by other random numbers can be
+/// read with keys from [`from_randoms`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::opening_book::{PolyglotKeys, RANDOM_COUNT};
+/// assert_eq!(PolyglotKeys::standard().key(&Game::startpos()), 0x463b_9618_1691_fc9c);
+/// let randoms: Vec<u64> = (1..=RANDOM_COUNT as u64)
+///     .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+///     .collect();
+/// let keys = PolyglotKeys::from_randoms(&randoms).unwrap();
+/// assert_ne!(keys.key(&Game::startpos()), 0);
+/// assert!(PolyglotKeys::from_randoms(&randoms[1..]).is_err());
+/// ```
+///
+/// [`Game::get_hash`]: ../game_representation/struct.Game.html#method.get_hash
+/// [`standard`]: #method.standard
+/// [`from_randoms`]: #method.from_randoms
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolyglotKeys {
+    randoms: Vec<u64>,
+}
+
+impl PolyglotKeys {
+    /// Returns the hash using the `Random64` array of Polyglot, used by standard opening books
+    pub fn standard() -> PolyglotKeys {
+        PolyglotKeys {
+            randoms: RANDOM64.to_vec(),
+        }
+    }
+
+    /// Returns the hash using the given random numbers
+    ///
+    /// # Errors
+    /// * There are not exactly [`RANDOM_COUNT`] numbers
+    ///
+    /// [`RANDOM_COUNT`]: constant.RANDOM_COUNT.html
+    pub fn from_randoms(randoms: &[u64]) -> Result<PolyglotKeys, ParserError> {
+        if randoms.len() != RANDOM_COUNT {
+            return Err(ParserError::WrongParameterNumber);
+        }
+        Ok(PolyglotKeys {
+            randoms: randoms.to_vec(),
+        })
+    }
+
+    /// Returns the hash using random numbers stored as big endian 64 bit integers
+    ///
+    /// # Errors
+    /// * The data does not hold exactly [`RANDOM_COUNT`] numbers
+    ///
+    /// [`RANDOM_COUNT`]: constant.RANDOM_COUNT.html
+    pub fn from_bytes(bytes: &[u8]) -> Result<PolyglotKeys, ParserError> {
+        if bytes.len() != RANDOM_COUNT * 8 {
+            return Err(ParserError::WrongParameterNumber);
+        }
+        let randoms: Vec<u64> = bytes
+            .chunks_exact(8)
+            .map(|chunk| u64::from_be_bytes(chunk.try_into().expect("chunks have 8 bytes")))
+            .collect();
+        PolyglotKeys::from_randoms(&randoms)
+    }
+
+    /// Returns the Polyglot hash of the position
+    pub fn key(&self, game: &Game) -> u64 {
+        let mut key = 0;
+        for (square, piece, color) in game.board.iter() {
+            key ^= self.randoms[piece_offset(piece, color, square)];
+        }
+        let castling = game.get_castling();
+        for (offset, right) in [
+            Castling::get_white_kingside(),
+            Castling::get_white_queenside(),
+            Castling::get_black_kingside(),
+            Castling::get_black_queenside(),
+        ]
+        .iter()
+        .enumerate()
+        {
+            if castling.is_available(*right) {
+                key ^= self.randoms[CASTLING_OFFSET + offset];
+            }
+        }
+        if let Some(square) = game.capturable_en_passant() {
+            key ^= self.randoms[EN_PASSANT_OFFSET + square.coords().0 as usize];
+        }
+        if game.color_to_move == Color::White {
+            key ^= self.randoms[TURN_OFFSET];
+        }
+        key
+    }
+}
+
+/// Returns the index of the random number of a piece on a field
+///
+/// Polyglot orders the pieces as black pawn, white pawn, black knight and so on up to the
+/// white king, and counts the fields from a1 to h8.
+fn piece_offset(piece: PieceType, color: Color, square: Square) -> usize {
+    let kind = match piece {
+        PieceType::Pawn => 0,
+        PieceType::Knight => 1,
+        PieceType::Bishop => 2,
+        PieceType::Rook => 3,
+        PieceType::Queen => 4,
+        PieceType::King => 5,
+    } * 2
+        + (color == Color::White) as usize;
+    let (file, rank_from_top) = square.coords();
+    64 * kind + 8 * (7 - rank_from_top as usize) + file as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::move_generation::Action;
+
+    fn test_keys() -> PolyglotKeys {
+        let randoms: Vec<u64> = (1..=RANDOM_COUNT as u64)
+            .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(17))
+            .collect();
+        PolyglotKeys::from_randoms(&randoms).unwrap()
+    }
+
+    #[test]
+    fn key_convention() {
+        let keys = test_keys();
+        let square = |name: &str| name.parse::<Square>().unwrap();
+        assert_eq!(piece_offset(PieceType::Pawn, Color::Black, square("a1")), 0);
+        assert_eq!(
+            piece_offset(PieceType::Pawn, Color::White, square("h8")),
+            127
+        );
+        assert_eq!(
+            piece_offset(PieceType::King, Color::White, square("e1")),
+            708
+        );
+
+        // the en passant file only counts if the capture is possible
+        let without = Game::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+        let blocked = Game::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+        let capturable = Game::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+        let no_capture = Game::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(keys.key(&without), keys.key(&blocked));
+        assert_eq!(
+            keys.key(&capturable) ^ keys.key(&no_capture),
+            keys.randoms[EN_PASSANT_OFFSET + 4]
+        );
+
+        let white = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let black = Game::from_fen("4k3/8/8/8/8/8/8/4K2R b - - 0 1").unwrap();
+        assert_eq!(
+            keys.key(&white) ^ keys.key(&black),
+            keys.randoms[CASTLING_OFFSET] ^ keys.randoms[TURN_OFFSET]
+        );
+
+        let bytes: Vec<u8> = keys.randoms.iter().flat_map(|r| r.to_be_bytes()).collect();
+        assert_eq!(PolyglotKeys::from_bytes(&bytes), Ok(keys));
+        assert!(PolyglotKeys::from_bytes(&bytes[8..]).is_err());
+    }
+
+    #[test]
+    fn standard_keys() {
+        // the keys published with the Polyglot book format
+        let keys = PolyglotKeys::standard();
+        let mut game = Game::startpos();
+        assert_eq!(keys.key(&game), 0x463b_9618_1691_fc9c);
+        for (uci, key) in [
+            ("e2e4", 0x823c_9b50_fd11_4196),
+            ("d7d5", 0x0756_b944_61c5_0fb0),
+            ("e4e5", 0x662f_afb9_65db_29d4),
+            // the pawn on e5 can capture en passant on f6
+            ("f7f5", 0x22a4_8b5a_8e47_ff78),
+            ("e1e2", 0x652a_607c_a3f2_42c1),
+            ("e8f7", 0x00fd_d303_c946_bdd9),
+        ] {
+            let action = Action::from_uci(uci, &game).unwrap();
+            game.execute_action(&action);
+            assert_eq!(keys.key(&game), key, "after {}", uci);
+        }
+
+        let mut game = Game::startpos();
+        for (uci, key) in [
+            ("a2a4", None),
+            ("b7b5", None),
+            ("h2h4", None),
+            ("b5b4", None),
+            // the pawn on b4 can capture en passant on c3
+            ("c2c4", Some(0x3c81_23ea_7b06_7637)),
+            ("b4c3", None),
+            ("a1a3", Some(0x5c3f_9b82_9b27_9560)),
+        ] {
+            let action = Action::from_uci(uci, &game).unwrap();
+            game.execute_action(&action);
+            if let Some(key) = key {
+                assert_eq!(keys.key(&game), key, "after {}", uci);
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! Opening books in the Polyglot format, probed by the Polyglot hash of a position
+
+mod book;
+mod keys;
+mod random64;
+
+pub use book::{BookEntry, PolyglotBook};
+pub use keys::{PolyglotKeys, RANDOM_COUNT};
@@ -0,0 +1,113 @@
+use super::lexer::BYTE_ORDER_MARK;
+use super::parser::{parse_pgn, PgnGame};
+use crate::core::ParserError;
+use std::borrow::Cow;
+use std::fmt;
+
+/// A problem of PGN data that was worked around while reading it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PgnWarning {
+    /// The line with the given number, counted from 1, is not valid UTF-8 and was read as
+    /// Latin-1 instead
+    Latin1Line(usize),
+}
+
+impl fmt::Display for PgnWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgnWarning::Latin1Line(line) => {
+                write!(f, "line {} is not valid UTF-8, read as Latin-1", line)
+            }
+        }
+    }
+}
+
+/// Turns the bytes of a PGN file into text
+///
+/// A leading UTF-8 byte order mark is removed. Lines that are not valid UTF-8 are decoded as
+/// Latin-1, which many older archives use for the names of players and places, and reported
+/// by a warning each. As Latin-1 maps every byte to a character, decoding never fails, but the
+/// result may be wrong for files in other legacy encodings. Valid UTF-8 without a byte order
+/// mark is borrowed.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::pgn::{decode_pgn, PgnWarning};
+/// let (text, warnings) = decode_pgn(b"[White \"M\xfcller\"]\r\n[Black \"K\xc3\xb6hler\"]\r\n");
+/// assert_eq!(text, "[White \"Müller\"]\r\n[Black \"Köhler\"]\r\n");
+/// assert_eq!(warnings, vec![PgnWarning::Latin1Line(1)]);
+/// ```
+pub fn decode_pgn(bytes: &[u8]) -> (Cow<'_, str>, Vec<PgnWarning>) {
+    let mut mark = [0; 3];
+    let mark = BYTE_ORDER_MARK.encode_utf8(&mut mark).as_bytes();
+    let bytes = bytes.strip_prefix(mark).unwrap_or(bytes);
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return (Cow::Borrowed(text), Vec::new());
+    }
+    let mut text = String::with_capacity(bytes.len());
+    let mut warnings = Vec::new();
+    for (index, line) in bytes.split_inclusive(|&b| b == b'\n').enumerate() {
+        match std::str::from_utf8(line) {
+            Ok(line) => text.push_str(line),
+            Err(_) => {
+                text.extend(line.iter().map(|&b| char::from(b)));
+                warnings.push(PgnWarning::Latin1Line(index + 1));
+            }
+        }
+    }
+    (Cow::Owned(text), warnings)
+}
+
+/// Parses all games of the bytes of a PGN file
+///
+/// The bytes are decoded by [`decode_pgn`] and then parsed by [`parse_pgn`]. The warnings of
+/// the decoding are returned together with the games.
+///
+/// # Errors
+/// * All errors of [`parse_pgn`]
+///
+/// # Examples
+/// ```
+/// # use rust_chess::pgn::parse_pgn_bytes;
+/// let (games, warnings) = parse_pgn_bytes(b"\xef\xbb\xbf[Site \"Z\xfcrich\"]\r\n\r\n1. e4 *\r\n")
+///     .unwrap();
+/// assert_eq!(games[0].tag("Site"), Some("Zürich"));
+/// assert_eq!(warnings.len(), 1);
+/// ```
+///
+/// [`decode_pgn`]: fn.decode_pgn.html
+/// [`parse_pgn`]: fn.parse_pgn.html
+pub fn parse_pgn_bytes(bytes: &[u8]) -> Result<(Vec<PgnGame>, Vec<PgnWarning>), ParserError> {
+    let (text, warnings) = decode_pgn(bytes);
+    Ok((parse_pgn(&text)?, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_encodings() {
+        let mut bytes = "\u{feff}[Event \"Café\"]\r\n".as_bytes().to_vec();
+        bytes.extend_from_slice(b"[White \"Gr\xfcnfeld\"]\r\n\r\n1. e4 {\xe9tude} e5 1-0\r\n");
+        let (text, warnings) = decode_pgn(&bytes);
+        assert!(text.starts_with("[Event \"Café\"]\r\n[White \"Grünfeld\"]"));
+        assert_eq!(
+            warnings,
+            vec![PgnWarning::Latin1Line(2), PgnWarning::Latin1Line(4)]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "line 2 is not valid UTF-8, read as Latin-1"
+        );
+
+        let (games, _) = parse_pgn_bytes(&bytes).unwrap();
+        assert_eq!(games[0].tag("White"), Some("Grünfeld"));
+        assert_eq!(games[0].moves[0].comments, vec![String::from("étude")]);
+
+        assert!(matches!(
+            decode_pgn(b"1. e4 *"),
+            (Cow::Borrowed("1. e4 *"), _)
+        ));
+    }
+}
@@ -0,0 +1,295 @@
+use crate::core::ParserError;
+
+/// A single token of PGN text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    /// `[`, the start of a tag pair
+    TagStart,
+    /// `]`, the end of a tag pair
+    TagEnd,
+    /// A quoted string with all escapes resolved
+    Str(String),
+    /// A tag name or a move in SAN, including check and mate suffixes, or a null move
+    Symbol(String),
+    /// A move number, the periods following it are dropped
+    MoveNumber(u32),
+    /// A numeric annotation glyph, either written as `$n` or as a suffix like `!?`
+    Nag(u8),
+    /// The text of a `{}` or `;` comment, trimmed
+    Comment(String),
+    /// `(`, the start of a variation
+    VariationStart,
+    /// `)`, the end of a variation
+    VariationEnd,
+    /// A game termination marker: `1-0`, `0-1`, `1/2-1/2` or `*`
+    Result(String),
+}
+
+/// The byte order mark some editors write at the start of UTF-8 files
+pub(crate) const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Splits PGN text into tokens
+///
+/// Lines starting with `%` are escaped and skipped entirely. Windows line endings and a byte
+/// order mark are treated as whitespace, line breaks inside comments are returned as `\n`.
+///
+/// # Errors
+/// * A string or a brace comment is not terminated
+/// * A NAG is not a number between 0 and 255
+/// * A character is not valid in PGN
+pub fn tokenize(text: &str) -> Result<Vec<Token>, ParserError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let line_start = i == 0 || chars[i - 1] == '\n' || (i == 1 && chars[0] == BYTE_ORDER_MARK);
+        match c {
+            '%' if line_start => {
+                i = skip_line(&chars, i);
+            }
+            _ if c.is_whitespace() || c == BYTE_ORDER_MARK => i += 1,
+            '[' => {
+                tokens.push(Token::TagStart);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::TagEnd);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::VariationStart);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::VariationEnd);
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Result(String::from("*")));
+                i += 1;
+            }
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some('\\') => {
+                            if let Some(escaped) = chars.get(i + 1) {
+                                value.push(*escaped);
+                            }
+                            i += 2;
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            value.push(*c);
+                            i += 1;
+                        }
+                        None => {
+                            return Err(ParserError::InvalidParameter("String is not terminated"))
+                        }
+                    }
+                }
+                tokens.push(Token::Str(value));
+                i += 1;
+            }
+            '{' => {
+                let end = match chars[i..].iter().position(|c| *c == '}') {
+                    Some(end) => i + end,
+                    None => return Err(ParserError::InvalidParameter("Comment is not terminated")),
+                };
+                let comment: String = chars[i + 1..end].iter().collect();
+                tokens.push(Token::Comment(comment.trim().replace("\r\n", "\n")));
+                i = end + 1;
+            }
+            ';' => {
+                let end = skip_line(&chars, i);
+                let comment: String = chars[i + 1..end].iter().collect();
+                tokens.push(Token::Comment(comment.trim().to_string()));
+                i = end;
+            }
+            '$' => {
+                let end = scan(&chars, i + 1, |c| c.is_ascii_digit());
+                let nag: String = chars[i + 1..end].iter().collect();
+                match nag.parse() {
+                    Ok(nag) => tokens.push(Token::Nag(nag)),
+                    Err(_) => return Err(ParserError::InvalidParameter("NAG is not a number")),
+                }
+                i = end;
+            }
+            '!' | '?' => {
+                let end = scan(&chars, i, |c| c == '!' || c == '?');
+                let glyph: String = chars[i..end].iter().collect();
+                match suffix_nag(&glyph) {
+                    Some(nag) => tokens.push(Token::Nag(nag)),
+                    None => {
+                        return Err(ParserError::InvalidParameter(
+                            "Move suffix annotation is invalid",
+                        ))
+                    }
+                }
+                i = end;
+            }
+            _ if c.is_ascii_digit() => {
+                let end = scan(&chars, i, |c| {
+                    c.is_ascii_alphanumeric() || c == '-' || c == '/'
+                });
+                let word: String = chars[i..end].iter().collect();
+                match word.as_str() {
+                    "1-0" | "0-1" | "1/2-1/2" => tokens.push(Token::Result(word)),
+                    _ if word.chars().all(|c| c.is_ascii_digit()) => {
+                        let number = word
+                            .parse()
+                            .map_err(|_| ParserError::InvalidParameter("Move number is too big"))?;
+                        tokens.push(Token::MoveNumber(number));
+                        i = scan(&chars, end, |c| c == '.');
+                        continue;
+                    }
+                    _ => {
+                        // castling written with zeros
+                        let end = scan_symbol(&chars, i);
+                        tokens.push(Token::Symbol(chars[i..end].iter().collect()));
+                        i = end;
+                        continue;
+                    }
+                }
+                i = end;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                // null move of analysis PGNs
+                tokens.push(Token::Symbol(String::from("--")));
+                i += 2;
+            }
+            _ if c.is_ascii_alphabetic() => {
+                let end = scan_symbol(&chars, i);
+                tokens.push(Token::Symbol(chars[i..end].iter().collect()));
+                i = end;
+            }
+            _ => return Err(ParserError::InvalidParameter("Invalid character in PGN")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Returns the NAG of a move suffix annotation like `!?`
+pub fn suffix_nag(glyph: &str) -> Option<u8> {
+    match glyph {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    }
+}
+
+/// Returns the index of the first character after the symbol starting at `start`
+///
+/// A symbol ends after a check or mate suffix, so results glued to a move are split off.
+fn scan_symbol(chars: &[char], start: usize) -> usize {
+    let end = scan(chars, start, |c| {
+        c.is_ascii_alphanumeric() || "_=:-/".contains(c)
+    });
+    match chars.get(end) {
+        Some('+') | Some('#') => end + 1,
+        _ => end,
+    }
+}
+
+/// Returns the index of the first character from `start` on not matching the predicate
+fn scan(chars: &[char], start: usize, predicate: impl Fn(char) -> bool) -> usize {
+    let mut end = start;
+    while end < chars.len() && predicate(chars[end]) {
+        end += 1;
+    }
+    end
+}
+
+/// Returns the index of the next line break after `start`, or the end of the text
+fn skip_line(chars: &[char], start: usize) -> usize {
+    scan(chars, start, |c| c != '\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_of_movetext() {
+        let tokens =
+            tokenize("[White \"A \\\"B\\\"\"]\n8... d5!? {good} (8... Nf6 $2) Qxf7#1-0").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::TagStart,
+                Token::Symbol(String::from("White")),
+                Token::Str(String::from("A \"B\"")),
+                Token::TagEnd,
+                Token::MoveNumber(8),
+                Token::Symbol(String::from("d5")),
+                Token::Nag(5),
+                Token::Comment(String::from("good")),
+                Token::VariationStart,
+                Token::MoveNumber(8),
+                Token::Symbol(String::from("Nf6")),
+                Token::Nag(2),
+                Token::VariationEnd,
+                Token::Symbol(String::from("Qxf7#")),
+                Token::Result(String::from("1-0")),
+            ]
+        );
+    }
+
+    #[test]
+    fn windows_files() {
+        let tokens =
+            tokenize("\u{feff}%escaped\r\n[A \"b\"]\r\n\r\n1. e4 {two\r\nlines} ; end\r\n*\r\n")
+                .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::TagStart,
+                Token::Symbol(String::from("A")),
+                Token::Str(String::from("b")),
+                Token::TagEnd,
+                Token::MoveNumber(1),
+                Token::Symbol(String::from("e4")),
+                Token::Comment(String::from("two\nlines")),
+                Token::Comment(String::from("end")),
+                Token::Result(String::from("*")),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_and_escapes() {
+        let tokens = tokenize("%ignored line\n1. e4 ; rest of line\n0-0-0 1/2-1/2").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::MoveNumber(1),
+                Token::Symbol(String::from("e4")),
+                Token::Comment(String::from("rest of line")),
+                Token::Symbol(String::from("0-0-0")),
+                Token::Result(String::from("1/2-1/2")),
+            ]
+        );
+        assert!(tokenize("1. e4 {open").is_err());
+        assert!(tokenize("[Event \"open").is_err());
+        assert!(tokenize("1. e4 $300").is_err());
+        assert!(tokenize("1. e4 !!!").is_err());
+        assert!(tokenize("1. e4 -").is_err());
+        assert_eq!(
+            tokenize("(1... -- 2. Z0)").unwrap(),
+            vec![
+                Token::VariationStart,
+                Token::MoveNumber(1),
+                Token::Symbol(String::from("--")),
+                Token::MoveNumber(2),
+                Token::Symbol(String::from("Z0")),
+                Token::VariationEnd,
+            ]
+        );
+    }
+}
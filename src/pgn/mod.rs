@@ -0,0 +1,18 @@
+//! Reading and writing of Portable Game Notation, including comments, annotations and variations
+
+mod encoding;
+mod lexer;
+mod parser;
+mod splitter;
+mod tags;
+mod variant;
+mod writer;
+
+pub use encoding::{decode_pgn, parse_pgn_bytes, PgnWarning};
+pub use lexer::{tokenize, Token};
+pub(crate) use parser::pass;
+pub use parser::{parse_pgn, PgnGame, PgnMove};
+pub use splitter::{split_games, GameChunks};
+pub use tags::{parse_elo, parse_result, PgnDate, PlayerName};
+pub use variant::Variant;
+pub use writer::{GameRecord, DEFAULT_LINE_LENGTH, SEVEN_TAG_ROSTER};
@@ -0,0 +1,354 @@
+use super::lexer::{tokenize, Token};
+use crate::core::ParserError;
+use crate::game_representation::Game;
+use crate::move_generation::Action;
+use crate::record::{MoveMetadata, RecordedGame};
+
+/// A move of the movetext together with its annotations
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnMove {
+    /// The move in SAN as written, including check or mate suffixes
+    pub san: String,
+    /// Numeric annotation glyphs, suffix annotations like `!?` are converted to their NAG
+    pub nags: Vec<u8>,
+    /// Comments written in front of the move, only used for the first move of a line
+    pub leading_comments: Vec<String>,
+    /// Comments written after the move
+    pub comments: Vec<String>,
+    /// Alternatives to this move, each starting in the position this move is played from
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+impl PgnMove {
+    fn new(san: String) -> PgnMove {
+        PgnMove {
+            san,
+            nags: Vec::new(),
+            leading_comments: Vec::new(),
+            comments: Vec::new(),
+            variations: Vec::new(),
+        }
+    }
+
+    /// Returns if the move is a null move, written as `--` or `Z0`
+    ///
+    /// Null moves pass the turn to the opponent without moving a piece. They are used by
+    /// analysis PGNs to show threats inside of variations.
+    pub fn is_null(&self) -> bool {
+        self.san == "--" || self.san == "Z0"
+    }
+
+    /// Returns the action of the move in the given position
+    ///
+    /// # Errors
+    /// * The move is a null move, which has no action
+    /// * The SAN can not be parsed by [`Action::from_san`]
+    ///
+    /// [`Action::from_san`]: ../move_generation/struct.Action.html#method.from_san
+    pub fn to_action(&self, state: &Game) -> Result<Action, ParserError> {
+        if self.is_null() {
+            return Err(ParserError::InvalidParameter("Null move has no action"));
+        }
+        Action::from_san(&self.san, state)
+    }
+}
+
+/// A single game of a PGN file
+///
+/// The movetext is stored as a tree: the main line is a list of moves and every move can hold
+/// variations replacing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PgnGame {
+    /// The tag pairs in the order they were written
+    pub tags: Vec<(String, String)>,
+    /// The main line of the game
+    pub moves: Vec<PgnMove>,
+    /// The game termination marker, if one was written
+    pub result: Option<String>,
+}
+
+impl PgnGame {
+    /// Parses the first game of the PGN text
+    ///
+    /// # Errors
+    /// * The text contains no game
+    /// * Any error of [`parse_pgn`]
+    ///
+    /// [`parse_pgn`]: fn.parse_pgn.html
+    pub fn parse(text: &str) -> Result<PgnGame, ParserError> {
+        let mut games = parse_pgn(text)?;
+        if games.is_empty() {
+            return Err(ParserError::InvalidParameter("PGN contains no game"));
+        }
+        Ok(games.swap_remove(0))
+    }
+
+    /// Returns the value of the first tag pair with the given name
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the position at the end of the main line
+    ///
+    /// Null moves pass the turn and count like a quiet move for the clocks and the history.
+    ///
+    /// # Errors
+    /// * The start position can not be determined, see [`start_position`]
+    /// * Any move of the main line can not be parsed in its position
+    /// * A null move is played while the side to move is in check
+    ///
+    /// [`start_position`]: #method.start_position
+    pub fn to_game(&self) -> Result<Game, ParserError> {
+        let mut game = self.start_position()?;
+        for pgn_move in &self.moves {
+            if pgn_move.is_null() {
+                pass(&mut game)?;
+                continue;
+            }
+            let action = pgn_move.to_action(&game)?;
+            game.execute_action(&action);
+        }
+        Ok(game)
+    }
+
+    /// Returns the main line as a recorded game, dropping variations and annotations
+    ///
+    /// Commands like `[%clk 0:05:00]` in the comments after a move are kept as its metadata.
+    ///
+    /// # Errors
+    /// * The start position can not be determined, see [`start_position`]
+    /// * Any move of the main line can not be parsed in its position, including null moves
+    ///
+    /// [`start_position`]: #method.start_position
+    pub fn to_record(&self) -> Result<RecordedGame, ParserError> {
+        let mut game = self.start_position()?;
+        let mut record = RecordedGame::from_fen(&game.to_fen())?;
+        for pgn_move in &self.moves {
+            let action = pgn_move.to_action(&game)?;
+            game.execute_action(&action);
+            let comments = pgn_move.comments.join(" ");
+            record.push_with_metadata(action, MoveMetadata::from_pgn_comment(&comments));
+        }
+        Ok(record)
+    }
+}
+
+/// Passes the turn of the side to move, which must not be in check
+pub(crate) fn pass(game: &mut Game) -> Result<(), ParserError> {
+    if game.is_in_check() {
+        return Err(ParserError::InvalidParameter("Null move while in check"));
+    }
+    game.make_null_move();
+    Ok(())
+}
+
+/// Parses all games of the PGN text
+///
+/// Games are separated by their termination marker or by the tag pairs of the next game.
+/// Move numbers are optional and not checked.
+///
+/// # Errors
+/// * The text can not be tokenized
+/// * A tag pair is not of the form `[Name "value"]`
+/// * Parentheses of variations are unbalanced or a variation has no move to replace
+/// * A NAG is not preceded by a move
+///
+/// # Examples
+/// ```
+/// # use rust_chess::pgn::parse_pgn;
+/// let games = parse_pgn(
+///     "[Event \"A\"]\n\n1. e4 {best by test} e5 (1... c5 2. Nf3) 2. Nf3 $1 *\n\n\
+///      [Event \"B\"]\n\n1. d4 1-0",
+/// )
+/// .unwrap();
+/// assert_eq!(games.len(), 2);
+/// assert_eq!(games[0].tag("Event"), Some("A"));
+/// assert_eq!(games[0].moves[0].comments, vec![String::from("best by test")]);
+/// assert_eq!(games[0].moves[1].variations[0][1].san, "Nf3");
+/// assert_eq!(games[0].moves[2].nags, vec![1]);
+/// assert_eq!(games[1].result.as_deref(), Some("1-0"));
+/// ```
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, ParserError> {
+    let tokens = tokenize(text)?;
+    let mut pos = 0;
+    let mut games = Vec::new();
+    while pos < tokens.len() {
+        let game = parse_game(&tokens, &mut pos)?;
+        // comments after the last game do not form a game of their own
+        if !game.tags.is_empty() || !game.moves.is_empty() || game.result.is_some() {
+            games.push(game);
+        }
+    }
+    Ok(games)
+}
+
+fn parse_game(tokens: &[Token], pos: &mut usize) -> Result<PgnGame, ParserError> {
+    let mut tags = Vec::new();
+    while tokens.get(*pos) == Some(&Token::TagStart) {
+        match (
+            tokens.get(*pos + 1),
+            tokens.get(*pos + 2),
+            tokens.get(*pos + 3),
+        ) {
+            (Some(Token::Symbol(name)), Some(Token::Str(value)), Some(Token::TagEnd)) => {
+                tags.push((name.clone(), value.clone()));
+                *pos += 4;
+            }
+            _ => return Err(ParserError::InvalidParameter("Tag pair is invalid")),
+        }
+    }
+    let mut result = None;
+    let moves = parse_line(tokens, pos, 0, &mut result)?;
+    Ok(PgnGame {
+        tags,
+        moves,
+        result,
+    })
+}
+
+/// Parses moves until the end of the line at the given variation depth
+///
+/// The top level line ends at a termination marker, at the tags of the next game or at the end
+/// of the text. Variations end at their closing parenthesis.
+fn parse_line(
+    tokens: &[Token],
+    pos: &mut usize,
+    depth: usize,
+    result: &mut Option<String>,
+) -> Result<Vec<PgnMove>, ParserError> {
+    let mut moves: Vec<PgnMove> = Vec::new();
+    let mut leading_comments = Vec::new();
+    while let Some(token) = tokens.get(*pos) {
+        *pos += 1;
+        match token {
+            Token::MoveNumber(_) => {}
+            Token::Symbol(san) => {
+                let mut pgn_move = PgnMove::new(san.clone());
+                if moves.is_empty() {
+                    pgn_move.leading_comments = std::mem::take(&mut leading_comments);
+                }
+                moves.push(pgn_move);
+            }
+            Token::Nag(nag) => match moves.last_mut() {
+                Some(last) => last.nags.push(*nag),
+                None => return Err(ParserError::InvalidParameter("NAG without a move")),
+            },
+            Token::Comment(comment) => match moves.last_mut() {
+                Some(last) => last.comments.push(comment.clone()),
+                None => leading_comments.push(comment.clone()),
+            },
+            Token::VariationStart => {
+                if moves.is_empty() {
+                    return Err(ParserError::InvalidParameter(
+                        "Variation has no move to replace",
+                    ));
+                }
+                let variation = parse_line(tokens, pos, depth + 1, result)?;
+                moves
+                    .last_mut()
+                    .expect("checked above")
+                    .variations
+                    .push(variation);
+            }
+            Token::VariationEnd => {
+                if depth == 0 {
+                    return Err(ParserError::InvalidParameter("Unbalanced parentheses"));
+                }
+                return Ok(moves);
+            }
+            Token::Result(marker) => {
+                if depth == 0 {
+                    *result = Some(marker.clone());
+                    return Ok(moves);
+                }
+            }
+            Token::TagStart if depth == 0 => {
+                // the next game starts without a termination marker in this one
+                *pos -= 1;
+                return Ok(moves);
+            }
+            Token::TagStart | Token::TagEnd | Token::Str(_) => {
+                return Err(ParserError::InvalidParameter(
+                    "Tag pair inside of the movetext",
+                ));
+            }
+        }
+    }
+    if depth > 0 {
+        return Err(ParserError::InvalidParameter("Unbalanced parentheses"));
+    }
+    Ok(moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_variations() {
+        let game = PgnGame::parse(
+            "1. e4 e5 (1... c5 2. Nf3 (2. c3 d5) 2... d6) (1... e6) 2. Nf3 Nc6 3. Bb5 a6 *",
+        )
+        .unwrap();
+        assert_eq!(game.moves.len(), 6);
+        let variations = &game.moves[1].variations;
+        assert_eq!(variations.len(), 2);
+        assert_eq!(variations[0].len(), 3);
+        assert_eq!(variations[0][1].variations[0][1].san, "d5");
+        assert_eq!(variations[1][0].san, "e6");
+        assert_eq!(game.result.as_deref(), Some("*"));
+        assert_eq!(
+            game.to_game().unwrap().to_fen(),
+            "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 0 4"
+        );
+    }
+
+    #[test]
+    fn annotations_and_suffixes() {
+        let game = PgnGame::parse("{Start} 1. f3?! e5 2. g4?? Qh4# {Fool's mate} 0-1").unwrap();
+        assert_eq!(game.moves[0].leading_comments, vec![String::from("Start")]);
+        assert_eq!(game.moves[0].nags, vec![6]);
+        assert_eq!(game.moves[2].nags, vec![4]);
+        assert_eq!(game.moves[3].san, "Qh4#");
+        assert_eq!(game.moves[3].comments, vec![String::from("Fool's mate")]);
+        assert!(game.to_game().unwrap().is_checkmate());
+        assert_eq!(game.to_record().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn games_without_result_and_errors() {
+        let games = parse_pgn("[Event \"A\"]\n1. e4\n[Event \"B\"]\n1. d4").unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].result, None);
+        assert_eq!(games[1].moves[0].san, "d4");
+
+        assert!(parse_pgn("1. e4 (e5").is_err());
+        assert!(parse_pgn("1. e4 e5)").is_err());
+        assert!(parse_pgn("(1. e4)").is_err());
+        assert!(parse_pgn("[Event]").is_err());
+        assert!(parse_pgn("$1 1. e4").is_err());
+        assert!(PgnGame::parse("").is_err());
+    }
+
+    #[test]
+    fn null_moves() {
+        let game = PgnGame::parse("1. e4 e5 2. Nf3 (2. -- Nc6 3. Z0 d5) 2... Nc6 *").unwrap();
+        let variation = &game.moves[2].variations[0];
+        assert!(variation[0].is_null() && variation[2].is_null());
+        assert!(!variation[1].is_null());
+        assert!(variation[0].to_action(&Game::startpos()).is_err());
+        assert_eq!(game.to_record().unwrap().len(), 4);
+
+        let game = PgnGame::parse("1. e4 -- 2. d4 d5 *").unwrap();
+        assert_eq!(
+            game.to_game().unwrap().to_fen(),
+            "rnbqkbnr/ppp1pppp/8/3p4/3PP3/8/PPP2PPP/RNBQKBNR w KQkq d6 0 3"
+        );
+        assert!(game.to_record().is_err());
+        let checked = PgnGame::parse("1. e4 f5 2. Qh5+ -- *").unwrap();
+        assert!(checked.to_game().is_err());
+    }
+}
@@ -0,0 +1,189 @@
+/// Splits PGN data into the chunks of its single games without parsing them
+///
+/// A new game starts at the beginning of a line with a tag pair following the movetext of the
+/// previous game, or at the first line with text after a game termination marker. Comments,
+/// strings and escaped lines are skipped, so a `[` or a result inside them does not split a
+/// game. The chunks are slices of the data: every byte belongs to exactly one chunk, with the
+/// whitespace between two games belonging to the first one. Data consisting of whitespace only
+/// yields no chunk.
+///
+/// Chunks always start at the beginning of a line, so chunks of valid UTF-8 are valid UTF-8 as
+/// well. This makes the splitter a cheap way to distribute a large database over several
+/// threads before each chunk is parsed with [`parse_pgn`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::pgn::{parse_pgn, split_games};
+/// let pgn = "[Event \"A\"]\n\n1. e4 e5 1-0\n\n[Event \"B\"]\n\n1. d4 {[%clk 0:05]} d5 *\n";
+/// let chunks: Vec<&[u8]> = split_games(pgn.as_bytes()).collect();
+/// assert_eq!(chunks.len(), 2);
+/// assert_eq!(chunks[0], b"[Event \"A\"]\n\n1. e4 e5 1-0\n\n");
+/// let second = std::str::from_utf8(chunks[1]).unwrap();
+/// assert_eq!(parse_pgn(second).unwrap()[0].tag("Event"), Some("B"));
+/// ```
+///
+/// [`parse_pgn`]: fn.parse_pgn.html
+pub fn split_games(data: &[u8]) -> GameChunks<'_> {
+    GameChunks { data, position: 0 }
+}
+
+/// An iterator over the games of PGN data, created by [`split_games`]
+///
+/// [`split_games`]: fn.split_games.html
+#[derive(Clone, Debug)]
+pub struct GameChunks<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Iterator for GameChunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let start = self.position;
+        let end = next_game_start(self.data, start);
+        self.position = end;
+        let chunk = &self.data[start..end];
+        if chunk.iter().all(u8::is_ascii_whitespace) {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+/// Returns the index of the line starting the game after the one starting at `start`
+fn next_game_start(data: &[u8], start: usize) -> usize {
+    let mut i = start;
+    let mut line_start = true;
+    let mut in_tag = false;
+    let mut has_movetext = false;
+    let mut ended = false;
+    let mut depth = 0u32;
+    while i < data.len() {
+        if line_start {
+            line_start = false;
+            let indent = data[i..]
+                .iter()
+                .take_while(|&&b| b == b' ' || b == b'\t' || b == b'\r')
+                .count();
+            match data.get(i + indent) {
+                Some(b'[') if has_movetext => return i,
+                Some(b'\n') | None => {}
+                Some(_) if ended => return i,
+                Some(b'%') => {
+                    i = skip_until(data, i, b'\n');
+                    continue;
+                }
+                Some(_) => {}
+            }
+        }
+        match data[i] {
+            b'\n' => {
+                line_start = true;
+                i += 1;
+            }
+            b'{' => i = skip_until(data, i, b'}') + 1,
+            b';' => i = skip_until(data, i, b'\n'),
+            b'"' => i = skip_string(data, i),
+            b'[' => {
+                in_tag = true;
+                i += 1;
+            }
+            b']' => {
+                in_tag = false;
+                i += 1;
+            }
+            b'(' => {
+                depth += 1;
+                has_movetext = true;
+                i += 1;
+            }
+            b')' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            b if b.is_ascii_whitespace() => i += 1,
+            _ => {
+                let end = skip_word(data, i);
+                if !in_tag {
+                    has_movetext = true;
+                    let word = &data[i..end];
+                    let is_result = matches!(word, b"1-0" | b"0-1" | b"1/2-1/2" | b"*");
+                    ended |= depth == 0 && is_result;
+                }
+                i = end;
+            }
+        }
+    }
+    data.len()
+}
+
+/// Returns the index of the next occurrence of the byte after `start`, or the end of the data
+fn skip_until(data: &[u8], start: usize, byte: u8) -> usize {
+    data[start + 1..]
+        .iter()
+        .position(|&b| b == byte)
+        .map_or(data.len(), |offset| start + 1 + offset)
+}
+
+/// Returns the index after the string starting at `start`, which may contain escaped quotes
+fn skip_string(data: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < data.len() {
+        match data[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    data.len()
+}
+
+/// Returns the index of the first byte after the word starting at `start`
+///
+/// The word is at least one byte long, so a stray `}` is skipped like a word.
+fn skip_word(data: &[u8], start: usize) -> usize {
+    data[start + 1..]
+        .iter()
+        .position(|&b| b.is_ascii_whitespace() || b"{};()[]\"".contains(&b))
+        .map_or(data.len(), |offset| start + 1 + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pgn::parse_pgn;
+
+    #[test]
+    fn chunks_are_lossless() {
+        let pgn = "\n[Event \"1-0\"]\n[Site \"*\"]\n\n1. e4 {comment\n[not a tag]} e5 \
+                   (1... c5 2. Nf3 1-0) 2. Nf3 1/2-1/2\n\
+                   1. d4 d5 0-1 \n\n\
+                   %[Event \"escaped\"]\n\
+                   [Event \"last\"]\n1. c4 ; 1-0 in a comment\n*\n\n";
+        let chunks: Vec<&str> = split_games(pgn.as_bytes())
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+            .collect();
+        assert_eq!(chunks.concat(), pgn);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[1].starts_with("1. d4"));
+        assert!(chunks[2].starts_with("%[Event"));
+
+        let games: Vec<_> = chunks
+            .iter()
+            .flat_map(|chunk| parse_pgn(chunk).unwrap())
+            .collect();
+        assert_eq!(games, parse_pgn(pgn).unwrap());
+
+        assert_eq!(split_games(b"").count(), 0);
+        assert_eq!(split_games(b" \n\r\n").count(), 0);
+        // a game without a result ends with the next tag section
+        assert_eq!(
+            split_games(b"[A \"\"]\n1. e4\n[B \"\"]\n1. d4\n").count(),
+            2
+        );
+        // a closing brace outside of a comment
+        assert_eq!(split_games(b"1. e4 } e5 *\n1. d4 *\n").count(), 2);
+    }
+}
@@ -0,0 +1,244 @@
+use super::parser::PgnGame;
+use crate::core::ParserError;
+use crate::game_representation::GameResult;
+use std::fmt;
+
+/// The value of a `Date` tag, every part of which may be unknown
+///
+/// # Examples
+/// ```
+/// # use rust_chess::pgn::PgnDate;
+/// let date = PgnDate::parse("1972.??.??").unwrap();
+/// assert_eq!((date.year, date.month, date.day), (Some(1972), None, None));
+/// assert_eq!(date.to_string(), "1972.??.??");
+/// assert!(PgnDate::parse("1972.13.01").is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PgnDate {
+    pub year: Option<u16>,
+    /// The month from 1 to 12
+    pub month: Option<u8>,
+    /// The day of the month from 1 to 31
+    pub day: Option<u8>,
+}
+
+impl PgnDate {
+    /// Returns the date of a tag value in the form `YYYY.MM.DD`
+    ///
+    /// Unknown parts are written as question marks. Dashes and slashes are accepted as
+    /// separators as well, as some databases write them.
+    ///
+    /// # Errors
+    /// * The value does not consist of three parts
+    /// * A part is neither a number nor question marks
+    /// * The month or the day is out of range
+    pub fn parse(value: &str) -> Result<PgnDate, ParserError> {
+        let parts: Vec<&str> = value.trim().split(['.', '-', '/']).collect();
+        let (year, month, day) = match parts[..] {
+            [year, month, day] => (year, month, day),
+            _ => return Err(ParserError::WrongParameterNumber),
+        };
+        let month = date_part(month)?;
+        let day = date_part(day)?;
+        if month.is_some_and(|month| !(1..=12).contains(&month))
+            || day.is_some_and(|day| !(1..=31).contains(&day))
+        {
+            return Err(ParserError::InvalidParameter("Date is out of range"));
+        }
+        Ok(PgnDate {
+            year: date_part(year)?,
+            month: month.map(|month| month as u8),
+            day: day.map(|day| day as u8),
+        })
+    }
+}
+
+impl fmt::Display for PgnDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.year {
+            Some(year) => write!(f, "{:04}", year)?,
+            None => f.write_str("????")?,
+        }
+        for part in &[self.month, self.day] {
+            match part {
+                Some(part) => write!(f, ".{:02}", part)?,
+                None => f.write_str(".??")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the number of a date part, `None` if it is unknown
+fn date_part(part: &str) -> Result<Option<u16>, ParserError> {
+    if !part.is_empty() && part.chars().all(|c| c == '?') {
+        Ok(None)
+    } else {
+        part.parse()
+            .map(Some)
+            .map_err(|_| ParserError::InvalidParameter("Date part is not a number"))
+    }
+}
+
+/// A player name split into the last name and the first names
+///
+/// PGN writes names as `Last, First`, names without a comma are kept as the last name.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::pgn::PlayerName;
+/// let name = PlayerName::parse("Carlsen, Magnus");
+/// assert_eq!(name.last, "Carlsen");
+/// assert_eq!(name.first.as_deref(), Some("Magnus"));
+/// assert_eq!(name.full_name(), "Magnus Carlsen");
+/// assert_eq!(PlayerName::parse("Stockfish 16").full_name(), "Stockfish 16");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PlayerName {
+    pub last: String,
+    pub first: Option<String>,
+}
+
+impl PlayerName {
+    /// Returns the name of a `White` or `Black` tag value
+    pub fn parse(value: &str) -> PlayerName {
+        match value.split_once(',') {
+            Some((last, first)) if !first.trim().is_empty() => PlayerName {
+                last: last.trim().to_string(),
+                first: Some(first.trim().to_string()),
+            },
+            Some((last, _)) => PlayerName {
+                last: last.trim().to_string(),
+                first: None,
+            },
+            None => PlayerName {
+                last: value.trim().to_string(),
+                first: None,
+            },
+        }
+    }
+
+    /// Returns the name with the first names in front of the last name
+    pub fn full_name(&self) -> String {
+        match &self.first {
+            Some(first) => format!("{} {}", first, self.last),
+            None => self.last.clone(),
+        }
+    }
+}
+
+impl fmt::Display for PlayerName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.first {
+            Some(first) => write!(f, "{}, {}", self.last, first),
+            None => f.write_str(&self.last),
+        }
+    }
+}
+
+/// Returns the rating of a `WhiteElo` or `BlackElo` tag value
+///
+/// Unrated players are written as an empty value, `-`, `?` or `0`, all of which return `None`.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::pgn::parse_elo;
+/// assert_eq!(parse_elo("2882"), Some(2882));
+/// assert_eq!(parse_elo("-"), None);
+/// assert_eq!(parse_elo("0"), None);
+/// ```
+pub fn parse_elo(value: &str) -> Option<u16> {
+    value.trim().parse().ok().filter(|elo| *elo > 0)
+}
+
+/// Returns the result of a `Result` tag value or a game termination marker
+///
+/// A game in progress or with an unknown result, written as `*`, is [`Ongoing`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::GameResult;
+/// # use rust_chess::pgn::parse_result;
+/// assert_eq!(parse_result("1/2-1/2"), Some(GameResult::Draw));
+/// assert_eq!(parse_result("*"), Some(GameResult::Ongoing));
+/// assert_eq!(parse_result("draw"), None);
+/// ```
+///
+/// [`Ongoing`]: ../game_representation/enum.GameResult.html#variant.Ongoing
+pub fn parse_result(value: &str) -> Option<GameResult> {
+    match value.trim() {
+        "1-0" => Some(GameResult::WhiteWins),
+        "0-1" => Some(GameResult::BlackWins),
+        "1/2-1/2" => Some(GameResult::Draw),
+        "*" => Some(GameResult::Ongoing),
+        _ => None,
+    }
+}
+
+impl PgnGame {
+    /// Returns the date of the `Date` tag, `None` if it is missing or invalid
+    pub fn date(&self) -> Option<PgnDate> {
+        PgnDate::parse(self.tag("Date")?).ok()
+    }
+
+    /// Returns the name of the white player
+    pub fn white(&self) -> Option<PlayerName> {
+        self.tag("White").map(PlayerName::parse)
+    }
+
+    /// Returns the name of the black player
+    pub fn black(&self) -> Option<PlayerName> {
+        self.tag("Black").map(PlayerName::parse)
+    }
+
+    /// Returns the rating of the white player
+    pub fn white_elo(&self) -> Option<u16> {
+        parse_elo(self.tag("WhiteElo")?)
+    }
+
+    /// Returns the rating of the black player
+    pub fn black_elo(&self) -> Option<u16> {
+        parse_elo(self.tag("BlackElo")?)
+    }
+
+    /// Returns the result of the `Result` tag, or of the termination marker without the tag
+    pub fn game_result(&self) -> Option<GameResult> {
+        self.tag("Result")
+            .or(self.result.as_deref())
+            .and_then(parse_result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_tags() {
+        let game = PgnGame::parse(
+            "[White \"Fischer, Robert James\"]\n[Black \"Spassky,Boris V.\"]\n\
+             [Date \"1972-07-23\"]\n[WhiteElo \"2785\"]\n[BlackElo \"\"]\n\n1. c4 1-0",
+        )
+        .unwrap();
+        assert_eq!(
+            game.date(),
+            Some(PgnDate {
+                year: Some(1972),
+                month: Some(7),
+                day: Some(23)
+            })
+        );
+        assert_eq!(game.date().unwrap().to_string(), "1972.07.23");
+        assert_eq!(game.white().unwrap().full_name(), "Robert James Fischer");
+        assert_eq!(game.black().unwrap().to_string(), "Spassky, Boris V.");
+        assert_eq!((game.white_elo(), game.black_elo()), (Some(2785), None));
+        assert_eq!(game.game_result(), Some(GameResult::WhiteWins));
+
+        assert_eq!(PgnDate::parse("????.??.??"), Ok(PgnDate::default()));
+        assert!(PgnDate::parse("1972.07").is_err());
+        assert!(PgnDate::parse("1972.07.x").is_err());
+        assert!(PgnDate::parse("1972..01").is_err());
+        assert_eq!(PlayerName::parse("Anand,").first, None);
+        assert_eq!(parse_elo("99999"), None);
+    }
+}
@@ -0,0 +1,162 @@
+use super::PgnGame;
+use crate::core::ParserError;
+use crate::game_representation::Game;
+
+/// The rules a PGN game is played by, taken from its `Variant` tag
+///
+/// Only standard chess is supported by this crate, every other variant is refused when a game
+/// is read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Standard,
+}
+
+impl Variant {
+    /// Returns the variant named by the value of a `Variant` tag
+    ///
+    /// The names used by common servers for standard chess are accepted, ignoring case.
+    ///
+    /// # Errors
+    /// * The variant is not standard chess
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::pgn::Variant;
+    /// assert_eq!(Variant::from_tag("From Position").unwrap(), Variant::Standard);
+    /// assert!(Variant::from_tag("Atomic").is_err());
+    /// ```
+    pub fn from_tag(value: &str) -> Result<Variant, ParserError> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "standard" | "chess" | "normal" | "from position" => Ok(Variant::Standard),
+            _ => Err(ParserError::InvalidParameter(
+                "Variant is not supported, only standard chess can be read",
+            )),
+        }
+    }
+
+    /// Returns the value written to the `Variant` tag
+    pub fn to_tag(self) -> &'static str {
+        match self {
+            Variant::Standard => "Standard",
+        }
+    }
+}
+
+impl PgnGame {
+    /// Returns the variant of the game, standard chess if there is no `Variant` tag
+    ///
+    /// # Errors
+    /// * The variant is not supported, see [`Variant::from_tag`]
+    ///
+    /// [`Variant::from_tag`]: enum.Variant.html#method.from_tag
+    pub fn variant(&self) -> Result<Variant, ParserError> {
+        match self.tag("Variant") {
+            Some(value) => Variant::from_tag(value),
+            None => Ok(Variant::Standard),
+        }
+    }
+
+    /// Returns the position the game starts from
+    ///
+    /// That is the position of the `FEN` tag if there is one, the standard starting position
+    /// otherwise. A `SetUp` tag of `1` requires a `FEN` tag, `0` forbids it.
+    ///
+    /// # Errors
+    /// * The variant is not supported
+    /// * The `SetUp` tag is neither `0` nor `1` or contradicts the presence of a `FEN` tag
+    /// * The `FEN` tag can not be parsed by [`Game::from_fen`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::pgn::PgnGame;
+    /// let game = PgnGame::parse(
+    ///     "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"]\n\n1. e4 *",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(game.to_game().unwrap().to_fen(), "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1");
+    /// ```
+    ///
+    /// [`Game::from_fen`]: ../game_representation/struct.Game.html#method.from_fen
+    pub fn start_position(&self) -> Result<Game, ParserError> {
+        self.variant()?;
+        let fen = self.tag("FEN");
+        match (self.tag("SetUp"), fen) {
+            (None, _) | (Some("1"), Some(_)) | (Some("0"), None) => {}
+            (Some("1"), None) => {
+                return Err(ParserError::InvalidParameter(
+                    "SetUp tag requires a FEN tag",
+                ))
+            }
+            (Some("0"), Some(_)) => {
+                return Err(ParserError::InvalidParameter(
+                    "FEN tag given although SetUp tag is 0",
+                ))
+            }
+            (Some(_), _) => return Err(ParserError::InvalidParameter("SetUp tag is not 0 or 1")),
+        }
+        match fen {
+            Some(fen) => Game::from_fen(fen.trim()),
+            None => Ok(Game::startpos()),
+        }
+    }
+
+    /// Sets the tags describing the start position and the variant of the game
+    ///
+    /// Writes `SetUp` and `FEN` tags unless the position is the standard starting position,
+    /// in which case existing ones are removed. The `Variant` tag is only written for games
+    /// starting from a set up position.
+    pub fn set_start_position(&mut self, start: &Game) {
+        self.tags
+            .retain(|(name, _)| name != "SetUp" && name != "FEN" && name != "Variant");
+        if !start.is_standard_startpos() {
+            self.tags.push((
+                String::from("Variant"),
+                String::from(Variant::Standard.to_tag()),
+            ));
+            self.tags.push((String::from("SetUp"), String::from("1")));
+            self.tags.push((String::from("FEN"), start.to_fen()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_unsupported_variants() {
+        let game = PgnGame::parse("[Variant \"Crazyhouse\"]\n\n1. e4 *").unwrap();
+        assert!(game.variant().is_err());
+        assert!(game.to_game().is_err());
+        assert!(game.to_record().is_err());
+    }
+
+    #[test]
+    fn setup_tags() {
+        let fen = "[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"]";
+        for tags in &[
+            String::from("[SetUp \"1\"]"),
+            format!("[SetUp \"0\"]{}", fen),
+            format!("[SetUp \"2\"]{}", fen),
+            String::from("[FEN \"4k3/8/8/8/8/8/4P3/4K3 x - - 0 1\"]"),
+        ] {
+            let game = PgnGame::parse(&format!("{}\n\n*", tags)).unwrap();
+            assert!(game.start_position().is_err(), "{}", tags);
+        }
+        let game = PgnGame::parse(&format!("{}\n\n1. e4 *", fen)).unwrap();
+        let record = game.to_record().unwrap();
+        assert_eq!(record.start_fen(), "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+    }
+
+    #[test]
+    fn writing_setup_tags() {
+        let mut game =
+            PgnGame::parse("[Event \"?\"]\n[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n\n*").unwrap();
+        game.set_start_position(&Game::startpos());
+        assert_eq!(game.tags.len(), 1);
+        let start = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+        game.set_start_position(&start);
+        assert_eq!(game.tag("SetUp"), Some("1"));
+        assert_eq!(game.start_position().unwrap().to_fen(), start.to_fen());
+    }
+}
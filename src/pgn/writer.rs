@@ -0,0 +1,259 @@
+use super::PgnGame;
+use crate::core::ParserError;
+use crate::game_representation::Color;
+use crate::record::RecordedGame;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The tags every PGN game has to contain, in the order they are written
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+/// Line length of the PGN export format
+pub const DEFAULT_LINE_LENGTH: usize = 79;
+
+/// A recorded game together with its tag pairs, which can be written as PGN
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::pgn::GameRecord;
+/// # use rust_chess::record::RecordedGame;
+/// let mut record = RecordedGame::new();
+/// record.push(Action::from_san("e4", &Game::startpos()).unwrap());
+/// let mut game = GameRecord::new(record);
+/// game.set_tag("White", "Kasparov, Garry");
+/// game.set_tag("Result", "1-0");
+/// assert_eq!(
+///     game.to_pgn(),
+///     "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+///      [White \"Kasparov, Garry\"]\n[Black \"?\"]\n[Result \"1-0\"]\n\n1. e4 1-0\n"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRecord {
+    tags: Vec<(String, String)>,
+    record: RecordedGame,
+}
+
+impl GameRecord {
+    /// Returns a record without tags, the seven tag roster is filled with unknown values
+    pub fn new(record: RecordedGame) -> GameRecord {
+        GameRecord {
+            tags: Vec::new(),
+            record,
+        }
+    }
+
+    /// Returns the main line and the tags of a parsed game
+    ///
+    /// # Errors
+    /// * The main line can not be replayed, see [`PgnGame::to_record`]
+    ///
+    /// [`PgnGame::to_record`]: struct.PgnGame.html#method.to_record
+    pub fn from_pgn_game(game: &PgnGame) -> Result<GameRecord, ParserError> {
+        let mut record = GameRecord::new(game.to_record()?);
+        for (name, value) in &game.tags {
+            record.set_tag(name, value);
+        }
+        if let Some(result) = &game.result {
+            if record.tag("Result").is_none() {
+                record.set_tag("Result", result);
+            }
+        }
+        Ok(record)
+    }
+
+    /// Returns the recorded moves
+    pub fn record(&self) -> &RecordedGame {
+        &self.record
+    }
+
+    /// Returns the recorded moves for modification
+    pub fn record_mut(&mut self) -> &mut RecordedGame {
+        &mut self.record
+    }
+
+    /// Returns all tags that were set, in the order they were set first
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    /// Returns the value of the tag with the given name
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of a tag, replacing the previous value
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Returns the game as PGN with movetext lines of at most [`DEFAULT_LINE_LENGTH`]
+    ///
+    /// [`DEFAULT_LINE_LENGTH`]: constant.DEFAULT_LINE_LENGTH.html
+    pub fn to_pgn(&self) -> String {
+        self.to_pgn_wrapped(DEFAULT_LINE_LENGTH)
+    }
+
+    /// Returns the game as PGN, wrapping the movetext at the given line length
+    ///
+    /// The seven tag roster comes first, missing tags are written with their unknown value.
+    /// `SetUp` and `FEN` tags are only written for games not starting from the standard position,
+    /// set values of these tags are replaced by the actual start. Then all other tags follow in
+    /// the order they were set. The moves are written in SAN, followed by a comment with the
+    /// commands of their metadata, and the movetext ends with the value of the `Result` tag.
+    /// Tokens are never split, so a line only exceeds the length if a single token does. A
+    /// length of 0 disables wrapping.
+    pub fn to_pgn_wrapped(&self, line_length: usize) -> String {
+        let mut pgn = String::new();
+        for name in SEVEN_TAG_ROSTER.iter() {
+            let value = self.tag(name).unwrap_or(match *name {
+                "Date" => "????.??.??",
+                "Result" => "*",
+                _ => "?",
+            });
+            write_tag(&mut pgn, name, value);
+        }
+        let mut game = self.record.position_at(0);
+        if !game.is_standard_startpos() {
+            write_tag(&mut pgn, "SetUp", "1");
+            write_tag(&mut pgn, "FEN", self.record.start_fen());
+        }
+        for (name, value) in &self.tags {
+            let written =
+                SEVEN_TAG_ROSTER.contains(&name.as_str()) || name == "SetUp" || name == "FEN";
+            if !written {
+                write_tag(&mut pgn, name, value);
+            }
+        }
+        pgn.push('\n');
+
+        let mut tokens = Vec::new();
+        let mut after_comment = false;
+        for (ply, action) in self.record.actions().iter().enumerate() {
+            match game.color_to_move {
+                Color::White => tokens.push(format!("{}.", game.get_full_move_clock())),
+                Color::Black if ply == 0 || after_comment => {
+                    tokens.push(format!("{}...", game.get_full_move_clock()))
+                }
+                Color::Black => {}
+            }
+            tokens.push(action.to_san(&game));
+            after_comment = false;
+            if let Some(metadata) = self.record.metadata(ply) {
+                tokens.push(format!("{{{}}}", metadata.to_pgn_comment()));
+                after_comment = true;
+            }
+            game.execute_action(action);
+        }
+        tokens.push(self.tag("Result").unwrap_or("*").to_string());
+
+        let mut line_start = pgn.len();
+        for (i, token) in tokens.iter().enumerate() {
+            if i > 0 {
+                if line_length > 0 && pgn.len() - line_start + 1 + token.len() > line_length {
+                    pgn.push('\n');
+                    line_start = pgn.len();
+                } else {
+                    pgn.push(' ');
+                }
+            }
+            pgn.push_str(token);
+        }
+        pgn.push('\n');
+        pgn
+    }
+}
+
+/// Writes a single tag pair line, escaping quotes and backslashes of the value
+fn write_tag(pgn: &mut String, name: &str, value: &str) {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    pgn.push_str(&format!("[{} \"{}\"]\n", name, escaped));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde")]
+    use crate::move_generation::Action;
+
+    const GAME: &str = "[Event \"Casual \\\"Game\\\"\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n\
+                        [Round \"?\"]\n[White \"?\"]\n[Black \"?\"]\n[Result \"0-1\"]\n\
+                        [Annotator \"Me\"]\n\n\
+                        1. f3 e5 2. g4 Qh4# 0-1\n";
+
+    #[test]
+    fn round_trip() {
+        let game = GameRecord::from_pgn_game(&PgnGame::parse(GAME).unwrap()).unwrap();
+        assert_eq!(game.to_pgn(), GAME);
+
+        // the start position tags are only written when needed
+        let mut game = game;
+        game.set_tag("SetUp", "1");
+        game.set_tag("FEN", "8/8/8/8/8/8/8/8 w - - 0 1");
+        assert_eq!(game.to_pgn(), GAME);
+    }
+
+    #[test]
+    fn wrapping_lines() {
+        let game = GameRecord::from_pgn_game(&PgnGame::parse(GAME).unwrap()).unwrap();
+        let pgn = game.to_pgn_wrapped(10);
+        let movetext: Vec<&str> = pgn.split("\n\n").nth(1).unwrap().lines().collect();
+        assert_eq!(movetext, vec!["1. f3 e5", "2. g4 Qh4#", "0-1"]);
+        let pgn = game.to_pgn_wrapped(0);
+        assert!(pgn.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+    }
+
+    #[test]
+    fn set_up_positions() {
+        let mut record = RecordedGame::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 7").unwrap();
+        let position = record.position_at(0);
+        let action = crate::move_generation::Action::from_san("Kd7", &position).unwrap();
+        record.push(action);
+        let mut game = GameRecord::new(record);
+        game.set_tag("FEN", "ignored");
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 7\"]\n\n"));
+        assert!(pgn.ends_with("\n7... Kd7 *\n"));
+        let parsed = GameRecord::from_pgn_game(&PgnGame::parse(&pgn).unwrap()).unwrap();
+        assert_eq!(parsed.to_pgn(), pgn);
+    }
+
+    #[test]
+    fn metadata_comments() {
+        let pgn = "1. e4 {[%clk 0:05:00] a good start} e5 {[%clk 0:04:58]} 2. Nf3 *";
+        let game = GameRecord::from_pgn_game(&PgnGame::parse(pgn).unwrap()).unwrap();
+        let clock = |ply| game.record().metadata(ply).and_then(|m| m.time("clk"));
+        assert_eq!(clock(0), Some(std::time::Duration::from_secs(300)));
+        assert_eq!(clock(2), None);
+        let written = game.to_pgn_wrapped(0);
+        assert!(written.ends_with("\n1. e4 {[%clk 0:05:00]} 1... e5 {[%clk 0:04:58]} 2. Nf3 *\n"));
+        let read = GameRecord::from_pgn_game(&PgnGame::parse(&written).unwrap()).unwrap();
+        assert_eq!(read.to_pgn(), game.to_pgn());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let game = GameRecord::from_pgn_game(&PgnGame::parse(GAME).unwrap()).unwrap();
+        let json = serde_json::to_string(&game).unwrap();
+        let read: GameRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.to_pgn(), GAME);
+
+        let action = game.record.actions()[3];
+        let json = serde_json::to_string(&action).unwrap();
+        assert_eq!(
+            json,
+            r#"{"from":"d8","to":"h4","piece":"Queen","action_type":"Quiet"}"#
+        );
+        assert_eq!(serde_json::from_str::<Action>(&json).unwrap(), action);
+    }
+}
@@ -0,0 +1,14 @@
+//! The most commonly used types, meant to be glob imported with `use rust_chess::prelude::*`
+//!
+//! ```
+//! use rust_chess::prelude::*;
+//!
+//! let game = Game::startpos();
+//! let action = Action::from_san("e4", &game).unwrap();
+//! assert_eq!(action.get_piecetype(), PieceType::Pawn);
+//! assert_eq!(game.color_to_move, Color::White);
+//! ```
+
+pub use crate::core::{ParserError, Square};
+pub use crate::game_representation::{Board, Color, Game, PieceType};
+pub use crate::move_generation::{Action, ActionType, MoveList};
@@ -0,0 +1,300 @@
+//! Python bindings, enabled with the `python` feature
+//!
+//! The classes `Game`, `Action` and `Board` of the Python module `rust_chess` follow the naming
+//! of python-chess where possible. Input is validated by the same parsers as in Rust, a
+//! [`ParserError`] is raised as a `ValueError` with its message.
+//!
+//! The extension module is built with
+//! `cargo rustc --lib --release --features python --crate-type cdylib` and the library is
+//! renamed to `rust_chess.so`, or `rust_chess.pyd` on Windows.
+//!
+//! ```python
+//! import rust_chess
+//! game = rust_chess.Game()
+//! game.push_san("e4")
+//! print(game.fen, [action.uci() for action in game.legal_moves()])
+//! ```
+//!
+//! [`ParserError`]: ../core/enum.ParserError.html
+
+use crate::core::{ParserError, Square};
+use crate::game_representation::{Board, Game, GameResult};
+use crate::move_generation::{movegen, Action};
+use crate::pgn::GameRecord;
+use crate::record::RecordedGame;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Returns the Python exception of a parser error
+fn value_error(error: ParserError) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+/// A game together with the moves played since its start, exported to Python as `Game`
+#[pyclass(name = "Game", module = "rust_chess", skip_from_py_object)]
+#[derive(Clone, Debug)]
+pub struct PyGame {
+    game: Game,
+    record: RecordedGame,
+}
+
+#[pymethods]
+impl PyGame {
+    /// Returns a game starting from the FEN, or from the standard starting position
+    #[new]
+    #[pyo3(signature = (fen=None))]
+    pub fn new(fen: Option<&str>) -> PyResult<PyGame> {
+        let record = match fen {
+            Some(fen) => RecordedGame::from_fen(fen).map_err(value_error)?,
+            None => RecordedGame::new(),
+        };
+        Ok(PyGame {
+            game: record.position_at(0),
+            record,
+        })
+    }
+
+    /// Plays a move in Standard Algebraic Notation and returns it
+    pub fn push_san(&mut self, san: &str) -> PyResult<PyAction> {
+        let action = Action::from_san(san, &self.game).map_err(value_error)?;
+        Ok(self.play(action))
+    }
+
+    /// Plays a move in UCI notation and returns it
+    pub fn push_uci(&mut self, uci: &str) -> PyResult<PyAction> {
+        let action = PyAction {
+            action: Action::from_uci(uci, &self.game).map_err(value_error)?,
+        };
+        self.push(&action)?;
+        Ok(action)
+    }
+
+    /// Plays a legal move
+    pub fn push(&mut self, action: &PyAction) -> PyResult<()> {
+        if !movegen::legal_moves(&self.game).contains(&action.action) {
+            return Err(PyValueError::new_err("move is not legal in the position"));
+        }
+        self.play(action.action);
+        Ok(())
+    }
+
+    /// Returns all legal moves
+    pub fn legal_moves(&self) -> Vec<PyAction> {
+        movegen::legal_moves(&self.game)
+            .iter()
+            .map(|action| PyAction { action: *action })
+            .collect()
+    }
+
+    /// Returns the move in Standard Algebraic Notation in the current position
+    pub fn san(&self, action: &PyAction) -> String {
+        action.action.to_san(&self.game)
+    }
+
+    /// The Forsyth-Edwards Notation of the current position
+    #[getter]
+    pub fn fen(&self) -> String {
+        self.game.to_fen()
+    }
+
+    /// The pieces of the current position
+    #[getter]
+    pub fn board(&self) -> PyBoard {
+        PyBoard {
+            board: self.game.board.clone(),
+        }
+    }
+
+    /// True if white is to move
+    #[getter]
+    pub fn turn(&self) -> bool {
+        self.game.color_to_move == crate::game_representation::Color::White
+    }
+
+    pub fn is_check(&self) -> bool {
+        self.game.is_in_check()
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        self.game.is_checkmate()
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        self.game.is_stalemate()
+    }
+
+    /// Returns the result as written in PGN, `*` while the game is ongoing
+    pub fn result(&self) -> &'static str {
+        match self.game.result() {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Ongoing => "*",
+        }
+    }
+
+    /// Returns the moves played so far as PGN
+    pub fn pgn(&self) -> String {
+        let mut record = GameRecord::new(self.record.clone());
+        record.set_tag("Result", self.result());
+        record.to_pgn()
+    }
+
+    pub fn __str__(&self) -> String {
+        self.game.to_string()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("Game('{}')", self.game.to_fen())
+    }
+}
+
+impl PyGame {
+    fn play(&mut self, action: Action) -> PyAction {
+        self.game.execute_action(&action);
+        self.record.push(action);
+        PyAction { action }
+    }
+}
+
+/// A move, exported to Python as `Action`
+#[pyclass(
+    name = "Action",
+    module = "rust_chess",
+    frozen,
+    eq,
+    hash,
+    skip_from_py_object
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PyAction {
+    action: Action,
+}
+
+#[pymethods]
+impl PyAction {
+    /// Returns the move in UCI notation
+    pub fn uci(&self) -> String {
+        self.action.to_uci()
+    }
+
+    /// The name of the field the piece moves from, like `e2`
+    #[getter]
+    pub fn from_square(&self) -> String {
+        square_name(self.action.get_from_index())
+    }
+
+    /// The name of the field the piece moves to, like `e4`
+    #[getter]
+    pub fn to_square(&self) -> String {
+        square_name(self.action.get_to_index())
+    }
+
+    /// The letter of the piece a pawn promotes to, `None` for other moves
+    #[getter]
+    pub fn promotion(&self) -> Option<String> {
+        self.action
+            .get_promotion_piece()
+            .map(|_| self.uci()[4..].to_string())
+    }
+
+    pub fn __str__(&self) -> String {
+        self.uci()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("Action('{}')", self.uci())
+    }
+}
+
+/// The pieces of a position, exported to Python as `Board`
+#[pyclass(name = "Board", module = "rust_chess", skip_from_py_object)]
+#[derive(Clone, Debug)]
+pub struct PyBoard {
+    board: Board,
+}
+
+#[pymethods]
+impl PyBoard {
+    /// Returns the board of the board-part of a FEN
+    #[new]
+    pub fn new(fen: &str) -> PyResult<PyBoard> {
+        Ok(PyBoard {
+            board: Board::from_fen(fen).map_err(value_error)?,
+        })
+    }
+
+    /// Returns the board-part of the FEN
+    pub fn fen(&self) -> String {
+        self.board.to_fen()
+    }
+
+    /// Returns the FEN letter of the piece on the field, `None` if it is empty
+    pub fn piece_at(&self, square: &str) -> PyResult<Option<String>> {
+        let square: Square = square.parse().map_err(value_error)?;
+        let piece = self.board.get_piecestr_at(u8::from(square));
+        Ok(Some(piece.to_string()).filter(|piece| !piece.is_empty()))
+    }
+
+    pub fn __str__(&self) -> String {
+        self.board.to_string()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("Board('{}')", self.board.to_fen())
+    }
+}
+
+fn square_name(index: u8) -> String {
+    Square::from_index(index)
+        .expect("index is on the board")
+        .to_string()
+}
+
+/// The Python module `rust_chess`
+#[pymodule]
+fn rust_chess(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGame>()?;
+    module.add_class::<PyAction>()?;
+    module.add_class::<PyBoard>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn python_classes() {
+        let mut game = PyGame::new(None).unwrap();
+        assert_eq!(game.legal_moves().len(), 20);
+        let action = game.push_san("e4").unwrap();
+        assert_eq!(
+            (action.from_square(), action.to_square()),
+            ("e2".into(), "e4".into())
+        );
+        // the rook can not pass the pawns
+        assert!(game.push_uci("a8a1").is_err());
+        game.push_uci("e7e5").unwrap();
+        let knight = PyAction {
+            action: Action::from_san("Nf3", &game.game).unwrap(),
+        };
+        assert_eq!(game.san(&knight), "Nf3");
+        game.push(&knight).unwrap();
+        assert!(game.push(&knight).is_err());
+        assert!(game.push_san("Ke3").is_err());
+        assert!(game.pgn().ends_with("\n1. e4 e5 2. Nf3 *\n"));
+        assert_eq!(game.board().piece_at("f3").unwrap().as_deref(), Some("N"));
+        assert!(!game.turn());
+
+        let game = PyGame::new(Some("4k3/P7/8/8/8/8/8/4K3 w - - 0 1")).unwrap();
+        let promotions: Vec<PyAction> = game
+            .legal_moves()
+            .into_iter()
+            .filter(|action| action.promotion().is_some())
+            .collect();
+        assert_eq!(promotions.len(), 4);
+        assert!(PyGame::new(Some("8/8 w")).is_err());
+        assert!(PyBoard::new("8/8/8/8/8/8/8/9").is_err());
+    }
+}
@@ -0,0 +1,513 @@
+use crate::core::ParserError;
+use crate::game_representation::{Color, Game};
+use crate::move_generation::{movegen, Action};
+use crate::pgn::{self, PgnMove};
+
+/// A single "if the opponent plays `condition`, reply with `response`" instruction
+///
+/// The continuations are the conditions that apply once both moves were played.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conditional {
+    /// The move of the opponent, `None` for every move without a condition of its own
+    pub condition: Option<Action>,
+    pub response: Action,
+    /// Always empty if the condition is `None`, as the position after it is not known
+    pub continuations: Vec<Conditional>,
+}
+
+/// Conditional moves as offered on correspondence chess servers
+///
+/// The player who just moved announces replies to the moves the opponent might play next,
+/// e.g. "if 12...Nf6 then 13.e5". The announcements form a tree: every node answers one
+/// opponent move and may be followed by further conditions. Every move is checked for legality
+/// when it is added.
+///
+/// The tree can be written to and read from PGN movetext. The first branch of every node is
+/// the main line, the others are written as variations of the condition they replace:
+/// `12... Nf6 (12... d5 13. exd5) 13. e5`. Moves are written in SAN and read with the PGN
+/// parser, see [`parse_pgn`].
+///
+/// Correspondence servers write the response to every other move of the opponent after a
+/// null move: `12... Nf6 (12... -- 13. Bd3) 13. e5` plays Bd3 unless the opponent answers with
+/// Nf6, as long as Bd3 is still legal then. Such a condition is always the last branch.
+///
+/// [`parse_pgn`]: ../pgn/fn.parse_pgn.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConditionalMoves {
+    start_fen: String,
+    branches: Vec<Conditional>,
+}
+
+impl ConditionalMoves {
+    /// Returns an empty set of conditions, waiting for the opponent to move in the given position
+    ///
+    /// # Errors
+    /// * The FEN can not be parsed by [`Game::from_fen`]
+    ///
+    /// [`Game::from_fen`]: ../game_representation/struct.Game.html#method.from_fen
+    pub fn from_fen(fen: &str) -> Result<ConditionalMoves, ParserError> {
+        let game = Game::from_fen(fen)?;
+        Ok(ConditionalMoves {
+            start_fen: game.to_fen(),
+            branches: Vec::new(),
+        })
+    }
+
+    /// Returns the position the next condition is played from
+    pub fn start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    /// Returns the conditions for the next opponent move
+    pub fn branches(&self) -> &[Conditional] {
+        &self.branches
+    }
+
+    /// Adds a line of alternating conditions and responses, starting with a condition
+    ///
+    /// Parts of the line that are already known are shared with the existing conditions.
+    ///
+    /// # Errors
+    /// * The line is empty or ends with a condition without response
+    /// * Any move of the line is not legal in its position
+    /// * The line answers a known condition with a different response
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// # use rust_chess::record::ConditionalMoves;
+    /// let mut game = Game::startpos();
+    /// game.execute_action(&Action::from_san("e4", &game).unwrap());
+    /// let mut conditionals = ConditionalMoves::from_fen(&game.to_fen()).unwrap();
+    ///
+    /// let condition = Action::from_san("e5", &game).unwrap();
+    /// game.execute_action(&condition);
+    /// let response = Action::from_san("Nf3", &game).unwrap();
+    /// conditionals.add_line(&[condition, response]).unwrap();
+    ///
+    /// assert_eq!(conditionals.play(&condition), Some(response));
+    /// ```
+    pub fn add_line(&mut self, line: &[Action]) -> Result<(), ParserError> {
+        if line.is_empty() || line.len() % 2 != 0 {
+            return Err(ParserError::InvalidParameter(
+                "Conditional line does not end with a response",
+            ));
+        }
+        let mut game = Game::from_fen(&self.start_fen).expect("start fen was validated");
+        for action in line {
+            if !movegen::legal_moves(&game).contains(action) {
+                return Err(ParserError::InvalidParameter(
+                    "Conditional line contains an illegal move",
+                ));
+            }
+            game.execute_action(action);
+        }
+
+        let mut branches = &mut self.branches;
+        for pair in line.chunks(2) {
+            let index = match branches.iter().position(|b| b.condition == Some(pair[0])) {
+                Some(index) => {
+                    if branches[index].response != pair[1] {
+                        return Err(ParserError::InvalidParameter(
+                            "Condition already has a different response",
+                        ));
+                    }
+                    index
+                }
+                None => {
+                    // the response to all other moves stays last
+                    let index = match branches.last() {
+                        Some(last) if last.condition.is_none() => branches.len() - 1,
+                        _ => branches.len(),
+                    };
+                    branches.insert(
+                        index,
+                        Conditional {
+                            condition: Some(pair[0]),
+                            response: pair[1],
+                            continuations: Vec::new(),
+                        },
+                    );
+                    index
+                }
+            };
+            branches = &mut branches[index].continuations;
+        }
+        Ok(())
+    }
+
+    /// Adds the response to every opponent move without a condition after the given line
+    ///
+    /// The line of conditions and responses has to be known already, an empty line adds the
+    /// response to the next opponent move. The response is checked for legality after a null
+    /// move and only played if it is still legal after the move of the opponent.
+    ///
+    /// # Errors
+    /// * The line is not a known line of conditions and responses
+    /// * The response is not legal after the line and a null move
+    /// * A different response to all other moves is already known
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// # use rust_chess::record::ConditionalMoves;
+    /// let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+    /// let mut conditionals = ConditionalMoves::from_fen(fen).unwrap();
+    /// let mut game = Game::from_fen(fen).unwrap();
+    /// game.make_null_move();
+    /// conditionals.add_default(&[], Action::from_san("d4", &game).unwrap()).unwrap();
+    /// assert_eq!(conditionals.to_pgn(), "1... -- 2. d4");
+    ///
+    /// let mut game = Game::from_fen(fen).unwrap();
+    /// let c5 = Action::from_san("c5", &game).unwrap();
+    /// game.execute_action(&c5);
+    /// let response = conditionals.play(&c5);
+    /// assert_eq!(response, Some(Action::from_san("d4", &game).unwrap()));
+    /// ```
+    pub fn add_default(&mut self, line: &[Action], response: Action) -> Result<(), ParserError> {
+        let mut branches = &mut self.branches;
+        for pair in line.chunks(2) {
+            let known = match pair {
+                [condition, response] => branches
+                    .iter()
+                    .position(|b| b.condition == Some(*condition) && b.response == *response),
+                _ => None,
+            };
+            match known {
+                Some(index) => branches = &mut branches[index].continuations,
+                None => {
+                    return Err(ParserError::InvalidParameter(
+                        "Conditional line is not known",
+                    ))
+                }
+            }
+        }
+        let mut game = position_after(&self.start_fen, line);
+        game.make_null_move();
+        if !movegen::legal_moves(&game).contains(&response) {
+            return Err(ParserError::InvalidParameter(
+                "Conditional line contains an illegal move",
+            ));
+        }
+        match branches.last() {
+            Some(last) if last.condition.is_none() => {
+                if last.response != response {
+                    return Err(ParserError::InvalidParameter(
+                        "Condition already has a different response",
+                    ));
+                }
+            }
+            _ => branches.push(Conditional {
+                condition: None,
+                response,
+                continuations: Vec::new(),
+            }),
+        }
+        Ok(())
+    }
+
+    /// Applies the move the opponent actually played and returns the announced response
+    ///
+    /// If the move was one of the conditions, the remaining conditions start after the response.
+    /// Otherwise the response to all other moves is played if there is one and it is legal, and
+    /// the tree is cleared.
+    pub fn play(&mut self, condition: &Action) -> Option<Action> {
+        let mut game = Game::from_fen(&self.start_fen).expect("start fen was validated");
+        game.execute_action(condition);
+        let mut branches = std::mem::take(&mut self.branches);
+        let index = branches
+            .iter()
+            .position(|b| b.condition == Some(*condition))
+            .or_else(|| {
+                branches.iter().position(|b| {
+                    b.condition.is_none() && movegen::legal_moves(&game).contains(&b.response)
+                })
+            });
+        match index.map(|index| branches.swap_remove(index)) {
+            Some(branch) => {
+                game.execute_action(&branch.response);
+                self.start_fen = game.to_fen();
+                self.branches = branch.continuations;
+                Some(branch.response)
+            }
+            None => {
+                self.start_fen = game.to_fen();
+                None
+            }
+        }
+    }
+
+    /// Returns the conditions as PGN movetext with variations for alternative conditions
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::move_generation::Action;
+    /// # use rust_chess::record::ConditionalMoves;
+    /// let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+    /// let movetext = "1... e5 (1... c5 2. Nf3) 2. Nf3 Nc6 3. Bb5";
+    /// let conditionals = ConditionalMoves::from_pgn(fen, movetext).unwrap();
+    /// assert_eq!(conditionals.branches().len(), 2);
+    /// assert_eq!(conditionals.to_pgn(), movetext);
+    /// ```
+    pub fn to_pgn(&self) -> String {
+        let game = Game::from_fen(&self.start_fen).expect("start fen was validated");
+        let mut tokens = Vec::new();
+        write_branches(&self.branches, game, true, &mut tokens);
+        tokens.join(" ")
+    }
+
+    /// Reads conditions from PGN movetext, played from the given position
+    ///
+    /// The movetext is read like the movetext of a game by [`parse_pgn`], so move numbers,
+    /// annotations, comments and the game termination marker are ignored. Every variation
+    /// replaces the move in front of it. A null move `--` followed by a response ends a line,
+    /// see [`add_default`].
+    ///
+    /// # Errors
+    /// * The FEN can not be parsed
+    /// * Any error of [`parse_pgn`] for the movetext
+    /// * A move can not be parsed by [`Action::from_san`]
+    /// * A null move is played in check or is not the last condition of its line
+    /// * Any error of [`add_line`] for the lines contained in the movetext
+    ///
+    /// [`parse_pgn`]: ../pgn/fn.parse_pgn.html
+    /// [`Action::from_san`]: ../move_generation/struct.Action.html#method.from_san
+    /// [`add_line`]: #method.add_line
+    /// [`add_default`]: #method.add_default
+    pub fn from_pgn(fen: &str, movetext: &str) -> Result<ConditionalMoves, ParserError> {
+        let mut conditionals = ConditionalMoves::from_fen(fen)?;
+        let moves = pgn::parse_pgn(movetext)?
+            .into_iter()
+            .next()
+            .map(|game| game.moves)
+            .unwrap_or_default();
+        let game = Game::from_fen(&conditionals.start_fen).expect("start fen was validated");
+        let mut lines = Vec::new();
+        collect_lines(&moves, game, Vec::new(), &mut lines)?;
+        for line in lines {
+            let actions: Vec<Action> = line.iter().flatten().copied().collect();
+            match line.iter().position(Option::is_none) {
+                None => conditionals.add_line(&actions)?,
+                // a null move condition followed by its response ends the line
+                Some(index) if index % 2 == 0 && index + 2 == line.len() => {
+                    conditionals.add_default(&actions[..index], actions[index])?
+                }
+                Some(_) => {
+                    return Err(ParserError::InvalidParameter(
+                        "Null move is only allowed as the last condition",
+                    ))
+                }
+            }
+        }
+        Ok(conditionals)
+    }
+}
+
+/// Writes the first branch as main line and the others as variations of its condition
+fn write_branches(
+    branches: &[Conditional],
+    mut game: Game,
+    force_number: bool,
+    tokens: &mut Vec<String>,
+) {
+    let main = match branches.first() {
+        Some(main) => main,
+        None => return,
+    };
+    let before_condition = game.clone();
+    write_move(main.condition.as_ref(), &mut game, force_number, tokens);
+    for alternative in &branches[1..] {
+        let mut variation = Vec::new();
+        let mut alt_game = before_condition.clone();
+        write_move(
+            alternative.condition.as_ref(),
+            &mut alt_game,
+            true,
+            &mut variation,
+        );
+        write_move(
+            Some(&alternative.response),
+            &mut alt_game,
+            false,
+            &mut variation,
+        );
+        write_branches(&alternative.continuations, alt_game, false, &mut variation);
+        tokens.push(format!("({})", variation.join(" ")));
+    }
+    write_move(Some(&main.response), &mut game, branches.len() > 1, tokens);
+    write_branches(&main.continuations, game, false, tokens);
+}
+
+/// Writes the move with its move number if needed and executes it on the game
+///
+/// `None` is written as the null move `--`.
+fn write_move(
+    action: Option<&Action>,
+    game: &mut Game,
+    force_number: bool,
+    tokens: &mut Vec<String>,
+) {
+    match game.color_to_move {
+        Color::White => tokens.push(format!("{}.", game.get_full_move_clock())),
+        Color::Black if force_number => tokens.push(format!("{}...", game.get_full_move_clock())),
+        Color::Black => {}
+    }
+    match action {
+        Some(action) => {
+            tokens.push(action.to_san(game));
+            game.execute_action(action);
+        }
+        None => {
+            tokens.push(String::from("--"));
+            game.make_null_move();
+        }
+    }
+}
+
+/// Collects the line of the moves and the lines of their variations, played from the game
+///
+/// Every line is added to `lines` with the moves in front of it, the line of this call before
+/// those of its variations. Null moves are added as `None`.
+fn collect_lines(
+    moves: &[PgnMove],
+    mut game: Game,
+    prefix: Vec<Option<Action>>,
+    lines: &mut Vec<Vec<Option<Action>>>,
+) -> Result<(), ParserError> {
+    if moves.is_empty() {
+        return Ok(());
+    }
+    let index = lines.len();
+    lines.push(Vec::new());
+    let mut line = prefix;
+    for pgn_move in moves {
+        for variation in &pgn_move.variations {
+            collect_lines(variation, game.clone(), line.clone(), lines)?;
+        }
+        if pgn_move.is_null() {
+            pgn::pass(&mut game)?;
+            line.push(None);
+        } else {
+            let action = pgn_move.to_action(&game)?;
+            game.execute_action(&action);
+            line.push(Some(action));
+        }
+    }
+    lines[index] = line;
+    Ok(())
+}
+
+/// Returns the position after playing the actions from the given position
+fn position_after(fen: &str, actions: &[Action]) -> Game {
+    let mut game = Game::from_fen(fen).expect("fen was validated");
+    for action in actions {
+        game.execute_action(action);
+    }
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFTER_E4: &str = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+
+    #[test]
+    fn illegal_conditions_are_rejected() {
+        let mut conditionals = ConditionalMoves::from_fen(AFTER_E4).unwrap();
+        let game = Game::from_fen(AFTER_E4).unwrap();
+        let e5 = Action::from_san("e5", &game).unwrap();
+        // white can not answer with a black move
+        assert!(conditionals.add_line(&[e5, e5]).is_err());
+        assert!(conditionals.add_line(&[e5]).is_err());
+        assert!(conditionals.branches().is_empty());
+    }
+
+    #[test]
+    fn conflicting_responses() {
+        let mut conditionals = ConditionalMoves::from_pgn(AFTER_E4, "1... e5 2. Nf3").unwrap();
+        let mut game = Game::from_fen(AFTER_E4).unwrap();
+        let e5 = Action::from_san("e5", &game).unwrap();
+        game.execute_action(&e5);
+        let nc3 = Action::from_san("Nc3", &game).unwrap();
+        assert!(conditionals.add_line(&[e5, nc3]).is_err());
+    }
+
+    #[test]
+    fn playing_through_conditions() {
+        let mut conditionals =
+            ConditionalMoves::from_pgn(AFTER_E4, "1... e5 2. Nf3 Nc6 (2... d6 3. d4) 3. Bb5 *")
+                .unwrap();
+        let game = Game::from_fen(AFTER_E4).unwrap();
+        let e5 = Action::from_san("e5", &game).unwrap();
+        assert!(conditionals.play(&e5).is_some());
+        assert_eq!(conditionals.branches().len(), 2);
+        assert_eq!(conditionals.to_pgn(), "2... Nc6 (2... d6 3. d4) 3. Bb5");
+
+        // a move without condition voids the rest
+        let game = Game::from_fen(conditionals.start_fen()).unwrap();
+        let a6 = Action::from_san("a6", &game).unwrap();
+        assert_eq!(conditionals.play(&a6), None);
+        assert!(conditionals.branches().is_empty());
+    }
+
+    #[test]
+    fn null_move_conditions() {
+        let movetext = "1... d5 (1... -- 2. e5) 2. exd5 Qxd5 (2... -- 3. Nf3) 3. Nc3";
+        let conditionals = ConditionalMoves::from_pgn(AFTER_E4, movetext).unwrap();
+        assert_eq!(conditionals.branches()[1].condition, None);
+        assert_eq!(conditionals.to_pgn(), movetext);
+
+        // the response to all other moves is played while it is legal
+        let game = Game::from_fen(AFTER_E4).unwrap();
+        let mut played = conditionals.clone();
+        let c5 = Action::from_san("c5", &game).unwrap();
+        let mut after_c5 = Game::from_fen(AFTER_E4).unwrap();
+        after_c5.execute_action(&c5);
+        let push = Action::from_san("e5", &after_c5).unwrap();
+        assert_eq!(played.play(&c5), Some(push));
+        assert!(played.branches().is_empty());
+        let mut played = conditionals.clone();
+        let e5 = Action::from_san("e5", &game).unwrap();
+        assert_eq!(played.play(&e5), None);
+
+        // a null move has to be the last condition of its line and must not escape check
+        assert!(ConditionalMoves::from_pgn(AFTER_E4, "1... -- 2. d4 d5").is_err());
+        assert!(ConditionalMoves::from_pgn(AFTER_E4, "1... -- 2. d4 (2. Nf3)").is_err());
+        let check = "rnbqkbnr/ppppp1pp/8/5p1Q/4P3/8/PPPP1PPP/RNB1KBNR b KQkq - 1 2";
+        assert!(ConditionalMoves::from_pgn(check, "2... -- 3. Qe8+").is_err());
+    }
+
+    #[test]
+    fn movetext_shared_with_pgn() {
+        // annotations and comments of analysis PGNs are skipped
+        let annotated = "1... e5! {the main line} (1... c5 $2 2. Nf3) 2. Nf3 Nc6 3. Bb5 $14 *";
+        let conditionals = ConditionalMoves::from_pgn(AFTER_E4, annotated).unwrap();
+        let movetext = conditionals.to_pgn();
+        assert_eq!(movetext, "1... e5 (1... c5 2. Nf3) 2. Nf3 Nc6 3. Bb5");
+
+        // the written conditions are a valid PGN movetext
+        let game = pgn::PgnGame::parse(&movetext).unwrap();
+        let sans: Vec<&str> = game.moves.iter().map(|m| m.san.as_str()).collect();
+        assert_eq!(sans, vec!["e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(game.moves[0].variations[0].len(), 2);
+
+        // checks and mates are marked like in any other SAN
+        let movetext = "1... f6 2. d4 g5 3. Qh5#";
+        let conditionals = ConditionalMoves::from_pgn(AFTER_E4, "1... f6 2. d4 g5 3. Qh5").unwrap();
+        assert_eq!(conditionals.to_pgn(), movetext);
+        assert_eq!(
+            ConditionalMoves::from_pgn(AFTER_E4, movetext),
+            Ok(conditionals)
+        );
+    }
+
+    #[test]
+    fn unbalanced_movetext() {
+        assert!(ConditionalMoves::from_pgn(AFTER_E4, "1... e5 2. Nf3)").is_err());
+        assert!(ConditionalMoves::from_pgn(AFTER_E4, "(1... e5 2. Nf3)").is_err());
+        assert!(ConditionalMoves::from_pgn(AFTER_E4, "1... e5 (1... d5 2. exd5").is_err());
+    }
+}
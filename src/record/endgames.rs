@@ -0,0 +1,118 @@
+use super::RecordedGame;
+use crate::game_representation::MaterialKey;
+
+impl RecordedGame {
+    /// Returns the part of the game played from the first position with the given material
+    ///
+    /// The key matches regardless of which color holds which side of the material, so a key for
+    /// KRPvKR also finds games where black has the extra pawn. Returns `None` if the game never
+    /// reaches the material balance.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::{Game, MaterialKey};
+    /// # use rust_chess::move_generation::Action;
+    /// # use rust_chess::record::RecordedGame;
+    /// let mut record = RecordedGame::from_fen("4k3/8/8/8/8/8/1r2PR2/4K3 w - - 0 1").unwrap();
+    /// let game = record.position_at(0);
+    /// record.push(Action::from_san("Rf8", &game).unwrap());
+    /// let key = MaterialKey::from_signature("KRPvKR").unwrap();
+    /// let ending = record.material_entry(key).unwrap();
+    /// assert_eq!(ending.len(), 1);
+    /// assert!(record.material_entry(MaterialKey::from_signature("KRvKR").unwrap()).is_none());
+    /// ```
+    pub fn material_entry(&self, key: MaterialKey) -> Option<RecordedGame> {
+        let flipped = key.flipped();
+        let mut game = self.position_at(0);
+        for (ply, action) in self.actions().iter().enumerate() {
+            let position = MaterialKey::from_board(&game.board);
+            if position == key || position == flipped {
+                return Some(self.tail_from(&game.to_fen(), ply));
+            }
+            game.execute_action(action);
+        }
+        let position = MaterialKey::from_board(&game.board);
+        if position == key || position == flipped {
+            Some(self.tail_from(&game.to_fen(), self.len()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a record of all actions from `ply` on, starting at the given position
+    fn tail_from(&self, fen: &str, ply: usize) -> RecordedGame {
+        let mut tail = RecordedGame::from_fen(fen).expect("fen was produced by Game::to_fen");
+        for (index, action) in self.actions().iter().enumerate().skip(ply) {
+            let metadata = self.metadata(index).cloned().unwrap_or_default();
+            tail.push_with_metadata(*action, metadata);
+        }
+        tail
+    }
+}
+
+/// Streams the endings with the given material out of a collection of games
+///
+/// Every game reaching the material balance yields the sub-game starting at the position where
+/// the balance was first entered, see [`RecordedGame::material_entry`]. Games never reaching it
+/// are skipped. The games are only replayed while the returned iterator is advanced.
+///
+/// [`RecordedGame::material_entry`]: struct.RecordedGame.html#method.material_entry
+pub fn endgames_by_material<'a, I>(
+    games: I,
+    key: MaterialKey,
+) -> impl Iterator<Item = RecordedGame> + 'a
+where
+    I: IntoIterator<Item = &'a RecordedGame>,
+    I::IntoIter: 'a,
+{
+    games
+        .into_iter()
+        .filter_map(move |game| game.material_entry(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_representation::Game;
+    use crate::move_generation::Action;
+
+    fn record(fen: &str, sans: &[&str]) -> RecordedGame {
+        let mut record = RecordedGame::from_fen(fen).unwrap();
+        let mut game = Game::from_fen(fen).unwrap();
+        for san in sans {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+            record.push(action);
+        }
+        record
+    }
+
+    #[test]
+    fn entry_after_a_capture() {
+        // white trades the rooks on e7, leaving the bare kings
+        let game = record(
+            "4k3/4r3/8/8/8/8/4R3/3K4 w - - 0 1",
+            &["Rxe7", "Kxe7", "Kd2"],
+        );
+        let key = MaterialKey::from_signature("KvK").unwrap();
+        let ending = game.material_entry(key).unwrap();
+        assert_eq!(ending.len(), 1);
+        assert_eq!(ending.start_fen(), "8/4k3/8/8/8/8/8/3K4 w - - 0 2");
+    }
+
+    #[test]
+    fn filtering_a_database() {
+        let games = vec![
+            record("4k3/8/8/8/8/8/1r2PR2/4K3 w - - 0 1", &["Rf8"]),
+            record("4k3/1p3r2/8/8/8/8/1R6/4K3 b - - 0 1", &["Kd7"]),
+            RecordedGame::new(),
+        ];
+        let key = MaterialKey::from_signature("KRPvKR").unwrap();
+        let endings: Vec<_> = endgames_by_material(&games, key).collect();
+        assert_eq!(endings.len(), 2);
+        assert_eq!(
+            endings[1].start_fen(),
+            "4k3/1p3r2/8/8/8/8/1R6/4K3 b - - 0 1"
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use super::RecordedGame;
+use crate::core::ParserError;
+use crate::game_representation::{Game, UndoToken};
+use crate::move_generation::{movegen, Action};
+
+/// Handle of a node in a [`GameTree`], the root has the id 0
+///
+/// [`GameTree`]: struct.GameTree.html
+pub type NodeId = usize;
+
+struct TreeNode {
+    action: Option<Action>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+/// A game with variations and a cursor to navigate it
+///
+/// Every node is the position after the action leading to it, the root is the starting
+/// position. The first child of a node continues the main line, all other children are
+/// variations. The cursor points at the current node, whose position is kept up to date while
+/// stepping through the tree.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::record::GameTree;
+/// let mut tree = GameTree::new();
+/// let e4 = Action::from_san("e4", tree.position()).unwrap();
+/// tree.play(e4).unwrap();
+/// tree.play(Action::from_san("e5", tree.position()).unwrap()).unwrap();
+/// tree.back();
+/// tree.play(Action::from_san("c5", tree.position()).unwrap()).unwrap();
+/// assert_eq!(tree.children(tree.parent(tree.current()).unwrap()).len(), 2);
+/// tree.go_to_ply(0);
+/// tree.forward();
+/// tree.forward();
+/// assert_eq!(
+///     tree.position().to_fen(),
+///     "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
+/// );
+/// ```
+pub struct GameTree {
+    start_fen: String,
+    nodes: Vec<TreeNode>,
+    current: NodeId,
+    game: Game,
+    // tokens to undo the actions from the root to the current node, oldest first
+    undo_tokens: Vec<UndoToken>,
+}
+
+impl GameTree {
+    /// Returns a tree containing only the canonical starting position
+    pub fn new() -> GameTree {
+        GameTree::from_game(Game::startpos())
+    }
+
+    /// Returns a tree containing only the position of the given Forsyth-Edwards Notation
+    ///
+    /// # Errors
+    /// * The FEN can not be parsed by [`Game::from_fen`]
+    ///
+    /// [`Game::from_fen`]: ../game_representation/struct.Game.html#method.from_fen
+    pub fn from_fen(fen: &str) -> Result<GameTree, ParserError> {
+        Ok(GameTree::from_game(Game::from_fen(fen)?))
+    }
+
+    /// Returns a tree with the recorded game as its main line, the cursor is at its end
+    ///
+    /// # Errors
+    /// * A recorded action is not legal in its position
+    pub fn from_record(record: &RecordedGame) -> Result<GameTree, ParserError> {
+        let mut tree = GameTree::from_fen(record.start_fen())?;
+        for action in record.actions() {
+            tree.play(*action)?;
+        }
+        Ok(tree)
+    }
+
+    fn from_game(game: Game) -> GameTree {
+        GameTree {
+            start_fen: game.to_fen(),
+            nodes: vec![TreeNode {
+                action: None,
+                parent: None,
+                children: Vec::new(),
+            }],
+            current: 0,
+            game,
+            undo_tokens: Vec::new(),
+        }
+    }
+
+    /// Returns the FEN of the starting position
+    pub fn start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    /// Returns the id of the root node
+    pub fn root(&self) -> NodeId {
+        0
+    }
+
+    /// Returns the id of the node the cursor points at
+    pub fn current(&self) -> NodeId {
+        self.current
+    }
+
+    /// Returns the position of the current node
+    pub fn position(&self) -> &Game {
+        &self.game
+    }
+
+    /// Returns the number of halfmoves from the root to the current node
+    pub fn ply(&self) -> usize {
+        self.undo_tokens.len()
+    }
+
+    /// Returns the action leading to the node, `None` for the root
+    ///
+    /// # Panics
+    /// * if the node does not exist
+    pub fn action(&self, node: NodeId) -> Option<Action> {
+        self.nodes[node].action
+    }
+
+    /// Returns the parent of the node, `None` for the root
+    ///
+    /// # Panics
+    /// * if the node does not exist
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes[node].parent
+    }
+
+    /// Returns the children of the node, starting with the main line
+    ///
+    /// # Panics
+    /// * if the node does not exist
+    pub fn children(&self, node: NodeId) -> &[NodeId] {
+        &self.nodes[node].children
+    }
+
+    /// Plays the action from the current node and moves the cursor to the resulting node
+    ///
+    /// If the action was already played from the current node, the existing node is used.
+    /// Otherwise a new node is added, continuing the main line if the current node has no
+    /// children yet and starting a new variation otherwise.
+    ///
+    /// # Errors
+    /// * The action is not legal in the current position
+    pub fn play(&mut self, action: Action) -> Result<NodeId, ParserError> {
+        let existing = self.nodes[self.current]
+            .children
+            .iter()
+            .find(|child| self.nodes[**child].action == Some(action))
+            .copied();
+        let node = match existing {
+            Some(node) => node,
+            None => {
+                if !movegen::legal_moves(&self.game).contains(&action) {
+                    return Err(ParserError::InvalidParameter("Action is not legal"));
+                }
+                self.nodes.push(TreeNode {
+                    action: Some(action),
+                    parent: Some(self.current),
+                    children: Vec::new(),
+                });
+                let node = self.nodes.len() - 1;
+                self.nodes[self.current].children.push(node);
+                node
+            }
+        };
+        self.enter(node);
+        Ok(node)
+    }
+
+    /// Moves the cursor to the parent of the current node
+    ///
+    /// Returns false if the cursor already is at the root.
+    pub fn back(&mut self) -> bool {
+        let parent = match self.nodes[self.current].parent {
+            Some(parent) => parent,
+            None => return false,
+        };
+        let action = self.nodes[self.current]
+            .action
+            .expect("only the root has no action");
+        let token = self.undo_tokens.pop().expect("one token per ply");
+        self.game.undo_action(&action, token);
+        self.current = parent;
+        true
+    }
+
+    /// Moves the cursor to the main line child of the current node
+    ///
+    /// Returns false if the current node has no children.
+    pub fn forward(&mut self) -> bool {
+        match self.nodes[self.current].children.first() {
+            Some(child) => {
+                self.enter(*child);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the cursor to the node of the given ply on the current line
+    ///
+    /// Earlier plies are reached by going back, later ones by following the main line from the
+    /// current node. Returns false and stops at the end of the line if the line is too short.
+    pub fn go_to_ply(&mut self, ply: usize) -> bool {
+        while self.ply() > ply {
+            self.back();
+        }
+        while self.ply() < ply {
+            if !self.forward() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Moves the cursor to the given node
+    ///
+    /// # Panics
+    /// * if the node does not exist
+    pub fn go_to(&mut self, node: NodeId) {
+        let path = self.path_to(node);
+        // go back to the last node shared by both lines, then follow the path to the target
+        let shared = self
+            .path_to(self.current)
+            .iter()
+            .zip(&path)
+            .take_while(|(a, b)| a == b)
+            .count();
+        while self.ply() > shared {
+            self.back();
+        }
+        for next in &path[shared..] {
+            self.enter(*next);
+        }
+    }
+
+    /// Makes the variation containing the node the main line from its branching point on
+    ///
+    /// Every ancestor of the node becomes the first child of its parent.
+    ///
+    /// # Panics
+    /// * if the node does not exist
+    pub fn promote_variation(&mut self, node: NodeId) {
+        for child in self.path_to(node) {
+            let parent = self.nodes[child]
+                .parent
+                .expect("the root is not on the path");
+            let children = &mut self.nodes[parent].children;
+            let index = children
+                .iter()
+                .position(|c| *c == child)
+                .expect("child of its parent");
+            let promoted = children.remove(index);
+            children.insert(0, promoted);
+        }
+    }
+
+    /// Returns the main line of the tree, ignoring all variations
+    pub fn main_line(&self) -> RecordedGame {
+        let mut record = self.empty_record();
+        let mut node = self.root();
+        while let Some(child) = self.nodes[node].children.first() {
+            record.push(
+                self.nodes[*child]
+                    .action
+                    .expect("only the root has no action"),
+            );
+            node = *child;
+        }
+        record
+    }
+
+    /// Returns the actions from the root to the current node
+    pub fn current_line(&self) -> RecordedGame {
+        let mut record = self.empty_record();
+        for node in self.path_to(self.current) {
+            record.push(
+                self.nodes[node]
+                    .action
+                    .expect("only the root has no action"),
+            );
+        }
+        record
+    }
+
+    fn empty_record(&self) -> RecordedGame {
+        RecordedGame::from_fen(&self.start_fen).expect("start fen was validated")
+    }
+
+    /// Returns the nodes from the first move to the given node, excluding the root
+    fn path_to(&self, node: NodeId) -> Vec<NodeId> {
+        let mut path = Vec::new();
+        let mut node = node;
+        while let Some(parent) = self.nodes[node].parent {
+            path.push(node);
+            node = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Moves the cursor to a child of the current node
+    fn enter(&mut self, child: NodeId) {
+        let action = self.nodes[child]
+            .action
+            .expect("only the root has no action");
+        self.undo_tokens.push(self.game.execute_action(&action));
+        self.current = child;
+    }
+}
+
+impl Default for GameTree {
+    fn default() -> Self {
+        GameTree::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_san(tree: &mut GameTree, moves: &[&str]) -> NodeId {
+        for san in moves {
+            let action = Action::from_san(san, tree.position()).unwrap();
+            tree.play(action).unwrap();
+        }
+        tree.current()
+    }
+
+    #[test]
+    fn branching_and_navigation() {
+        let mut tree = GameTree::new();
+        let main = play_san(&mut tree, &["e4", "e5", "Nf3", "Nc6"]);
+        tree.go_to_ply(1);
+        let sicilian = play_san(&mut tree, &["c5", "Nf3", "d6"]);
+        assert_eq!(tree.ply(), 4);
+        assert_eq!(tree.current_line().len(), 4);
+        assert_eq!(tree.main_line().len(), 4);
+
+        tree.go_to(main);
+        assert_eq!(
+            tree.position().to_fen(),
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3"
+        );
+        tree.go_to(sicilian);
+        assert_eq!(
+            tree.position().to_fen(),
+            "rnbqkbnr/pp2pppp/3p4/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 3"
+        );
+        assert!(!tree.forward());
+        assert!(!tree.go_to_ply(6));
+        assert_eq!(tree.ply(), 4);
+
+        tree.go_to_ply(0);
+        assert!(!tree.back());
+        assert_eq!(tree.position().to_fen(), Game::startpos().to_fen());
+        assert_eq!(tree.position().get_hash(), Game::startpos().get_hash());
+    }
+
+    #[test]
+    fn existing_moves_are_reused() {
+        let mut tree = GameTree::new();
+        let first = play_san(&mut tree, &["d4", "d5"]);
+        tree.go_to_ply(0);
+        assert_eq!(play_san(&mut tree, &["d4", "d5"]), first);
+        assert_eq!(tree.children(tree.root()).len(), 1);
+
+        let illegal = Action::from_san("Ke2", tree.position());
+        assert!(illegal.is_err() || tree.play(illegal.unwrap()).is_err());
+    }
+
+    #[test]
+    fn promoting_variations() {
+        let mut tree = GameTree::new();
+        play_san(&mut tree, &["e4", "e5"]);
+        tree.back();
+        let variation = play_san(&mut tree, &["c5", "Nf3"]);
+        tree.promote_variation(variation);
+        let main_line = tree.main_line();
+        assert_eq!(main_line.len(), 3);
+        assert_eq!(
+            main_line.position_at(3).to_fen(),
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+        assert_eq!(GameTree::from_record(&main_line).unwrap().ply(), 3);
+    }
+}
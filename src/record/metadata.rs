@@ -0,0 +1,232 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// A single value of the metadata of a move
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MetadataValue {
+    /// A duration like the remaining clock time, written as `H:MM:SS` with optional fractions
+    Time(Duration),
+    /// An engine evaluation in centipawns from the view of white, written in pawns like `0.31`
+    Eval(i32),
+    Integer(i64),
+    /// Any other text, which should not contain a `]`
+    Text(String),
+}
+
+impl MetadataValue {
+    /// Returns the value of a PGN comment command, the type depends on the key and the format
+    ///
+    /// Values of the `eval` key in pawns are evaluations, values of the form `H:MM:SS` are
+    /// durations and whole numbers are integers. Everything else is kept as text.
+    fn parse(key: &str, value: &str) -> MetadataValue {
+        if key == "eval" {
+            if let Ok(pawns) = value.parse::<f64>() {
+                return MetadataValue::Eval((pawns * 100.0).round() as i32);
+            }
+        }
+        if let Some(time) = parse_time(value) {
+            return MetadataValue::Time(time);
+        }
+        match value.parse() {
+            Ok(integer) => MetadataValue::Integer(integer),
+            Err(_) => MetadataValue::Text(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for MetadataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataValue::Time(time) => {
+                let seconds = time.as_secs();
+                write!(
+                    f,
+                    "{}:{:02}:{:02}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                )?;
+                match time.subsec_millis() {
+                    0 => Ok(()),
+                    millis => write!(f, ".{}", format!("{:03}", millis).trim_end_matches('0')),
+                }
+            }
+            MetadataValue::Eval(centipawns) => write!(f, "{:.2}", f64::from(*centipawns) / 100.0),
+            MetadataValue::Integer(integer) => write!(f, "{}", integer),
+            MetadataValue::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+/// Returns the duration written as `H:MM:SS` with an optional fraction of a second
+fn parse_time(value: &str) -> Option<Duration> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let (hours, minutes, seconds) = match parts[..] {
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        _ => return None,
+    };
+    let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !digits(hours) || minutes.len() != 2 || seconds.len() != 2 {
+        return None;
+    }
+    if !digits(minutes) || !digits(seconds) || !(fraction.is_empty() || digits(fraction)) {
+        return None;
+    }
+    let minutes: u64 = minutes.parse().ok()?;
+    let seconds: u64 = seconds.parse().ok()?;
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    let millis = format!("{:0<3}", fraction)[..3].parse().ok()?;
+    let total = hours.parse::<u64>().ok()? * 3600 + minutes * 60 + seconds;
+    Some(Duration::from_secs(total) + Duration::from_millis(millis))
+}
+
+/// A keyed store of typed values attached to a single move of a [`RecordedGame`]
+///
+/// Metadata is written to PGN as comment commands like `[%clk 0:05:00]`, the form lichess and
+/// ChessBase use for clock times and evaluations. Keys must not contain whitespace.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::record::{MetadataValue, MoveMetadata};
+/// # use std::time::Duration;
+/// let mut metadata = MoveMetadata::new();
+/// metadata.insert("clk", MetadataValue::Time(Duration::from_secs(295)));
+/// metadata.insert("eval", MetadataValue::Eval(-31));
+/// assert_eq!(metadata.to_pgn_comment(), "[%clk 0:04:55] [%eval -0.31]");
+///
+/// let read = MoveMetadata::from_pgn_comment("[%eval -0.31] Black is fine [%clk 0:04:55]");
+/// assert_eq!(read, metadata);
+/// ```
+///
+/// [`RecordedGame`]: struct.RecordedGame.html
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MoveMetadata {
+    entries: BTreeMap<String, MetadataValue>,
+}
+
+impl MoveMetadata {
+    /// Returns an empty store
+    pub fn new() -> MoveMetadata {
+        MoveMetadata::default()
+    }
+
+    /// Returns the metadata of all commands of a PGN comment, ignoring the other text
+    pub fn from_pgn_comment(comment: &str) -> MoveMetadata {
+        let mut metadata = MoveMetadata::new();
+        let mut rest = comment;
+        while let Some(start) = rest.find("[%") {
+            let command = &rest[start + 2..];
+            let end = match command.find(']') {
+                Some(end) => end,
+                None => break,
+            };
+            if let Some((key, value)) = command[..end].trim().split_once(char::is_whitespace) {
+                let value = MetadataValue::parse(key, value.trim());
+                metadata.insert(key, value);
+            }
+            rest = &command[end + 1..];
+        }
+        metadata
+    }
+
+    /// Returns the value of the key
+    pub fn get(&self, key: &str) -> Option<&MetadataValue> {
+        self.entries.get(key)
+    }
+
+    /// Returns the value of the key if it is a duration
+    pub fn time(&self, key: &str) -> Option<Duration> {
+        match self.get(key)? {
+            MetadataValue::Time(time) => Some(*time),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the key if it is an evaluation
+    pub fn eval(&self, key: &str) -> Option<i32> {
+        match self.get(key)? {
+            MetadataValue::Eval(centipawns) => Some(*centipawns),
+            _ => None,
+        }
+    }
+
+    /// Sets the value of the key and returns the value it replaces
+    pub fn insert(&mut self, key: &str, value: MetadataValue) -> Option<MetadataValue> {
+        self.entries.insert(key.to_string(), value)
+    }
+
+    /// Removes the key and returns its value
+    pub fn remove(&mut self, key: &str) -> Option<MetadataValue> {
+        self.entries.remove(key)
+    }
+
+    /// Returns all keys with their values, ordered by key
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &MetadataValue)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Returns the number of keys
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there is no metadata
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the metadata as commands of a PGN comment, without the braces
+    pub fn to_pgn_comment(&self) -> String {
+        let commands: Vec<String> = self
+            .iter()
+            .map(|(key, value)| format!("[%{} {}]", key, value))
+            .collect();
+        commands.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_round_trip() {
+        let mut metadata = MoveMetadata::new();
+        metadata.insert("emt", MetadataValue::Time(Duration::from_millis(3_250)));
+        metadata.insert(
+            "clk",
+            MetadataValue::Time(Duration::from_secs(3 * 3600 + 7)),
+        );
+        metadata.insert("depth", MetadataValue::Integer(24));
+        metadata.insert("tag", MetadataValue::Text(String::from("opening trap")));
+        let comment = metadata.to_pgn_comment();
+        assert_eq!(
+            comment,
+            "[%clk 3:00:07] [%depth 24] [%emt 0:00:03.25] [%tag opening trap]"
+        );
+        assert_eq!(MoveMetadata::from_pgn_comment(&comment), metadata);
+        assert_eq!(metadata.time("emt"), Some(Duration::from_millis(3_250)));
+        assert_eq!(metadata.eval("clk"), None);
+
+        let read = MoveMetadata::from_pgn_comment("[%clk 0:61:00] [%eval #-3] [%empty] [%open");
+        assert_eq!(
+            read.get("clk"),
+            Some(&MetadataValue::Text(String::from("0:61:00")))
+        );
+        assert_eq!(
+            read.get("eval"),
+            Some(&MetadataValue::Text(String::from("#-3")))
+        );
+        assert_eq!(read.len(), 2);
+    }
+}
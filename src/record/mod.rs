@@ -0,0 +1,19 @@
+//! All code related to recording and replaying played games
+
+mod conditional;
+mod endgames;
+mod game_tree;
+mod metadata;
+mod phases;
+mod recorded_game;
+mod repertoire;
+mod schedule;
+
+pub use conditional::{Conditional, ConditionalMoves};
+pub use endgames::endgames_by_material;
+pub use game_tree::{GameTree, NodeId};
+pub use metadata::{MetadataValue, MoveMetadata};
+pub use phases::GamePhase;
+pub use recorded_game::RecordedGame;
+pub use repertoire::Repertoire;
+pub use schedule::{Day, Recall, ReviewCard, ReviewScheduler};
@@ -0,0 +1,179 @@
+use super::RecordedGame;
+use crate::game_representation::{Board, Game};
+use std::ops::Range;
+
+/// The three classical phases of a chess game
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GamePhase {
+    Opening,
+    Middlegame,
+    Endgame,
+}
+
+/// Combined non-pawn material of both sides (N = B = 3, R = 5, Q = 9) at or below which the
+/// game is considered an endgame. That is for example rook and minor piece for both sides.
+const ENDGAME_MATERIAL: u32 = 26;
+/// Non-pawn material in the starting position minus a single exchange of minor pieces
+const OPENING_MATERIAL: u32 = 56;
+/// The opening is over after this many full moves at the latest
+const OPENING_MOVES: u32 = 15;
+/// The opening is over once fewer minor pieces than this stand on their starting fields
+const UNDEVELOPED_MINORS: u32 = 4;
+
+impl RecordedGame {
+    /// Returns the halfmove ranges spent in opening, middlegame and endgame
+    ///
+    /// The halfmove index `i` belongs to the phase of the position the `i`th recorded action was
+    /// played from. A game only moves forward through the phases, so every phase occurs at most
+    /// once and phases that were skipped (e.g. a game starting from an endgame position) are not
+    /// part of the result.
+    ///
+    /// The classification is heuristic:
+    /// * Endgame, if the combined non-pawn material is at most that of rook and minor piece
+    ///   for both sides
+    /// * Opening, if it is still early in the game, at most one pair of minor pieces was traded
+    ///   and most minor pieces are still undeveloped
+    /// * Middlegame otherwise
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// # use rust_chess::record::{GamePhase, RecordedGame};
+    /// let mut record = RecordedGame::new();
+    /// record.push(Action::from_san("e4", &Game::startpos()).unwrap());
+    /// assert_eq!(record.phases(), vec![(GamePhase::Opening, 0..1)]);
+    /// ```
+    pub fn phases(&self) -> Vec<(GamePhase, Range<usize>)> {
+        let mut game = self.position_at(0);
+        let mut phases: Vec<(GamePhase, Range<usize>)> = Vec::new();
+        for (ply, action) in self.actions().iter().enumerate() {
+            let mut phase = classify(&game);
+            if let Some((last_phase, range)) = phases.last_mut() {
+                phase = phase.max(*last_phase);
+                if phase == *last_phase {
+                    range.end = ply + 1;
+                    game.execute_action(action);
+                    continue;
+                }
+            }
+            phases.push((phase, ply..ply + 1));
+            game.execute_action(action);
+        }
+        phases
+    }
+}
+
+/// Returns the phase of a single position without considering the history of the game
+fn classify(game: &Game) -> GamePhase {
+    let material = non_pawn_material(&game.board);
+    if material <= ENDGAME_MATERIAL {
+        return GamePhase::Endgame;
+    }
+    if material >= OPENING_MATERIAL
+        && game.get_full_move_clock() <= OPENING_MOVES
+        && undeveloped_minors(&game.board) >= UNDEVELOPED_MINORS
+    {
+        return GamePhase::Opening;
+    }
+    GamePhase::Middlegame
+}
+
+/// Returns the non-pawn material of both sides combined
+fn non_pawn_material(board: &Board) -> u32 {
+    let queens = board.bishops & board.rooks;
+    let rooks = board.rooks & !queens;
+    let bishops = board.bishops & !queens;
+    queens.count_ones() * 9
+        + rooks.count_ones() * 5
+        + bishops.count_ones() * 3
+        + board.knights.count_ones() * 3
+}
+
+/// Returns the number of knights and bishops still standing on their starting fields
+fn undeveloped_minors(board: &Board) -> u32 {
+    // b1, c1, f1, g1 for white and b8, c8, f8, g8 for black
+    let white_home = 0b0110_0110u64 << 56;
+    let black_home = 0b0110_0110u64;
+    let knights_home = 0b0100_0010u64;
+    let bishops_home = 0b0010_0100u64;
+    let knights = board.knights & (knights_home | knights_home << 56);
+    let bishops = board.bishops & !board.rooks & (bishops_home | bishops_home << 56);
+    let minors = knights | bishops;
+    let whites = minors & board.whites & white_home;
+    let blacks = minors & !board.whites & black_home;
+    whites.count_ones() + blacks.count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bitboard;
+    use crate::game_representation::PieceType;
+    use crate::move_generation::{Action, ActionType};
+
+    fn record_from_san(fen: &str, moves: &[&str]) -> RecordedGame {
+        let mut record = RecordedGame::from_fen(fen).unwrap();
+        let mut game = Game::from_fen(fen).unwrap();
+        for san in moves {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+            record.push(action);
+        }
+        record
+    }
+
+    #[test]
+    fn opening_to_middlegame() {
+        let record = record_from_san(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[
+                "e4", "c5", "Nf3", "d6", "d4", "cxd4", "Nxd4", "Nf6", "Nc3", "g6", "Be3", "Bg7",
+                "f3", "O-O", "Qd2", "Nc6",
+            ],
+        );
+        assert_eq!(
+            record.phases(),
+            vec![(GamePhase::Opening, 0..12), (GamePhase::Middlegame, 12..16)]
+        );
+    }
+
+    #[test]
+    fn middlegame_to_endgame() {
+        let mut record = RecordedGame::from_fen("r2qk3/8/8/8/8/8/8/R2QK3 w - - 0 30").unwrap();
+        record.push(Action::new(
+            bitboard::field_repr_to_coords("d1").unwrap(),
+            bitboard::field_repr_to_coords("d8").unwrap(),
+            PieceType::Queen,
+            ActionType::Capture(PieceType::Queen),
+        ));
+        record.push(Action::new(
+            bitboard::field_repr_to_coords("e8").unwrap(),
+            bitboard::field_repr_to_coords("d8").unwrap(),
+            PieceType::King,
+            ActionType::Capture(PieceType::Queen),
+        ));
+        assert_eq!(
+            record.phases(),
+            vec![(GamePhase::Middlegame, 0..1), (GamePhase::Endgame, 1..2)]
+        );
+    }
+
+    #[test]
+    fn phases_never_go_back() {
+        // material only decreases, but development could be undone by retreating pieces
+        let record = record_from_san(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[
+                "Nf3", "Nf6", "Nc3", "Nc6", "Ng1", "Ng8", "Nb1", "Nb8", "Nc3", "Nc6", "Nf3", "Nf6",
+                "Ng1", "Ng8", "Nb1", "Nb8", "Nc3", "Nc6", "Nf3", "Nf6", "Ng1", "Ng8", "Nb1", "Nb8",
+                "Nc3", "Nc6", "Nb1", "Nb8", "Nc3", "Nc6", "Nb1", "Nb8", "Nc3",
+            ],
+        );
+        assert_eq!(
+            record.phases(),
+            vec![(GamePhase::Opening, 0..30), (GamePhase::Middlegame, 30..33)]
+        );
+        assert!(RecordedGame::new().phases().is_empty());
+    }
+}
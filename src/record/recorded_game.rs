@@ -0,0 +1,190 @@
+use super::MoveMetadata;
+use crate::core::ParserError;
+use crate::game_representation::Game;
+use crate::move_generation::Action;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A game of chess recorded as a starting position and the actions played from it
+///
+/// Only the starting position is stored, every later position is reconstructed by replaying
+/// the recorded actions. Actions are not checked for legality when they are recorded. Every
+/// action can carry [`MoveMetadata`] like clock times or evaluations.
+///
+/// [`MoveMetadata`]: struct.MoveMetadata.html
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecordedGame {
+    start_fen: String,
+    actions: Vec<Action>,
+    // metadata of the actions with the same index, may be shorter than the actions
+    #[cfg_attr(feature = "serde", serde(default))]
+    metadata: Vec<MoveMetadata>,
+}
+
+impl RecordedGame {
+    /// Returns an empty record starting from the canonical starting position
+    pub fn new() -> RecordedGame {
+        RecordedGame {
+            start_fen: Game::startpos().to_fen(),
+            actions: Vec::new(),
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Returns an empty record starting from the given Forsyth-Edwards Notation
+    ///
+    /// # Errors
+    /// * The FEN can not be parsed by [`Game::from_fen`]
+    ///
+    /// [`Game::from_fen`]: ../game_representation/struct.Game.html#method.from_fen
+    pub fn from_fen(fen: &str) -> Result<RecordedGame, ParserError> {
+        let start = Game::from_fen(fen)?;
+        Ok(RecordedGame {
+            start_fen: start.to_fen(),
+            actions: Vec::new(),
+            metadata: Vec::new(),
+        })
+    }
+
+    /// Appends an action to the end of the record
+    pub fn push(&mut self, action: Action) {
+        self.actions.push(action);
+    }
+
+    /// Appends an action together with its metadata to the end of the record
+    pub fn push_with_metadata(&mut self, action: Action, metadata: MoveMetadata) {
+        self.actions.push(action);
+        if !metadata.is_empty() {
+            self.metadata
+                .resize_with(self.actions.len() - 1, MoveMetadata::new);
+            self.metadata.push(metadata);
+        }
+    }
+
+    /// Returns the metadata of the action with the given index, `None` if it has none
+    pub fn metadata(&self, index: usize) -> Option<&MoveMetadata> {
+        self.metadata
+            .get(index)
+            .filter(|metadata| !metadata.is_empty())
+    }
+
+    /// Returns the metadata of the action with the given index for modification
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// # use rust_chess::record::{MetadataValue, RecordedGame};
+    /// let mut record = RecordedGame::new();
+    /// record.push(Action::from_san("e4", &Game::startpos()).unwrap());
+    /// assert!(record.metadata(0).is_none());
+    /// let metadata = record.metadata_mut(0).unwrap();
+    /// metadata.insert("eval", MetadataValue::Eval(30));
+    /// assert_eq!(record.metadata(0).unwrap().eval("eval"), Some(30));
+    /// assert!(record.metadata_mut(1).is_none());
+    /// ```
+    pub fn metadata_mut(&mut self, index: usize) -> Option<&mut MoveMetadata> {
+        if index >= self.actions.len() {
+            return None;
+        }
+        if index >= self.metadata.len() {
+            self.metadata.resize_with(index + 1, MoveMetadata::new);
+        }
+        self.metadata.get_mut(index)
+    }
+
+    /// Returns all recorded actions in the order they were played
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Returns the number of recorded halfmoves
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns true if no action was recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Returns the FEN of the starting position
+    pub fn start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    /// Returns the position after the given number of halfmoves
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::move_generation::Action;
+    /// # use rust_chess::record::RecordedGame;
+    /// let mut record = RecordedGame::new();
+    /// record.push(Action::from_san("e4", &Game::startpos()).unwrap());
+    /// assert_eq!(record.position_at(0).to_fen(), Game::startpos().to_fen());
+    /// assert_eq!(
+    ///     record.position_at(1).to_fen(),
+    ///     "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    /// * if ply is bigger than the number of recorded actions
+    pub fn position_at(&self, ply: usize) -> Game {
+        assert!(ply <= self.actions.len());
+        let mut game = Game::from_fen(&self.start_fen).expect("start fen was validated");
+        for action in &self.actions[..ply] {
+            game.execute_action(action);
+        }
+        game
+    }
+
+    /// Returns the FEN of every position in the game, starting with the initial position
+    pub fn fens(&self) -> Vec<String> {
+        let mut game = Game::from_fen(&self.start_fen).expect("start fen was validated");
+        let mut fens = Vec::with_capacity(self.actions.len() + 1);
+        fens.push(game.to_fen());
+        for action in &self.actions {
+            game.execute_action(action);
+            fens.push(game.to_fen());
+        }
+        fens
+    }
+}
+
+impl Default for RecordedGame {
+    fn default() -> Self {
+        RecordedGame::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaying_positions() {
+        let mut record = RecordedGame::new();
+        let mut game = Game::startpos();
+        for san in &["e4", "c5", "Nf3"] {
+            let action = Action::from_san(san, &game).unwrap();
+            game.execute_action(&action);
+            record.push(action);
+        }
+        assert_eq!(record.len(), 3);
+        assert_eq!(record.position_at(3).to_fen(), game.to_fen());
+        let fens = record.fens();
+        assert_eq!(fens.len(), 4);
+        assert_eq!(
+            fens[2],
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2"
+        );
+    }
+
+    #[test]
+    fn invalid_start_fen() {
+        assert!(RecordedGame::from_fen("8/8/8/8 w - -").is_err());
+    }
+}
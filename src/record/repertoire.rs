@@ -0,0 +1,460 @@
+use crate::core::ParserError;
+use crate::game_representation::{Color, Game};
+use crate::move_generation::{movegen, Action};
+use crate::pgn::{parse_pgn, PgnMove};
+use std::collections::HashMap;
+
+const PRACTICE_COMMAND: &str = "[%practiced ";
+
+/// An opening repertoire: the moves prepared for positions reachable from a start position
+///
+/// Moves are stored by position, so lines transposing into each other share their
+/// continuations. The first move added for a position is its main move, the others are
+/// alternatives. Repertoires contain the moves of both sides, the replies of the opponent
+/// define which lines have to be known.
+///
+/// Every move also counts how often it was practiced, a line is practiced once all of its moves
+/// were. Repertoires are read from and written to PGN with variations, practice counts are
+/// kept in `{[%practiced 3]}` comments after the moves.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::move_generation::Action;
+/// # use rust_chess::record::Repertoire;
+/// let mut repertoire = Repertoire::from_pgn("1. e4 e5 (1... c5 2. Nf3) 2. Nf3 *").unwrap();
+/// let mut game = Game::startpos();
+/// game.execute_action(&Action::from_san("e4", &game).unwrap());
+/// let replies: Vec<String> = repertoire.moves(&game).iter().map(|a| a.to_san(&game)).collect();
+/// assert_eq!(replies, vec!["e5", "c5"]);
+///
+/// let line = repertoire.lines().remove(0);
+/// repertoire.record_practice(&line).unwrap();
+/// assert_eq!(repertoire.unpracticed_lines().len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Repertoire {
+    start_fen: String,
+    // prepared moves by the hash of the position they are played in, main move first
+    moves: HashMap<u64, Vec<Action>>,
+    practiced: HashMap<(u64, Action), u32>,
+}
+
+impl Repertoire {
+    /// Returns an empty repertoire starting from the canonical starting position
+    pub fn new() -> Repertoire {
+        Repertoire::from_game(&Game::startpos())
+    }
+
+    /// Returns an empty repertoire starting from the given Forsyth-Edwards Notation
+    ///
+    /// # Errors
+    /// * The FEN can not be parsed by [`Game::from_fen`]
+    ///
+    /// [`Game::from_fen`]: ../game_representation/struct.Game.html#method.from_fen
+    pub fn from_fen(fen: &str) -> Result<Repertoire, ParserError> {
+        Ok(Repertoire::from_game(&Game::from_fen(fen)?))
+    }
+
+    fn from_game(game: &Game) -> Repertoire {
+        Repertoire {
+            start_fen: game.to_fen(),
+            moves: HashMap::new(),
+            practiced: HashMap::new(),
+        }
+    }
+
+    /// Returns the position all lines start from
+    pub fn start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    /// Returns the prepared moves for the position, the main move first
+    ///
+    /// The slice is empty if the position is not part of the repertoire or ends a line.
+    pub fn moves(&self, state: &Game) -> &[Action] {
+        self.moves
+            .get(&state.get_hash())
+            .map_or(&[], |moves| moves.as_slice())
+    }
+
+    /// Returns whether the action is one of the prepared moves in the position
+    pub fn contains(&self, state: &Game, action: &Action) -> bool {
+        self.moves(state).contains(action)
+    }
+
+    /// Adds a line played from the start position
+    ///
+    /// Moves that are already known keep their place, new moves become alternatives.
+    ///
+    /// # Errors
+    /// * Any move of the line is not legal in its position
+    pub fn add_line(&mut self, line: &[Action]) -> Result<(), ParserError> {
+        let mut game = self.start_position();
+        for action in line {
+            if !movegen::legal_moves(&game).contains(action) {
+                return Err(ParserError::InvalidParameter(
+                    "Repertoire line contains an illegal move",
+                ));
+            }
+            game.execute_action(action);
+        }
+        let mut game = self.start_position();
+        for action in line {
+            self.insert(&game, *action);
+            game.execute_action(action);
+        }
+        Ok(())
+    }
+
+    /// Returns all lines from the start position to a position without prepared moves
+    ///
+    /// Main moves come before alternatives. Lines reaching the same position by transposition
+    /// are listed separately, a line ends early if it repeats one of its own positions.
+    pub fn lines(&self) -> Vec<Vec<Action>> {
+        let mut lines = Vec::new();
+        self.collect_lines(
+            &mut self.start_position(),
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut lines,
+        );
+        lines
+    }
+
+    fn collect_lines(
+        &self,
+        game: &mut Game,
+        line: &mut Vec<Action>,
+        path: &mut Vec<u64>,
+        lines: &mut Vec<Vec<Action>>,
+    ) {
+        let hash = game.get_hash();
+        let moves = self.moves(game);
+        if moves.is_empty() || path.contains(&hash) {
+            if !line.is_empty() {
+                lines.push(line.clone());
+            }
+            return;
+        }
+        path.push(hash);
+        for action in moves {
+            let token = game.execute_action(action);
+            line.push(*action);
+            self.collect_lines(game, line, path, lines);
+            line.pop();
+            game.undo_action(action, token);
+        }
+        path.pop();
+    }
+
+    /// Records that the line was played through from the start position
+    ///
+    /// The line does not have to end a repertoire line, every move of it counts as practiced once.
+    ///
+    /// # Errors
+    /// * Any move of the line is not a prepared move of its position, nothing is recorded then
+    pub fn record_practice(&mut self, line: &[Action]) -> Result<(), ParserError> {
+        let mut game = self.start_position();
+        let mut keys = Vec::with_capacity(line.len());
+        for action in line {
+            if !self.contains(&game, action) {
+                return Err(ParserError::InvalidParameter(
+                    "Practiced move is not part of the repertoire",
+                ));
+            }
+            keys.push((game.get_hash(), *action));
+            game.execute_action(action);
+        }
+        for key in keys {
+            *self.practiced.entry(key).or_insert(0) += 1;
+        }
+        Ok(())
+    }
+
+    /// Returns how often the move was practiced in the position
+    pub fn times_practiced(&self, state: &Game, action: &Action) -> u32 {
+        self.practiced
+            .get(&(state.get_hash(), *action))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the lines containing a move that was never practiced, in the order of [`lines`]
+    ///
+    /// [`lines`]: #method.lines
+    pub fn unpracticed_lines(&self) -> Vec<Vec<Action>> {
+        self.lines()
+            .into_iter()
+            .filter(|line| {
+                let mut game = self.start_position();
+                line.iter().any(|action| {
+                    let practiced = self.times_practiced(&game, action) > 0;
+                    game.execute_action(action);
+                    !practiced
+                })
+            })
+            .collect()
+    }
+
+    /// Reads a repertoire from PGN, taking the moves of all games including their variations
+    ///
+    /// All games have to start from the same position, given by their `SetUp` and `FEN` tags.
+    /// Practice counts are read from `[%practiced n]` commands in the comments after a move.
+    /// A null move ends its line, as the moves after it show what the opponent threatens.
+    ///
+    /// # Errors
+    /// * Any error of [`parse_pgn`] or [`PgnGame::start_position`]
+    /// * The games start from different positions
+    /// * A move can not be parsed in its position
+    /// * A practice count is not a number
+    ///
+    /// [`parse_pgn`]: ../pgn/fn.parse_pgn.html
+    /// [`PgnGame::start_position`]: ../pgn/struct.PgnGame.html#method.start_position
+    pub fn from_pgn(text: &str) -> Result<Repertoire, ParserError> {
+        let games = parse_pgn(text)?;
+        let mut repertoire: Option<Repertoire> = None;
+        for pgn_game in &games {
+            let mut game = pgn_game.start_position()?;
+            let repertoire = repertoire.get_or_insert_with(|| Repertoire::from_game(&game));
+            if repertoire.start_position().get_hash() != game.get_hash() {
+                return Err(ParserError::InvalidParameter(
+                    "Repertoire games start from different positions",
+                ));
+            }
+            repertoire.read_moves(&pgn_game.moves, &mut game)?;
+        }
+        Ok(repertoire.unwrap_or_default())
+    }
+
+    fn read_moves(&mut self, moves: &[PgnMove], game: &mut Game) -> Result<(), ParserError> {
+        for pgn_move in moves {
+            if pgn_move.is_null() {
+                // the moves after a pass show a threat, only the alternatives to it are prepared
+                for variation in &pgn_move.variations {
+                    self.read_moves(variation, &mut game.clone())?;
+                }
+                return Ok(());
+            }
+            let action = pgn_move.to_action(game)?;
+            self.insert(game, action);
+            if let Some(count) = practice_count(&pgn_move.comments)? {
+                self.practiced.insert((game.get_hash(), action), count);
+            }
+            for variation in &pgn_move.variations {
+                self.read_moves(variation, &mut game.clone())?;
+            }
+            game.execute_action(&action);
+        }
+        Ok(())
+    }
+
+    /// Returns the repertoire as a PGN game with alternatives written as variations
+    ///
+    /// `SetUp` and `FEN` tags are only written for repertoires not starting from the standard
+    /// position. Positions reached by transposition are written with their continuations on
+    /// every path leading to them.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::record::Repertoire;
+    /// let pgn = "1. d4 d5 (1... Nf6 2. c4 {[%practiced 2]} 2... e6) 2. c4 *\n";
+    /// assert_eq!(Repertoire::from_pgn(pgn).unwrap().to_pgn(), pgn);
+    /// ```
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+        let mut game = self.start_position();
+        if self.start_fen != Game::startpos().to_fen() {
+            pgn.push_str(&format!("[SetUp \"1\"]\n[FEN \"{}\"]\n\n", self.start_fen));
+        }
+        let mut tokens = Vec::new();
+        self.write_moves(&mut game, true, &mut Vec::new(), &mut tokens);
+        tokens.push(String::from("*"));
+        pgn.push_str(&tokens.join(" "));
+        pgn.push('\n');
+        pgn
+    }
+
+    /// Writes the main move and its continuation, alternatives as variations after the main move
+    fn write_moves(
+        &self,
+        game: &mut Game,
+        force_number: bool,
+        path: &mut Vec<u64>,
+        tokens: &mut Vec<String>,
+    ) {
+        let hash = game.get_hash();
+        let (main, alternatives) = match self.moves(game).split_first() {
+            Some(moves) if !path.contains(&hash) => moves,
+            _ => return,
+        };
+        path.push(hash);
+        let mut after_main = game.clone();
+        let commented = self.write_move(main, &mut after_main, force_number, tokens);
+        for alternative in alternatives {
+            let mut variation = Vec::new();
+            let mut alt_game = game.clone();
+            let commented = self.write_move(alternative, &mut alt_game, true, &mut variation);
+            self.write_moves(&mut alt_game, commented, path, &mut variation);
+            tokens.push(format!("({})", variation.join(" ")));
+        }
+        let force_number = commented || !alternatives.is_empty();
+        self.write_moves(&mut after_main, force_number, path, tokens);
+        path.pop();
+    }
+
+    /// Writes the move with its move number if needed and executes it on the game
+    ///
+    /// Returns whether a practice comment was written after the move.
+    fn write_move(
+        &self,
+        action: &Action,
+        game: &mut Game,
+        force_number: bool,
+        tokens: &mut Vec<String>,
+    ) -> bool {
+        match game.color_to_move {
+            Color::White => tokens.push(format!("{}.", game.get_full_move_clock())),
+            Color::Black if force_number => {
+                tokens.push(format!("{}...", game.get_full_move_clock()))
+            }
+            Color::Black => {}
+        }
+        tokens.push(action.to_san(game));
+        let count = self.times_practiced(game, action);
+        game.execute_action(action);
+        if count > 0 {
+            tokens.push(format!("{{{}{}]}}", PRACTICE_COMMAND, count));
+        }
+        count > 0
+    }
+
+    fn insert(&mut self, state: &Game, action: Action) {
+        let moves = self.moves.entry(state.get_hash()).or_default();
+        if !moves.contains(&action) {
+            moves.push(action);
+        }
+    }
+
+    fn start_position(&self) -> Game {
+        Game::from_fen(&self.start_fen).expect("start fen was validated")
+    }
+}
+
+impl Default for Repertoire {
+    fn default() -> Self {
+        Repertoire::new()
+    }
+}
+
+/// Returns the count of the first `[%practiced n]` command in the comments
+fn practice_count(comments: &[String]) -> Result<Option<u32>, ParserError> {
+    for comment in comments {
+        if let Some(start) = comment.find(PRACTICE_COMMAND) {
+            let rest = &comment[start + PRACTICE_COMMAND.len()..];
+            let count = rest
+                .split(']')
+                .next()
+                .and_then(|count| count.trim().parse().ok())
+                .ok_or(ParserError::InvalidParameter(
+                    "Practice count is not a number",
+                ))?;
+            return Ok(Some(count));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(sans: &[&str]) -> Vec<Action> {
+        let mut game = Game::startpos();
+        sans.iter()
+            .map(|san| {
+                let action = Action::from_san(san, &game).unwrap();
+                game.execute_action(&action);
+                action
+            })
+            .collect()
+    }
+
+    #[test]
+    fn transpositions_share_moves() {
+        let mut repertoire = Repertoire::new();
+        repertoire
+            .add_line(&line(&["d4", "Nf6", "c4", "e6"]))
+            .unwrap();
+        let transposition = line(&["c4", "e6", "d4", "Nf6", "Nc3"]);
+        repertoire.add_line(&transposition).unwrap();
+        // the first line now continues with Nc3 as well
+        let lines = repertoire.lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines
+            .iter()
+            .all(|line| line.len() == 5 && line[4] == transposition[4]));
+        let mut game = Game::startpos();
+        for action in line(&["d4", "Nf6", "c4", "e6"]) {
+            game.execute_action(&action);
+        }
+        assert_eq!(repertoire.moves(&game).len(), 1);
+        assert_eq!(repertoire.moves(&Game::startpos()).len(), 2);
+    }
+
+    #[test]
+    fn illegal_lines_are_rejected() {
+        let mut repertoire = Repertoire::new();
+        let mut illegal = line(&["e4", "e5"]);
+        illegal.push(illegal[1]);
+        assert!(repertoire.add_line(&illegal).is_err());
+        assert!(repertoire.lines().is_empty());
+    }
+
+    #[test]
+    fn practice_tracking() {
+        let mut repertoire = Repertoire::from_pgn("1. e4 e5 (1... c5 2. Nf3 d6) 2. Nf3 *").unwrap();
+        assert_eq!(repertoire.unpracticed_lines().len(), 2);
+        assert!(repertoire.record_practice(&line(&["d4"])).is_err());
+        repertoire.record_practice(&line(&["e4", "c5"])).unwrap();
+        assert_eq!(repertoire.unpracticed_lines().len(), 2);
+        repertoire
+            .record_practice(&line(&["e4", "c5", "Nf3", "d6"]))
+            .unwrap();
+        assert_eq!(
+            repertoire.unpracticed_lines(),
+            vec![line(&["e4", "e5", "Nf3"])]
+        );
+
+        let pgn = repertoire.to_pgn();
+        assert_eq!(
+            pgn,
+            "1. e4 {[%practiced 2]} 1... e5 (1... c5 {[%practiced 2]} 2. Nf3 {[%practiced 1]} \
+             2... d6 {[%practiced 1]}) 2. Nf3 *\n"
+        );
+        let read = Repertoire::from_pgn(&pgn).unwrap();
+        assert_eq!(read.unpracticed_lines(), repertoire.unpracticed_lines());
+        assert_eq!(read.to_pgn(), pgn);
+    }
+
+    #[test]
+    fn games_share_the_start_position() {
+        let repertoire = Repertoire::from_pgn("1. e4 e5 *\n\n1. d4 d5 *").unwrap();
+        assert_eq!(repertoire.lines().len(), 2);
+        let pgn = "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"]\n\n1. e4 *\n";
+        assert_eq!(Repertoire::from_pgn(pgn).unwrap().to_pgn(), pgn);
+        let mixed = format!("1. e4 *\n\n{}", pgn);
+        assert!(Repertoire::from_pgn(&mixed).is_err());
+        assert!(Repertoire::from_pgn("1. e4 {[%practiced many]} *").is_err());
+    }
+
+    #[test]
+    fn null_moves_end_their_line() {
+        let repertoire =
+            Repertoire::from_pgn("1. e4 e5 (1... Z0 (1... c5) 2. d4) 2. Nf3 -- 3. d4 *").unwrap();
+        assert_eq!(
+            repertoire.lines(),
+            vec![line(&["e4", "e5", "Nf3"]), line(&["e4", "c5"])]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar day, counted in days since the Unix epoch
+pub type Day = u32;
+
+/// How well a position was recalled during a review, the grades 0 to 5 of SM-2
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Recall {
+    /// Complete blackout
+    Blackout = 0,
+    /// Incorrect, but the correct move seemed familiar once shown
+    Incorrect = 1,
+    /// Incorrect, but the correct move was easy to recall once shown
+    IncorrectButEasy = 2,
+    /// Correct, recalled with serious difficulty
+    Difficult = 3,
+    /// Correct after some hesitation
+    Hesitant = 4,
+    /// Correct without hesitation
+    Perfect = 5,
+}
+
+impl Recall {
+    /// Returns true if the grade counts as a successful review
+    pub fn is_correct(self) -> bool {
+        self as u8 >= Recall::Difficult as u8
+    }
+}
+
+/// The review state of a single position
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReviewCard {
+    /// Number of successful reviews in a row
+    pub repetitions: u32,
+    /// Days between the last review and the next one
+    pub interval: u32,
+    /// Factor the interval grows by with every successful review, at least 1.3
+    pub ease: f64,
+    /// The day the position is due for its next review
+    pub due: Day,
+}
+
+impl ReviewCard {
+    /// Returns a card that has never been reviewed, due on the given day
+    pub fn new(due: Day) -> ReviewCard {
+        ReviewCard {
+            repetitions: 0,
+            interval: 0,
+            ease: 2.5,
+            due,
+        }
+    }
+
+    /// Applies the result of a review on the given day as described by SM-2
+    ///
+    /// A correct answer grows the interval to 1 day, then 6 days and then by the ease factor.
+    /// An incorrect answer starts over with an interval of 1 day. The ease factor is adjusted by
+    /// the grade in both cases.
+    pub fn review(&mut self, recall: Recall, today: Day) {
+        if recall.is_correct() {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (f64::from(self.interval) * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        } else {
+            self.repetitions = 0;
+            self.interval = 1;
+        }
+        let missing = f64::from(5 - recall as u8);
+        self.ease = (self.ease + 0.1 - missing * (0.08 + missing * 0.02)).max(1.3);
+        self.due = today + self.interval;
+    }
+}
+
+/// A spaced repetition schedule for training positions, following the SM-2 algorithm
+///
+/// Positions are identified by a key, usually their hash from [`Game::get_hash`]. Every
+/// position has a [`ReviewCard`] storing when it is due next. With the `serde` feature the
+/// schedule can be serialized to store the training progress.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::record::{Recall, ReviewScheduler};
+/// let mut scheduler = ReviewScheduler::new();
+/// let key = Game::startpos().get_hash();
+/// let today = 19000;
+/// scheduler.add(key, today);
+/// assert_eq!(scheduler.due(today), vec![key]);
+///
+/// scheduler.review(key, Recall::Perfect, today);
+/// assert!(scheduler.due(today).is_empty());
+/// assert_eq!(scheduler.due(today + 1), vec![key]);
+/// ```
+///
+/// [`Game::get_hash`]: ../game_representation/struct.Game.html#method.get_hash
+/// [`ReviewCard`]: struct.ReviewCard.html
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReviewScheduler {
+    cards: HashMap<u64, ReviewCard>,
+}
+
+impl ReviewScheduler {
+    /// Returns an empty schedule
+    pub fn new() -> ReviewScheduler {
+        ReviewScheduler::default()
+    }
+
+    /// Returns the current day according to the system clock
+    pub fn today() -> Day {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        (seconds / (24 * 60 * 60)) as Day
+    }
+
+    /// Adds a new position that is due on the given day, known positions are kept as they are
+    pub fn add(&mut self, key: u64, due: Day) {
+        self.cards
+            .entry(key)
+            .or_insert_with(|| ReviewCard::new(due));
+    }
+
+    /// Removes a position from the schedule and returns its card
+    pub fn remove(&mut self, key: u64) -> Option<ReviewCard> {
+        self.cards.remove(&key)
+    }
+
+    /// Returns the review state of the position
+    pub fn card(&self, key: u64) -> Option<&ReviewCard> {
+        self.cards.get(&key)
+    }
+
+    /// Returns the number of scheduled positions
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns true if no position is scheduled
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Records the result of a review on the given day and returns the updated card
+    ///
+    /// Positions that were not scheduled yet are added.
+    pub fn review(&mut self, key: u64, recall: Recall, today: Day) -> &ReviewCard {
+        let card = self
+            .cards
+            .entry(key)
+            .or_insert_with(|| ReviewCard::new(today));
+        card.review(recall, today);
+        card
+    }
+
+    /// Returns the positions due on or before the given day, the longest overdue first
+    ///
+    /// Positions due on the same day are ordered by their key.
+    pub fn due(&self, today: Day) -> Vec<u64> {
+        let mut due: Vec<(Day, u64)> = self
+            .cards
+            .iter()
+            .filter(|(_, card)| card.due <= today)
+            .map(|(key, card)| (card.due, *key))
+            .collect();
+        due.sort_unstable();
+        due.into_iter().map(|(_, key)| key).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_follow_sm2() {
+        let mut card = ReviewCard::new(0);
+        let mut today = 0;
+        let mut intervals = Vec::new();
+        for _ in 0..4 {
+            card.review(Recall::Hesitant, today);
+            intervals.push(card.interval);
+            today = card.due;
+        }
+        // grade 4 keeps the ease factor at 2.5
+        assert_eq!(intervals, vec![1, 6, 15, 38]);
+        assert_eq!(card.due, 60);
+
+        card.review(Recall::Incorrect, today);
+        assert_eq!((card.repetitions, card.interval, card.due), (0, 1, 61));
+        assert!((card.ease - 1.96).abs() < 1e-9);
+        for _ in 0..5 {
+            card.review(Recall::Blackout, today);
+        }
+        assert_eq!(card.ease, 1.3);
+    }
+
+    #[test]
+    fn due_positions() {
+        let mut scheduler = ReviewScheduler::new();
+        scheduler.add(3, 10);
+        scheduler.add(2, 12);
+        scheduler.add(1, 10);
+        // adding again does not reset the card
+        scheduler.add(2, 5);
+        assert_eq!(scheduler.due(9), Vec::<u64>::new());
+        assert_eq!(scheduler.due(12), vec![1, 3, 2]);
+
+        scheduler.review(1, Recall::Difficult, 12);
+        assert_eq!(scheduler.due(12), vec![3, 2]);
+        assert_eq!(scheduler.card(1).unwrap().due, 13);
+        assert_eq!(scheduler.remove(3).map(|card| card.due), Some(10));
+        assert_eq!(scheduler.len(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialization_round_trip() {
+        let mut scheduler = ReviewScheduler::new();
+        scheduler.review(42, Recall::Perfect, 100);
+        scheduler.add(7, 101);
+        let json = serde_json::to_string(&scheduler).unwrap();
+        let read: ReviewScheduler = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, scheduler);
+    }
+}
@@ -0,0 +1,7 @@
+//! Self-checks of the move notation that can be run against any position, and reproducible
+//! random games to run them on
+
+mod random;
+mod san;
+
+pub use san::{check_san_roundtrip, SanMismatch};
@@ -0,0 +1,93 @@
+use crate::core::zobrist::splitmix64;
+use crate::game_representation::Game;
+use crate::move_generation::movegen;
+
+/// The longest game played by [`Game::random_position`], in plies
+///
+/// [`Game::random_position`]: ../game_representation/struct.Game.html#method.random_position
+const MAX_RANDOM_PLIES: u64 = 120;
+
+impl Game {
+    /// Returns the game after playing random legal actions from the start position
+    ///
+    /// Every ply picks one of the legal actions uniformly, the playout ends early once the
+    /// color to move has no legal action. The same seed always plays the same game, so a
+    /// failing check found by a fuzz or property test can be reproduced from its seed. The
+    /// returned game keeps its history, so repetitions are detected as usual.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// let game = Game::random_playout(42, 10);
+    /// assert_eq!(game.get_full_move_clock(), 6);
+    /// assert_eq!(game, Game::random_playout(42, 10));
+    /// ```
+    pub fn random_playout(seed: u64, plies: usize) -> Game {
+        let mut game = Game::startpos();
+        let mut state = seed;
+        for _ in 0..plies {
+            let actions = movegen::legal_moves(&game);
+            if actions.is_empty() {
+                break;
+            }
+            let (next_state, random) = splitmix64(state);
+            state = next_state;
+            game.execute_action(&actions[(random % actions.len() as u64) as usize]);
+        }
+        game
+    }
+
+    /// Returns a random position reached by a [`random_playout`] of random length
+    ///
+    /// The playouts last up to 120 plies, so the positions range from the opening to
+    /// simplified endgames and include castling rights, en passant fields and promoted pieces
+    /// often enough to exercise the notation and the move generation.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::sanity::check_san_roundtrip;
+    /// for seed in 0..20 {
+    ///     let game = Game::random_position(seed);
+    ///     assert_eq!(Game::from_fen(&game.to_fen()).unwrap(), game);
+    ///     assert!(check_san_roundtrip(&game).is_ok());
+    /// }
+    /// ```
+    ///
+    /// [`random_playout`]: #method.random_playout
+    pub fn random_position(seed: u64) -> Game {
+        let (seed, plies) = splitmix64(seed);
+        Game::random_playout(seed, (plies % (MAX_RANDOM_PLIES + 1)) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sanity::check_san_roundtrip;
+
+    #[test]
+    fn random_games_roundtrip() {
+        let mut lengths = Vec::new();
+        for seed in 0..100 {
+            let mut game = Game::random_position(seed);
+            assert_eq!(game, Game::random_position(seed));
+            lengths.push(game.get_full_move_clock());
+
+            let fen = game.to_fen();
+            assert_eq!(Game::from_fen(&fen).unwrap().to_fen(), fen);
+            assert_eq!(check_san_roundtrip(&game).map(|_| ()), Ok(()));
+            let hash = game.get_hash();
+            for action in movegen::legal_moves(&game) {
+                let token = game.execute_action(&action);
+                game.undo_action(&action, token);
+                assert_eq!((game.to_fen(), game.get_hash()), (fen.clone(), hash));
+            }
+        }
+        lengths.sort_unstable();
+        lengths.dedup();
+        assert!(lengths.len() > 20);
+        assert_ne!(Game::random_playout(1, 40), Game::random_playout(2, 40));
+        assert_eq!(Game::random_playout(1, 0), Game::startpos());
+    }
+}
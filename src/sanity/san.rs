@@ -0,0 +1,89 @@
+use crate::game_representation::Game;
+use crate::move_generation::{movegen, Action};
+
+/// A legal move whose SAN does not lead back to the move itself
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SanMismatch {
+    /// The FEN of the checked position
+    pub fen: String,
+    /// The legal move that was written
+    pub action: Action,
+    /// The SAN written for the move
+    pub san: String,
+    /// The move read back from the SAN, `None` if it could not be read
+    pub parsed: Option<Action>,
+}
+
+/// Writes every legal move of the position as SAN and reads it back
+///
+/// Returns the number of checked moves. Every move has to be read back as itself, which also
+/// means no two legal moves share their SAN. Check and mate suffixes have to match the
+/// position after the move.
+///
+/// # Errors
+/// * The first move failing the round trip
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::sanity::check_san_roundtrip;
+/// assert_eq!(check_san_roundtrip(&Game::startpos()), Ok(20));
+/// ```
+pub fn check_san_roundtrip(state: &Game) -> Result<usize, SanMismatch> {
+    let moves = movegen::legal_moves(state);
+    for action in &moves {
+        let san = action.to_san(state);
+        let mismatch = |parsed| SanMismatch {
+            fen: state.to_fen(),
+            action: *action,
+            san: san.clone(),
+            parsed,
+        };
+        let parsed = match Action::from_san(&san, state) {
+            Ok(parsed) => parsed,
+            Err(_) => return Err(mismatch(None)),
+        };
+        if parsed != *action || !suffix_matches(&san, state, action) {
+            return Err(mismatch(Some(parsed)));
+        }
+    }
+    Ok(moves.len())
+}
+
+/// Returns true if the check or mate suffix of the SAN is correct
+fn suffix_matches(san: &str, state: &Game, action: &Action) -> bool {
+    let mut after = state.clone();
+    after.execute_action(action);
+    if after.is_checkmate() {
+        san.ends_with('#')
+    } else if after.is_in_check() {
+        san.ends_with('+')
+    } else {
+        !san.ends_with(['+', '#'])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_of_tricky_positions() {
+        for (fen, count) in &[
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                48,
+            ),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 14),
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                6,
+            ),
+            ("4k3/8/8/8/1Q3Q2/8/1Q6/4K3 w - - 0 1", 63),
+            ("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", 7),
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            assert_eq!(check_san_roundtrip(&game), Ok(*count), "{}", fen);
+        }
+    }
+}
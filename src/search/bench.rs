@@ -0,0 +1,93 @@
+use super::{SearchLimits, SearchOptions, Searcher};
+use crate::game_representation::Game;
+use std::fmt;
+use std::time::Duration;
+
+/// The positions searched by [`bench`], from the opening over tactical middlegames to endgames
+///
+/// [`bench`]: fn.bench.html
+const BENCH_POSITIONS: [&str; 6] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r1b2k1r/ppp1bppp/8/1B1Q4/5q2/2P5/PPP2PPP/R3R1K1 w - - 1 1",
+    "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1",
+];
+
+/// The summed up statistics of the searches of a [`bench`] run
+///
+/// [`bench`]: fn.bench.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BenchStats {
+    /// The number of searched positions
+    pub positions: usize,
+    pub nodes: u64,
+    pub quiescence_nodes: u64,
+    /// The number of positions searched deeper because of a check
+    pub check_extensions: u64,
+    /// The number of actions searched deeper because they were singular
+    pub singular_extensions: u64,
+    pub elapsed: Duration,
+}
+
+impl BenchStats {
+    /// Returns the number of nodes searched per second
+    pub fn nps(&self) -> u64 {
+        let micros = self.elapsed.as_micros().max(1);
+        (self.nodes as u128 * 1_000_000 / micros) as u64
+    }
+}
+
+impl fmt::Display for BenchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "positions {} nodes {} qnodes {} check extensions {} singular extensions {} time {} nps {}",
+            self.positions,
+            self.nodes,
+            self.quiescence_nodes,
+            self.check_extensions,
+            self.singular_extensions,
+            self.elapsed.as_millis(),
+            self.nps()
+        )
+    }
+}
+
+/// Searches a fixed set of positions to the given depth and sums up the statistics
+///
+/// The transposition table is cleared before every position, so the number of nodes only
+/// depends on the depth and the options. Running the bench with different options shows the
+/// effect of an extension on the size of the tree.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::search::{bench, SearchOptions};
+/// let without = SearchOptions {
+///     check_extensions: false,
+///     singular_extensions: false,
+///     ..SearchOptions::default()
+/// };
+/// let stats = bench(2, without);
+/// assert_eq!(stats.positions, 6);
+/// assert_eq!((stats.check_extensions, stats.singular_extensions), (0, 0));
+/// assert!(bench(2, SearchOptions::default()).check_extensions > 0);
+/// ```
+pub fn bench(depth: u32, options: SearchOptions) -> BenchStats {
+    let mut searcher = Searcher::new(SearchLimits::depth(depth));
+    searcher.set_options(options);
+    let mut stats = BenchStats::default();
+    for fen in BENCH_POSITIONS.iter() {
+        let game = Game::from_fen(fen).expect("bench positions are valid");
+        searcher.table_mut().clear();
+        let result = searcher.search(&game);
+        stats.positions += 1;
+        stats.nodes += result.nodes;
+        stats.quiescence_nodes += result.quiescence_nodes;
+        stats.check_extensions += result.check_extensions;
+        stats.singular_extensions += result.singular_extensions;
+        stats.elapsed += result.elapsed;
+    }
+    stats
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+/// The deepest iteration a search runs, in plies
+pub const MAX_DEPTH: u32 = 64;
+
+/// Conditions ending a search, the search stops as soon as one of them is reached
+///
+/// The default has no limit besides [`MAX_DEPTH`]. The first iteration is always completed,
+/// so even a search with a tiny limit returns a legal action.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::search::SearchLimits;
+/// # use std::time::Duration;
+/// let limits = SearchLimits {
+///     time: Some(Duration::from_millis(100)),
+///     ..SearchLimits::default()
+/// };
+/// assert_eq!(limits.depth, None);
+/// ```
+///
+/// [`MAX_DEPTH`]: constant.MAX_DEPTH.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// The last iteration to run, in plies
+    pub depth: Option<u32>,
+    /// The number of nodes after which the running iteration is aborted
+    pub nodes: Option<u64>,
+    /// The time after which the running iteration is aborted
+    pub time: Option<Duration>,
+}
+
+impl SearchLimits {
+    /// Returns limits searching exactly to the given depth
+    pub fn depth(depth: u32) -> SearchLimits {
+        SearchLimits {
+            depth: Some(depth),
+            ..SearchLimits::default()
+        }
+    }
+
+    /// Returns the deepest iteration allowed by the limits
+    ///
+    /// A depth of 0 still runs the first iteration, so that a legal action is found.
+    pub(crate) fn max_depth(&self) -> u32 {
+        self.depth
+            .map_or(MAX_DEPTH, |depth| depth.clamp(1, MAX_DEPTH))
+    }
+}
@@ -0,0 +1,15 @@
+//! Finding the best action of a position with an iterative deepening alpha-beta search
+
+mod bench;
+mod limits;
+mod options;
+mod searcher;
+mod transposition;
+mod tree;
+
+pub use bench::{bench, BenchStats};
+pub use limits::{SearchLimits, MAX_DEPTH};
+pub use options::SearchOptions;
+pub use searcher::{is_mate_score, SearchResult, Searcher, MATE_SCORE};
+pub use transposition::{Bound, TableEntry, TranspositionTable};
+pub use tree::{SearchNode, SearchTree};
@@ -0,0 +1,56 @@
+/// How the search looks deeper into forcing lines and varies its choice in the opening
+///
+/// An extended action is searched one ply deeper than the remaining depth. Extensions are only
+/// applied up to twice the depth of the running iteration, so a long series of checks can not
+/// blow up the search. Both extensions are enabled by default, [`bench`] shows how they change
+/// the number of nodes and how many actions they extend.
+///
+/// Opening randomization is disabled by default. With `random_plies` set, the action played in
+/// the first plies of a game is picked at random among the best root actions scoring within
+/// `random_window` centipawns of the best one, so self-play games and casual opponents do not
+/// repeat the same opening every game. The choice is deterministic for a given seed.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::search::{SearchLimits, SearchOptions, Searcher};
+/// let mut searcher = Searcher::new(SearchLimits::depth(4));
+/// searcher.set_options(SearchOptions {
+///     singular_extensions: false,
+///     ..SearchOptions::default()
+/// });
+/// assert!(searcher.options().check_extensions);
+///
+/// // pick any of the moves within 30 centipawns in the first eight plies
+/// let options = SearchOptions { random_plies: 8, random_seed: 7, ..SearchOptions::default() };
+/// searcher.set_options(options);
+/// ```
+///
+/// [`bench`]: fn.bench.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Searches positions in which the color to move is in check one ply deeper
+    pub check_extensions: bool,
+    /// Searches the action of the transposition table one ply deeper if every other action
+    /// fails low against a reduced search with a lowered bound
+    pub singular_extensions: bool,
+    /// The number of plies from the start of the game in which the action is picked at random
+    pub random_plies: u32,
+    /// How many centipawns worse than the best action a randomly picked action may score
+    pub random_window: i32,
+    /// The seed of the random choices, set again by every call of [`Searcher::set_options`]
+    ///
+    /// [`Searcher::set_options`]: struct.Searcher.html#method.set_options
+    pub random_seed: u64,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            check_extensions: true,
+            singular_extensions: true,
+            random_plies: 0,
+            random_window: 30,
+            random_seed: 0,
+        }
+    }
+}
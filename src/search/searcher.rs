@@ -0,0 +1,908 @@
+use super::{Bound, SearchLimits, SearchOptions, SearchTree, TableEntry, TranspositionTable};
+use crate::core::zobrist::splitmix64;
+use crate::evaluation::see;
+use crate::game_representation::{Game, PieceType};
+use crate::move_generation::{movegen, Action, MovePicker};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The score of a position in which the color to move is checkmated
+///
+/// Mates further away score closer to zero by one per ply, so shorter mates are preferred.
+pub const MATE_SCORE: i32 = 30_000;
+
+/// Returns true if the score announces a checkmate for either side
+///
+/// # Examples
+/// ```
+/// # use rust_chess::search::{is_mate_score, MATE_SCORE};
+/// assert!(is_mate_score(MATE_SCORE - 3));
+/// assert!(is_mate_score(-MATE_SCORE + 2));
+/// assert!(!is_mate_score(900));
+/// ```
+pub fn is_mate_score(score: i32) -> bool {
+    score.abs() > MATE_SCORE - super::MAX_DEPTH as i32 * 2
+}
+
+// how many nodes are searched between two looks at the clock
+const TIME_CHECK_INTERVAL: u64 = 1024;
+// the shallowest remaining depth at which the hash move is tested for being singular
+const SINGULAR_MIN_DEPTH: u32 = 4;
+// how far below the stored score the other actions have to stay per ply of depth
+const SINGULAR_MARGIN: i32 = 25;
+// how many of the best root actions the opening randomization chooses from
+const RANDOM_CANDIDATES: usize = 4;
+
+/// The outcome of a search
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    /// The best action found, `None` if the color to move has no legal action
+    pub best_move: Option<Action>,
+    /// The expected line of play starting with the best action
+    pub pv: Vec<Action>,
+    /// The score of the position in centipawns from the point of view of the color to move
+    pub score: i32,
+    /// The depth of the last completed iteration
+    pub depth: u32,
+    /// The number of positions visited by all iterations, including quiescence nodes
+    pub nodes: u64,
+    /// The number of positions visited by the quiescence search
+    pub quiescence_nodes: u64,
+    /// The number of positions searched deeper because the color to move was in check
+    pub check_extensions: u64,
+    /// The number of actions searched deeper because no other action came close to them
+    pub singular_extensions: u64,
+    /// The time spent searching
+    pub elapsed: Duration,
+}
+
+impl SearchResult {
+    /// Returns the number of nodes searched per second
+    pub fn nps(&self) -> u64 {
+        let micros = self.elapsed.as_micros().max(1);
+        (self.nodes as u128 * 1_000_000 / micros) as u64
+    }
+}
+
+/// An iterative deepening alpha-beta search with a quiescence search at the leaves
+///
+/// Every iteration searches one ply deeper than the previous one, trying the best action of the
+/// previous iteration first. Results are kept in a [`TranspositionTable`], which cuts off
+/// positions reached again and provides the action to try first. At the leaves, captures and
+/// queen promotions not losing material are followed until the position is quiet and then
+/// scored with [`Game::evaluate`]. Repetitions and the fifty move rule count as draws. Checks
+/// and singular actions are searched deeper as configured by [`SearchOptions`].
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::search::{SearchLimits, Searcher, MATE_SCORE};
+/// let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+/// let result = Searcher::new(SearchLimits::depth(3)).search(&game);
+/// assert_eq!(result.best_move.unwrap().to_uci(), "a1a8");
+/// assert_eq!(result.score, MATE_SCORE - 1);
+/// ```
+///
+/// [`TranspositionTable`]: struct.TranspositionTable.html
+/// [`SearchOptions`]: struct.SearchOptions.html
+/// [`Game::evaluate`]: ../game_representation/struct.Game.html#method.evaluate
+#[derive(Clone, Debug)]
+pub struct Searcher {
+    limits: SearchLimits,
+    options: SearchOptions,
+    table: TranspositionTable,
+    start: Instant,
+    nodes: u64,
+    quiescence_nodes: u64,
+    check_extensions: u64,
+    singular_extensions: u64,
+    // the depth of the running iteration, bounding the extensions
+    iteration_depth: u32,
+    // state of the generator of the opening randomization
+    random_state: u64,
+    stopped: bool,
+    // the first iteration is never aborted
+    can_stop: bool,
+    stop_signal: Option<Arc<AtomicBool>>,
+    // the number of plies recorded into the search tree, 0 if no tree is recorded
+    tree_plies: u32,
+    // the tree of the running iteration and the node of the current position in it
+    tree: Option<SearchTree>,
+    tree_node: usize,
+    last_tree: Option<SearchTree>,
+}
+
+impl Searcher {
+    /// Returns a searcher stopping at the given limits, using a table of the default size
+    pub fn new(limits: SearchLimits) -> Searcher {
+        Searcher::with_table(limits, TranspositionTable::default())
+    }
+
+    /// Returns a searcher stopping at the given limits and using the given table
+    ///
+    /// The entries of the table are kept, so a table filled by an earlier search of the same
+    /// game speeds up the next one.
+    pub fn with_table(limits: SearchLimits, table: TranspositionTable) -> Searcher {
+        Searcher {
+            limits,
+            options: SearchOptions::default(),
+            table,
+            start: Instant::now(),
+            nodes: 0,
+            quiescence_nodes: 0,
+            check_extensions: 0,
+            singular_extensions: 0,
+            iteration_depth: 0,
+            random_state: 0,
+            stopped: false,
+            can_stop: false,
+            stop_signal: None,
+            tree_plies: 0,
+            tree: None,
+            tree_node: 0,
+            last_tree: None,
+        }
+    }
+
+    /// Returns the transposition table of the searcher
+    pub fn table(&self) -> &TranspositionTable {
+        &self.table
+    }
+
+    /// Returns the transposition table of the searcher, for example to resize or clear it
+    pub fn table_mut(&mut self) -> &mut TranspositionTable {
+        &mut self.table
+    }
+
+    /// Makes the searcher stop once the flag is set, for example by another thread
+    ///
+    /// The flag is treated like a limit, so the first iteration is still completed.
+    pub fn set_stop_signal(&mut self, signal: Arc<AtomicBool>) {
+        self.stop_signal = Some(signal);
+    }
+
+    /// Changes the limits of the following searches
+    pub fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    /// Makes the following searches record their first `plies` plies, 0 stops recording
+    ///
+    /// The tree grows quickly with the number of plies, see [`search_tree`]. With more than one
+    /// line, only the search of the first line is recorded.
+    ///
+    /// [`search_tree`]: #method.search_tree
+    pub fn record_tree(&mut self, plies: u32) {
+        self.tree_plies = plies;
+        if plies == 0 {
+            self.last_tree = None;
+        }
+    }
+
+    /// Returns the tree of the last completed iteration if recording is enabled
+    pub fn search_tree(&self) -> Option<&SearchTree> {
+        self.last_tree.as_ref()
+    }
+
+    /// Returns the options of the searcher
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    /// Changes the options of the following searches and restarts the random choices
+    pub fn set_options(&mut self, options: SearchOptions) {
+        self.options = options;
+        self.random_state = options.random_seed;
+    }
+
+    /// Searches the position until a limit is reached and returns the best action found
+    ///
+    /// In the first plies of a game, the action may be picked at random among several good
+    /// ones as configured by [`SearchOptions`].
+    ///
+    /// [`SearchOptions`]: struct.SearchOptions.html
+    pub fn search(&mut self, state: &Game) -> SearchResult {
+        let ply = (state.get_full_move_clock().max(1) - 1) * 2 + state.color_to_move as u32;
+        let lines = if ply < self.options.random_plies {
+            RANDOM_CANDIDATES
+        } else {
+            1
+        };
+        let mut results = self.search_multi_pv(state, lines);
+        match results.first() {
+            Some(best) => {
+                let best_score = best.score;
+                let candidates = results
+                    .iter()
+                    .take_while(|result| result.score >= best_score - self.options.random_window)
+                    .count();
+                let (state, random) = splitmix64(self.random_state);
+                self.random_state = state;
+                results.swap_remove(random as usize % candidates)
+            }
+            None => SearchResult {
+                best_move: None,
+                pv: Vec::new(),
+                score: if state.is_in_check() { -MATE_SCORE } else { 0 },
+                depth: 0,
+                nodes: 0,
+                quiescence_nodes: 0,
+                check_extensions: 0,
+                singular_extensions: 0,
+                elapsed: self.start.elapsed(),
+            },
+        }
+    }
+
+    /// Searches the position and returns the best lines starting with different actions
+    ///
+    /// Every iteration searches the root actions once per line, leaving out the first actions
+    /// of the lines found before, so every line has an exact score. The results are ordered
+    /// from the best to the worst line and there are fewer of them if the position has fewer
+    /// legal actions. Without legal actions no result is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Game;
+    /// # use rust_chess::search::{SearchLimits, Searcher, MATE_SCORE};
+    /// let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/R2R2K1 w - - 0 1").unwrap();
+    /// let lines = Searcher::new(SearchLimits::depth(2)).search_multi_pv(&game, 3);
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!((lines[0].score, lines[1].score), (MATE_SCORE - 1, MATE_SCORE - 1));
+    /// assert!(lines[2].score < lines[1].score);
+    /// ```
+    pub fn search_multi_pv(&mut self, state: &Game, lines: usize) -> Vec<SearchResult> {
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.quiescence_nodes = 0;
+        self.check_extensions = 0;
+        self.singular_extensions = 0;
+        self.stopped = false;
+        self.can_stop = false;
+        self.table.new_search();
+
+        let mut game = state.clone();
+        let mut root_moves = movegen::legal_moves(&game);
+        let lines = lines.max(1).min(root_moves.len());
+        let mut results: Vec<SearchResult> = Vec::with_capacity(lines);
+        for depth in 1..=self.limits.max_depth() {
+            self.iteration_depth = depth;
+            if self.tree_plies > 0 {
+                self.tree = Some(SearchTree::new(&game));
+                self.tree_node = 0;
+            }
+            let mut iteration = Vec::with_capacity(lines);
+            let mut tree = None;
+            for line in 0..lines {
+                let mut pv = Vec::new();
+                let score = self.search_root(&mut game, &mut root_moves[line..], depth, &mut pv);
+                if self.stopped {
+                    break;
+                }
+                iteration.push((score, pv));
+                // the later lines search the same root actions again
+                if line == 0 {
+                    tree = self.tree.take();
+                }
+            }
+            if self.stopped {
+                break;
+            }
+            if let Some(mut tree) = tree {
+                tree.set_score(tree.root(), iteration[0].0);
+                self.last_tree = Some(tree);
+            }
+            if lines > 1 {
+                // the later lines replaced the entry of the root with worse actions
+                let (score, pv) = &iteration[0];
+                let (score, best_move) = (score_to_table(*score, 0), pv.first().copied());
+                self.table
+                    .store(game.get_hash(), depth as u8, score, Bound::Exact, best_move);
+            }
+            results = iteration
+                .into_iter()
+                .map(|(score, pv)| SearchResult {
+                    best_move: pv.first().copied(),
+                    pv,
+                    score,
+                    depth,
+                    nodes: 0,
+                    quiescence_nodes: 0,
+                    check_extensions: 0,
+                    singular_extensions: 0,
+                    elapsed: Duration::default(),
+                })
+                .collect();
+            self.can_stop = true;
+            // a forced mate can not get any shorter by searching deeper
+            let all_mates_found = results.iter().all(|result| {
+                is_mate_score(result.score) && MATE_SCORE - result.score.abs() <= depth as i32
+            });
+            if all_mates_found || self.limit_reached() {
+                break;
+            }
+        }
+        let elapsed = self.start.elapsed();
+        for result in &mut results {
+            result.nodes = self.nodes;
+            result.quiescence_nodes = self.quiescence_nodes;
+            result.check_extensions = self.check_extensions;
+            result.singular_extensions = self.singular_extensions;
+            result.elapsed = elapsed;
+        }
+        results
+    }
+
+    /// Searches all root actions, moving the best one to the front for the next iteration
+    fn search_root(
+        &mut self,
+        game: &mut Game,
+        root_moves: &mut [Action],
+        depth: u32,
+        pv: &mut Vec<Action>,
+    ) -> i32 {
+        self.nodes += 1;
+        let mut alpha = -MATE_SCORE - 1;
+        let beta = MATE_SCORE + 1;
+        let mut best_index = 0;
+        for (index, action) in root_moves.iter().enumerate() {
+            let mut child_pv = Vec::new();
+            let token = game.execute_action(action);
+            let parent = self.enter_tree_node(0, action);
+            let score = -self.alpha_beta(game, depth - 1, 1, -beta, -alpha, &mut child_pv);
+            self.leave_tree_node(parent, score);
+            game.undo_action(action, token);
+            if self.stopped {
+                break;
+            }
+            if score > alpha {
+                alpha = score;
+                best_index = index;
+                pv.clear();
+                pv.push(*action);
+                pv.extend(child_pv);
+            }
+        }
+        root_moves[..=best_index].rotate_right(1);
+        if !self.stopped {
+            let hash = game.get_hash();
+            let score = score_to_table(alpha, 0);
+            let best_move = pv.first().copied();
+            self.table
+                .store(hash, depth as u8, score, Bound::Exact, best_move);
+        }
+        alpha
+    }
+
+    fn alpha_beta(
+        &mut self,
+        game: &mut Game,
+        mut depth: u32,
+        ply: u32,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Action>,
+    ) -> i32 {
+        // a checkmate on the last move before the fifty move rule applies still counts
+        if game.repetition_count() > 1 || game.is_draw_by_fifty_move_rule() && !game.is_checkmate()
+        {
+            self.nodes += 1;
+            return 0;
+        }
+        let can_extend = ply < 2 * self.iteration_depth;
+        if can_extend && self.options.check_extensions && game.is_in_check() {
+            depth += 1;
+            self.check_extensions += 1;
+        }
+        if depth == 0 || ply >= super::MAX_DEPTH {
+            return self.quiescence(game, ply, alpha, beta);
+        }
+        self.nodes += 1;
+        if self.should_stop() {
+            return 0;
+        }
+
+        let hash = game.get_hash();
+        let mut hash_move = None;
+        let entry = self.table.probe(hash);
+        if let Some(entry) = entry {
+            hash_move = entry.best_move;
+            let score = score_from_table(entry.score, ply);
+            if entry.depth as u32 >= depth {
+                let cutoff = match entry.bound {
+                    Bound::Exact => Some(score.clamp(alpha, beta)),
+                    Bound::Lower if score >= beta => Some(beta),
+                    Bound::Upper if score <= alpha => Some(alpha),
+                    _ => None,
+                };
+                if let Some(score) = cutoff {
+                    pv.clear();
+                    pv.extend(hash_move);
+                    return score;
+                }
+            }
+        }
+
+        let singular_move = match entry {
+            Some(entry) if can_extend && self.options.singular_extensions => {
+                self.singular_move(game, &entry, depth, ply)
+            }
+            _ => None,
+        };
+        if self.stopped {
+            return 0;
+        }
+
+        let mut picker = MovePicker::with_hash_move(game, hash_move);
+        let mut any_legal = false;
+        let mut bound = Bound::Upper;
+        let mut best_move = None;
+        while let Some(action) = picker.next(game) {
+            any_legal = true;
+            let mut child_depth = depth - 1;
+            if singular_move == Some(action) {
+                child_depth += 1;
+                self.singular_extensions += 1;
+            }
+            let mut child_pv = Vec::new();
+            let token = game.execute_action(&action);
+            let parent = self.enter_tree_node(ply, &action);
+            let score = -self.alpha_beta(game, child_depth, ply + 1, -beta, -alpha, &mut child_pv);
+            self.leave_tree_node(parent, score);
+            game.undo_action(&action, token);
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                let score = score_to_table(beta, ply);
+                self.table
+                    .store(hash, depth as u8, score, Bound::Lower, Some(action));
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+                bound = Bound::Exact;
+                best_move = Some(action);
+                pv.clear();
+                pv.push(action);
+                pv.extend(child_pv);
+            }
+        }
+        if !any_legal {
+            return if picker.check_info().in_check() {
+                -MATE_SCORE + ply as i32
+            } else {
+                0
+            };
+        }
+        let score = score_to_table(alpha, ply);
+        self.table.store(hash, depth as u8, score, bound, best_move);
+        alpha
+    }
+
+    /// Returns the action of the table entry if it is singular
+    ///
+    /// The action is singular if all other actions fail low against a bound below the score
+    /// of the entry in a search of half the depth. Only entries of a search almost as deep as
+    /// the remaining one with a score that is not an upper bound are tested.
+    fn singular_move(
+        &mut self,
+        game: &mut Game,
+        entry: &TableEntry,
+        depth: u32,
+        ply: u32,
+    ) -> Option<Action> {
+        let hash_move = entry.best_move?;
+        let score = score_from_table(entry.score, ply);
+        if depth < SINGULAR_MIN_DEPTH
+            || entry.depth as u32 + 3 < depth
+            || entry.bound == Bound::Upper
+            || is_mate_score(score)
+        {
+            return None;
+        }
+        let singular_beta = score - SINGULAR_MARGIN * depth as i32;
+        // the verification is no part of the recorded tree
+        let tree = self.tree.take();
+        let singular = self.verify_singular(game, hash_move, depth, ply, singular_beta);
+        self.tree = tree;
+        singular.then_some(hash_move)
+    }
+
+    /// Returns true if all actions but `hash_move` fail low against `singular_beta`
+    fn verify_singular(
+        &mut self,
+        game: &mut Game,
+        hash_move: Action,
+        depth: u32,
+        ply: u32,
+        singular_beta: i32,
+    ) -> bool {
+        let mut picker = MovePicker::new(game);
+        while let Some(action) = picker.next(game) {
+            if action == hash_move {
+                continue;
+            }
+            let token = game.execute_action(&action);
+            let score = -self.alpha_beta(
+                game,
+                depth / 2 - 1,
+                ply + 1,
+                -singular_beta,
+                -singular_beta + 1,
+                &mut Vec::new(),
+            );
+            game.undo_action(&action, token);
+            if self.stopped || score >= singular_beta {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Adds the action played at `ply` to the recorded tree and makes it the current node
+    ///
+    /// Returns the node to go back to with [`leave_tree_node`], `None` if the ply is not
+    /// recorded.
+    ///
+    /// [`leave_tree_node`]: #method.leave_tree_node
+    fn enter_tree_node(&mut self, ply: u32, action: &Action) -> Option<usize> {
+        if ply >= self.tree_plies {
+            return None;
+        }
+        let tree = self.tree.as_mut()?;
+        let parent = self.tree_node;
+        self.tree_node = tree.add_child(parent, *action);
+        Some(parent)
+    }
+
+    /// Scores the current node of the recorded tree and goes back to its parent
+    fn leave_tree_node(&mut self, parent: Option<usize>, score: i32) {
+        if let (Some(parent), Some(tree)) = (parent, self.tree.as_mut()) {
+            tree.set_score(self.tree_node, score);
+            self.tree_node = parent;
+        }
+    }
+
+    /// Follows captures and queen promotions until the position is quiet
+    ///
+    /// The color to move may stand pat with the static evaluation unless it is in check, then
+    /// all actions are searched. Otherwise actions losing material by static exchange
+    /// evaluation are pruned, see [`is_worth_searching`].
+    ///
+    /// [`is_worth_searching`]: fn.is_worth_searching.html
+    fn quiescence(&mut self, game: &mut Game, ply: u32, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        self.quiescence_nodes += 1;
+        if self.should_stop() {
+            return 0;
+        }
+
+        let mut picker = MovePicker::new(game);
+        let in_check = picker.check_info().in_check();
+        if !in_check {
+            let stand_pat = game.evaluate();
+            if stand_pat >= beta || ply >= super::MAX_DEPTH * 2 {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+            picker.skip_quiets();
+        }
+
+        let mut any_legal = false;
+        while let Some(action) = picker.next(game) {
+            any_legal = true;
+            if !in_check && !is_worth_searching(game, &action) {
+                continue;
+            }
+            let token = game.execute_action(&action);
+            let score = -self.quiescence(game, ply + 1, -beta, -alpha);
+            game.undo_action(&action, token);
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        if in_check && !any_legal {
+            return -MATE_SCORE + ply as i32;
+        }
+        alpha
+    }
+
+    /// Returns true if the search has to be aborted, remembering the decision
+    fn should_stop(&mut self) -> bool {
+        if self.can_stop
+            && (self.nodes % TIME_CHECK_INTERVAL == 0 || self.limits.nodes.is_some())
+            && self.limit_reached()
+        {
+            self.stopped = true;
+        }
+        self.stopped
+    }
+
+    /// Returns true if the node or time limit is used up or the stop signal is set
+    fn limit_reached(&self) -> bool {
+        self.stop_signal
+            .as_ref()
+            .is_some_and(|signal| signal.load(Ordering::Relaxed))
+            || self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes)
+            || self
+                .limits
+                .time
+                .is_some_and(|time| self.start.elapsed() >= time)
+    }
+}
+
+/// Returns true if a capture or promotion of the quiescence search does not lose material
+///
+/// Underpromotions are left out, as they only matter in positions the quiescence search can not
+/// judge anyway. All other actions are kept if their static exchange evaluation is not
+/// negative, so a queen promotion on a defended field is pruned like a capture of a defended
+/// pawn with the queen.
+fn is_worth_searching(game: &Game, action: &Action) -> bool {
+    match action.get_promotion_piece() {
+        Some(piece) if piece != PieceType::Queen => false,
+        _ => see(game, action) >= 0,
+    }
+}
+
+/// Converts a mate score counted from the root into one counted from the stored position
+fn score_to_table(score: i32, ply: u32) -> i32 {
+    if is_mate_score(score) {
+        score + score.signum() * ply as i32
+    } else {
+        score
+    }
+}
+
+/// Converts a stored mate score counted from its position into one counted from the root
+fn score_from_table(score: i32, ply: u32) -> i32 {
+    if is_mate_score(score) {
+        score - score.signum() * ply as i32
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::MAX_DEPTH;
+
+    fn best(fen: &str, depth: u32) -> SearchResult {
+        let game = Game::from_fen(fen).unwrap();
+        Searcher::new(SearchLimits::depth(depth)).search(&game)
+    }
+
+    fn best_move(game: &Game) -> Option<Action> {
+        Searcher::new(SearchLimits::depth(2)).search(game).best_move
+    }
+
+    #[test]
+    fn finds_mates_and_material() {
+        // mate in two with the rooks, deeper iterations are skipped
+        let result = best("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1", 6);
+        let first = result.best_move.unwrap().to_uci();
+        assert!(first == "a2a7" || first == "b1b7", "{}", first);
+        assert_eq!(result.score, MATE_SCORE - 3);
+        assert_eq!(result.pv.len(), 3);
+        assert_eq!(result.depth, 3);
+
+        // the hanging queen is taken
+        let result = best("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1", 2);
+        assert_eq!(result.best_move.unwrap().to_uci(), "e4d5");
+        assert!(result.score > 0);
+
+        // a pawn defended by a pawn is not taken with the queen
+        let result = best("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", 2);
+        assert_ne!(result.best_move.unwrap().to_uci(), "d1d5");
+    }
+
+    #[test]
+    fn pv_is_playable() {
+        let mut game = Game::startpos();
+        let result = Searcher::new(SearchLimits::depth(3)).search(&game);
+        assert_eq!(result.depth, 3);
+        assert_eq!(result.best_move, result.pv.first().copied());
+        assert!(result.nodes > result.quiescence_nodes);
+        for action in &result.pv {
+            assert!(movegen::legal_moves(&game).contains(action));
+            game.execute_action(action);
+        }
+    }
+
+    #[test]
+    fn ends_without_legal_moves_and_at_limits() {
+        let mated = best(
+            "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+            3,
+        );
+        assert_eq!(mated.best_move, None);
+        assert_eq!(mated.score, -MATE_SCORE);
+        let stalemate = best("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", 3);
+        assert_eq!((stalemate.best_move, stalemate.score), (None, 0));
+
+        let limits = SearchLimits {
+            nodes: Some(2000),
+            ..SearchLimits::default()
+        };
+        let result = Searcher::new(limits).search(&Game::startpos());
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1 && result.depth < MAX_DEPTH);
+
+        let limits = SearchLimits {
+            time: Some(Duration::from_millis(20)),
+            ..SearchLimits::default()
+        };
+        let result = Searcher::new(limits).search(&Game::startpos());
+        assert!(result.best_move.is_some());
+        assert!(result.elapsed < Duration::from_secs(5));
+
+        // a depth of 0 still completes the first iteration
+        let result = Searcher::new(SearchLimits::depth(0)).search(&Game::startpos());
+        assert!(result.best_move.is_some());
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn multi_pv_lines() {
+        let game = Game::from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let mut searcher = Searcher::new(SearchLimits::depth(3));
+        let lines = searcher.search_multi_pv(&game, 4);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].best_move.unwrap().to_uci(), "e4d5");
+        let single = searcher.search(&game);
+        assert_eq!(
+            (lines[0].score, lines[0].best_move),
+            (single.score, single.best_move)
+        );
+        for pair in lines.windows(2) {
+            assert!(pair[0].score >= pair[1].score);
+            assert_ne!(pair[0].best_move, pair[1].best_move);
+        }
+        // the king in the corner has three actions, each recorded once
+        let game = Game::from_fen("7k/8/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        searcher.record_tree(1);
+        assert_eq!(searcher.search_multi_pv(&game, 5).len(), 3);
+        assert_eq!(searcher.search_tree().unwrap().len(), 3);
+        searcher.record_tree(0);
+        let mated = Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+            .unwrap();
+        assert!(searcher.search_multi_pv(&mated, 2).is_empty());
+    }
+
+    #[test]
+    fn extensions_are_configurable() {
+        // the mate in two starts with a check, which is extended
+        let game =
+            Game::from_fen("r1b2k1r/ppp1bppp/8/1B1Q4/5q2/2P5/PPP2PPP/R3R1K1 w - - 1 0").unwrap();
+        let mut searcher = Searcher::new(SearchLimits::depth(2));
+        let extended = searcher.search(&game);
+        assert_eq!(extended.score, MATE_SCORE - 3);
+        assert!(extended.check_extensions > 0);
+
+        let disabled = SearchOptions {
+            check_extensions: false,
+            singular_extensions: false,
+            ..SearchOptions::default()
+        };
+        searcher.set_options(disabled);
+        searcher.table_mut().clear();
+        let plain = searcher.search(&game);
+        assert!(!is_mate_score(plain.score));
+        assert_eq!((plain.check_extensions, plain.singular_extensions), (0, 0));
+
+        // the knight has to recapture the queen
+        let game = Game::from_fen("4k3/8/8/3q4/8/2N5/8/3QK3 b - - 0 1").unwrap();
+        let mut searcher = Searcher::new(SearchLimits::depth(5));
+        assert!(searcher.search(&game).singular_extensions > 0);
+    }
+
+    #[test]
+    fn quiescence_prunes_losing_actions() {
+        let worth = |fen: &str, uci: &str| {
+            let game = Game::from_fen(fen).unwrap();
+            is_worth_searching(&game, &Action::from_uci(uci, &game).unwrap())
+        };
+        // the pawn is defended by a pawn
+        assert!(!worth("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", "d1d5"));
+        assert!(worth("4k3/8/8/3p4/8/8/8/3QK3 w - - 0 1", "d1d5"));
+        // only the queen promotion is searched, unless the rook takes the new piece
+        assert!(worth("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q"));
+        assert!(!worth("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8n"));
+        assert!(!worth("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8q"));
+        assert!(worth("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8q"));
+
+        // the search still sees through the pruned exchanges
+        let result = best("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", 2);
+        assert_eq!(result.best_move.unwrap().to_uci(), "a7b8q");
+        let result = best("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", 3);
+        assert_ne!(result.best_move.unwrap().to_uci(), "d1d5");
+        // the rook is defended by a pawn, but the queen is not lost to the bishop
+        let result = best("4k3/8/2p5/3r4/8/1b6/8/3QK3 w - - 0 1", 1);
+        assert_eq!(result.best_move.unwrap().to_uci(), "d1b3");
+        let result = best("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", 2);
+        assert_eq!(result.best_move.unwrap().to_uci(), "e1e5");
+    }
+
+    #[test]
+    fn opening_randomization() {
+        let options = SearchOptions {
+            random_plies: 2,
+            random_window: 50,
+            ..SearchOptions::default()
+        };
+        let mut searcher = Searcher::new(SearchLimits::depth(2));
+        let best = searcher.search(&Game::startpos());
+        let mut picked = Vec::new();
+        for seed in 0..8 {
+            searcher.set_options(SearchOptions {
+                random_seed: seed,
+                ..options
+            });
+            let result = searcher.search(&Game::startpos());
+            assert!(result.score >= best.score - options.random_window);
+            picked.push(result.best_move.unwrap());
+        }
+        picked.dedup();
+        assert!(picked.len() > 1);
+
+        // equal seeds repeat the choices, later plies are not randomized
+        let mut first = Searcher::new(SearchLimits::depth(2));
+        let mut second = Searcher::new(SearchLimits::depth(2));
+        first.set_options(options);
+        second.set_options(options);
+        let game = Game::from_pgn("1. e4").unwrap();
+        assert_eq!(
+            first.search(&game).best_move,
+            second.search(&game).best_move
+        );
+        let game = Game::from_pgn("1. e4 e5").unwrap();
+        for _ in 0..4 {
+            assert_eq!(first.search(&game).best_move, best_move(&game));
+        }
+        // a move worse than the window is never picked
+        let game = Game::from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        for _ in 0..4 {
+            assert_eq!(first.search(&game).best_move.unwrap().to_uci(), "e4d5");
+        }
+    }
+
+    #[test]
+    fn table_is_reused() {
+        let game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let mut searcher = Searcher::new(SearchLimits::depth(2));
+        let first = searcher.search(&game);
+        let entry = searcher.table().probe(game.get_hash()).unwrap();
+        assert_eq!(entry.best_move, first.best_move);
+        assert_eq!(entry.bound, Bound::Exact);
+
+        // the second search finds all positions of the first one in the table
+        let second = searcher.search(&game);
+        assert_eq!(second.score, first.score);
+        assert!(second.nodes < first.nodes);
+        // mates stored deeper in the tree are reported from the root
+        let mut searcher = Searcher::with_table(SearchLimits::depth(5), TranspositionTable::new(1));
+        let result = searcher.search(&Game::from_fen("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1").unwrap());
+        assert_eq!(result.score, MATE_SCORE - 3);
+    }
+
+    #[test]
+    fn clocks_from_epd() {
+        let limits = SearchLimits::depth(3);
+        let winning = Game::from_epd("8/8/4k3/8/8/4K3/8/7R w - - hmvc 0;").unwrap();
+        assert!(Searcher::new(limits).search(&winning).score > 300);
+        // every action completes fifty moves without a capture or pawn move
+        let drawn = Game::from_epd("8/8/4k3/8/8/4K3/8/7R w - - hmvc 99;").unwrap();
+        assert_eq!(Searcher::new(limits).search(&drawn).score, 0);
+        // unless it mates right away
+        let mate = Game::from_epd("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - hmvc 99;").unwrap();
+        assert_eq!(Searcher::new(limits).search(&mate).score, MATE_SCORE - 1);
+    }
+}
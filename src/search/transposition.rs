@@ -0,0 +1,221 @@
+use crate::move_generation::Action;
+use std::mem;
+
+/// The number of entries sharing one slot of the table
+const BUCKET_SIZE: usize = 4;
+
+/// How a stored score relates to the true score of the position
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Bound {
+    /// The score is exact
+    Exact,
+    /// The search failed high, the true score is at least the stored one
+    Lower,
+    /// The search failed low, the true score is at most the stored one
+    Upper,
+}
+
+/// The result of an earlier search of a position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableEntry {
+    /// The zobrist hash of the position
+    pub key: u64,
+    /// The remaining depth the position was searched with
+    pub depth: u8,
+    /// The score of the search, see `bound`
+    pub score: i32,
+    /// Whether `score` is exact or a bound
+    pub bound: Bound,
+    /// The best action found, `None` if all actions failed low
+    pub best_move: Option<Action>,
+    // the search that stored the entry, see TranspositionTable::new_search
+    age: u8,
+}
+
+type Bucket = [Option<TableEntry>; BUCKET_SIZE];
+
+/// A hash table of search results keyed by the zobrist hash of the position
+///
+/// Positions are mapped to buckets of four entries. A store replaces the entry of the same
+/// position if there is one, otherwise a free entry, otherwise the entry that is least useful:
+/// entries of earlier searches go first, then the shallowest ones. The full hash is stored, so
+/// a probe only returns entries of a different position in case of a full hash collision.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::search::{Bound, TranspositionTable};
+/// let mut table = TranspositionTable::new(1);
+/// let hash = Game::startpos().get_hash();
+/// table.store(hash, 5, 30, Bound::Exact, None);
+/// let entry = table.probe(hash).unwrap();
+/// assert_eq!((entry.depth, entry.score, entry.bound), (5, 30, Bound::Exact));
+/// assert!(table.probe(hash ^ 1).is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TranspositionTable {
+    buckets: Vec<Bucket>,
+    age: u8,
+}
+
+impl TranspositionTable {
+    /// Returns an empty table using about `megabytes` of memory, but at least one bucket
+    ///
+    /// The number of buckets is rounded down to a power of two.
+    pub fn new(megabytes: usize) -> TranspositionTable {
+        let mut table = TranspositionTable {
+            buckets: Vec::new(),
+            age: 0,
+        };
+        table.resize(megabytes);
+        table
+    }
+
+    /// Changes the size of the table to about `megabytes`, dropping all entries
+    pub fn resize(&mut self, megabytes: usize) {
+        let buckets = (megabytes * 1024 * 1024 / mem::size_of::<Bucket>()).max(1);
+        // a power of two allows mapping hashes to buckets with a mask
+        let buckets = 1 << (usize::BITS - 1 - buckets.leading_zeros());
+        self.buckets = vec![[None; BUCKET_SIZE]; buckets];
+    }
+
+    /// Removes all entries
+    pub fn clear(&mut self) {
+        for bucket in self.buckets.iter_mut() {
+            *bucket = [None; BUCKET_SIZE];
+        }
+        self.age = 0;
+    }
+
+    /// Marks all entries as belonging to an earlier search, so they are replaced first
+    pub fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    /// Returns the number of entries the table can hold
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    /// Returns the stored entry of the position with the given hash
+    pub fn probe(&self, hash: u64) -> Option<TableEntry> {
+        self.buckets[self.bucket_index(hash)]
+            .iter()
+            .flatten()
+            .find(|entry| entry.key == hash)
+            .copied()
+    }
+
+    /// Stores the result of a search of the position with the given hash
+    ///
+    /// If the position is already stored without a best move being passed, the stored best
+    /// move is kept.
+    pub fn store(
+        &mut self,
+        hash: u64,
+        depth: u8,
+        score: i32,
+        bound: Bound,
+        best_move: Option<Action>,
+    ) {
+        let age = self.age;
+        let index = self.bucket_index(hash);
+        let bucket = &mut self.buckets[index];
+        let slot = match bucket
+            .iter()
+            .position(|entry| entry.is_some_and(|entry| entry.key == hash))
+        {
+            Some(slot) => slot,
+            None => bucket
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, entry)| match entry {
+                    None => (false, 0),
+                    Some(entry) => (entry.age == age, entry.depth),
+                })
+                .map(|(slot, _)| slot)
+                .expect("buckets are not empty"),
+        };
+        let best_move = best_move.or_else(|| {
+            bucket[slot]
+                .filter(|entry| entry.key == hash)
+                .and_then(|entry| entry.best_move)
+        });
+        bucket[slot] = Some(TableEntry {
+            key: hash,
+            depth,
+            score,
+            bound,
+            best_move,
+            age,
+        });
+    }
+
+    /// Returns how many of the first thousand entries are used by the current search
+    ///
+    /// This is the `hashfull` value reported by UCI engines.
+    pub fn hashfull(&self) -> usize {
+        let sampled = self.capacity().min(1000);
+        let used = self
+            .buckets
+            .iter()
+            .flatten()
+            .take(sampled)
+            .filter(|entry| entry.is_some_and(|entry| entry.age == self.age))
+            .count();
+        used * 1000 / sampled
+    }
+
+    fn bucket_index(&self, hash: u64) -> usize {
+        hash as usize & (self.buckets.len() - 1)
+    }
+}
+
+impl Default for TranspositionTable {
+    /// Returns a table of 16 megabytes
+    fn default() -> Self {
+        TranspositionTable::new(16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_representation::PieceType;
+    use crate::move_generation::ActionType;
+
+    #[test]
+    fn size_and_replacement() {
+        let mut table = TranspositionTable::new(1);
+        assert!(table.capacity().is_power_of_two());
+        assert!(table.capacity() * mem::size_of::<TableEntry>() <= 1024 * 1024 * 2);
+        let buckets = table.buckets.len() as u64;
+        let action = Action::new_from_index(52, 36, PieceType::Pawn, ActionType::Quiet);
+
+        // five positions mapping to the same bucket
+        let hashes: Vec<u64> = (1..=5).map(|i| 7 + i * buckets).collect();
+        for (depth, hash) in hashes.iter().take(4).enumerate() {
+            table.store(*hash, depth as u8 + 1, 0, Bound::Exact, Some(action));
+        }
+        // the shallowest entry is replaced
+        table.store(hashes[4], 9, 0, Bound::Lower, None);
+        assert!(table.probe(hashes[0]).is_none());
+        assert_eq!(table.probe(hashes[4]).unwrap().depth, 9);
+
+        assert!(table.hashfull() > 0);
+
+        // entries of earlier searches are replaced before deeper ones
+        table.new_search();
+        assert_eq!(table.hashfull(), 0);
+        table.store(hashes[3], 1, 0, Bound::Upper, None);
+        table.store(hashes[0], 1, 0, Bound::Upper, None);
+        assert!(table.probe(hashes[1]).is_none());
+        assert_eq!(table.probe(hashes[4]).unwrap().depth, 9);
+        // storing without a best move keeps the known one
+        assert_eq!(table.probe(hashes[3]).unwrap().best_move, Some(action));
+
+        table.clear();
+        assert!(table.probe(hashes[4]).is_none());
+        assert_eq!(TranspositionTable::new(0).capacity(), BUCKET_SIZE);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::game_representation::Game;
+use crate::move_generation::Action;
+
+/// A position visited by a search, see [`SearchTree`]
+///
+/// [`SearchTree`]: struct.SearchTree.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchNode {
+    /// The action leading to the position, `None` for the root
+    pub action: Option<Action>,
+    /// The score of the action from the point of view of the color playing it, for the root
+    /// the score of the position for the color to move
+    pub score: i32,
+    /// The handles of the positions searched after this one, in the order they were searched
+    pub children: Vec<usize>,
+}
+
+/// The first plies of the last completed iteration of a search
+///
+/// Recording is enabled by [`Searcher::record_tree`]. Only positions of the main search are
+/// part of the tree, the quiescence search and the verification of singular actions are left
+/// out. Cut off actions do not appear, so the tree shows which actions the search looked at.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::search::{SearchLimits, Searcher};
+/// let mut searcher = Searcher::new(SearchLimits::depth(3));
+/// searcher.record_tree(1);
+/// let result = searcher.search(&Game::startpos());
+/// let tree = searcher.search_tree().unwrap();
+/// let root = tree.node(tree.root());
+/// assert_eq!(root.children.len(), 20);
+/// assert_eq!(root.score, result.score);
+/// ```
+///
+/// [`Searcher::record_tree`]: struct.Searcher.html#method.record_tree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchTree {
+    start_fen: String,
+    nodes: Vec<SearchNode>,
+}
+
+impl SearchTree {
+    /// Returns a tree containing only the root position
+    pub(crate) fn new(game: &Game) -> SearchTree {
+        SearchTree {
+            start_fen: game.to_fen(),
+            nodes: vec![SearchNode {
+                action: None,
+                score: 0,
+                children: Vec::new(),
+            }],
+        }
+    }
+
+    /// Returns the FEN of the searched position
+    pub fn start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    /// Returns the handle of the root
+    pub fn root(&self) -> usize {
+        0
+    }
+
+    /// Returns the node with the given handle
+    ///
+    /// # Panics
+    /// * if the handle does not belong to the tree
+    pub fn node(&self, node: usize) -> &SearchNode {
+        &self.nodes[node]
+    }
+
+    /// Returns the number of recorded actions, which is the number of nodes besides the root
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    /// Returns true if no action was recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a position reached by the action from `parent` and returns its handle
+    pub(crate) fn add_child(&mut self, parent: usize, action: Action) -> usize {
+        let node = self.nodes.len();
+        self.nodes.push(SearchNode {
+            action: Some(action),
+            score: 0,
+            children: Vec::new(),
+        });
+        self.nodes[parent].children.push(node);
+        node
+    }
+
+    /// Sets the score of the node once it was searched
+    pub(crate) fn set_score(&mut self, node: usize, score: i32) {
+        self.nodes[node].score = score;
+    }
+}
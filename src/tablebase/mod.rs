@@ -0,0 +1,7 @@
+//! Endgame tablebases, giving the exact outcome of positions with few pieces
+
+mod probe;
+mod root;
+
+pub use probe::{InsufficientMaterial, Tablebase, Wdl};
+pub use root::{filter_root_moves, root_moves, TablebaseMove};
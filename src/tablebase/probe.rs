@@ -0,0 +1,112 @@
+use crate::game_representation::{Color, Game, MaterialKey, PieceType};
+
+/// The outcome of a position with perfect play, from the point of view of the color to move
+///
+/// Cursed wins and blessed losses are wins and losses that can not be forced before the fifty
+/// move rule ends the game in a draw.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl Wdl {
+    /// Returns the outcome from the point of view of the other color
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::tablebase::Wdl;
+    /// assert_eq!(Wdl::Win.negate(), Wdl::Loss);
+    /// assert_eq!(Wdl::CursedWin.negate(), Wdl::BlessedLoss);
+    /// assert_eq!(Wdl::Draw.negate(), Wdl::Draw);
+    /// ```
+    pub fn negate(self) -> Wdl {
+        match self {
+            Wdl::Loss => Wdl::Win,
+            Wdl::BlessedLoss => Wdl::CursedWin,
+            Wdl::Draw => Wdl::Draw,
+            Wdl::CursedWin => Wdl::BlessedLoss,
+            Wdl::Win => Wdl::Loss,
+        }
+    }
+
+    /// Returns true for wins and losses the fifty move rule does not turn into draws
+    pub fn is_decisive(self) -> bool {
+        self == Wdl::Win || self == Wdl::Loss
+    }
+}
+
+/// A source of exact endgame results, such as a set of Syzygy tables
+///
+/// The crate does not read table files itself, an implementation wraps a reader of the format
+/// and converts the position through the public [`Game`] and [`Board`] accessors. Probing is
+/// only attempted for positions with at most [`max_pieces`] pieces including the kings and
+/// without castling rights, so implementations do not have to check for them.
+///
+/// [`Game`]: ../game_representation/struct.Game.html
+/// [`Board`]: ../game_representation/struct.Board.html
+/// [`max_pieces`]: #tymethod.max_pieces
+pub trait Tablebase {
+    /// The largest number of pieces of the available tables
+    fn max_pieces(&self) -> u32;
+
+    /// Returns the outcome of the position, `None` if it is not in the tables
+    fn probe_wdl(&self, game: &Game) -> Option<Wdl>;
+
+    /// Returns the distance to zeroing of the position, `None` if it is not in the tables
+    ///
+    /// The distance counts the plies until the next capture or pawn move of the winning line,
+    /// positive if the color to move wins and negative if it loses. Drawn positions return 0.
+    fn probe_dtz(&self, game: &Game) -> Option<i32>;
+
+    /// Returns true if the position may be probed, see [`max_pieces`]
+    ///
+    /// [`max_pieces`]: #tymethod.max_pieces
+    fn can_probe(&self, game: &Game) -> bool {
+        game.get_castling().get_raw() == 0 && game.occupancy().count() <= self.max_pieces()
+    }
+}
+
+/// The positions neither side can ever win, a king against a king with at most one minor piece
+///
+/// They need no table files, so this tablebase is always available, for example as a fallback
+/// or to test code using the [`Tablebase`] trait.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::tablebase::{InsufficientMaterial, Tablebase, Wdl};
+/// let game = Game::from_fen("4k3/8/8/8/8/8/8/2B1K3 b - - 0 1").unwrap();
+/// assert_eq!(InsufficientMaterial.probe_wdl(&game), Some(Wdl::Draw));
+/// let game = Game::from_fen("4k3/8/8/8/8/8/8/2R1K3 b - - 0 1").unwrap();
+/// assert_eq!(InsufficientMaterial.probe_wdl(&game), None);
+/// ```
+///
+/// [`Tablebase`]: trait.Tablebase.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InsufficientMaterial;
+
+impl Tablebase for InsufficientMaterial {
+    fn max_pieces(&self) -> u32 {
+        3
+    }
+
+    fn probe_wdl(&self, game: &Game) -> Option<Wdl> {
+        let key = MaterialKey::from_board(&game.board);
+        let minors =
+            |color| key.count(color, PieceType::Bishop) + key.count(color, PieceType::Knight);
+        let pieces = game.occupancy().count();
+        if pieces == 2 || pieces == 3 && minors(Color::White) + minors(Color::Black) == 1 {
+            Some(Wdl::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn probe_dtz(&self, game: &Game) -> Option<i32> {
+        self.probe_wdl(game).map(|_| 0)
+    }
+}
@@ -0,0 +1,173 @@
+use super::{Tablebase, Wdl};
+use crate::game_representation::{Game, PieceType};
+use crate::move_generation::{movegen, Action};
+
+/// A legal action of a position together with its tablebase outcome
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TablebaseMove {
+    pub action: Action,
+    /// The outcome after the action from the point of view of the color making it
+    pub wdl: Wdl,
+    /// The distance to zeroing after the action from the point of view of the color making
+    /// it, see [`Tablebase::probe_dtz`], 0 for captures and pawn moves as they zero the
+    /// counter themselves
+    ///
+    /// [`Tablebase::probe_dtz`]: trait.Tablebase.html#tymethod.probe_dtz
+    pub dtz: Option<i32>,
+}
+
+/// Returns every legal action of the position with its outcome, `None` if any of them can not
+/// be probed
+///
+/// The positions after the actions are probed, so the outcome of the position itself is the
+/// best outcome of the returned actions. Actions ending the game are scored without probing.
+/// The actions are ordered from the best to the worst outcome; wins reaching a capture or pawn
+/// move sooner come first and losses delaying it longer come first.
+pub fn root_moves<T: Tablebase + ?Sized>(tablebase: &T, game: &Game) -> Option<Vec<TablebaseMove>> {
+    let mut state = game.clone();
+    let mut moves = Vec::new();
+    for action in movegen::legal_moves(game) {
+        let zeroing = action.is_capture() || action.get_piecetype() == PieceType::Pawn;
+        let token = state.execute_action(&action);
+        let probed = if state.is_checkmate() {
+            Some((Wdl::Win, Some(0)))
+        } else if state.is_stalemate() {
+            Some((Wdl::Draw, Some(0)))
+        } else if tablebase.can_probe(&state) {
+            tablebase.probe_wdl(&state).map(|wdl| {
+                let dtz = if zeroing {
+                    Some(0)
+                } else {
+                    tablebase.probe_dtz(&state).map(|dtz| -dtz)
+                };
+                (wdl.negate(), dtz)
+            })
+        } else {
+            None
+        };
+        state.undo_action(&action, token);
+        let (wdl, dtz) = probed?;
+        moves.push(TablebaseMove { action, wdl, dtz });
+    }
+    moves.sort_by_key(|root| {
+        let distance = root.dtz.map_or(i32::MAX, i32::abs);
+        let progress = if root.wdl > Wdl::Draw {
+            distance
+        } else {
+            -distance
+        };
+        (std::cmp::Reverse(root.wdl), progress)
+    });
+    Some(moves)
+}
+
+/// Returns the legal actions keeping the best outcome of the position, `None` if it can not be
+/// probed
+///
+/// A search restricted to these actions can not spoil a won or drawn ending, while it still
+/// picks the most natural of the equally good actions. The actions are ordered like by
+/// [`root_moves`], so the first one is the fastest progress in a won position.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::game_representation::Game;
+/// # use rust_chess::tablebase::{filter_root_moves, InsufficientMaterial};
+/// // every action draws, with or without taking the knight
+/// let game = Game::from_fen("4k3/8/8/8/8/8/3n4/4K3 w - - 0 1").unwrap();
+/// assert_eq!(filter_root_moves(&InsufficientMaterial, &game).unwrap().len(), 4);
+/// assert_eq!(filter_root_moves(&InsufficientMaterial, &Game::startpos()), None);
+/// ```
+///
+/// [`root_moves`]: fn.root_moves.html
+pub fn filter_root_moves<T: Tablebase + ?Sized>(tablebase: &T, game: &Game) -> Option<Vec<Action>> {
+    let moves = root_moves(tablebase, game)?;
+    let best = moves.first().map(|root| root.wdl);
+    Some(
+        moves
+            .into_iter()
+            .filter(|root| Some(root.wdl) == best)
+            .map(|root| root.action)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scores positions by the material of the two colors, the side with more material wins
+    struct MaterialTablebase;
+
+    impl Tablebase for MaterialTablebase {
+        fn max_pieces(&self) -> u32 {
+            4
+        }
+
+        fn probe_wdl(&self, game: &Game) -> Option<Wdl> {
+            let material = |color| {
+                game.board
+                    .occupancy_of(color)
+                    .filter_map(|square| game.board.piece_at(square))
+                    .filter(|(piece, _)| *piece != PieceType::King)
+                    .map(|(piece, _)| piece.get_value())
+                    .sum::<i32>()
+            };
+            let balance =
+                material(game.color_to_move) - material(game.color_to_move.get_opponent_color());
+            Some(match balance {
+                0 => Wdl::Draw,
+                b if b > 0 => Wdl::Win,
+                _ => Wdl::Loss,
+            })
+        }
+
+        fn probe_dtz(&self, game: &Game) -> Option<i32> {
+            let wdl = self.probe_wdl(game)?;
+            // queens further right take longer
+            let queens = game.board.bishops & game.board.rooks;
+            let distance = queens.trailing_zeros() as i32 % 8 + 1;
+            Some(match wdl {
+                Wdl::Win => distance,
+                Wdl::Loss => -distance,
+                _ => 0,
+            })
+        }
+    }
+
+    #[test]
+    fn root_moves_keep_the_outcome() {
+        // the queen giving check has to be taken, moving the king away only draws
+        let game = Game::from_fen("4k3/8/8/8/8/8/3q4/3QK3 w - - 0 1").unwrap();
+        let moves = root_moves(&MaterialTablebase, &game).unwrap();
+        assert_eq!(moves.len(), 3);
+        assert_eq!(moves[0].wdl, Wdl::Win);
+        assert_eq!(moves[2].wdl, Wdl::Draw);
+        let mut uci: Vec<String> = filter_root_moves(&MaterialTablebase, &game)
+            .unwrap()
+            .iter()
+            .map(Action::to_uci)
+            .collect();
+        uci.sort();
+        assert_eq!(uci, vec!["d1d2", "e1d2"]);
+
+        // wins are ordered by their distance to zeroing, Qb6 stalemates
+        let game = Game::from_fen("k7/8/8/8/8/8/8/1Q2K3 w - - 0 1").unwrap();
+        let moves = root_moves(&MaterialTablebase, &game).unwrap();
+        let wins: Vec<i32> = moves
+            .iter()
+            .filter(|root| root.wdl == Wdl::Win)
+            .map(|root| root.dtz.unwrap())
+            .collect();
+        assert!(wins.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(wins.len(), moves.len() - 1);
+        assert_eq!(moves.last().unwrap().action.to_uci(), "b1b6");
+        assert!(filter_root_moves(&MaterialTablebase, &game)
+            .unwrap()
+            .iter()
+            .all(|action| action.to_uci() != "b1b6"));
+
+        // too many pieces
+        let game = Game::from_fen("4k3/8/8/8/8/8/2pq4/3QK3 w - - 0 1").unwrap();
+        assert_eq!(root_moves(&MaterialTablebase, &game), None);
+    }
+}
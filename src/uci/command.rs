@@ -0,0 +1,253 @@
+use crate::core::ParserError;
+use crate::game_representation::{Color, Game};
+use crate::move_generation::{Action, LegalMove};
+use crate::search::SearchLimits;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The share of the remaining time spent on a move if the number of moves is unknown
+const EXPECTED_MOVES: u32 = 30;
+
+/// The parameters of a `go` command
+///
+/// All times are in milliseconds, as sent by the GUI.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GoParameters {
+    pub white_time: Option<u64>,
+    pub black_time: Option<u64>,
+    pub white_increment: Option<u64>,
+    pub black_increment: Option<u64>,
+    pub moves_to_go: Option<u32>,
+    pub move_time: Option<u64>,
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub infinite: bool,
+}
+
+/// A command sent by the GUI to the engine
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UciCommand {
+    Uci,
+    IsReady,
+    UciNewGame,
+    SetOption {
+        name: String,
+        value: Option<String>,
+    },
+    /// The position to search, with all moves already played
+    Position(Game),
+    Go(GoParameters),
+    Stop,
+    Quit,
+}
+
+impl UciCommand {
+    /// Parses a line sent by the GUI
+    ///
+    /// Returns `Ok(None)` for empty lines and unknown commands, which engines ignore.
+    ///
+    /// # Errors
+    /// * `position` is followed by neither `startpos` nor a valid `fen`
+    /// * A move of `position` is not legal
+    /// * A value of `go` or `setoption` is missing or not a number
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::uci::UciCommand;
+    /// let command = UciCommand::parse("position startpos moves e2e4 e7e5").unwrap().unwrap();
+    /// match command {
+    ///     UciCommand::Position(game) => assert_eq!(
+    ///         game.to_fen(),
+    ///         "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
+    ///     ),
+    ///     _ => unreachable!(),
+    /// }
+    /// assert_eq!(UciCommand::parse("isready").unwrap(), Some(UciCommand::IsReady));
+    /// assert_eq!(UciCommand::parse("xyzzy").unwrap(), None);
+    /// ```
+    pub fn parse(line: &str) -> Result<Option<UciCommand>, ParserError> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let command = match tokens.first() {
+            Some(&"uci") => UciCommand::Uci,
+            Some(&"isready") => UciCommand::IsReady,
+            Some(&"ucinewgame") => UciCommand::UciNewGame,
+            Some(&"setoption") => parse_setoption(&tokens[1..])?,
+            Some(&"position") => UciCommand::Position(parse_position(&tokens[1..])?),
+            Some(&"go") => UciCommand::Go(parse_go(&tokens[1..])?),
+            Some(&"stop") => UciCommand::Stop,
+            Some(&"quit") => UciCommand::Quit,
+            _ => return Ok(None),
+        };
+        Ok(Some(command))
+    }
+}
+
+impl GoParameters {
+    /// Returns the limits of a search by the color to move
+    ///
+    /// A fixed move time is used as is. Otherwise the engine spends its remaining time divided
+    /// by the moves to go, or by thirty if unknown, plus three quarters of its increment, but
+    /// never more than half of the remaining time.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::game_representation::Color;
+    /// # use rust_chess::uci::GoParameters;
+    /// # use std::time::Duration;
+    /// let go = GoParameters { white_time: Some(60_000), moves_to_go: Some(20), ..GoParameters::default() };
+    /// assert_eq!(go.limits(Color::White).time, Some(Duration::from_millis(3000)));
+    /// assert_eq!(go.limits(Color::Black).time, None);
+    /// ```
+    pub fn limits(&self, color: Color) -> SearchLimits {
+        let mut limits = SearchLimits {
+            depth: self.depth,
+            nodes: self.nodes,
+            time: None,
+        };
+        if self.infinite {
+            return limits;
+        }
+        let (remaining, increment) = match color {
+            Color::White => (self.white_time, self.white_increment),
+            Color::Black => (self.black_time, self.black_increment),
+        };
+        let millis = match (self.move_time, remaining) {
+            (Some(move_time), _) => Some(move_time),
+            (None, Some(remaining)) => {
+                let moves = self.moves_to_go.unwrap_or(EXPECTED_MOVES).max(1) as u64;
+                let budget = remaining / moves + increment.unwrap_or(0) * 3 / 4;
+                Some(budget.min(remaining / 2))
+            }
+            (None, None) => None,
+        };
+        limits.time = millis.map(Duration::from_millis);
+        limits
+    }
+}
+
+fn parse_setoption(tokens: &[&str]) -> Result<UciCommand, ParserError> {
+    // names and values may contain spaces
+    let name_start = match tokens.first() {
+        Some(&"name") => 1,
+        _ => return Err(ParserError::InvalidParameter("setoption is missing a name")),
+    };
+    let value_start = tokens.iter().position(|token| *token == "value");
+    let name_end = value_start.unwrap_or(tokens.len());
+    if name_end <= name_start {
+        return Err(ParserError::InvalidParameter("setoption is missing a name"));
+    }
+    Ok(UciCommand::SetOption {
+        name: tokens[name_start..name_end].join(" "),
+        value: value_start.map(|start| tokens[start + 1..].join(" ")),
+    })
+}
+
+fn parse_position(tokens: &[&str]) -> Result<Game, ParserError> {
+    let moves_start = tokens
+        .iter()
+        .position(|token| *token == "moves")
+        .unwrap_or(tokens.len());
+    let mut game = match tokens.first() {
+        Some(&"startpos") => Game::startpos(),
+        Some(&"fen") => Game::from_fen(&tokens[1..moves_start].join(" "))?,
+        _ => {
+            return Err(ParserError::InvalidParameter(
+                "position needs startpos or a fen",
+            ))
+        }
+    };
+    for uci in tokens.iter().skip(moves_start + 1) {
+        let action = LegalMove::validate(Action::from_uci(uci, &game)?, &game)?;
+        game.play(action)?;
+    }
+    Ok(game)
+}
+
+fn parse_go(tokens: &[&str]) -> Result<GoParameters, ParserError> {
+    let mut go = GoParameters::default();
+    let mut tokens = tokens.iter();
+    while let Some(token) = tokens.next() {
+        if *token == "infinite" {
+            go.infinite = true;
+            continue;
+        }
+        match *token {
+            "wtime" => go.white_time = Some(go_value(tokens.next())?),
+            "btime" => go.black_time = Some(go_value(tokens.next())?),
+            "winc" => go.white_increment = Some(go_value(tokens.next())?),
+            "binc" => go.black_increment = Some(go_value(tokens.next())?),
+            "movestogo" => go.moves_to_go = Some(go_value(tokens.next())?),
+            "movetime" => go.move_time = Some(go_value(tokens.next())?),
+            "depth" => go.depth = Some(go_value(tokens.next())?),
+            "nodes" => go.nodes = Some(go_value(tokens.next())?),
+            // searchmoves, ponder and mate are not supported
+            _ => {}
+        }
+    }
+    Ok(go)
+}
+
+/// Parses the value following a parameter of go, values out of range are not a number either
+fn go_value<T: FromStr>(value: Option<&&str>) -> Result<T, ParserError> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or(ParserError::InvalidParameter("go value is not a number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            UciCommand::parse("setoption name Clear Hash").unwrap(),
+            Some(UciCommand::SetOption {
+                name: String::from("Clear Hash"),
+                value: None
+            })
+        );
+        assert_eq!(
+            UciCommand::parse("setoption name Hash value 64").unwrap(),
+            Some(UciCommand::SetOption {
+                name: String::from("Hash"),
+                value: Some(String::from("64"))
+            })
+        );
+        assert!(UciCommand::parse("setoption value 3").is_err());
+
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1";
+        let command = UciCommand::parse(&format!("position fen {} moves e2e4", fen)).unwrap();
+        match command {
+            Some(UciCommand::Position(game)) => {
+                assert_eq!(game.to_fen(), "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1")
+            }
+            other => panic!("{:?}", other),
+        }
+        assert!(UciCommand::parse("position startpos moves e2e5").is_err());
+        assert!(UciCommand::parse("position").is_err());
+
+        let go = match UciCommand::parse("go wtime 1000 btime 2000 winc 10 binc 20 movestogo 5")
+            .unwrap()
+        {
+            Some(UciCommand::Go(go)) => go,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(go.white_time, Some(1000));
+        assert_eq!(go.black_increment, Some(20));
+        assert_eq!(go.moves_to_go, Some(5));
+        assert_eq!(
+            go.limits(Color::Black).time,
+            Some(Duration::from_millis(415))
+        );
+        assert!(UciCommand::parse("go movestogo 4294967296").is_err());
+        assert!(UciCommand::parse("go depth 5000000000").is_err());
+        assert!(UciCommand::parse("go depth x").is_err());
+        let infinite = GoParameters {
+            infinite: true,
+            white_time: Some(10),
+            ..GoParameters::default()
+        };
+        assert_eq!(infinite.limits(Color::White), SearchLimits::default());
+    }
+}
@@ -0,0 +1,195 @@
+use super::EngineInfo;
+use crate::core::ParserError;
+use crate::game_representation::Game;
+use crate::search::{SearchLimits, SearchOptions, SearchResult, Searcher, TranspositionTable};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// An option an engine announces to the GUI
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UciOption {
+    /// A number within bounds
+    Spin {
+        name: String,
+        default: i64,
+        min: i64,
+        max: i64,
+    },
+    /// An on and off switch
+    Check { name: String, default: bool },
+    /// A command without a value
+    Button { name: String },
+}
+
+impl UciOption {
+    /// Returns the `option` line announcing the option
+    ///
+    /// # Examples
+    /// ```
+    /// # use rust_chess::uci::UciOption;
+    /// let hash = UciOption::Spin { name: String::from("Hash"), default: 16, min: 1, max: 1024 };
+    /// assert_eq!(hash.to_uci(), "option name Hash type spin default 16 min 1 max 1024");
+    /// ```
+    pub fn to_uci(&self) -> String {
+        match self {
+            UciOption::Spin {
+                name,
+                default,
+                min,
+                max,
+            } => format!(
+                "option name {} type spin default {} min {} max {}",
+                name, default, min, max
+            ),
+            UciOption::Check { name, default } => {
+                format!("option name {} type check default {}", name, default)
+            }
+            UciOption::Button { name } => format!("option name {} type button", name),
+        }
+    }
+}
+
+/// An engine that can be driven by a UCI [`Session`]
+///
+/// The session runs [`search`] on a separate thread, so it can still answer the GUI and pass
+/// on `stop` through the flag.
+///
+/// [`Session`]: struct.Session.html
+/// [`search`]: #tymethod.search
+pub trait UciEngine: Send + 'static {
+    /// The identity sent in `id name` and `id author`
+    fn info(&self) -> EngineInfo;
+
+    /// The options sent after the identification
+    fn options(&self) -> Vec<UciOption> {
+        Vec::new()
+    }
+
+    /// Changes an option announced by [`options`]
+    ///
+    /// # Errors
+    /// * The option is unknown or the value is not valid for it
+    ///
+    /// [`options`]: #method.options
+    fn set_option(&mut self, _name: &str, _value: Option<&str>) -> Result<(), ParserError> {
+        Err(ParserError::InvalidParameter("Unknown option"))
+    }
+
+    /// Forgets everything learned about the previous game
+    fn new_game(&mut self) {}
+
+    /// Searches the position until a limit is reached or `stop` is set
+    ///
+    /// The result has to contain a best move unless the position has no legal action.
+    fn search(&mut self, state: &Game, limits: SearchLimits, stop: Arc<AtomicBool>)
+        -> SearchResult;
+}
+
+/// The engine of the crate, a [`Searcher`] with a configurable transposition table
+///
+/// [`Searcher`]: ../search/struct.Searcher.html
+#[derive(Clone, Debug)]
+pub struct SearchEngine {
+    searcher: Searcher,
+}
+
+impl SearchEngine {
+    /// The default size of the transposition table in megabytes
+    pub const DEFAULT_HASH: usize = 16;
+
+    /// Returns the engine with a table of the default size
+    pub fn new() -> SearchEngine {
+        SearchEngine {
+            searcher: Searcher::with_table(
+                SearchLimits::default(),
+                TranspositionTable::new(SearchEngine::DEFAULT_HASH),
+            ),
+        }
+    }
+}
+
+impl Default for SearchEngine {
+    fn default() -> Self {
+        SearchEngine::new()
+    }
+}
+
+impl UciEngine for SearchEngine {
+    fn info(&self) -> EngineInfo {
+        EngineInfo::new("rust-chess", env!("CARGO_PKG_AUTHORS"))
+            .with_version(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn options(&self) -> Vec<UciOption> {
+        vec![
+            UciOption::Spin {
+                name: String::from("Hash"),
+                default: SearchEngine::DEFAULT_HASH as i64,
+                min: 1,
+                max: 4096,
+            },
+            UciOption::Button {
+                name: String::from("Clear Hash"),
+            },
+            UciOption::Check {
+                name: String::from("CheckExtensions"),
+                default: SearchOptions::default().check_extensions,
+            },
+            UciOption::Check {
+                name: String::from("SingularExtensions"),
+                default: SearchOptions::default().singular_extensions,
+            },
+        ]
+    }
+
+    fn set_option(&mut self, name: &str, value: Option<&str>) -> Result<(), ParserError> {
+        match name.to_ascii_lowercase().as_str() {
+            "hash" => {
+                let megabytes = value
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .filter(|megabytes| (1..=4096).contains(megabytes))
+                    .ok_or(ParserError::InvalidParameter("Hash is not a valid size"))?;
+                self.searcher.table_mut().resize(megabytes);
+            }
+            "clear hash" => self.searcher.table_mut().clear(),
+            "checkextensions" => {
+                let mut options = self.searcher.options();
+                options.check_extensions = parse_check(value)?;
+                self.searcher.set_options(options);
+            }
+            "singularextensions" => {
+                let mut options = self.searcher.options();
+                options.singular_extensions = parse_check(value)?;
+                self.searcher.set_options(options);
+            }
+            _ => return Err(ParserError::InvalidParameter("Unknown option")),
+        }
+        Ok(())
+    }
+
+    fn new_game(&mut self) {
+        self.searcher.table_mut().clear();
+    }
+
+    fn search(
+        &mut self,
+        state: &Game,
+        limits: SearchLimits,
+        stop: Arc<AtomicBool>,
+    ) -> SearchResult {
+        self.searcher.set_limits(limits);
+        self.searcher.set_stop_signal(stop);
+        self.searcher.search(state)
+    }
+}
+
+/// Returns the value of a `check` option
+fn parse_check(value: Option<&str>) -> Result<bool, ParserError> {
+    match value {
+        Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        _ => Err(ParserError::InvalidParameter(
+            "Value is neither true nor false",
+        )),
+    }
+}
@@ -0,0 +1,89 @@
+use crate::pgn::GameRecord;
+use std::fmt;
+
+/// The identity of an engine, sent to GUIs and written to the games it plays
+///
+/// # Examples
+/// ```
+/// # use rust_chess::uci::EngineInfo;
+/// let info = EngineInfo::new("Bonsai", "A. Gardener").with_version("2.1");
+/// assert_eq!(info.to_string(), "Bonsai 2.1");
+/// assert_eq!(info.to_uci(), vec!["id name Bonsai 2.1", "id author A. Gardener"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EngineInfo {
+    pub name: String,
+    pub author: String,
+    pub version: Option<String>,
+}
+
+impl EngineInfo {
+    /// Returns the identity of an engine without a version
+    pub fn new(name: &str, author: &str) -> EngineInfo {
+        EngineInfo {
+            name: name.to_string(),
+            author: author.to_string(),
+            version: None,
+        }
+    }
+
+    /// Returns the identity with the given version
+    pub fn with_version(mut self, version: &str) -> EngineInfo {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// Returns the `id` lines answering the `uci` command
+    pub fn to_uci(&self) -> Vec<String> {
+        vec![
+            format!("id name {}", self),
+            format!("id author {}", self.author),
+        ]
+    }
+}
+
+/// Shows the name followed by the version, if there is one
+impl fmt::Display for EngineInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{} {}", self.name, version),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+impl GameRecord {
+    /// Sets the `White` and `Black` tags to the engines playing the game
+    pub fn set_engines(&mut self, white: &EngineInfo, black: &EngineInfo) {
+        self.set_tag("White", &white.to_string());
+        self.set_tag("Black", &black.to_string());
+    }
+
+    /// Sets the `Annotator` tag to the engine that commented the game
+    pub fn set_annotator(&mut self, annotator: &EngineInfo) {
+        self.set_tag("Annotator", &annotator.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::RecordedGame;
+    use crate::uci::{SearchEngine, UciEngine};
+
+    #[test]
+    fn engines_sign_their_games() {
+        let engine = SearchEngine::new().info();
+        assert_eq!(engine.version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+        let opponent = EngineInfo::new("Random mover", "nobody");
+        let mut game = GameRecord::new(RecordedGame::new());
+        game.set_engines(&engine, &opponent);
+        game.set_annotator(&engine);
+        assert_eq!(
+            game.tag("White"),
+            Some(format!("rust-chess {}", env!("CARGO_PKG_VERSION")).as_str())
+        );
+        assert_eq!(game.tag("Black"), Some("Random mover"));
+        assert!(game.to_pgn().contains("[Annotator \"rust-chess "));
+    }
+}
@@ -0,0 +1,11 @@
+//! The Universal Chess Interface, connecting engines built on the crate to chess GUIs
+
+mod command;
+mod engine;
+mod identity;
+mod session;
+
+pub use command::{GoParameters, UciCommand};
+pub use engine::{SearchEngine, UciEngine, UciOption};
+pub use identity::EngineInfo;
+pub use session::{run, Session};
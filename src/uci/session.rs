@@ -0,0 +1,253 @@
+use super::{UciCommand, UciEngine};
+use crate::game_representation::Game;
+use crate::search::{is_mate_score, SearchResult, MATE_SCORE};
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// A connection between a GUI and an engine speaking UCI
+///
+/// Commands are passed to [`handle`] one by one. A `go` command starts the search on another
+/// thread, which writes `info` and `bestmove` once it is done. `stop` ends the running search
+/// early, all other commands wait for it to finish first.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::uci::{SearchEngine, Session, UciCommand};
+/// let mut session = Session::new(SearchEngine::new(), Vec::new());
+/// for line in ["uci", "isready", "position startpos moves e2e4", "go depth 2"] {
+///     let command = UciCommand::parse(line).unwrap().unwrap();
+///     session.handle(command).unwrap();
+/// }
+/// let output = String::from_utf8(session.finish().unwrap()).unwrap();
+/// assert!(output.contains("uciok\nreadyok\n"));
+/// assert!(output.lines().last().unwrap().starts_with("bestmove "));
+/// ```
+///
+/// [`handle`]: #method.handle
+pub struct Session<E: UciEngine, W: Write + Send + 'static> {
+    engine: Arc<Mutex<E>>,
+    output: Arc<Mutex<W>>,
+    game: Game,
+    stop: Arc<AtomicBool>,
+    search: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl<E: UciEngine, W: Write + Send + 'static> Session<E, W> {
+    /// Returns a session of the engine writing its answers to `output`
+    pub fn new(engine: E, output: W) -> Session<E, W> {
+        Session {
+            engine: Arc::new(Mutex::new(engine)),
+            output: Arc::new(Mutex::new(output)),
+            game: Game::startpos(),
+            stop: Arc::new(AtomicBool::new(false)),
+            search: None,
+        }
+    }
+
+    /// Executes a command, returns false once the session is over
+    ///
+    /// Invalid options are reported to the GUI as an `info string`.
+    ///
+    /// # Errors
+    /// * Writing to the output failed
+    pub fn handle(&mut self, command: UciCommand) -> io::Result<bool> {
+        match command {
+            UciCommand::IsReady => {
+                // answered right away, even while searching
+                self.write_line("readyok")?;
+                return Ok(true);
+            }
+            UciCommand::Stop => {
+                self.wait()?;
+                return Ok(true);
+            }
+            _ => self.join()?,
+        }
+        match command {
+            UciCommand::Uci => {
+                let engine = self.engine.lock().expect("engine is not poisoned");
+                let mut lines = engine.info().to_uci();
+                lines.extend(engine.options().iter().map(|option| option.to_uci()));
+                lines.push(String::from("uciok"));
+                drop(engine);
+                for line in lines {
+                    self.write_line(&line)?;
+                }
+            }
+            UciCommand::UciNewGame => {
+                self.engine
+                    .lock()
+                    .expect("engine is not poisoned")
+                    .new_game();
+                self.game = Game::startpos();
+            }
+            UciCommand::SetOption { name, value } => {
+                let result = self
+                    .engine
+                    .lock()
+                    .expect("engine is not poisoned")
+                    .set_option(&name, value.as_deref());
+                if result.is_err() {
+                    self.write_line(&format!("info string invalid option {}", name))?;
+                }
+            }
+            UciCommand::Position(game) => self.game = game,
+            UciCommand::Go(go) => {
+                let limits = go.limits(self.game.color_to_move);
+                let engine = Arc::clone(&self.engine);
+                let output = Arc::clone(&self.output);
+                let game = self.game.clone();
+                self.stop = Arc::new(AtomicBool::new(false));
+                let stop = Arc::clone(&self.stop);
+                self.search = Some(thread::spawn(move || {
+                    let result = engine
+                        .lock()
+                        .expect("engine is not poisoned")
+                        .search(&game, limits, stop);
+                    let mut output = output.lock().expect("output is not poisoned");
+                    writeln!(output, "{}", info_line(&result))?;
+                    let best_move = result
+                        .best_move
+                        .map_or(String::from("0000"), |action| action.to_uci());
+                    writeln!(output, "bestmove {}", best_move)?;
+                    output.flush()
+                }));
+            }
+            UciCommand::Quit => {
+                self.wait()?;
+                return Ok(false);
+            }
+            UciCommand::IsReady | UciCommand::Stop => {}
+        }
+        Ok(true)
+    }
+
+    /// Stops a running search and waits until its best move is written
+    ///
+    /// # Errors
+    /// * Writing to the output failed
+    pub fn wait(&mut self) -> io::Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.join()
+    }
+
+    /// Ends the session and returns the output, after stopping a running search
+    ///
+    /// # Errors
+    /// * Writing to the output failed
+    pub fn finish(mut self) -> io::Result<W> {
+        self.wait()?;
+        let output = Arc::try_unwrap(self.output)
+            .ok()
+            .expect("the search thread ended");
+        Ok(output.into_inner().expect("output is not poisoned"))
+    }
+
+    fn join(&mut self) -> io::Result<()> {
+        match self.search.take() {
+            Some(search) => search.join().expect("search thread does not panic"),
+            None => Ok(()),
+        }
+    }
+
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        let mut output = self.output.lock().expect("output is not poisoned");
+        writeln!(output, "{}", line)?;
+        output.flush()
+    }
+}
+
+/// Reads commands from `input` and answers on `output` until `quit` or the end of the input
+///
+/// Lines that can not be parsed are ignored, as required by the protocol.
+///
+/// # Errors
+/// * Reading the input or writing the output failed
+pub fn run<E: UciEngine, R: BufRead, W: Write + Send + 'static>(
+    engine: E,
+    input: R,
+    output: W,
+) -> io::Result<W> {
+    let mut session = Session::new(engine, output);
+    for line in input.lines() {
+        if let Ok(Some(command)) = UciCommand::parse(&line?) {
+            if !session.handle(command)? {
+                break;
+            }
+        }
+    }
+    session.finish()
+}
+
+/// Returns the `info` line describing a finished search
+fn info_line(result: &SearchResult) -> String {
+    let score = if is_mate_score(result.score) {
+        // in moves, negative if the engine gets mated
+        let plies = MATE_SCORE - result.score.abs();
+        let moves = (plies + 1) / 2;
+        format!("mate {}", if result.score > 0 { moves } else { -moves })
+    } else {
+        format!("cp {}", result.score)
+    };
+    let pv: Vec<String> = result.pv.iter().map(|action| action.to_uci()).collect();
+    let mut line = format!(
+        "info depth {} score {} nodes {} nps {} time {}",
+        result.depth,
+        score,
+        result.nodes,
+        result.nps(),
+        result.elapsed.as_millis()
+    );
+    if !pv.is_empty() {
+        line.push_str(" pv ");
+        line.push_str(&pv.join(" "));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uci::SearchEngine;
+    use std::io::Cursor;
+
+    fn session_output(input: &str) -> String {
+        let output = run(SearchEngine::new(), Cursor::new(input), Vec::new()).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn answers_commands() {
+        let output = session_output(
+            "uci\nsetoption name Hash value 2\nsetoption name Ponder value true\n\
+             setoption name CheckExtensions value false\nsetoption name SingularExtensions value 1\n\
+             ucinewgame\nisready\nposition fen 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1\n\
+             go depth 3\nquit\nisready\n",
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].starts_with("id name rust-chess"));
+        assert!(lines.contains(&"option name Hash type spin default 16 min 1 max 4096"));
+        assert!(lines.contains(&"info string invalid option Ponder"));
+        assert!(lines.contains(&"option name CheckExtensions type check default true"));
+        assert!(!lines.contains(&"info string invalid option CheckExtensions"));
+        assert!(lines.contains(&"info string invalid option SingularExtensions"));
+        assert!(lines.contains(&"readyok"));
+        let info = lines[lines.len() - 2];
+        assert!(info.contains(" score mate 1 "), "{}", info);
+        assert!(info.ends_with(" pv a1a8"), "{}", info);
+        // nothing is answered after quit
+        assert_eq!(lines[lines.len() - 1], "bestmove a1a8");
+    }
+
+    #[test]
+    fn stop_ends_infinite_searches() {
+        let output = session_output("position startpos\ngo infinite\nstop\n");
+        assert!(output.lines().last().unwrap().starts_with("bestmove "));
+
+        let mated = "position fen rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3";
+        let output = session_output(&format!("{}\ngo depth 2\n", mated));
+        assert!(output.ends_with("score mate 0 nodes 0 nps 0 time 0\nbestmove 0000\n"));
+    }
+}
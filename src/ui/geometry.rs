@@ -0,0 +1,151 @@
+use crate::core::Square;
+use crate::game_representation::Color;
+
+/// Maps pixel coordinates of a square board image to fields and back
+///
+/// The board image is `size` pixels wide and high with the pixel `(0, 0)` in its top left corner.
+/// If `size` is not divisible by 8 the squares differ by at most one pixel in size, every pixel
+/// of the image still belongs to exactly one square.
+///
+/// # Examples
+/// ```
+/// # use rust_chess::core::Square;
+/// # use rust_chess::game_representation::Color;
+/// # use rust_chess::ui::BoardGeometry;
+/// let a1: Square = "a1".parse().unwrap();
+/// let white = BoardGeometry::new(400, Color::White);
+/// assert_eq!(white.square_at(0, 399), Some(a1));
+/// assert_eq!(white.square_origin(a1), (0, 350));
+/// let black = BoardGeometry::new(400, Color::Black);
+/// assert_eq!(black.square_at(0, 399), "h8".parse().ok());
+/// assert_eq!(black.square_at(400, 0), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoardGeometry {
+    size: u32,
+    bottom: Color,
+}
+
+impl BoardGeometry {
+    /// Returns the geometry of a board image with the given size in pixels
+    ///
+    /// `bottom` is the color whose first rank is drawn at the bottom of the image.
+    pub fn new(size: u32, bottom: Color) -> BoardGeometry {
+        BoardGeometry { size, bottom }
+    }
+
+    /// Returns the width and height of the board image in pixels
+    pub fn get_size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the color whose first rank is drawn at the bottom
+    pub fn get_bottom(&self) -> Color {
+        self.bottom
+    }
+
+    /// Returns the geometry with the board turned around
+    pub fn flipped(&self) -> BoardGeometry {
+        BoardGeometry::new(self.size, self.bottom.get_opponent_color())
+    }
+
+    /// Returns the field containing the pixel
+    ///
+    /// Returns `None` if the pixel lies outside of the board image.
+    pub fn square_at(&self, x: u32, y: u32) -> Option<Square> {
+        if x >= self.size || y >= self.size {
+            return None;
+        }
+        let column = (u64::from(x) * 8 / u64::from(self.size)) as u8;
+        let row = (u64::from(y) * 8 / u64::from(self.size)) as u8;
+        Square::from_index(self.orient(row * 8 + column)).ok()
+    }
+
+    /// Returns the top left pixel of the field
+    pub fn square_origin(&self, square: Square) -> (u32, u32) {
+        let (column, row) = self.cell(square);
+        (self.offset(column), self.offset(row))
+    }
+
+    /// Returns the center pixel of the field
+    pub fn square_center(&self, square: Square) -> (u32, u32) {
+        let (column, row) = self.cell(square);
+        let center = |cell| (self.offset(cell) + self.offset(cell + 1) - 1) / 2;
+        (center(column), center(row))
+    }
+
+    /// Returns the width and height in pixels of the field
+    pub fn square_extent(&self, square: Square) -> (u32, u32) {
+        let (column, row) = self.cell(square);
+        let extent = |cell| self.offset(cell + 1) - self.offset(cell);
+        (extent(column), extent(row))
+    }
+
+    /// Converts between field indices and the position of the square in the image
+    ///
+    /// Both are counted from the top left, so turning the board around mirrors the index.
+    fn orient(&self, index: u8) -> u8 {
+        match self.bottom {
+            Color::White => index,
+            Color::Black => 63 - index,
+        }
+    }
+
+    /// Returns the column and row of the square in the image, counted from the top left
+    fn cell(&self, square: Square) -> (u8, u8) {
+        let cell = self.orient(square.index());
+        (cell % 8, cell / 8)
+    }
+
+    /// Returns the first pixel of the given column or row
+    ///
+    /// Rounded up, so that it is the first pixel mapped to the cell by [`square_at`].
+    ///
+    /// [`square_at`]: #method.square_at
+    fn offset(&self, cell: u8) -> u32 {
+        (u64::from(cell) * u64::from(self.size)).div_ceil(8) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_pixel_maps_back_to_its_square() {
+        for size in [8, 100, 203, 400] {
+            for bottom in [Color::White, Color::Black] {
+                let geometry = BoardGeometry::new(size, bottom);
+                let mut pixels = 0;
+                for square in Square::all() {
+                    let (x, y) = geometry.square_origin(square);
+                    let (width, height) = geometry.square_extent(square);
+                    let (center_x, center_y) = geometry.square_center(square);
+                    assert_eq!(geometry.square_at(x, y), Some(square));
+                    assert_eq!(
+                        geometry.square_at(x + width - 1, y + height - 1),
+                        Some(square)
+                    );
+                    assert_eq!(geometry.square_at(center_x, center_y), Some(square));
+                    assert_ne!(geometry.square_at(x + width, y), Some(square));
+                    pixels += width * height;
+                }
+                assert_eq!(pixels, size * size);
+            }
+        }
+    }
+
+    #[test]
+    fn orientation() {
+        let white = BoardGeometry::new(80, Color::White);
+        let black = white.flipped();
+        assert_eq!(black.get_bottom(), Color::Black);
+        let square = |name: &str| name.parse::<Square>().unwrap();
+        assert_eq!(white.square_at(0, 0), Some(square("a8")));
+        assert_eq!(black.square_at(0, 0), Some(square("h1")));
+        assert_eq!(white.square_origin(square("e8")), (40, 0));
+        assert_eq!(black.square_origin(square("e8")), (30, 70));
+        assert_eq!(white.square_center(square("h1")), (74, 74));
+        assert_eq!(white.square_at(80, 79), None);
+    }
+}
@@ -0,0 +1,5 @@
+//! Helpers shared by graphical frontends drawing a chessboard
+
+mod geometry;
+
+pub use geometry::BoardGeometry;
@@ -0,0 +1,138 @@
+//! JavaScript bindings for browser boards, enabled with the `wasm` feature
+//!
+//! The bindings wrap a [`Game`] with moves and positions passed as strings. Errors are thrown
+//! as JavaScript `Error`s, so invalid input from a page can not abort the module by a panic.
+//!
+//! [`Game`]: ../game_representation/struct.Game.html
+
+use crate::core::ParserError;
+use crate::game_representation::{Game, GameResult};
+use crate::move_generation::{movegen, Action, LegalMove};
+use wasm_bindgen::prelude::*;
+
+/// A game of chess as seen from JavaScript
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WasmGame {
+    game: Game,
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    /// Returns a game in the standard starting position
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmGame {
+        WasmGame {
+            game: Game::startpos(),
+        }
+    }
+
+    /// Returns the game described by the Forsyth-Edwards Notation
+    #[wasm_bindgen(js_name = fromFen)]
+    pub fn from_fen(fen: &str) -> Result<WasmGame, JsError> {
+        Ok(WasmGame {
+            game: Game::from_fen(fen)?,
+        })
+    }
+
+    /// Plays a move given in Standard Algebraic Notation and returns it in UCI notation
+    #[wasm_bindgen(js_name = pushSan)]
+    pub fn push_san(&mut self, san: &str) -> Result<String, JsError> {
+        let action = Action::from_san(san, &self.game)?;
+        self.game.execute_action(&action);
+        Ok(action.to_uci())
+    }
+
+    /// Plays a move given in UCI notation and returns it in Standard Algebraic Notation
+    #[wasm_bindgen(js_name = pushUci)]
+    pub fn push_uci(&mut self, uci: &str) -> Result<String, JsError> {
+        let action = legal_uci(uci, &self.game)?;
+        let san = action.to_san(&self.game);
+        self.game.execute_action(&action);
+        Ok(san)
+    }
+
+    /// Returns all legal moves in UCI notation
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<String> {
+        movegen::legal_moves(&self.game)
+            .iter()
+            .map(Action::to_uci)
+            .collect()
+    }
+
+    /// Returns all legal moves in Standard Algebraic Notation
+    #[wasm_bindgen(js_name = legalMovesSan)]
+    pub fn legal_moves_san(&self) -> Vec<String> {
+        movegen::legal_moves(&self.game)
+            .iter()
+            .map(|action| action.to_san(&self.game))
+            .collect()
+    }
+
+    /// Returns the Forsyth-Edwards Notation of the current position
+    #[wasm_bindgen(getter)]
+    pub fn fen(&self) -> String {
+        self.game.to_fen()
+    }
+
+    /// Returns true if the color to move is in check
+    #[wasm_bindgen(getter, js_name = isCheck)]
+    pub fn is_check(&self) -> bool {
+        self.game.is_in_check()
+    }
+
+    /// Returns the result as written in PGN, `*` while the game is ongoing
+    #[wasm_bindgen(getter)]
+    pub fn result(&self) -> String {
+        let result = match self.game.result() {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Ongoing => "*",
+        };
+        result.to_string()
+    }
+}
+
+impl Default for WasmGame {
+    fn default() -> Self {
+        WasmGame::new()
+    }
+}
+
+/// Returns a game in the standard starting position
+#[wasm_bindgen(js_name = newGame)]
+pub fn new_game() -> WasmGame {
+    WasmGame::new()
+}
+
+/// Returns the action of the UCI notation if it is legal in the position
+fn legal_uci(uci: &str, game: &Game) -> Result<Action, ParserError> {
+    LegalMove::validate(Action::from_uci(uci, game)?, game).map(Action::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // errors create JavaScript objects, so only successful calls can run outside of wasm
+    #[test]
+    fn fools_mate() {
+        let mut game = new_game();
+        // the rook can not pass the pawns, moves are rejected before any error is thrown
+        assert!(legal_uci("a1a8", &game.game).is_err());
+        assert_eq!(game.legal_moves().len(), 20);
+        assert!(game.legal_moves_san().contains(&String::from("Nf3")));
+        assert_eq!(game.push_san("f3").ok(), Some(String::from("f2f3")));
+        assert_eq!(game.push_uci("e7e5").ok(), Some(String::from("e5")));
+        game.push_san("g4").ok();
+        assert_eq!(game.push_uci("d8h4").ok(), Some(String::from("Qh4#")));
+        assert!(game.is_check());
+        assert_eq!(game.result(), "0-1");
+        assert!(game.legal_moves().is_empty());
+
+        let read = WasmGame::from_fen(&game.fen()).ok().unwrap();
+        assert_eq!(read.fen(), game.fen());
+    }
+}